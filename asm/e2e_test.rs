@@ -314,7 +314,7 @@ fn abi_probe_freezes_the_calling_convention() {
 
 #[test]
 #[ignore]
-fn abi_fatal_probe_reports_overflow_and_exit_1() {
+fn abi_fatal_probe_reports_division_by_zero_and_exit_1() {
     let toolchain = match toolchain() {
         Some(toolchain) => toolchain,
         None => return,
@@ -322,7 +322,7 @@ fn abi_fatal_probe_reports_overflow_and_exit_1() {
     let program = toolchain.link("abi_fatal_probe", toolchain.platform.abi_fatal_probe());
     let output = toolchain.execute(&program, None);
     assert_eq!(output.status.code(), Some(1));
-    assert!(stderr_of(&output).contains("monkey: IntegerOverflow"));
+    assert!(stderr_of(&output).contains("monkey: DivisionByZero"));
     assert_eq!(stdout_of(&output), "");
 }
 
@@ -359,6 +359,46 @@ fn e2e_programs_behave_like_the_interpreter() {
             "class Counter {\n  constructor(start) { this.count = start; }\n  inc() { this.count = this.count + 1; this.count }\n}\nlet c = new Counter(5);\nputs(c.inc());\nputs(c.inc());\nputs(c.count);",
             "6\n7\n7\n",
         ),
+        (
+            "special_methods",
+            "class V {\n  constructor(x) { this.x = x; }\n  add(o) { new V(this.x + o.x) }\n  eq(o) { this.x == o.x }\n  get(i) { this.x * i }\n  toString() { `V(${this.x})` }\n}\nlet v = new V(1) + new V(2);\nputs(v);\nputs(`<${v}>`);\nputs(v == new V(3));\nputs(v != new V(3));\nputs(v[2]);\nputs(str(v) + \"!\");",
+            "V(3)\n<V(3)>\ntrue\nfalse\n6\nV(3)!\n",
+        ),
+        (
+            "higher_order",
+            "let xs = [3, 1, 2];\nputs(map(xs, fn(x) { x * 2 }));\nputs(filter(xs, fn(x) { x > 1 }));\nputs(reduce(xs, fn(acc, x) { acc + x }, 0));\nputs(sort(xs));\nputs(sort(xs, fn(a, b) { b - a }));\nputs(find(xs, fn(x) { x < 3 }));\nputs(any(xs, fn(x) { x > 2 }));\nputs(all(xs, fn(x) { x > 2 }));\nputs(map([\"a\"], upper));",
+            "[6, 2, 4]\n[3, 2]\n6\n[1, 2, 3]\n[3, 2, 1]\n1\ntrue\nfalse\n[A]\n",
+        ),
+        (
+            "hash_builtins",
+            "let h = {\"b\": 1, \"a\": 2};\nputs(h);\nputs(keys(h));\nputs(values(h));\nputs(entries(h));\nputs(has(h, \"a\"));\nputs(delete(h, \"b\"));\nputs(merge(h, {\"c\": 3, \"b\": 4}));",
+            "{b: 1, a: 2}\n[b, a]\n[1, 2]\n[[b, 1], [a, 2]]\ntrue\n{a: 2}\n{b: 4, a: 2, c: 3}\n",
+        ),
+        (
+            "math_builtins",
+            "puts(abs(-3));\nputs(max(1, 2.5));\nputs(pow(2, 64));\nputs(sqrt(16));\nputs(round(2.5));\nseed(42);\nputs(random(100));",
+            "3\n2.5\n18446744073709551616\n4.0\n3\n13\n",
+        ),
+        (
+            "json_builtins",
+            "let h = json_decode(json_encode({\"b\": [1, 2.5], \"a\": true}));\nputs(h);\nputs(json_encode(h[\"b\"]));",
+            "{b: [1, 2.5], a: true}\n[1,2.5]\n",
+        ),
+        (
+            "reflection_builtins",
+            "class Point { constructor(x) { this.x = x; } get() { this.x } }\nlet p = new Point(1);\nputs(type_of(p));\nputs(instance_of(p, Point));\nputs(fields(p));\nputs(methods(Point));",
+            "instance\ntrue\n[x]\n[get]\n",
+        ),
+        (
+            "builtin_methods",
+            "let xs = [3, 1, 2].push(4);\nputs(xs.sort().map(fn(x) { x * 10 }));\nputs(\"a,b\".split(\",\").len());\nputs({\"k\": 1}.keys());",
+            "[10, 20, 30, 40]\n2\n[k]\n",
+        ),
+        (
+            "iterators",
+            "let evens = take(range(0, 1000000, 2), 3);\nputs(to_array(evens));\nputs(to_array(enumerate(rest(evens))));\nputs(len(zip(evens, [1])));",
+            "[0, 2, 4]\n[[0, 2], [1, 4]]\n1\n",
+        ),
         (
             "big_integers",
             "puts(9223372036854775807 - 1);\nputs(0 - 9223372036854775807);\nputs(4611686018427387903 + 1);",
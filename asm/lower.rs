@@ -7,7 +7,7 @@ use compiler::symbol_table::{Symbol, SymbolScope, SymbolTable};
 use object::builtins::BuiltIns;
 use parser::ast::{
     BlockStatement, ClassDeclaration, Expression, FunctionDeclaration, Let, Literal,
-    MethodDefinition, MethodKind, Node, Statement,
+    MethodDefinition, MethodKind, Node, Statement, TemplateLiteral,
 };
 use parser::lexer::token::{Span, TokenKind};
 use parser::validation::validate_program;
@@ -457,9 +457,58 @@ impl<'a> Lowerer<'a> {
                     });
                 Ok(())
             }
+            Expression::Template(template) => self.lower_template(template),
+            Expression::Yield(yield_expression) => {
+                error("generators are not supported by the native backend", &yield_expression.span)
+            }
         }
     }
 
+    /// Stages every non-empty quasi and substitution value in a scratch area,
+    /// in source order, for `rt_concat` to render and join.
+    fn lower_template(&mut self, template: &TemplateLiteral) -> Result<(), LowerError> {
+        let span = template.span.clone();
+        let len = template.expressions.len()
+            + template
+                .quasis
+                .iter()
+                .filter(|quasi| !quasi.is_empty())
+                .count();
+        let area = scratch_area_size(len);
+        let comment = self.snippet(&span);
+        self.emitter.with_span(&span, |emitter| {
+            emitter.comment(&comment);
+            emitter.sp_sub(area);
+        });
+        let mut slot = 0;
+        for (index, quasi) in template.quasis.iter().enumerate() {
+            if !quasi.is_empty() {
+                let (label, bytes) = self.emitter.intern_string(quasi.as_bytes());
+                self.emitter.with_span(&span, |emitter| {
+                    emitter.load_label_address("x0", &label, "template text");
+                    emitter.load_imm64("x1", bytes, "byte length");
+                    emitter.call_runtime("rt_string_from_bytes", "");
+                    emitter.sp_store("x0", 8 * slot, &format!("part {}", slot));
+                });
+                slot += 1;
+            }
+            if let Some(expression) = template.expressions.get(index) {
+                self.lower_expression(expression)?;
+                self.emitter.with_span(&span, |emitter| {
+                    emitter.sp_store("x0", 8 * slot, &format!("part {}", slot));
+                });
+                slot += 1;
+            }
+        }
+        self.emitter.with_span(&span, |emitter| {
+            emitter.sp_address("x0", 0, "part base");
+            emitter.load_imm64("x1", len as u64, "part count");
+            emitter.call_runtime("rt_concat", "");
+            emitter.sp_add(area);
+        });
+        Ok(())
+    }
+
     fn lower_literal(&mut self, literal: &Literal) -> Result<(), LowerError> {
         match literal {
             Literal::Integer(integer) => {
@@ -474,6 +523,25 @@ impl<'a> Lowerer<'a> {
                 });
                 Ok(())
             }
+            Literal::BigInt(integer) => {
+                // Too wide for a register: the digits live in .rodata like a
+                // string literal and are parsed once at runtime.
+                let (label, len) = self.emitter.intern_string(integer.raw.as_bytes());
+                self.emitter.with_span(&integer.span.clone(), |emitter| {
+                    emitter.load_label_address("x0", &label, &integer.raw);
+                    emitter.load_imm64("x1", len, "digit count");
+                    emitter.call_runtime("rt_bigint_from_decimal", "");
+                });
+                Ok(())
+            }
+            Literal::Float(float) => {
+                let raw = float.raw.0;
+                self.emitter.with_span(&float.span.clone(), |emitter| {
+                    emitter.load_imm64("x0", raw.to_bits(), &format!("{} (f64 bits)", raw));
+                    emitter.call_runtime("rt_box_float", "");
+                });
+                Ok(())
+            }
             Literal::Boolean(boolean) => {
                 let (value, text) =
                     if boolean.raw { (TRUE_VALUE, "true") } else { (FALSE_VALUE, "false") };
@@ -603,6 +671,9 @@ impl<'a> Lowerer<'a> {
     }
 
     fn lower_function(&mut self, function: &FunctionDeclaration) -> Result<(), LowerError> {
+        if function.generator {
+            return error("generators are not supported by the native backend", &function.span);
+        }
         if function.params.len() > MAX_FUNCTION_PARAMETERS {
             return error(
                 format!("functions accept at most {} parameters", MAX_FUNCTION_PARAMETERS),
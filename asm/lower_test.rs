@@ -85,6 +85,18 @@ fn snapshot_boxed_integer_literal() {
     insta::assert_snapshot!(assembly("9223372036854775807;"));
 }
 
+#[test]
+fn snapshot_float_literal() {
+    // Floats are always boxed: the f64 bits go through x0 to rt_box_float.
+    insta::assert_snapshot!(assembly("1 + 0.5;"));
+}
+
+#[test]
+fn snapshot_template_literal() {
+    // Non-empty quasis and substitutions share one scratch area for rt_concat.
+    insta::assert_snapshot!(assembly("let n = 3; `n = ${n}${n + 1}!`;"));
+}
+
 #[test]
 fn snapshot_observe_mode() {
     insta::assert_snapshot!(
@@ -159,6 +171,10 @@ fn validation_runs_before_lowering() {
     assert!(error_message("this;").contains("this is only available inside a method"));
     assert!(error_message("class C { constructor() { return 1; } }")
         .contains("constructor cannot return a value"));
+    assert_eq!(
+        error_message("let g = fn*() { yield 1; };"),
+        "generators are not supported by the native backend"
+    );
 }
 
 #[test]
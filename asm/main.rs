@@ -18,7 +18,7 @@ use std::process::{Command, Stdio};
 
 use monkey_asm::emitter::AsmDialect;
 use monkey_asm::lower::compile_source;
-use monkey_asm::runtime_core::RuntimeErrorKind;
+use monkey_asm::runtime_core::{builtin_canonical_name, builtin_from_ordinal, RuntimeErrorKind};
 use serde_json::{Map as JsonMap, Value as JsonValue};
 
 /// Everything `build`/`run` do differently per target platform (design §9):
@@ -432,7 +432,10 @@ fn validate_canonical_value(value: &JsonValue, path: &str) -> Result<(), String>
         "builtin" => {
             expect_fields(object, &["type", "id"], path)?;
             let id = required_string(object, "id", path)?;
-            if !["len", "puts", "first", "last", "rest", "push"].contains(&id) {
+            let canonical = (0..)
+                .map_while(builtin_from_ordinal)
+                .any(|builtin| builtin_canonical_name(builtin) == id);
+            if !canonical {
                 return Err(format!("{}.id is not a canonical builtin id", path));
             }
             Ok(())
@@ -10,10 +10,13 @@
 //! its safe reference API exclusive across FFI entries and threads; generated
 //! function calls happen only after the guard has been released.
 
+use std::convert::Infallible;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::{Mutex, OnceLock};
 
+use object::higher_order::{self, HigherOrderError, Host, SortKey, ValueHost};
+
 use crate::runtime_backend::{CodeHandle, PointerStore};
 use crate::runtime_core::{
     self, CallDispatch, OutputSink, ReturnPolicy, RuntimeErrorKind, RuntimeFailure, RuntimeResult,
@@ -173,14 +176,110 @@ fn complete_dispatch(dispatch: CallDispatch) -> Value {
             return_policy,
         } => {
             let returned = unsafe { invoke_code(code, closure, &args) };
-            match return_policy {
-                ReturnPolicy::Direct => returned,
-                ReturnPolicy::ConstructorInstance(instance) => instance,
-            }
+            return_policy.finish(returned)
+        }
+    }
+}
+
+/// Runs `receiver.name(args)` when the receiver is an instance whose class
+/// defines that special method (design §7.3); `None` leaves the operator to
+/// its built-in semantics.
+fn call_special_method(
+    receiver: Value,
+    name: &str,
+    args: &[Value],
+    return_policy: ReturnPolicy,
+) -> Option<Value> {
+    let dispatch = ffi_shell(|store| {
+        runtime_core::special_method_dispatch(store, receiver, name, args, return_policy)
+    });
+    dispatch.map(complete_dispatch)
+}
+
+/// Replaces instances that define `toString` with that method's result, so
+/// the shared display rules see the user's text.
+fn render_instances(values: &mut [Value]) {
+    for value in values.iter_mut() {
+        if let Some(shown) = call_special_method(*value, "toString", &[], ReturnPolicy::Direct) {
+            *value = shown;
         }
     }
 }
 
+/// A plain call, made by `rt_call` or by a higher-order builtin calling back.
+fn call_value(callee: Value, args: Vec<Value>) -> Value {
+    let (callee, mut args) =
+        ffi_shell(|store| Ok(runtime_core::unbind_builtin(store, callee, args)));
+    if runtime_core::shows_instances(callee) {
+        render_instances(&mut args);
+    }
+    if let Some(id) = runtime_core::higher_order_id(callee) {
+        return match higher_order::call(&mut NativeHost, id, &args) {
+            Ok(result) => result,
+            Err(HigherOrderError::Builtin(error)) => {
+                let failure = runtime_core::builtin_failure(error);
+                fatal(failure.kind, &failure.message)
+            }
+            Err(HigherOrderError::Callback(never)) => match never {},
+        };
+    }
+    let dispatch =
+        ffi_shell(|store| runtime_core::dispatch_call(store, &mut StdoutSink, callee, &args));
+    complete_dispatch(dispatch)
+}
+
+/// The native runtime as seen by `map`, `sort` and the other higher-order
+/// builtins. Each step takes the store lock on its own: a callback runs
+/// generated code, which re-enters these shells. Nothing is ever freed, so
+/// retaining and releasing are no-ops; failures never return.
+struct NativeHost;
+
+impl ValueHost for NativeHost {
+    type Value = Value;
+
+    fn array(&mut self, elements: &[Value]) -> Value {
+        ffi_shell(|store| Ok(runtime_core::array_from_values(store, elements)))
+    }
+
+    fn null(&mut self) -> Value {
+        NULL_VALUE
+    }
+
+    fn retain(&mut self, value: &Value) -> Value {
+        *value
+    }
+
+    fn release(&mut self, _value: Value) {}
+
+    fn describe(&self, value: &Value) -> String {
+        ffi_shell(|store| runtime_core::display(store, *value))
+    }
+}
+
+impl Host for NativeHost {
+    type Error = Infallible;
+
+    fn call(&mut self, function: &Value, args: &[Value]) -> Result<Value, Infallible> {
+        Ok(call_value(*function, args.to_vec()))
+    }
+
+    fn elements(&self, value: &Value) -> Option<Vec<Value>> {
+        ffi_shell(|store| Ok(runtime_core::array_elements(store, *value)))
+    }
+
+    fn boolean(&mut self, value: bool) -> Value {
+        runtime_core::bool_value(value)
+    }
+
+    fn truthy(&self, value: &Value) -> bool {
+        runtime_core::truthy(*value)
+    }
+
+    fn sort_key(&self, value: &Value) -> Option<SortKey> {
+        ffi_shell(|store| Ok(runtime_core::sort_key(store, *value)))
+    }
+}
+
 #[no_mangle]
 /// # Safety
 /// `base` must be writable for `count` consecutive, properly aligned values
@@ -213,6 +312,23 @@ pub extern "C" fn rt_box_int(raw: i64) -> Value {
     ffi_shell(|store| Ok(runtime_core::make_int(store, raw)))
 }
 
+#[no_mangle]
+/// # Safety
+/// For nonzero `len`, `ptr` must reference `len` readable ASCII digits.
+pub unsafe extern "C" fn rt_bigint_from_decimal(ptr: *const u8, len: u64) -> Value {
+    ffi_shell(|store| {
+        let bytes = unsafe { byte_slice(ptr, len) };
+        runtime_core::bigint_from_decimal(store, bytes)
+    })
+}
+
+/// Takes the IEEE 754 bit pattern in a general-purpose register so generated
+/// code never touches the FP register file.
+#[no_mangle]
+pub extern "C" fn rt_box_float(bits: u64) -> Value {
+    ffi_shell(|store| Ok(runtime_core::make_float(store, f64::from_bits(bits))))
+}
+
 #[no_mangle]
 /// # Safety
 /// For nonzero `len`, `argv` must reference `len` readable values.
@@ -223,6 +339,15 @@ pub unsafe extern "C" fn rt_array(argv: *const Value, len: u64) -> Value {
     })
 }
 
+#[no_mangle]
+/// # Safety
+/// For nonzero `len`, `argv` must reference `len` readable values.
+pub unsafe extern "C" fn rt_concat(argv: *const Value, len: u64) -> Value {
+    let mut values = unsafe { value_slice(argv, len) }.to_vec();
+    render_instances(&mut values);
+    ffi_shell(|store| runtime_core::concat(store, &values))
+}
+
 #[no_mangle]
 /// # Safety
 /// `argv` must reference `pairs * 2` readable values, and that multiplication
@@ -307,38 +432,53 @@ pub unsafe extern "C" fn rt_set_property(obj: Value, name: *const u8, len: u64,
 
 #[no_mangle]
 pub extern "C" fn rt_index(obj: Value, idx: Value) -> Value {
-    ffi_shell(|store| runtime_core::index(store, obj, idx))
+    call_special_method(obj, "get", &[idx], ReturnPolicy::Direct)
+        .unwrap_or_else(|| ffi_shell(|store| runtime_core::index(store, obj, idx)))
 }
 
 #[no_mangle]
 pub extern "C" fn rt_add(l: Value, r: Value) -> Value {
-    ffi_shell(|store| runtime_core::add(store, l, r))
+    call_special_method(l, "add", &[r], ReturnPolicy::Direct)
+        .unwrap_or_else(|| ffi_shell(|store| runtime_core::add(store, l, r)))
 }
 
 #[no_mangle]
 pub extern "C" fn rt_sub(l: Value, r: Value) -> Value {
-    ffi_shell(|store| runtime_core::sub(store, l, r))
+    call_special_method(l, "sub", &[r], ReturnPolicy::Direct)
+        .unwrap_or_else(|| ffi_shell(|store| runtime_core::sub(store, l, r)))
 }
 
 #[no_mangle]
 pub extern "C" fn rt_mul(l: Value, r: Value) -> Value {
-    ffi_shell(|store| runtime_core::mul(store, l, r))
+    call_special_method(l, "mul", &[r], ReturnPolicy::Direct)
+        .unwrap_or_else(|| ffi_shell(|store| runtime_core::mul(store, l, r)))
 }
 
 #[no_mangle]
 pub extern "C" fn rt_div(l: Value, r: Value) -> Value {
-    ffi_shell(|store| runtime_core::div(store, l, r))
+    call_special_method(l, "div", &[r], ReturnPolicy::Direct)
+        .unwrap_or_else(|| ffi_shell(|store| runtime_core::div(store, l, r)))
 }
 
 #[no_mangle]
 pub extern "C" fn rt_eq(l: Value, r: Value) -> Value {
-    ffi_shell(|store| runtime_core::eq_values(store, l, r).map(runtime_core::bool_value))
+    let policy = ReturnPolicy::Truthiness {
+        negate: false,
+    };
+    call_special_method(l, "eq", &[r], policy).unwrap_or_else(|| {
+        ffi_shell(|store| runtime_core::eq_values(store, l, r).map(runtime_core::bool_value))
+    })
 }
 
 #[no_mangle]
 pub extern "C" fn rt_neq(l: Value, r: Value) -> Value {
-    ffi_shell(|store| {
-        runtime_core::eq_values(store, l, r).map(|equal| runtime_core::bool_value(!equal))
+    let policy = ReturnPolicy::Truthiness {
+        negate: true,
+    };
+    call_special_method(l, "eq", &[r], policy).unwrap_or_else(|| {
+        ffi_shell(|store| {
+            runtime_core::eq_values(store, l, r).map(|equal| runtime_core::bool_value(!equal))
+        })
     })
 }
 
@@ -367,11 +507,8 @@ pub extern "C" fn rt_truthy(v: Value) -> u64 {
 /// For nonzero `argc`, `argv` must reference `argc` readable values. Any
 /// closure reached through `callee` must contain a valid generated code entry.
 pub unsafe extern "C" fn rt_call(callee: Value, argc: u64, argv: *const Value) -> Value {
-    let dispatch = ffi_shell(|store| {
-        let args = unsafe { value_slice(argv, argc) };
-        runtime_core::dispatch_call(store, &mut StdoutSink, callee, args)
-    });
-    complete_dispatch(dispatch)
+    let args = unsafe { value_slice(argv, argc) }.to_vec();
+    call_value(callee, args)
 }
 
 #[no_mangle]
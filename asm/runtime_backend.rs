@@ -11,6 +11,8 @@
 #[cfg(not(target_family = "wasm"))]
 use std::collections::HashMap;
 
+use object::random::Random;
+
 use crate::runtime_core::{HeapObject, Value, HEAP_TAG, PTR_TAG_MASK};
 
 /// Opaque code reference stored inside closures. Only the execution adapter
@@ -24,6 +26,9 @@ pub trait ValueStore {
     /// heap tag or does not name a live object of this store.
     fn try_get(&self, value: Value) -> Option<&HeapObject>;
     fn try_get_mut(&mut self, value: Value) -> Option<&mut HeapObject>;
+    /// The program's generator for `random`/`seed`; it lives as long as the
+    /// store, i.e. one run.
+    fn random(&mut self) -> &mut Random;
 }
 
 /// Arena-backed store: `((index << 3) | 0b001)`. Used by the wasm simulator
@@ -31,6 +36,7 @@ pub trait ValueStore {
 #[derive(Default)]
 pub struct HandleStore {
     arena: Vec<HeapObject>,
+    random: Random,
 }
 
 impl HandleStore {
@@ -60,6 +66,10 @@ impl ValueStore for HandleStore {
         let index = Self::index_of(value)?;
         self.arena.get_mut(index)
     }
+
+    fn random(&mut self) -> &mut Random {
+        &mut self.random
+    }
 }
 
 /// Native store: heap objects live in owned, 8-byte-aligned cells and the
@@ -80,6 +90,7 @@ struct HeapCell(HeapObject);
 #[derive(Default)]
 pub struct PointerStore {
     cells: HashMap<Value, Box<HeapCell>>,
+    random: Random,
 }
 
 #[cfg(not(target_family = "wasm"))]
@@ -114,4 +125,8 @@ impl ValueStore for PointerStore {
         }
         self.cells.get_mut(&value).map(|cell| &mut cell.0)
     }
+
+    fn random(&mut self) -> &mut Random {
+        &mut self.random
+    }
 }
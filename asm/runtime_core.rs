@@ -2,14 +2,25 @@
 //!
 //! Everything here is shared between the native runtime (`runtime.rs`) and
 //! the wasm simulator: tagged value encoding, the frozen semantics matrix
-//! (checked `i64` arithmetic, equality, truthiness, indexing, builtins),
+//! (integer arithmetic promoting to BigInt, equality, truthiness, indexing, builtins),
 //! the canonical observer encoding, and call/construct dispatch. Failures
 //! never exit the process at this layer; they are returned as stable
 //! [`RuntimeErrorKind`] categories.
 
 use std::collections::HashMap;
 
-use object::builtins::{BuiltIns, BuiltinId};
+use object::builtins::{BuiltIns, BuiltinError, BuiltinId};
+use object::hashes::{self, HashArg, HashResult};
+use object::higher_order::{self, SortKey, ValueHost};
+use object::integer::{self, Int, IntegerOp};
+use object::iterators::{self, Iter, IterHost, IterSource};
+use object::json::{self, Json, JsonArg, JsonView};
+use object::math::{self, NumArg, NumResult};
+use object::methods::{self, Receiver};
+use object::random;
+use object::reflect::{self, ReflectArg, ReflectResult, TypeOf};
+use object::strings::{self, TextArg, TextResult};
+use object::{format_float, BigInt, OrderedMap, Vector};
 
 use crate::runtime_backend::{CodeHandle, ValueStore};
 
@@ -76,6 +87,55 @@ pub fn builtin_ordinal(id: BuiltinId) -> u64 {
         BuiltinId::Last => 3,
         BuiltinId::Rest => 4,
         BuiltinId::Push => 5,
+        BuiltinId::Split => 6,
+        BuiltinId::Join => 7,
+        BuiltinId::Trim => 8,
+        BuiltinId::Upper => 9,
+        BuiltinId::Lower => 10,
+        BuiltinId::Contains => 11,
+        BuiltinId::StartsWith => 12,
+        BuiltinId::EndsWith => 13,
+        BuiltinId::Replace => 14,
+        BuiltinId::Substr => 15,
+        BuiltinId::IndexOf => 16,
+        BuiltinId::Chars => 17,
+        BuiltinId::Str => 18,
+        BuiltinId::Int => 19,
+        BuiltinId::Map => 20,
+        BuiltinId::Filter => 21,
+        BuiltinId::Reduce => 22,
+        BuiltinId::Sort => 23,
+        BuiltinId::Find => 24,
+        BuiltinId::Any => 25,
+        BuiltinId::All => 26,
+        BuiltinId::Keys => 27,
+        BuiltinId::Values => 28,
+        BuiltinId::Entries => 29,
+        BuiltinId::Has => 30,
+        BuiltinId::Delete => 31,
+        BuiltinId::Merge => 32,
+        BuiltinId::Abs => 33,
+        BuiltinId::Min => 34,
+        BuiltinId::Max => 35,
+        BuiltinId::Pow => 36,
+        BuiltinId::Sqrt => 37,
+        BuiltinId::Floor => 38,
+        BuiltinId::Ceil => 39,
+        BuiltinId::Round => 40,
+        BuiltinId::Random => 41,
+        BuiltinId::Seed => 42,
+        BuiltinId::JsonEncode => 43,
+        BuiltinId::JsonDecode => 44,
+        BuiltinId::TypeOf => 45,
+        BuiltinId::InstanceOf => 46,
+        BuiltinId::Fields => 47,
+        BuiltinId::Methods => 48,
+        BuiltinId::Range => 49,
+        BuiltinId::Take => 50,
+        BuiltinId::Zip => 51,
+        BuiltinId::Enumerate => 52,
+        BuiltinId::ToArray => 53,
+        BuiltinId::Next => 54,
     }
 }
 
@@ -87,6 +147,55 @@ pub fn builtin_from_ordinal(ordinal: u64) -> Option<BuiltinId> {
         3 => Some(BuiltinId::Last),
         4 => Some(BuiltinId::Rest),
         5 => Some(BuiltinId::Push),
+        6 => Some(BuiltinId::Split),
+        7 => Some(BuiltinId::Join),
+        8 => Some(BuiltinId::Trim),
+        9 => Some(BuiltinId::Upper),
+        10 => Some(BuiltinId::Lower),
+        11 => Some(BuiltinId::Contains),
+        12 => Some(BuiltinId::StartsWith),
+        13 => Some(BuiltinId::EndsWith),
+        14 => Some(BuiltinId::Replace),
+        15 => Some(BuiltinId::Substr),
+        16 => Some(BuiltinId::IndexOf),
+        17 => Some(BuiltinId::Chars),
+        18 => Some(BuiltinId::Str),
+        19 => Some(BuiltinId::Int),
+        20 => Some(BuiltinId::Map),
+        21 => Some(BuiltinId::Filter),
+        22 => Some(BuiltinId::Reduce),
+        23 => Some(BuiltinId::Sort),
+        24 => Some(BuiltinId::Find),
+        25 => Some(BuiltinId::Any),
+        26 => Some(BuiltinId::All),
+        27 => Some(BuiltinId::Keys),
+        28 => Some(BuiltinId::Values),
+        29 => Some(BuiltinId::Entries),
+        30 => Some(BuiltinId::Has),
+        31 => Some(BuiltinId::Delete),
+        32 => Some(BuiltinId::Merge),
+        33 => Some(BuiltinId::Abs),
+        34 => Some(BuiltinId::Min),
+        35 => Some(BuiltinId::Max),
+        36 => Some(BuiltinId::Pow),
+        37 => Some(BuiltinId::Sqrt),
+        38 => Some(BuiltinId::Floor),
+        39 => Some(BuiltinId::Ceil),
+        40 => Some(BuiltinId::Round),
+        41 => Some(BuiltinId::Random),
+        42 => Some(BuiltinId::Seed),
+        43 => Some(BuiltinId::JsonEncode),
+        44 => Some(BuiltinId::JsonDecode),
+        45 => Some(BuiltinId::TypeOf),
+        46 => Some(BuiltinId::InstanceOf),
+        47 => Some(BuiltinId::Fields),
+        48 => Some(BuiltinId::Methods),
+        49 => Some(BuiltinId::Range),
+        50 => Some(BuiltinId::Take),
+        51 => Some(BuiltinId::Zip),
+        52 => Some(BuiltinId::Enumerate),
+        53 => Some(BuiltinId::ToArray),
+        54 => Some(BuiltinId::Next),
         _ => None,
     }
 }
@@ -105,6 +214,55 @@ pub fn builtin_canonical_name(id: BuiltinId) -> &'static str {
         BuiltinId::Last => "last",
         BuiltinId::Rest => "rest",
         BuiltinId::Push => "push",
+        BuiltinId::Split => "split",
+        BuiltinId::Join => "join",
+        BuiltinId::Trim => "trim",
+        BuiltinId::Upper => "upper",
+        BuiltinId::Lower => "lower",
+        BuiltinId::Contains => "contains",
+        BuiltinId::StartsWith => "starts_with",
+        BuiltinId::EndsWith => "ends_with",
+        BuiltinId::Replace => "replace",
+        BuiltinId::Substr => "substr",
+        BuiltinId::IndexOf => "index_of",
+        BuiltinId::Chars => "chars",
+        BuiltinId::Str => "str",
+        BuiltinId::Int => "int",
+        BuiltinId::Map => "map",
+        BuiltinId::Filter => "filter",
+        BuiltinId::Reduce => "reduce",
+        BuiltinId::Sort => "sort",
+        BuiltinId::Find => "find",
+        BuiltinId::Any => "any",
+        BuiltinId::All => "all",
+        BuiltinId::Keys => "keys",
+        BuiltinId::Values => "values",
+        BuiltinId::Entries => "entries",
+        BuiltinId::Has => "has",
+        BuiltinId::Delete => "delete",
+        BuiltinId::Merge => "merge",
+        BuiltinId::Abs => "abs",
+        BuiltinId::Min => "min",
+        BuiltinId::Max => "max",
+        BuiltinId::Pow => "pow",
+        BuiltinId::Sqrt => "sqrt",
+        BuiltinId::Floor => "floor",
+        BuiltinId::Ceil => "ceil",
+        BuiltinId::Round => "round",
+        BuiltinId::Random => "random",
+        BuiltinId::Seed => "seed",
+        BuiltinId::JsonEncode => "json_encode",
+        BuiltinId::JsonDecode => "json_decode",
+        BuiltinId::TypeOf => "type_of",
+        BuiltinId::InstanceOf => "instance_of",
+        BuiltinId::Fields => "fields",
+        BuiltinId::Methods => "methods",
+        BuiltinId::Range => "range",
+        BuiltinId::Take => "take",
+        BuiltinId::Zip => "zip",
+        BuiltinId::Enumerate => "enumerate",
+        BuiltinId::ToArray => "to_array",
+        BuiltinId::Next => "next",
     }
 }
 
@@ -127,6 +285,8 @@ pub enum RuntimeErrorKind {
     MissingProperty = 5,
     InvalidHashKey = 6,
     DivisionByZero = 7,
+    /// No longer raised: overflowing integers promote to BigInt. The
+    /// discriminant stays reserved so the numbering remains frozen.
     IntegerOverflow = 8,
     ResourceLimit = 9,
 }
@@ -182,16 +342,17 @@ fn fail<T>(kind: RuntimeErrorKind, message: impl Into<String>) -> RuntimeResult<
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum HashKey {
     Integer(i64),
+    BigInt(BigInt),
     Boolean(bool),
     Str(String),
 }
 
 impl HashKey {
-    /// Rank + canonical bytes ordering used by displays and the observer
-    /// (integer=0, boolean=1, string=2; design §10.2).
+    /// Rank + canonical bytes ordering used by the observer (integer=0,
+    /// boolean=1, string=2; design §10.2).
     fn rank(&self) -> u8 {
         match self {
-            HashKey::Integer(_) => 0,
+            HashKey::Integer(_) | HashKey::BigInt(_) => 0,
             HashKey::Boolean(_) => 1,
             HashKey::Str(_) => 2,
         }
@@ -200,6 +361,7 @@ impl HashKey {
     fn canonical_bytes(&self) -> Vec<u8> {
         match self {
             HashKey::Integer(raw) => raw.to_string().into_bytes(),
+            HashKey::BigInt(raw) => raw.to_string().into_bytes(),
             HashKey::Boolean(raw) => raw.to_string().into_bytes(),
             HashKey::Str(raw) => raw.clone().into_bytes(),
         }
@@ -238,13 +400,21 @@ pub struct BoundMethodData {
 #[derive(Clone, Debug)]
 pub enum HeapObject {
     BoxedInt(i64),
+    /// Integers outside the `i64` range, produced when arithmetic overflows.
+    /// Never holds a value that would fit in `BoxedInt`.
+    BoxedBigInt(BigInt),
+    /// Floats are always boxed; the tagged word has no spare room for an f64.
+    BoxedFloat(f64),
     Str(String),
-    Array(Vec<Value>),
-    Hash(HashMap<HashKey, Value>),
+    Array(Vector<Value>),
+    /// Entries in insertion order; `display` keeps it, the observer sorts.
+    Hash(OrderedMap<HashKey, Value>),
     Closure(ClosureData),
     Class(ClassData),
     Instance(InstanceData),
     BoundMethod(BoundMethodData),
+    /// A lazy sequence, see `object::iterators`.
+    Iterator(Iter<Value>),
 }
 
 fn get_obj<S: ValueStore>(store: &S, value: Value) -> RuntimeResult<&HeapObject> {
@@ -275,6 +445,61 @@ pub fn make_int<S: ValueStore>(store: &mut S, raw: i64) -> Value {
     }
 }
 
+/// Any integer (SMI, boxed or BigInt) for the shared `object::integer`
+/// arithmetic.
+fn int_operand<S: ValueStore>(store: &S, value: Value) -> Option<Int> {
+    if let Some(raw) = int_value(store, value) {
+        return Some(Int::Small(raw));
+    }
+    match store.try_get(value) {
+        Some(HeapObject::BoxedBigInt(raw)) => Some(Int::Big(raw.clone())),
+        _ => None,
+    }
+}
+
+fn make_integer<S: ValueStore>(store: &mut S, value: Int) -> Value {
+    match value {
+        Int::Small(raw) => make_int(store, raw),
+        Int::Big(raw) => store.alloc(HeapObject::BoxedBigInt(raw)),
+    }
+}
+
+/// Parses the decimal digits of an integer literal too wide for `i64`.
+pub fn bigint_from_decimal<S: ValueStore>(store: &mut S, bytes: &[u8]) -> RuntimeResult<Value> {
+    match BigInt::parse_bytes(bytes, 10) {
+        Some(raw) => Ok(make_integer(store, Int::narrow(raw))),
+        None => fail(RuntimeErrorKind::InternalError, "integer literal is not decimal"),
+    }
+}
+
+pub fn float_value<S: ValueStore>(store: &S, value: Value) -> Option<f64> {
+    if is_smi(value) {
+        return None;
+    }
+    match store.try_get(value) {
+        Some(HeapObject::BoxedFloat(raw)) => Some(*raw),
+        _ => None,
+    }
+}
+
+pub fn make_float<S: ValueStore>(store: &mut S, raw: f64) -> Value {
+    store.alloc(HeapObject::BoxedFloat(raw))
+}
+
+/// Both operands widened to `f64` when at least one is a float and the other
+/// a number (SMI, boxed int, BigInt or float). Int/int operands return
+/// `None` and stay on the exact integer path.
+fn float_operands<S: ValueStore>(store: &S, left: Value, right: Value) -> Option<(f64, f64)> {
+    let left_float = float_value(store, left);
+    let right_float = float_value(store, right);
+    if left_float.is_none() && right_float.is_none() {
+        return None;
+    }
+    let l = left_float.or_else(|| int_operand(store, left).map(|raw| raw.to_f64()))?;
+    let r = right_float.or_else(|| int_operand(store, right).map(|raw| raw.to_f64()))?;
+    Some((l, r))
+}
+
 /// Frozen truthiness: only `false` and `null` are falsy (design §10.1).
 pub fn truthy(value: Value) -> bool {
     value != FALSE_VALUE && value != NULL_VALUE
@@ -288,7 +513,16 @@ pub fn string_from_utf8<S: ValueStore>(store: &mut S, bytes: &[u8]) -> RuntimeRe
 }
 
 pub fn array_from_values<S: ValueStore>(store: &mut S, values: &[Value]) -> Value {
-    store.alloc(HeapObject::Array(values.to_vec()))
+    store.alloc(HeapObject::Array(values.iter().copied().collect()))
+}
+
+/// Template literal parts joined with the same rendering `puts` uses.
+pub fn concat<S: ValueStore>(store: &mut S, parts: &[Value]) -> RuntimeResult<Value> {
+    let mut text = String::new();
+    for part in parts {
+        text.push_str(&display(store, *part)?);
+    }
+    Ok(store.alloc(HeapObject::Str(text)))
 }
 
 pub fn hash_key<S: ValueStore>(store: &S, value: Value) -> Option<HashKey> {
@@ -299,16 +533,18 @@ pub fn hash_key<S: ValueStore>(store: &S, value: Value) -> Option<HashKey> {
         TRUE_VALUE => Some(HashKey::Boolean(true)),
         FALSE_VALUE => Some(HashKey::Boolean(false)),
         _ => match store.try_get(value) {
+            Some(HeapObject::BoxedBigInt(raw)) => Some(HashKey::BigInt(raw.clone())),
             Some(HeapObject::Str(text)) => Some(HashKey::Str(text.clone())),
             _ => None,
         },
     }
 }
 
-/// `pairs` is `k0,v0,k1,v1,…`; later duplicates win, like the VMs.
+/// `pairs` is `k0,v0,k1,v1,…`; later duplicates win, like the VMs, and keep
+/// the position of the first.
 pub fn hash_from_pairs<S: ValueStore>(store: &mut S, pairs: &[Value]) -> RuntimeResult<Value> {
     debug_assert_eq!(pairs.len() % 2, 0);
-    let mut entries = HashMap::new();
+    let mut entries = OrderedMap::new();
     for pair in pairs.chunks_exact(2) {
         let key = match hash_key(store, pair[0]) {
             Some(key) => key,
@@ -387,12 +623,26 @@ pub fn class_add_method<S: ValueStore>(
 }
 
 /// Field first, then a freshly bound method; missing → `MissingProperty`
-/// (design §8, matching the VMs' error surface).
+/// (design §8, matching the VMs' error surface). Strings, arrays and hashes
+/// bind the builtin `object::methods` names instead.
 pub fn get_property<S: ValueStore>(
     store: &mut S,
     object: Value,
     name: &str,
 ) -> RuntimeResult<Value> {
+    let kind = match store.try_get(object) {
+        Some(HeapObject::Str(_)) => Some(Receiver::String),
+        Some(HeapObject::Array(_)) => Some(Receiver::Array),
+        Some(HeapObject::Hash(_)) => Some(Receiver::Hash),
+        _ => None,
+    };
+    if let Some(id) = kind.and_then(|kind| methods::lookup(kind, name)) {
+        return Ok(store.alloc(HeapObject::BoundMethod(BoundMethodData {
+            receiver: object,
+            method: builtin_value(id),
+            name: name.to_string(),
+        })));
+    }
     let (class, field) = match store.try_get(object) {
         Some(HeapObject::Instance(instance)) => {
             (instance.class, instance.fields.get(name).copied())
@@ -480,13 +730,16 @@ pub fn index<S: ValueStore>(store: &S, object: Value, index: Value) -> RuntimeRe
     )
 }
 
-/// Frozen equality matrix (design §10.1): integers by raw value, scalars by
-/// value, builtins by id, aggregates recursively, identity types by object
+/// Frozen equality matrix (design §10.1): numbers by value (an integer equals
+/// the float of the same value, NaN equals nothing), scalars by value, builtins by id, aggregates recursively, identity types by object
 /// identity, differing types compare unequal.
 pub fn eq_values<S: ValueStore>(store: &S, left: Value, right: Value) -> RuntimeResult<bool> {
-    let left_int = int_value(store, left);
-    let right_int = int_value(store, right);
-    if let (Some(l), Some(r)) = (left_int, right_int) {
+    if let Some((l, r)) = float_operands(store, left, right) {
+        return Ok(l == r);
+    }
+    let left_int = int_operand(store, left);
+    let right_int = int_operand(store, right);
+    if let (Some(l), Some(r)) = (&left_int, &right_int) {
         return Ok(l == r);
     }
     if left_int.is_some() != right_int.is_some() {
@@ -529,14 +782,18 @@ pub fn eq_values<S: ValueStore>(store: &S, left: Value, right: Value) -> Runtime
         (HeapObject::Closure(_), HeapObject::Closure(_))
         | (HeapObject::Class(_), HeapObject::Class(_))
         | (HeapObject::Instance(_), HeapObject::Instance(_))
-        | (HeapObject::BoundMethod(_), HeapObject::BoundMethod(_)) => Ok(left == right),
+        | (HeapObject::BoundMethod(_), HeapObject::BoundMethod(_))
+        | (HeapObject::Iterator(_), HeapObject::Iterator(_)) => Ok(left == right),
         _ => Ok(false),
     }
 }
 
-/// `>` accepts integers only (design §10.1).
+/// `>` accepts numbers only (design §10.1).
 pub fn gt<S: ValueStore>(store: &S, left: Value, right: Value) -> RuntimeResult<Value> {
-    if let (Some(l), Some(r)) = (int_value(store, left), int_value(store, right)) {
+    if let (Some(l), Some(r)) = (int_operand(store, left), int_operand(store, right)) {
+        return Ok(bool_value(integer::compare(&l, &r).is_gt()));
+    }
+    if let Some((l, r)) = float_operands(store, left, right) {
         return Ok(bool_value(l > r));
     }
     let left_shown = display(store, left)?;
@@ -547,22 +804,26 @@ pub fn gt<S: ValueStore>(store: &S, left: Value, right: Value) -> RuntimeResult<
     )
 }
 
-fn checked_arith<S: ValueStore>(
+/// Integers go through `object::integer`, promoting to BigInt instead of
+/// overflowing; as soon as one operand is a float both are widened and
+/// `apply_float` follows IEEE 754 (no overflow, `x / 0.0` is an infinity or
+/// NaN).
+fn arith<S: ValueStore>(
     store: &mut S,
     left: Value,
     right: Value,
-    operation: &str,
-    apply: impl Fn(i64, i64) -> Option<i64>,
+    operation: IntegerOp,
+    apply_float: impl Fn(f64, f64) -> f64,
 ) -> RuntimeResult<Value> {
-    if let (Some(l), Some(r)) = (int_value(store, left), int_value(store, right)) {
-        return match apply(l, r) {
-            Some(raw) => Ok(make_int(store, raw)),
-            None => fail(
-                RuntimeErrorKind::IntegerOverflow,
-                format!("integer overflow in {}", operation),
-            ),
+    if let (Some(l), Some(r)) = (int_operand(store, left), int_operand(store, right)) {
+        return match integer::apply(operation, &l, &r) {
+            Some(result) => Ok(make_integer(store, result)),
+            None => fail(RuntimeErrorKind::DivisionByZero, "division by zero"),
         };
     }
+    if let Some((l, r)) = float_operands(store, left, right) {
+        return Ok(make_float(store, apply_float(l, r)));
+    }
     let left_shown = display(store, left)?;
     let right_shown = display(store, right)?;
     fail(
@@ -571,7 +832,7 @@ fn checked_arith<S: ValueStore>(
     )
 }
 
-/// Checked addition; also string concatenation (design §8).
+/// Addition; also string concatenation (design §8).
 pub fn add<S: ValueStore>(store: &mut S, left: Value, right: Value) -> RuntimeResult<Value> {
     if int_value(store, left).is_none() {
         if let (Some(HeapObject::Str(l)), Some(HeapObject::Str(r))) =
@@ -581,32 +842,30 @@ pub fn add<S: ValueStore>(store: &mut S, left: Value, right: Value) -> RuntimeRe
             return Ok(store.alloc(HeapObject::Str(combined)));
         }
     }
-    checked_arith(store, left, right, "addition", i64::checked_add)
+    arith(store, left, right, IntegerOp::Add, |l, r| l + r)
 }
 
 pub fn sub<S: ValueStore>(store: &mut S, left: Value, right: Value) -> RuntimeResult<Value> {
-    checked_arith(store, left, right, "subtraction", i64::checked_sub)
+    arith(store, left, right, IntegerOp::Sub, |l, r| l - r)
 }
 
 pub fn mul<S: ValueStore>(store: &mut S, left: Value, right: Value) -> RuntimeResult<Value> {
-    checked_arith(store, left, right, "multiplication", i64::checked_mul)
+    arith(store, left, right, IntegerOp::Mul, |l, r| l * r)
 }
 
-/// Division separates `DivisionByZero` first, then truncates toward zero via
-/// checked `i64` division (`i64::MIN / -1` → `IntegerOverflow`).
+/// Integer division truncates toward zero and fails with `DivisionByZero` on
+/// a zero divisor; `i64::MIN / -1` promotes like any other overflow.
 pub fn div<S: ValueStore>(store: &mut S, left: Value, right: Value) -> RuntimeResult<Value> {
-    if let (Some(_), Some(0)) = (int_value(store, left), int_value(store, right)) {
-        return fail(RuntimeErrorKind::DivisionByZero, "division by zero");
-    }
-    checked_arith(store, left, right, "division", i64::checked_div)
+    arith(store, left, right, IntegerOp::Div, |l, r| l / r)
 }
 
 pub fn minus<S: ValueStore>(store: &mut S, value: Value) -> RuntimeResult<Value> {
-    if let Some(raw) = int_value(store, value) {
-        return match raw.checked_neg() {
-            Some(negated) => Ok(make_int(store, negated)),
-            None => fail(RuntimeErrorKind::IntegerOverflow, "integer overflow in negation"),
-        };
+    if let Some(raw) = int_operand(store, value) {
+        let negated = integer::negate(&raw);
+        return Ok(make_integer(store, negated));
+    }
+    if let Some(raw) = float_value(store, value) {
+        return Ok(make_float(store, -raw));
     }
     let shown = display(store, value)?;
     fail(RuntimeErrorKind::TypeError, format!("unsupported type for negation: {}", shown))
@@ -618,7 +877,7 @@ pub fn bang(value: Value) -> Value {
 }
 
 /// Hash entries in canonical order: `(key type rank, canonical key bytes)`.
-fn sorted_hash_entries(entries: &HashMap<HashKey, Value>) -> Vec<(&HashKey, Value)> {
+fn sorted_hash_entries(entries: &OrderedMap<HashKey, Value>) -> Vec<(&HashKey, Value)> {
     let mut sorted: Vec<(&HashKey, Value)> =
         entries.iter().map(|(key, value)| (key, *value)).collect();
     sorted.sort_by(|(a, _), (b, _)| {
@@ -630,11 +889,23 @@ fn sorted_hash_entries(entries: &HashMap<HashKey, Value>) -> Vec<(&HashKey, Valu
 fn key_display(key: &HashKey) -> String {
     match key {
         HashKey::Integer(raw) => raw.to_string(),
+        HashKey::BigInt(raw) => raw.to_string(),
         HashKey::Boolean(raw) => raw.to_string(),
         HashKey::Str(raw) => raw.clone(),
     }
 }
 
+/// The class of an instance receiver, or the type of a string, array or hash
+/// one.
+fn receiver_name<S: ValueStore>(store: &S, receiver: Value) -> RuntimeResult<String> {
+    match get_obj(store, receiver)? {
+        HeapObject::Str(_) => Ok(Receiver::String.name().to_string()),
+        HeapObject::Array(_) => Ok(Receiver::Array.name().to_string()),
+        HeapObject::Hash(_) => Ok(Receiver::Hash.name().to_string()),
+        _ => instance_class_name(store, receiver),
+    }
+}
+
 fn instance_class_name<S: ValueStore>(store: &S, instance: Value) -> RuntimeResult<String> {
     match get_obj(store, instance)? {
         HeapObject::Instance(data) => match get_obj(store, data.class)? {
@@ -661,6 +932,8 @@ pub fn display<S: ValueStore>(store: &S, value: Value) -> RuntimeResult<String>
     }
     match get_obj(store, value)? {
         HeapObject::BoxedInt(_) => unreachable!("handled by int_value"),
+        HeapObject::BoxedBigInt(raw) => Ok(raw.to_string()),
+        HeapObject::BoxedFloat(raw) => Ok(format_float(*raw)),
         HeapObject::Str(text) => Ok(text.clone()),
         HeapObject::Array(elements) => {
             let mut rendered = Vec::with_capacity(elements.len());
@@ -671,19 +944,18 @@ pub fn display<S: ValueStore>(store: &S, value: Value) -> RuntimeResult<String>
         }
         HeapObject::Hash(entries) => {
             let mut rendered = Vec::with_capacity(entries.len());
-            for (key, entry_value) in sorted_hash_entries(entries) {
-                rendered.push(format!("{}: {}", key_display(key), display(store, entry_value)?));
+            for (key, entry_value) in entries.iter() {
+                rendered.push(format!("{}: {}", key_display(key), display(store, *entry_value)?));
             }
             Ok(format!("{{{}}}", rendered.join(", ")))
         }
         HeapObject::Closure(_) => Ok("[function]".to_string()),
+        HeapObject::Iterator(_) => Ok("[iterator]".to_string()),
         HeapObject::Class(data) => Ok(format!("[class {}]", data.name)),
         HeapObject::Instance(_) => Ok(format!("[object {}]", instance_class_name(store, value)?)),
-        HeapObject::BoundMethod(data) => Ok(format!(
-            "[bound method {}.{}]",
-            instance_class_name(store, data.receiver)?,
-            data.name
-        )),
+        HeapObject::BoundMethod(data) => {
+            Ok(format!("[bound method {}.{}]", receiver_name(store, data.receiver)?, data.name))
+        }
     }
 }
 
@@ -725,6 +997,12 @@ pub fn canonical_value<S: ValueStore>(store: &S, value: Value) -> RuntimeResult<
     }
     match get_obj(store, value)? {
         HeapObject::BoxedInt(_) => unreachable!("handled by int_value"),
+        HeapObject::BoxedBigInt(raw) => {
+            Ok(format!("{{\"type\":\"integer\",\"value\":\"{}\"}}", raw))
+        }
+        HeapObject::BoxedFloat(raw) => {
+            Ok(format!("{{\"type\":\"float\",\"value\":\"{}\"}}", format_float(*raw)))
+        }
         HeapObject::Str(text) => {
             Ok(format!("{{\"type\":\"string\",\"value\":\"{}\"}}", json_escape(text)))
         }
@@ -742,6 +1020,9 @@ pub fn canonical_value<S: ValueStore>(store: &S, value: Value) -> RuntimeResult<
                     HashKey::Integer(raw) => {
                         format!("{{\"type\":\"integer\",\"value\":\"{}\"}}", raw)
                     }
+                    HashKey::BigInt(raw) => {
+                        format!("{{\"type\":\"integer\",\"value\":\"{}\"}}", raw)
+                    }
                     HashKey::Boolean(raw) => {
                         format!("{{\"type\":\"boolean\",\"value\":{}}}", raw)
                     }
@@ -758,6 +1039,7 @@ pub fn canonical_value<S: ValueStore>(store: &S, value: Value) -> RuntimeResult<
             Ok(format!("{{\"type\":\"hash\",\"entries\":[{}]}}", rendered.join(",")))
         }
         HeapObject::Closure(_) => Ok("{\"type\":\"function\"}".to_string()),
+        HeapObject::Iterator(_) => Ok("{\"type\":\"iterator\"}".to_string()),
         HeapObject::Class(data) => {
             Ok(format!("{{\"type\":\"class\",\"name\":\"{}\"}}", json_escape(&data.name)))
         }
@@ -767,17 +1049,15 @@ pub fn canonical_value<S: ValueStore>(store: &S, value: Value) -> RuntimeResult<
         )),
         HeapObject::BoundMethod(data) => Ok(format!(
             "{{\"type\":\"bound_method\",\"class\":\"{}\",\"method\":\"{}\"}}",
-            json_escape(&instance_class_name(store, data.receiver)?),
+            json_escape(&receiver_name(store, data.receiver)?),
             json_escape(&data.name)
         )),
     }
 }
 
 /// Where `puts`/`print` bytes go: stdout natively, a buffer in the simulator
-/// and in tests (design §8.1).
-pub trait OutputSink {
-    fn write_line(&mut self, line: &str);
-}
+/// and in tests (design §8.1). The trait is the one every runtime writes to.
+pub use object::output::OutputSink;
 
 /// Test/simulator sink collecting raw stdout bytes.
 pub struct BufferSink {
@@ -834,6 +1114,7 @@ pub fn call_builtin<S: ValueStore>(
             let length = match store.try_get(args[0]) {
                 Some(HeapObject::Str(text)) => text.len() as i64,
                 Some(HeapObject::Array(elements)) => elements.len() as i64,
+                Some(HeapObject::Iterator(_)) => return call_iterator_builtin(store, id, args),
                 _ => {
                     let shown = display(store, args[0])?;
                     return fail(
@@ -856,9 +1137,12 @@ pub fn call_builtin<S: ValueStore>(
             match store.try_get(args[0]) {
                 Some(HeapObject::Array(elements)) => {
                     let element =
-                        if id == BuiltinId::First { elements.first() } else { elements.last() };
+                        if id == BuiltinId::First { elements.front() } else { elements.back() };
                     Ok(element.copied().unwrap_or(NULL_VALUE))
                 }
+                Some(HeapObject::Iterator(_)) if id == BuiltinId::First => {
+                    call_iterator_builtin(store, id, args)
+                }
                 _ => {
                     let shown = display(store, args[0])?;
                     fail(
@@ -879,8 +1163,9 @@ pub fn call_builtin<S: ValueStore>(
                     if elements.is_empty() {
                         return Ok(NULL_VALUE);
                     }
-                    elements[1..].to_vec()
+                    elements.skip(1)
                 }
+                Some(HeapObject::Iterator(_)) => return call_iterator_builtin(store, id, args),
                 _ => {
                     let shown = display(store, args[0])?;
                     return fail(
@@ -896,7 +1181,7 @@ pub fn call_builtin<S: ValueStore>(
             let pushed = match store.try_get(args[0]) {
                 Some(HeapObject::Array(elements)) => {
                     let mut extended = elements.clone();
-                    extended.push(args[1]);
+                    extended.push_back(args[1]);
                     extended
                 }
                 _ => {
@@ -909,15 +1194,469 @@ pub fn call_builtin<S: ValueStore>(
             };
             Ok(store.alloc(HeapObject::Array(pushed)))
         }
+        BuiltinId::Split
+        | BuiltinId::Join
+        | BuiltinId::Trim
+        | BuiltinId::Upper
+        | BuiltinId::Lower
+        | BuiltinId::Contains
+        | BuiltinId::StartsWith
+        | BuiltinId::EndsWith
+        | BuiltinId::Replace
+        | BuiltinId::Substr
+        | BuiltinId::IndexOf
+        | BuiltinId::Chars
+        | BuiltinId::Str
+        | BuiltinId::Int => call_text_builtin(store, id, args),
+        BuiltinId::Keys
+        | BuiltinId::Values
+        | BuiltinId::Entries
+        | BuiltinId::Has
+        | BuiltinId::Delete
+        | BuiltinId::Merge => call_hash_builtin(store, id, args),
+        BuiltinId::Abs
+        | BuiltinId::Min
+        | BuiltinId::Max
+        | BuiltinId::Pow
+        | BuiltinId::Sqrt
+        | BuiltinId::Floor
+        | BuiltinId::Ceil
+        | BuiltinId::Round => {
+            let args = num_args(store, args)?;
+            num_result(store, math::call(id, args))
+        }
+        BuiltinId::Random | BuiltinId::Seed => {
+            let args = num_args(store, args)?;
+            let result = random::call(store.random(), id, args);
+            num_result(store, result)
+        }
+        BuiltinId::JsonEncode | BuiltinId::JsonDecode => call_json_builtin(store, id, args),
+        BuiltinId::TypeOf | BuiltinId::InstanceOf | BuiltinId::Fields | BuiltinId::Methods => {
+            call_reflect_builtin(store, id, args)
+        }
+        BuiltinId::Range
+        | BuiltinId::Take
+        | BuiltinId::Zip
+        | BuiltinId::Enumerate
+        | BuiltinId::ToArray => call_iterator_builtin(store, id, args),
+        BuiltinId::Next => {
+            // Generator functions do not lower, so no value is a generator.
+            if args.is_empty() || args.len() > 2 {
+                return fail(
+                    RuntimeErrorKind::ArityError,
+                    format!("builtin next expected 1 or 2 arguments, got {}", args.len()),
+                );
+            }
+            let shown = display(store, args[0])?;
+            fail(
+                RuntimeErrorKind::TypeError,
+                format!("builtin next not supported for type {}", shown),
+            )
+        }
+        BuiltinId::Map
+        | BuiltinId::Filter
+        | BuiltinId::Reduce
+        | BuiltinId::Sort
+        | BuiltinId::Find
+        | BuiltinId::Any
+        | BuiltinId::All => fail(
+            RuntimeErrorKind::InternalError,
+            format!("builtin {} must be called through rt_call", builtin_canonical_name(id)),
+        ),
+    }
+}
+
+/// The string library lives in `object::strings`; this only converts values
+/// in and out of it.
+fn call_text_builtin<S: ValueStore>(
+    store: &mut S,
+    id: BuiltinId,
+    args: &[Value],
+) -> RuntimeResult<Value> {
+    let mut text_args = Vec::with_capacity(args.len());
+    for argument in args {
+        let text_arg = if let Some(value) = int_operand(store, *argument) {
+            TextArg::Int(value)
+        } else if let Some(value) = float_value(store, *argument) {
+            TextArg::Float(value)
+        } else {
+            match store.try_get(*argument) {
+                Some(HeapObject::Str(text)) => TextArg::Str(text.clone()),
+                Some(HeapObject::Array(elements)) => {
+                    let elements = elements.clone();
+                    let mut shown = Vec::with_capacity(elements.len());
+                    for element in elements {
+                        shown.push(display(store, element)?);
+                    }
+                    TextArg::Array(shown)
+                }
+                _ => TextArg::Other(display(store, *argument)?),
+            }
+        };
+        text_args.push(text_arg);
+    }
+    match strings::call(id, text_args) {
+        Ok(TextResult::Str(text)) => Ok(store.alloc(HeapObject::Str(text))),
+        Ok(TextResult::Int(value)) => Ok(make_integer(store, value)),
+        Ok(TextResult::Bool(value)) => Ok(bool_value(value)),
+        Ok(TextResult::Array(items)) => {
+            let elements = items
+                .into_iter()
+                .map(|item| store.alloc(HeapObject::Str(item)))
+                .collect();
+            Ok(store.alloc(HeapObject::Array(elements)))
+        }
+        Ok(TextResult::Null) => Ok(NULL_VALUE),
+        Err(error) => Err(builtin_failure(error)),
+    }
+}
+
+/// Like the string library, the hash builtins live in `object::hashes`.
+fn call_hash_builtin<S: ValueStore>(
+    store: &mut S,
+    id: BuiltinId,
+    args: &[Value],
+) -> RuntimeResult<Value> {
+    let result = {
+        let store = &*store;
+        let decoded: Vec<HashArg<'_, HashKey, Value>> = args
+            .iter()
+            .map(|argument| match store.try_get(*argument) {
+                Some(HeapObject::Hash(entries)) => HashArg::Hash(entries),
+                _ => match hash_key(store, *argument) {
+                    Some(key) => HashArg::Key(key),
+                    None => HashArg::Other,
+                },
+            })
+            .collect();
+        hashes::call(id, &decoded, |index| {
+            display(store, args[index]).unwrap_or_else(|failure| failure.message)
+        })
+    };
+    match result {
+        Ok(HashResult::Keys(keys)) => {
+            let elements = keys.iter().map(|key| key_value(store, key)).collect();
+            Ok(store.alloc(HeapObject::Array(elements)))
+        }
+        Ok(HashResult::Values(values)) => Ok(store.alloc(HeapObject::Array(values.into()))),
+        Ok(HashResult::Entries(entries)) => {
+            let pairs = entries
+                .iter()
+                .map(|(key, value)| {
+                    let key = key_value(store, key);
+                    store.alloc(HeapObject::Array(vec![key, *value].into()))
+                })
+                .collect();
+            Ok(store.alloc(HeapObject::Array(pairs)))
+        }
+        Ok(HashResult::Bool(value)) => Ok(bool_value(value)),
+        Ok(HashResult::Hash(entries)) => Ok(store.alloc(HeapObject::Hash(entries))),
+        Err(error) => Err(builtin_failure(error)),
+    }
+}
+
+/// The iterator builtins live in `object::iterators`.
+fn call_iterator_builtin<S: ValueStore>(
+    store: &mut S,
+    id: BuiltinId,
+    args: &[Value],
+) -> RuntimeResult<Value> {
+    iterators::call(&mut StoreIterHost(store), id, args).map_err(builtin_failure)
+}
+
+struct StoreIterHost<'a, S>(&'a mut S);
+
+impl<S: ValueStore> ValueHost for StoreIterHost<'_, S> {
+    type Value = Value;
+
+    fn array(&mut self, elements: &[Value]) -> Value {
+        array_from_values(self.0, elements)
+    }
+
+    fn null(&mut self) -> Value {
+        NULL_VALUE
+    }
+
+    fn retain(&mut self, value: &Value) -> Value {
+        *value
+    }
+
+    fn release(&mut self, _value: Value) {}
+
+    fn describe(&self, value: &Value) -> String {
+        display(&*self.0, *value).unwrap_or_else(|failure| failure.message)
+    }
+}
+
+impl<S: ValueStore> IterHost for StoreIterHost<'_, S> {
+    fn source(&self, value: &Value) -> Option<IterSource<Value>> {
+        match self.0.try_get(*value) {
+            Some(HeapObject::Array(elements)) => Some(IterSource::Array(elements.clone())),
+            Some(HeapObject::Iterator(iter)) => Some(IterSource::Iter(iter.clone())),
+            _ => None,
+        }
+    }
+
+    fn integer(&self, value: &Value) -> Option<Int> {
+        int_operand(&*self.0, *value)
+    }
+
+    fn int(&mut self, value: i64) -> Value {
+        make_int(self.0, value)
+    }
+
+    fn iterator(&mut self, iter: Iter<Value>) -> Value {
+        self.0.alloc(HeapObject::Iterator(iter))
+    }
+}
+
+/// The JSON builtins live in `object::json`.
+fn call_json_builtin<S: ValueStore>(
+    store: &mut S,
+    id: BuiltinId,
+    args: &[Value],
+) -> RuntimeResult<Value> {
+    if id == BuiltinId::JsonEncode {
+        let store_ref = &*store;
+        let result = json::encode(args, |argument| {
+            if let Some(value) = int_operand(store_ref, *argument) {
+                return JsonView::Int(value);
+            }
+            match *argument {
+                NULL_VALUE => return JsonView::Null,
+                TRUE_VALUE => return JsonView::Bool(true),
+                FALSE_VALUE => return JsonView::Bool(false),
+                _ => {}
+            }
+            match store_ref.try_get(*argument) {
+                Some(HeapObject::BoxedFloat(value)) => JsonView::Float(*value),
+                Some(HeapObject::Str(text)) => JsonView::Str(text.clone()),
+                Some(HeapObject::Array(elements)) => {
+                    JsonView::Array(*argument as usize, elements.iter().copied().collect())
+                }
+                Some(HeapObject::Hash(entries)) => JsonView::Hash(
+                    *argument as usize,
+                    entries
+                        .iter()
+                        .map(|(key, value)| (json_key(key), *value))
+                        .collect(),
+                ),
+                _ => JsonView::Other(
+                    display(store_ref, *argument).unwrap_or_else(|failure| failure.message),
+                ),
+            }
+        });
+        return match result {
+            Ok(text) => Ok(store.alloc(HeapObject::Str(text))),
+            Err(error) => Err(builtin_failure(error)),
+        };
+    }
+    let mut decoded = Vec::with_capacity(args.len());
+    for argument in args {
+        let arg = match store.try_get(*argument) {
+            Some(HeapObject::Str(text)) => JsonArg::Str(text),
+            _ => JsonArg::Other(display(store, *argument)?),
+        };
+        decoded.push(arg);
+    }
+    match json::decode(&decoded) {
+        Ok(document) => Ok(json_value(store, document)),
+        Err(error) => Err(builtin_failure(error)),
+    }
+}
+
+fn json_key(key: &HashKey) -> String {
+    match key {
+        HashKey::Integer(raw) => raw.to_string(),
+        HashKey::BigInt(raw) => raw.to_string(),
+        HashKey::Boolean(raw) => raw.to_string(),
+        HashKey::Str(raw) => raw.clone(),
+    }
+}
+
+fn json_value<S: ValueStore>(store: &mut S, document: Json) -> Value {
+    match document {
+        Json::Null => NULL_VALUE,
+        Json::Bool(value) => bool_value(value),
+        Json::Int(value) => make_integer(store, value),
+        Json::Float(value) => make_float(store, value),
+        Json::Str(text) => store.alloc(HeapObject::Str(text)),
+        Json::Array(items) => {
+            let elements = items
+                .into_iter()
+                .map(|item| json_value(store, item))
+                .collect();
+            store.alloc(HeapObject::Array(elements))
+        }
+        Json::Hash(entries) => {
+            let mut hash = OrderedMap::new();
+            for (key, value) in entries {
+                let value = json_value(store, value);
+                hash.insert(HashKey::Str(key), value);
+            }
+            store.alloc(HeapObject::Hash(hash))
+        }
+    }
+}
+
+/// The reflection builtins live in `object::reflect`. A class's id is its
+/// value, which every reference to it shares.
+fn call_reflect_builtin<S: ValueStore>(
+    store: &mut S,
+    id: BuiltinId,
+    args: &[Value],
+) -> RuntimeResult<Value> {
+    let mut decoded = Vec::with_capacity(args.len());
+    for argument in args {
+        let kind = if int_operand(store, *argument).is_some() {
+            TypeOf::Integer
+        } else if is_builtin(*argument) {
+            TypeOf::Builtin
+        } else {
+            match *argument {
+                NULL_VALUE => TypeOf::Null,
+                TRUE_VALUE | FALSE_VALUE => TypeOf::Boolean,
+                _ => match get_obj(store, *argument)? {
+                    HeapObject::Class(class) => {
+                        decoded.push(ReflectArg::Class(
+                            *argument as usize,
+                            class.methods.keys().cloned().collect(),
+                        ));
+                        continue;
+                    }
+                    HeapObject::Instance(instance) => {
+                        decoded.push(ReflectArg::Instance(
+                            instance.class as usize,
+                            instance.fields.keys().cloned().collect(),
+                        ));
+                        continue;
+                    }
+                    HeapObject::BoxedInt(_) | HeapObject::BoxedBigInt(_) => TypeOf::Integer,
+                    HeapObject::BoxedFloat(_) => TypeOf::Float,
+                    HeapObject::Str(_) => TypeOf::String,
+                    HeapObject::Array(_) => TypeOf::Array,
+                    HeapObject::Hash(_) => TypeOf::Hash,
+                    HeapObject::Closure(_) => TypeOf::Closure,
+                    HeapObject::BoundMethod(_) => TypeOf::BoundMethod,
+                    HeapObject::Iterator(_) => TypeOf::Iterator,
+                },
+            }
+        };
+        decoded.push(ReflectArg::Other(kind));
+    }
+    let result = reflect::call(id, decoded, |index| {
+        display(store, args[index]).unwrap_or_else(|failure| failure.message)
+    });
+    match result {
+        Ok(ReflectResult::Str(name)) => Ok(store.alloc(HeapObject::Str(name.to_string()))),
+        Ok(ReflectResult::Bool(value)) => Ok(bool_value(value)),
+        Ok(ReflectResult::Names(names)) => {
+            let elements = names
+                .into_iter()
+                .map(|name| store.alloc(HeapObject::Str(name)))
+                .collect();
+            Ok(store.alloc(HeapObject::Array(elements)))
+        }
+        Err(error) => Err(builtin_failure(error)),
+    }
+}
+
+fn num_args<S: ValueStore>(store: &S, args: &[Value]) -> RuntimeResult<Vec<NumArg>> {
+    let mut num_args = Vec::with_capacity(args.len());
+    for argument in args {
+        let num_arg = if let Some(value) = int_operand(store, *argument) {
+            NumArg::Int(value)
+        } else if let Some(value) = float_value(store, *argument) {
+            NumArg::Float(value)
+        } else {
+            NumArg::Other(display(store, *argument)?)
+        };
+        num_args.push(num_arg);
+    }
+    Ok(num_args)
+}
+
+fn num_result<S: ValueStore>(
+    store: &mut S,
+    result: Result<NumResult, BuiltinError>,
+) -> RuntimeResult<Value> {
+    match result {
+        Ok(NumResult::Int(value)) => Ok(make_integer(store, value)),
+        Ok(NumResult::Float(value)) => Ok(make_float(store, value)),
+        Ok(NumResult::Null) => Ok(NULL_VALUE),
+        Err(error) => Err(builtin_failure(error)),
+    }
+}
+
+/// A hash key as a value again, for `keys` and `entries`.
+fn key_value<S: ValueStore>(store: &mut S, key: &HashKey) -> Value {
+    match key {
+        HashKey::Integer(raw) => make_int(store, *raw),
+        HashKey::BigInt(raw) => make_integer(store, Int::Big(raw.clone())),
+        HashKey::Boolean(raw) => bool_value(*raw),
+        HashKey::Str(raw) => store.alloc(HeapObject::Str(raw.clone())),
+    }
+}
+
+/// Misused builtins are terminating errors like any other runtime failure.
+pub fn builtin_failure(error: BuiltinError) -> RuntimeFailure {
+    let (kind, message) = match error {
+        BuiltinError::Arity(message) => (RuntimeErrorKind::ArityError, message),
+        BuiltinError::Type(message) => (RuntimeErrorKind::TypeError, message),
+    };
+    RuntimeFailure {
+        kind,
+        message,
+    }
+}
+
+/// The higher-order builtin `callee` names, if any. Those call back into
+/// generated code, so the native shell runs them outside [`call_builtin`].
+pub fn higher_order_id(callee: Value) -> Option<BuiltinId> {
+    if !is_builtin(callee) {
+        return None;
+    }
+    builtin_from_ordinal(callee >> 3).filter(|id| higher_order::is_higher_order(*id))
+}
+
+pub fn array_elements<S: ValueStore>(store: &S, value: Value) -> Option<Vec<Value>> {
+    match store.try_get(value) {
+        Some(HeapObject::Array(elements)) => Some(elements.iter().copied().collect()),
+        _ => None,
+    }
+}
+
+pub fn sort_key<S: ValueStore>(store: &S, value: Value) -> Option<SortKey> {
+    if let Some(raw) = int_operand(store, value) {
+        return Some(SortKey::Int(raw));
+    }
+    match store.try_get(value) {
+        Some(HeapObject::BoxedFloat(raw)) => Some(SortKey::Float(*raw)),
+        Some(HeapObject::Str(text)) => Some(SortKey::Str(text.clone())),
+        _ => None,
     }
 }
 
 /// How the execution adapter finishes an `Invoke` (design §8.1):
-/// constructors always yield their instance.
+/// constructors always yield their instance, and an `eq` special method
+/// backing `==` / `!=` yields its (possibly negated) truthiness.
 #[derive(Clone, Debug, PartialEq)]
 pub enum ReturnPolicy {
     Direct,
     ConstructorInstance(Value),
+    Truthiness { negate: bool },
+}
+
+impl ReturnPolicy {
+    pub fn finish(&self, returned: Value) -> Value {
+        match self {
+            ReturnPolicy::Direct => returned,
+            ReturnPolicy::ConstructorInstance(instance) => *instance,
+            ReturnPolicy::Truthiness {
+                negate,
+            } => bool_value(truthy(returned) != *negate),
+        }
+    }
 }
 
 /// Dispatch result: builtins resolve immediately, code invocations bounce
@@ -973,38 +1712,11 @@ pub fn dispatch_call<S: ValueStore>(
             }
             HeapObject::BoundMethod(bound) => {
                 let (receiver, method, name) = (bound.receiver, bound.method, bound.name.clone());
-                let (code, num_parameters) = match closure_signature(store, method) {
-                    Some(signature) => signature,
-                    None => {
-                        return fail(
-                            RuntimeErrorKind::InternalError,
-                            "bound method is not a closure",
-                        )
-                    }
-                };
-                let expected = num_parameters.saturating_sub(1);
-                if expected != args.len() as u64 {
-                    let class_name = instance_class_name(store, receiver)?;
-                    return fail(
-                        RuntimeErrorKind::ArityError,
-                        format!(
-                            "wrong number of arguments for {}.{}: want={}, got={}",
-                            class_name,
-                            name,
-                            expected,
-                            args.len()
-                        ),
-                    );
+                if is_builtin(method) {
+                    let args = unbind_builtin_args(receiver, args);
+                    return dispatch_call(store, sink, method, &args);
                 }
-                let mut invoke_args = Vec::with_capacity(args.len() + 1);
-                invoke_args.push(receiver);
-                invoke_args.extend_from_slice(args);
-                return Ok(CallDispatch::Invoke {
-                    code,
-                    closure: method,
-                    args: invoke_args,
-                    return_policy: ReturnPolicy::Direct,
-                });
+                return method_invoke(store, receiver, method, &name, args, ReturnPolicy::Direct);
             }
             HeapObject::Class(data) => {
                 return fail(
@@ -1019,6 +1731,98 @@ pub fn dispatch_call<S: ValueStore>(
     fail(RuntimeErrorKind::NotCallable, format!("cannot call {}", shown))
 }
 
+/// A builtin bound to a string, array or hash: the builtin itself and the
+/// arguments it runs with, receiver first. Anything else comes back as is.
+pub fn unbind_builtin<S: ValueStore>(
+    store: &S,
+    callee: Value,
+    args: Vec<Value>,
+) -> (Value, Vec<Value>) {
+    match store.try_get(callee) {
+        Some(HeapObject::BoundMethod(bound)) if is_builtin(bound.method) => {
+            (bound.method, unbind_builtin_args(bound.receiver, &args))
+        }
+        _ => (callee, args),
+    }
+}
+
+fn unbind_builtin_args(receiver: Value, args: &[Value]) -> Vec<Value> {
+    let mut all = Vec::with_capacity(args.len() + 1);
+    all.push(receiver);
+    all.extend_from_slice(args);
+    all
+}
+
+/// Invocation of `method` with `receiver` as its hidden first argument; the
+/// arity check counts explicit arguments only.
+fn method_invoke<S: ValueStore>(
+    store: &S,
+    receiver: Value,
+    method: Value,
+    name: &str,
+    args: &[Value],
+    return_policy: ReturnPolicy,
+) -> RuntimeResult<CallDispatch> {
+    let (code, num_parameters) = match closure_signature(store, method) {
+        Some(signature) => signature,
+        None => return fail(RuntimeErrorKind::InternalError, "bound method is not a closure"),
+    };
+    let expected = num_parameters.saturating_sub(1);
+    if expected != args.len() as u64 {
+        let class_name = instance_class_name(store, receiver)?;
+        return fail(
+            RuntimeErrorKind::ArityError,
+            format!(
+                "wrong number of arguments for {}.{}: want={}, got={}",
+                class_name,
+                name,
+                expected,
+                args.len()
+            ),
+        );
+    }
+    let mut invoke_args = Vec::with_capacity(args.len() + 1);
+    invoke_args.push(receiver);
+    invoke_args.extend_from_slice(args);
+    Ok(CallDispatch::Invoke {
+        code,
+        closure: method,
+        args: invoke_args,
+        return_policy,
+    })
+}
+
+/// Special-method dispatch for operators on instances (`add`, `sub`, `mul`,
+/// `div`, `eq`, `get`, `toString`). `None` means the receiver is not an
+/// instance or its class does not define `name`, and the caller falls back
+/// to the built-in operator. Fields never shadow special methods.
+pub fn special_method_dispatch<S: ValueStore>(
+    store: &S,
+    receiver: Value,
+    name: &str,
+    args: &[Value],
+    return_policy: ReturnPolicy,
+) -> RuntimeResult<Option<CallDispatch>> {
+    let class = match store.try_get(receiver) {
+        Some(HeapObject::Instance(instance)) => instance.class,
+        _ => return Ok(None),
+    };
+    let method = match get_obj(store, class)? {
+        HeapObject::Class(data) => data.methods.get(name).copied(),
+        _ => return fail(RuntimeErrorKind::InternalError, "instance has an invalid class"),
+    };
+    match method {
+        Some(method) => method_invoke(store, receiver, method, name, args, return_policy).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// `puts` and `str`, which show instances through their `toString`.
+pub fn shows_instances(callee: Value) -> bool {
+    is_builtin(callee)
+        && matches!(builtin_from_ordinal(callee >> 3), Some(BuiltinId::Puts | BuiltinId::Str))
+}
+
 /// `new` dispatch (design §7.2): callee must be a class; the constructor —
 /// when present — runs with the fresh instance as `this` and the instance is
 /// always the result.
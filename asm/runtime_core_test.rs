@@ -3,6 +3,9 @@
 //! comparing canonical values, error kinds, and `CallDispatch` shapes.
 
 use object::builtins::BuiltinId;
+use object::higher_order::{self, HigherOrderError, Host, SortKey, ValueHost};
+use object::random::Random;
+use object::BigInt;
 
 use crate::runtime_backend::{HandleStore, PointerStore, ValueStore};
 use crate::runtime_core::*;
@@ -56,6 +59,9 @@ impl ValueStore for &mut dyn ValueStore {
     fn try_get_mut(&mut self, value: Value) -> Option<&mut HeapObject> {
         (**self).try_get_mut(value)
     }
+    fn random(&mut self) -> &mut Random {
+        (**self).random()
+    }
 }
 
 fn kind_of<T>(result: RuntimeResult<T>) -> RuntimeErrorKind {
@@ -103,7 +109,7 @@ fn integers_shrink_and_box_across_backends() {
 }
 
 #[test]
-fn checked_arithmetic_is_fatal_at_the_boundaries() {
+fn integer_overflow_promotes_to_bigint() {
     on_both_backends(|store| {
         let mut store = store.as_store();
         let max = make_int(&mut store, i64::MAX);
@@ -112,23 +118,40 @@ fn checked_arithmetic_is_fatal_at_the_boundaries() {
         let two = make_int(&mut store, 2);
         let neg_one = make_int(&mut store, -1);
         let zero = make_int(&mut store, 0);
+        let promoted = [
+            add(&mut store, max, one).unwrap(),
+            sub(&mut store, min, one).unwrap(),
+            mul(&mut store, max, two).unwrap(),
+            div(&mut store, min, neg_one).unwrap(),
+            minus(&mut store, min).unwrap(),
+        ];
+        for value in promoted {
+            assert!(is_heap(value));
+            assert_eq!(int_value(&store, value), None);
+        }
+        // Results that fit again narrow back to the i64 representations.
+        let back = sub(&mut store, promoted[0], one).unwrap();
+        assert_eq!(int_value(&store, back), Some(i64::MAX));
+        assert!(eq_values(&store, promoted[0], promoted[3]).unwrap());
+        assert_eq!(gt(&store, promoted[2], max).unwrap(), TRUE_VALUE);
+        assert_eq!(gt(&store, promoted[1], min).unwrap(), FALSE_VALUE);
+        let key = hash_key(&store, promoted[4]).unwrap();
+        assert_eq!(key, hash_key(&store, promoted[0]).unwrap());
+
+        let literal = bigint_from_decimal(&mut store, b"9223372036854775808").unwrap();
+        assert!(eq_values(&store, literal, promoted[0]).unwrap());
+        let small_literal = bigint_from_decimal(&mut store, b"42").unwrap();
+        assert!(is_smi(small_literal));
+
         let kinds = vec![
-            kind_of(add(&mut store, max, one)),
-            kind_of(sub(&mut store, min, one)),
-            kind_of(mul(&mut store, max, two)),
-            kind_of(div(&mut store, min, neg_one)),
-            kind_of(minus(&mut store, min)),
             kind_of(div(&mut store, one, zero)),
+            kind_of(div(&mut store, promoted[0], zero)),
             kind_of(add(&mut store, one, TRUE_VALUE)),
         ];
         assert_eq!(
             kinds,
             vec![
-                RuntimeErrorKind::IntegerOverflow,
-                RuntimeErrorKind::IntegerOverflow,
-                RuntimeErrorKind::IntegerOverflow,
-                RuntimeErrorKind::IntegerOverflow,
-                RuntimeErrorKind::IntegerOverflow,
+                RuntimeErrorKind::DivisionByZero,
                 RuntimeErrorKind::DivisionByZero,
                 RuntimeErrorKind::TypeError,
             ]
@@ -137,7 +160,50 @@ fn checked_arithmetic_is_fatal_at_the_boundaries() {
         let neg_seven = make_int(&mut store, -7);
         let quotient = div(&mut store, neg_seven, two).unwrap();
         assert_eq!(int_value(&store, quotient), Some(-3));
-        kinds
+        promoted
+            .iter()
+            .map(|value| canonical_value(&store, *value).unwrap())
+            .collect::<Vec<_>>()
+    });
+}
+
+#[test]
+fn floats_are_boxed_and_widen_mixed_arithmetic() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let half = make_float(&mut store, 0.5);
+        assert!(is_heap(half));
+        assert_eq!(float_value(&store, half), Some(0.5));
+        assert_eq!(int_value(&store, half), None);
+        let two = smi_from_i64(2);
+        let zero = smi_from_i64(0);
+        let results = [
+            add(&mut store, two, half).unwrap(),
+            sub(&mut store, half, two).unwrap(),
+            mul(&mut store, half, half).unwrap(),
+            div(&mut store, two, half).unwrap(),
+            // IEEE semantics: no DivisionByZero once a float is involved.
+            div(&mut store, half, zero).unwrap(),
+            minus(&mut store, half).unwrap(),
+        ];
+        let shown = results
+            .iter()
+            .map(|value| display(&store, *value).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(shown, ["2.5", "-1.5", "0.25", "4.0", "inf", "-0.5"]);
+        // Numbers compare by value across representations; NaN equals nothing.
+        let one_float = make_float(&mut store, 1.0);
+        assert!(eq_values(&store, one_float, smi_from_i64(1)).unwrap());
+        let nan = make_float(&mut store, f64::NAN);
+        assert!(!eq_values(&store, nan, nan).unwrap());
+        assert!(truthy(make_float(&mut store, 0.0)));
+        assert_eq!(hash_key(&store, half), None);
+        assert_eq!(
+            kind_of(hash_from_pairs(&mut store, &[half, two])),
+            RuntimeErrorKind::InvalidHashKey
+        );
+        assert_eq!(canonical_value(&store, half).unwrap(), r#"{"type":"float","value":"0.5"}"#);
+        shown
     });
 }
 
@@ -154,6 +220,23 @@ fn add_concatenates_strings() {
     });
 }
 
+#[test]
+fn concat_renders_parts_like_puts() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let label = string_from_utf8(&mut store, b"n = ").unwrap();
+        let three = make_int(&mut store, 3);
+        let half = make_float(&mut store, 0.5);
+        let list = array_from_values(&mut store, &[three, TRUE_VALUE]);
+        let joined = concat(&mut store, &[label, three, half, list, NULL_VALUE]).unwrap();
+        let text = display(&store, joined).unwrap();
+        assert_eq!(text, "n = 30.5[3, true]null");
+        let empty = concat(&mut store, &[]).unwrap();
+        assert_eq!(display(&store, empty).unwrap(), "");
+        text
+    });
+}
+
 #[test]
 fn equality_matrix() {
     on_both_backends(|store| {
@@ -197,12 +280,15 @@ fn equality_matrix() {
 }
 
 #[test]
-fn gt_accepts_integers_only() {
+fn gt_accepts_numbers_only() {
     on_both_backends(|store| {
         let mut store = store.as_store();
         assert_eq!(gt(&store, smi_from_i64(3), smi_from_i64(2)).unwrap(), TRUE_VALUE);
         let boxed = make_int(&mut store, i64::MAX);
         assert_eq!(gt(&store, boxed, smi_from_i64(5)).unwrap(), TRUE_VALUE);
+        let half = make_float(&mut store, 0.5);
+        assert_eq!(gt(&store, smi_from_i64(1), half).unwrap(), TRUE_VALUE);
+        assert_eq!(gt(&store, half, boxed).unwrap(), FALSE_VALUE);
         assert_eq!(kind_of(gt(&store, TRUE_VALUE, FALSE_VALUE)), RuntimeErrorKind::TypeError);
         true
     });
@@ -306,7 +392,350 @@ fn builtins_semantics_and_errors() {
 }
 
 #[test]
-fn display_uses_stable_hash_ordering() {
+fn string_builtins_share_the_object_library() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let mut sink = BufferSink::new();
+        let csv = string_from_utf8(&mut store, b"a,b").unwrap();
+        let comma = string_from_utf8(&mut store, b",").unwrap();
+        let dash = string_from_utf8(&mut store, b"-").unwrap();
+
+        let parts = call_builtin(&mut store, &mut sink, BuiltinId::Split, &[csv, comma]).unwrap();
+        assert_eq!(display(&store, parts).unwrap(), "[a, b]");
+        let joined = call_builtin(&mut store, &mut sink, BuiltinId::Join, &[parts, dash]).unwrap();
+        assert_eq!(display(&store, joined).unwrap(), "a-b");
+        let found =
+            call_builtin(&mut store, &mut sink, BuiltinId::Contains, &[csv, comma]).unwrap();
+        assert_eq!(found, TRUE_VALUE);
+        let at = call_builtin(&mut store, &mut sink, BuiltinId::IndexOf, &[csv, dash]).unwrap();
+        assert_eq!(int_value(&store, at), Some(-1));
+
+        let digits = string_from_utf8(&mut store, b"99999999999999999999").unwrap();
+        let big = call_builtin(&mut store, &mut sink, BuiltinId::Int, &[digits]).unwrap();
+        assert_eq!(display(&store, big).unwrap(), "99999999999999999999");
+        assert_eq!(
+            call_builtin(&mut store, &mut sink, BuiltinId::Int, &[csv]).unwrap(),
+            NULL_VALUE
+        );
+        let shown =
+            call_builtin(&mut store, &mut sink, BuiltinId::Str, &[smi_from_i64(7)]).unwrap();
+        assert_eq!(display(&store, shown).unwrap(), "7");
+
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Upper, &[csv, csv])),
+            RuntimeErrorKind::ArityError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Trim, &[smi_from_i64(1)])),
+            RuntimeErrorKind::TypeError
+        );
+        // Ordinals after `push` are appended, never renumbered.
+        assert_eq!(builtin_ordinal(BuiltinId::Split), 6);
+        assert_eq!(builtin_from_ordinal(19), Some(BuiltinId::Int));
+        assert_eq!(builtin_from_ordinal(20), Some(BuiltinId::Map));
+        assert_eq!(builtin_from_ordinal(26), Some(BuiltinId::All));
+        true
+    });
+}
+
+#[test]
+fn hash_builtins_share_the_object_library() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let mut sink = BufferSink::new();
+        let b = string_from_utf8(&mut store, b"b").unwrap();
+        let a = string_from_utf8(&mut store, b"a").unwrap();
+        let left = hash_from_pairs(&mut store, &[b, smi_from_i64(1), a, smi_from_i64(2)]).unwrap();
+        let right = hash_from_pairs(&mut store, &[TRUE_VALUE, smi_from_i64(3), b, smi_from_i64(4)])
+            .unwrap();
+
+        let keys = call_builtin(&mut store, &mut sink, BuiltinId::Keys, &[left]).unwrap();
+        assert_eq!(display(&store, keys).unwrap(), "[b, a]");
+        let merged = call_builtin(&mut store, &mut sink, BuiltinId::Merge, &[left, right]).unwrap();
+        assert_eq!(display(&store, merged).unwrap(), "{b: 4, a: 2, true: 3}");
+        let entries = call_builtin(&mut store, &mut sink, BuiltinId::Entries, &[merged]).unwrap();
+        assert_eq!(display(&store, entries).unwrap(), "[[b, 4], [a, 2], [true, 3]]");
+        let deleted = call_builtin(&mut store, &mut sink, BuiltinId::Delete, &[merged, b]).unwrap();
+        assert_eq!(display(&store, deleted).unwrap(), "{a: 2, true: 3}");
+        assert_eq!(display(&store, merged).unwrap(), "{b: 4, a: 2, true: 3}");
+        assert_eq!(
+            call_builtin(&mut store, &mut sink, BuiltinId::Has, &[deleted, b]).unwrap(),
+            FALSE_VALUE
+        );
+
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Values, &[a])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Has, &[left, keys])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Merge, &[left])),
+            RuntimeErrorKind::ArityError
+        );
+        assert_eq!(builtin_from_ordinal(27), Some(BuiltinId::Keys));
+        assert_eq!(builtin_from_ordinal(32), Some(BuiltinId::Merge));
+        true
+    });
+}
+
+#[test]
+fn math_and_random_builtins_share_the_object_library() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let mut sink = BufferSink::new();
+        let half = make_float(&mut store, 2.5);
+
+        let absolute =
+            call_builtin(&mut store, &mut sink, BuiltinId::Abs, &[smi_from_i64(-3)]).unwrap();
+        assert_eq!(int_value(&store, absolute), Some(3));
+        let smallest = call_builtin(
+            &mut store,
+            &mut sink,
+            BuiltinId::Min,
+            &[smi_from_i64(4), half, smi_from_i64(3)],
+        )
+        .unwrap();
+        assert_eq!(float_value(&store, smallest), Some(2.5));
+        let power = call_builtin(
+            &mut store,
+            &mut sink,
+            BuiltinId::Pow,
+            &[smi_from_i64(2), smi_from_i64(70)],
+        )
+        .unwrap();
+        assert_eq!(display(&store, power).unwrap(), "1180591620717411303424");
+        let rounded = call_builtin(&mut store, &mut sink, BuiltinId::Round, &[half]).unwrap();
+        assert_eq!(int_value(&store, rounded), Some(3));
+        let root =
+            call_builtin(&mut store, &mut sink, BuiltinId::Sqrt, &[smi_from_i64(9)]).unwrap();
+        assert_eq!(float_value(&store, root), Some(3.0));
+
+        let mut draw = |id: BuiltinId, arg: i64| {
+            let value = call_builtin(&mut store, &mut sink, id, &[smi_from_i64(arg)]).unwrap();
+            int_value(&store, value)
+        };
+        assert_eq!(draw(BuiltinId::Seed, 7), None);
+        let first = [draw(BuiltinId::Random, 100), draw(BuiltinId::Random, 100)];
+        draw(BuiltinId::Seed, 7);
+        let again = [draw(BuiltinId::Random, 100), draw(BuiltinId::Random, 100)];
+        assert_eq!(first, again);
+
+        let text = string_from_utf8(&mut store, b"x").unwrap();
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Sqrt, &[text])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Random, &[smi_from_i64(0)])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Max, &[])),
+            RuntimeErrorKind::ArityError
+        );
+        assert_eq!(builtin_from_ordinal(33), Some(BuiltinId::Abs));
+        assert_eq!(builtin_from_ordinal(42), Some(BuiltinId::Seed));
+        first
+    });
+}
+
+#[test]
+fn json_builtins_share_the_object_library() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let mut sink = BufferSink::new();
+        let key = string_from_utf8(&mut store, b"a").unwrap();
+        let half = make_float(&mut store, 2.5);
+        let items = array_from_values(&mut store, &[smi_from_i64(1), half, NULL_VALUE]);
+        let hash = hash_from_pairs(&mut store, &[key, items, TRUE_VALUE, FALSE_VALUE]).unwrap();
+
+        let encoded = call_builtin(&mut store, &mut sink, BuiltinId::JsonEncode, &[hash]).unwrap();
+        let text = display(&store, encoded).unwrap();
+        assert_eq!(text, r#"{"a":[1,2.5,null],"true":false}"#);
+        let decoded =
+            call_builtin(&mut store, &mut sink, BuiltinId::JsonDecode, &[encoded]).unwrap();
+        let shown = display(&store, decoded).unwrap();
+        assert_eq!(shown, "{a: [1, 2.5, null], true: false}");
+
+        let closure = closure_new(&mut store, 100, 0, &[]).unwrap();
+        let nested = array_from_values(&mut store, &[closure]);
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::JsonEncode, &[nested])),
+            RuntimeErrorKind::TypeError
+        );
+        let broken = string_from_utf8(&mut store, b"[1,").unwrap();
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::JsonDecode, &[broken])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::JsonDecode, &[])),
+            RuntimeErrorKind::ArityError
+        );
+        assert_eq!(builtin_from_ordinal(44), Some(BuiltinId::JsonDecode));
+        (text, shown)
+    });
+}
+
+#[test]
+fn reflection_builtins_share_the_object_library() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let mut sink = BufferSink::new();
+        let point = class_new(&mut store, "Point");
+        let other = class_new(&mut store, "Other");
+        for name in ["sum", "norm"] {
+            let method = closure_new(&mut store, 500, 1, &[]).unwrap();
+            class_add_method(&mut store, point, name, method, false).unwrap();
+        }
+        let instance = match dispatch_construct(&mut store, point, &[]).unwrap() {
+            CallDispatch::Return(instance) => instance,
+            other => panic!("expected Return, got {:?}", other),
+        };
+        set_property(&mut store, instance, "y", smi_from_i64(2)).unwrap();
+        set_property(&mut store, instance, "x", smi_from_i64(1)).unwrap();
+        let bound = get_property(&mut store, instance, "sum").unwrap();
+        let half = make_float(&mut store, 0.5);
+
+        let mut shown = Vec::new();
+        for (id, args) in [
+            (BuiltinId::TypeOf, vec![smi_from_i64(1)]),
+            (BuiltinId::TypeOf, vec![half]),
+            (BuiltinId::TypeOf, vec![NULL_VALUE]),
+            (BuiltinId::TypeOf, vec![builtin_value(BuiltinId::Len)]),
+            (BuiltinId::TypeOf, vec![point]),
+            (BuiltinId::TypeOf, vec![instance]),
+            (BuiltinId::TypeOf, vec![bound]),
+            (BuiltinId::InstanceOf, vec![instance, point]),
+            (BuiltinId::InstanceOf, vec![instance, other]),
+            (BuiltinId::Fields, vec![instance]),
+            (BuiltinId::Methods, vec![point]),
+        ] {
+            let result = call_builtin(&mut store, &mut sink, id, &args).unwrap();
+            shown.push(display(&store, result).unwrap());
+        }
+        assert_eq!(
+            shown,
+            [
+                "integer",
+                "float",
+                "null",
+                "builtin",
+                "class",
+                "instance",
+                "boundMethod",
+                "true",
+                "false",
+                "[x, y]",
+                "[norm, sum]"
+            ]
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Fields, &[point])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::InstanceOf, &[instance])),
+            RuntimeErrorKind::ArityError
+        );
+        assert_eq!(builtin_from_ordinal(48), Some(BuiltinId::Methods));
+        shown
+    });
+}
+
+#[test]
+fn primitive_methods_bind_builtins() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let mut sink = BufferSink::new();
+        let text = string_from_utf8(&mut store, b"a,b").unwrap();
+        let comma = string_from_utf8(&mut store, b",").unwrap();
+        let split = get_property(&mut store, text, "split").unwrap();
+        let list = array_from_values(&mut store, &[smi_from_i64(1)]);
+        let push = get_property(&mut store, list, "push").unwrap();
+        let map = get_property(&mut store, list, "map").unwrap();
+
+        let mut shown = vec![display(&store, push).unwrap()];
+        for (callee, args) in [(split, vec![comma]), (push, vec![smi_from_i64(2)])] {
+            match dispatch_call(&mut store, &mut sink, callee, &args).unwrap() {
+                CallDispatch::Return(result) => shown.push(display(&store, result).unwrap()),
+                other => panic!("expected Return, got {:?}", other),
+            }
+        }
+        assert_eq!(shown, ["[bound method array.push]", "[a, b]", "[1, 2]"]);
+        let (callee, args) = unbind_builtin(&store, map, vec![NULL_VALUE]);
+        assert_eq!(higher_order_id(callee), Some(BuiltinId::Map));
+        assert_eq!(args, vec![list, NULL_VALUE]);
+        assert_eq!(kind_of(get_property(&mut store, text, "keys")), RuntimeErrorKind::TypeError);
+        shown
+    });
+}
+
+#[test]
+fn iterators_are_lazy_and_rereadable() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let mut sink = BufferSink::new();
+        let mut call =
+            |store: &mut _, id, args: &[Value]| call_builtin(store, &mut sink, id, args).unwrap();
+        let max = make_int(&mut store, i64::MAX);
+        let huge = call(&mut store, BuiltinId::Range, &[max]);
+        let three = call(&mut store, BuiltinId::Take, &[huge, smi_from_i64(3)]);
+        let letters = array_from_values(&mut store, &[TRUE_VALUE, NULL_VALUE]);
+        let zipped = call(&mut store, BuiltinId::Zip, &[three, letters]);
+        let tail = call(&mut store, BuiltinId::Rest, &[three]);
+
+        let mut shown = vec![display(&store, three).unwrap()];
+        for (id, arg) in [
+            (BuiltinId::ToArray, three),
+            (BuiltinId::Len, three),
+            (BuiltinId::ToArray, zipped),
+            (BuiltinId::First, tail),
+            (BuiltinId::TypeOf, tail),
+        ] {
+            let result = call(&mut store, id, &[arg]);
+            shown.push(display(&store, result).unwrap());
+        }
+        assert_eq!(
+            shown,
+            [
+                "[iterator]",
+                "[0, 1, 2]",
+                "3",
+                "[[0, true], [1, null]]",
+                "1",
+                "iterator"
+            ]
+        );
+        let zero = [smi_from_i64(0), smi_from_i64(1), smi_from_i64(0)];
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Range, &zero)),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Last, &[three])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Next, &[three])),
+            RuntimeErrorKind::TypeError
+        );
+        assert_eq!(
+            kind_of(call_builtin(&mut store, &mut sink, BuiltinId::Next, &[])),
+            RuntimeErrorKind::ArityError
+        );
+        assert_eq!(builtin_from_ordinal(53), Some(BuiltinId::ToArray));
+        assert_eq!(builtin_from_ordinal(54), Some(BuiltinId::Next));
+        assert_eq!(builtin_from_ordinal(55), None);
+        shown
+    });
+}
+
+#[test]
+fn display_keeps_hash_insertion_order() {
     on_both_backends(|store| {
         let mut store = store.as_store();
         let key_str = string_from_utf8(&mut store, b"s").unwrap();
@@ -322,11 +751,10 @@ fn display_uses_stable_hash_ordering() {
             ],
         )
         .unwrap();
-        // Rank: integer < boolean < string.
         let text = display(&store, hash).unwrap();
-        assert_eq!(text, "{10: 1, true: 2, s: 3}");
+        assert_eq!(text, "{s: 3, true: 2, 10: 1}");
         let nested = array_from_values(&mut store, &[hash, NULL_VALUE]);
-        assert_eq!(display(&store, nested).unwrap(), "[{10: 1, true: 2, s: 3}, null]");
+        assert_eq!(display(&store, nested).unwrap(), "[{s: 3, true: 2, 10: 1}, null]");
         text
     });
 }
@@ -557,6 +985,62 @@ fn construct_dispatch_matrix() {
     });
 }
 
+#[test]
+fn special_methods_dispatch_on_instances_only() {
+    on_both_backends(|store| {
+        let mut store = store.as_store();
+        let money = class_new(&mut store, "Money");
+        let add = closure_new(&mut store, 7001, 2, &[]).unwrap(); // this + other
+        let eq = closure_new(&mut store, 7002, 2, &[]).unwrap();
+        class_add_method(&mut store, money, "add", add, false).unwrap();
+        class_add_method(&mut store, money, "eq", eq, false).unwrap();
+        let instance = match dispatch_construct(&mut store, money, &[]).unwrap() {
+            CallDispatch::Return(instance) => instance,
+            other => panic!("expected Return, got {:?}", other),
+        };
+        let one = smi_from_i64(1);
+
+        match special_method_dispatch(&store, instance, "add", &[one], ReturnPolicy::Direct)
+            .unwrap()
+        {
+            Some(CallDispatch::Invoke {
+                code,
+                closure,
+                args,
+                return_policy,
+            }) => {
+                assert_eq!(code, 7001);
+                assert_eq!(closure, add);
+                assert_eq!(args, vec![instance, one]);
+                assert_eq!(return_policy, ReturnPolicy::Direct);
+            }
+            other => panic!("expected Invoke, got {:?}", other),
+        }
+
+        // Fields never shadow special methods; missing methods and
+        // non-instances fall back to the built-in operator.
+        set_property(&mut store, instance, "sub", one).unwrap();
+        assert!(special_method_dispatch(&store, instance, "sub", &[one], ReturnPolicy::Direct)
+            .unwrap()
+            .is_none());
+        assert!(special_method_dispatch(&store, one, "add", &[one], ReturnPolicy::Direct)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            kind_of(special_method_dispatch(&store, instance, "add", &[], ReturnPolicy::Direct)),
+            RuntimeErrorKind::ArityError
+        );
+
+        let negated = ReturnPolicy::Truthiness {
+            negate: true,
+        };
+        assert_eq!(negated.finish(NULL_VALUE), TRUE_VALUE);
+        assert_eq!(negated.finish(one), FALSE_VALUE);
+        assert_eq!(ReturnPolicy::ConstructorInstance(instance).finish(one), instance);
+        true
+    });
+}
+
 #[test]
 fn closure_parameter_limit_is_enforced() {
     on_both_backends(|store| {
@@ -588,3 +1072,117 @@ fn runtime_error_kind_numbering_is_frozen() {
     }
     assert_eq!(RuntimeErrorKind::from_u64(10), None);
 }
+
+/// `runtime::NativeHost` without the FFI. There is no generated code to run
+/// in unit tests, so callbacks here can only be builtins.
+struct StoreHost<'a> {
+    store: &'a mut dyn ValueStore,
+    sink: BufferSink,
+}
+
+impl ValueHost for StoreHost<'_> {
+    type Value = Value;
+
+    fn array(&mut self, elements: &[Value]) -> Value {
+        array_from_values(&mut self.store, elements)
+    }
+
+    fn null(&mut self) -> Value {
+        NULL_VALUE
+    }
+
+    fn retain(&mut self, value: &Value) -> Value {
+        *value
+    }
+
+    fn release(&mut self, _value: Value) {}
+
+    fn describe(&self, value: &Value) -> String {
+        display(&self.store, *value).unwrap()
+    }
+}
+
+impl Host for StoreHost<'_> {
+    type Error = RuntimeFailure;
+
+    fn call(&mut self, function: &Value, args: &[Value]) -> RuntimeResult<Value> {
+        match dispatch_call(&mut self.store, &mut self.sink, *function, args)? {
+            CallDispatch::Return(value) => Ok(value),
+            CallDispatch::Invoke {
+                ..
+            } => panic!("unit tests cannot invoke generated code"),
+        }
+    }
+
+    fn elements(&self, value: &Value) -> Option<Vec<Value>> {
+        array_elements(&self.store, *value)
+    }
+
+    fn boolean(&mut self, value: bool) -> Value {
+        bool_value(value)
+    }
+
+    fn truthy(&self, value: &Value) -> bool {
+        truthy(*value)
+    }
+
+    fn sort_key(&self, value: &Value) -> Option<SortKey> {
+        sort_key(&self.store, *value)
+    }
+}
+
+#[test]
+fn higher_order_builtins_run_over_store_values() {
+    on_both_backends(|store| {
+        let mut host = StoreHost {
+            store: store.as_store(),
+            sink: BufferSink::new(),
+        };
+        let one = string_from_utf8(&mut host.store, b"1").unwrap();
+        let word = string_from_utf8(&mut host.store, b"x").unwrap();
+        let texts = array_from_values(&mut host.store, &[word, one]);
+        let upper = builtin_value(BuiltinId::Upper);
+        let int = builtin_value(BuiltinId::Int);
+
+        let run = |host: &mut StoreHost, id: BuiltinId, args: &[Value]| match higher_order::call(
+            host, id, args,
+        ) {
+            Ok(value) => Ok(display(&host.store, value).unwrap()),
+            Err(HigherOrderError::Builtin(error)) => Err(builtin_failure(error).kind),
+            Err(HigherOrderError::Callback(failure)) => Err(failure.kind),
+        };
+        assert_eq!(run(&mut host, BuiltinId::Map, &[texts, upper]), Ok("[X, 1]".to_string()));
+        assert_eq!(run(&mut host, BuiltinId::Filter, &[texts, int]), Ok("[1]".to_string()));
+        assert_eq!(run(&mut host, BuiltinId::Find, &[texts, int]), Ok("1".to_string()));
+        assert_eq!(run(&mut host, BuiltinId::All, &[texts, int]), Ok("false".to_string()));
+        assert_eq!(run(&mut host, BuiltinId::Sort, &[texts]), Ok("[1, x]".to_string()));
+
+        let big = host
+            .store
+            .alloc(HeapObject::BoxedBigInt(BigInt::from(u64::MAX)));
+        let half = make_float(&mut host.store, 0.5);
+        let numbers = array_from_values(&mut host.store, &[big, smi_from_i64(-1), half]);
+        assert_eq!(
+            run(&mut host, BuiltinId::Sort, &[numbers]),
+            Ok("[-1, 0.5, 18446744073709551615]".to_string())
+        );
+
+        let mixed = array_from_values(&mut host.store, &[one, smi_from_i64(1)]);
+        assert_eq!(run(&mut host, BuiltinId::Sort, &[mixed]), Err(RuntimeErrorKind::TypeError));
+        assert_eq!(run(&mut host, BuiltinId::Map, &[texts]), Err(RuntimeErrorKind::ArityError));
+        // A failing callback surfaces the callback's own error.
+        assert_eq!(
+            run(&mut host, BuiltinId::Map, &[numbers, upper]),
+            Err(RuntimeErrorKind::TypeError)
+        );
+
+        // Plain dispatch cannot run them; only the native shell can.
+        assert_eq!(higher_order_id(builtin_value(BuiltinId::Sort)), Some(BuiltinId::Sort));
+        assert_eq!(higher_order_id(upper), None);
+        assert_eq!(
+            kind_of(call_builtin(&mut host.store, &mut host.sink, BuiltinId::Map, &[texts, upper])),
+            RuntimeErrorKind::InternalError
+        );
+        true
+    });
+}
@@ -1,6 +1,6 @@
 // Handwritten fatal-path probe, Mach-O dialect (design §10.3): the macOS
-// twin of abi_fatal_probe.s. rt_add on i64::MAX + 1 must terminate through
-// rt_fatal semantics — "monkey: IntegerOverflow: ..." on stderr and exit
+// twin of abi_fatal_probe.s. rt_div on 1 / 0 must terminate through
+// rt_fatal semantics — "monkey: DivisionByZero: ..." on stderr and exit
 // code 1. The trailing exit-0 epilogue must be unreachable.
 
     .text
@@ -15,13 +15,9 @@ _main:
     movz x1, #0
     bl _rt_globals_init
 
-    movz x0, #0xffff                 // i64::MAX = 0x7fffffffffffffff
-    movk x0, #0xffff, lsl #16
-    movk x0, #0xffff, lsl #32
-    movk x0, #0x7fff, lsl #48
-    bl _rt_box_int
-    movz x1, #2                      // SMI 1
-    bl _rt_add                       // fatal: IntegerOverflow
+    movz x0, #2                      // SMI 1
+    movz x1, #0                      // SMI 0
+    bl _rt_div                       // fatal: DivisionByZero
 
     mov w0, #0                       // unreachable
     mov sp, x29
@@ -1,11 +1,13 @@
-use object::builtins::BuiltIns;
+use object::registry::Builtins;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use object::Object;
+use object::integer::Int;
+use object::{BigInt, Object};
 use parser::ast::{
     BlockStatement, Expression, Literal, MethodDefinition, MethodKind, Node, Statement,
+    YieldExpression,
 };
 use parser::lexer::token::Span;
 use parser::lexer::token::TokenKind;
@@ -29,6 +31,7 @@ pub struct Compiler {
     scopes: Vec<CompilationScope>,
     scope_index: usize,
     callable_kinds: Vec<CallableKind>,
+    builtins: Vec<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -37,6 +40,9 @@ pub struct Bytecode {
     pub constants: Vec<Rc<Object>>,
     pub debug_info: DebugInfo,
     pub function_debug_info: HashMap<usize, DebugInfo>,
+    /// The builtin names `OpGetBuiltin` operands index into. VMs resolve them
+    /// by name against their own registry.
+    pub builtins: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
@@ -237,6 +243,7 @@ type CompileError = String;
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum CallableKind {
     Function,
+    Generator,
     Method,
     Constructor,
 }
@@ -249,6 +256,14 @@ impl Default for Compiler {
 
 impl Compiler {
     pub fn new() -> Compiler {
+        return Compiler::with_builtins(&Builtins::standard());
+    }
+
+    /// A compiler whose programs see `builtins` instead of the standard set.
+    /// `OpGetBuiltin` has a one-byte operand, so the registry can hold at
+    /// most 256 builtins.
+    pub fn with_builtins(builtins: &Builtins) -> Compiler {
+        assert!(builtins.len() <= 256, "at most 256 builtins can be compiled against");
         let main_scope = CompilationScope {
             instructions: Instructions {
                 data: vec![],
@@ -265,8 +280,9 @@ impl Compiler {
         };
 
         let mut symbol_table = SymbolTable::new();
-        for (key, value) in BuiltIns.iter().enumerate() {
-            symbol_table.define_builtin(key, value.name.to_string());
+        let builtins = builtins.names();
+        for (index, name) in builtins.iter().enumerate() {
+            symbol_table.define_builtin(index, name.clone());
         }
 
         return Compiler {
@@ -276,6 +292,7 @@ impl Compiler {
             scopes: vec![main_scope],
             scope_index: 0,
             callable_kinds: vec![],
+            builtins,
         };
     }
 
@@ -290,7 +307,7 @@ impl Compiler {
         match node {
             Node::Program(p) => {
                 let mut predefined_names = self.symbol_table.visible_names();
-                predefined_names.extend(BuiltIns.iter().map(|builtin| builtin.name.to_string()));
+                predefined_names.extend(self.builtins.iter().cloned());
                 let predefined_names = predefined_names
                     .iter()
                     .map(String::as_str)
@@ -332,6 +349,13 @@ impl Compiler {
                 if self.callable_kinds.last() == Some(&CallableKind::Constructor) {
                     return Err("constructor cannot return a value".to_string());
                 }
+                if let Expression::Yield(yield_expression) = &r.argument {
+                    if yield_expression.delegate {
+                        // The generator hands over to the delegate for good,
+                        // so nothing after the yield runs.
+                        return self.compile_yield(yield_expression, 2);
+                    }
+                }
                 self.compile_expr(&r.argument)?;
                 self.emit_with_span(Opcode::OpReturnValue, &[], &r.span);
                 return Ok(());
@@ -392,6 +416,19 @@ impl Compiler {
                     let operands = vec![self.add_constant(int)];
                     self.emit_with_span(OpConst, &operands, &i.span);
                 }
+                Literal::BigInt(i) => {
+                    let value = match i.raw.parse::<BigInt>() {
+                        Ok(value) => value,
+                        Err(_) => return Err(format!("invalid integer literal {}", i.raw)),
+                    };
+                    let operands = vec![self.add_constant(Object::from(Int::narrow(value)))];
+                    self.emit_with_span(OpConst, &operands, &i.span);
+                }
+                Literal::Float(x) => {
+                    let float = Object::Float(x.raw.0);
+                    let operands = vec![self.add_constant(float)];
+                    self.emit_with_span(OpConst, &operands, &x.span);
+                }
                 Literal::Boolean(i) => {
                     if i.raw {
                         self.emit_with_span(OpTrue, &[], &i.span);
@@ -491,15 +528,28 @@ impl Compiler {
             Expression::FUNCTION(f) => {
                 let function_span = f.span.clone();
                 self.enter_scope();
-                self.callable_kinds.push(CallableKind::Function);
+                self.callable_kinds.push(if f.generator {
+                    CallableKind::Generator
+                } else {
+                    CallableKind::Function
+                });
                 if !f.name.is_empty() {
                     self.symbol_table.define_function_name(f.name.clone());
                 }
                 for param in f.params.iter() {
                     self.symbol_table.define(param.name.clone());
                 }
+                if f.generator {
+                    // Resuming pushes the value sent in, which the first run
+                    // drops.
+                    self.emit_with_span(OpGenerator, &[], &function_span);
+                    self.emit_with_span(OpPop, &[], &function_span);
+                }
+                let body_start = self.current_instruction().data.len();
                 self.compile_block_statement(&f.body)?;
-                if self.last_instruction_is(OpPop) {
+                if self.current_instruction().data.len() > body_start
+                    && self.last_instruction_is(OpPop)
+                {
                     self.replace_last_pop_with_return();
                 }
                 if !(self.last_instruction_is(OpReturnValue)) {
@@ -558,11 +608,46 @@ impl Compiler {
                 }
                 self.emit_with_span(OpNew, &[new_expression.arguments.len()], &new_expression.span);
             }
+            Expression::Template(template) => {
+                // Empty text between substitutions contributes nothing, so
+                // only the non-empty quasis become operands.
+                let mut parts = 0;
+                for (index, quasi) in template.quasis.iter().enumerate() {
+                    if !quasi.is_empty() {
+                        let text = self.add_constant(Object::String(quasi.clone()));
+                        self.emit_with_span(OpConst, &[text], &template.span);
+                        parts += 1;
+                    }
+                    if let Some(expression) = template.expressions.get(index) {
+                        self.compile_expr(expression)?;
+                        parts += 1;
+                    }
+                }
+                self.emit_with_span(OpConcat, &[parts], &template.span);
+            }
+            Expression::Yield(yield_expression) => {
+                let kind = if yield_expression.delegate { 1 } else { 0 };
+                self.compile_yield(yield_expression, kind)?;
+            }
         }
 
         return Ok(());
     }
 
+    fn compile_yield(&mut self, e: &YieldExpression, kind: usize) -> Result<(), CompileError> {
+        if self.callable_kinds.last() != Some(&CallableKind::Generator) {
+            return Err("yield outside a generator function".to_string());
+        }
+        match &e.argument {
+            Some(argument) => self.compile_expr(argument)?,
+            None => {
+                self.emit_with_span(OpNull, &[], &e.span);
+            }
+        }
+        self.emit_with_span(OpYield, &[kind], &e.span);
+        return Ok(());
+    }
+
     fn load_symbol(&mut self, symbol: &Rc<Symbol>, span: &Span) {
         match symbol.scope {
             SymbolScope::Global => {
@@ -589,6 +674,7 @@ impl Compiler {
             constants: self.constants.clone(),
             debug_info: self.current_debug_info().clone(),
             function_debug_info: self.function_debug_info.clone(),
+            builtins: self.builtins.clone(),
         };
     }
 
@@ -331,4 +331,36 @@ mod tests {
         ];
         run_compiler_test(tests);
     }
+
+    #[test]
+    fn test_generators() {
+        let tests = vec![CompilerTestCase {
+            input: "fn*(g) { let x = yield 1; return yield* g; }",
+            expected_constants: vec![
+                Object::Integer(1),
+                Object::CompiledFunction(Rc::from(object::CompiledFunction {
+                    name: String::new(),
+                    instructions: concat_instructions(&vec![
+                        make_instructions(OpGenerator, &[]),
+                        make_instructions(OpPop, &[]),
+                        make_instructions(OpConst, &[0]),
+                        make_instructions(OpYield, &[0]),
+                        make_instructions(OpSetLocal, &[1]),
+                        make_instructions(OpGetLocal, &[0]),
+                        make_instructions(OpYield, &[2]),
+                        make_instructions(OpReturn, &[]),
+                    ])
+                    .data,
+                    num_locals: 2,
+                    num_parameters: 1,
+                })),
+            ],
+            expected_instructions: vec![
+                make_instructions(OpClosure, &[1, 0]),
+                make_instructions(OpPop, &[]),
+            ],
+        }];
+
+        run_compiler_test(tests);
+    }
 }
@@ -511,6 +511,39 @@ mod tests {
         run_compiler_test(tests);
     }
 
+    #[test]
+    fn test_template_literal() {
+        let tests = vec![
+            CompilerTestCase {
+                input: "`n = ${1}!`",
+                expected_constants: vec![
+                    Object::String("n = ".to_string()),
+                    Object::Integer(1),
+                    Object::String("!".to_string()),
+                ],
+                expected_instructions: vec![
+                    make_instructions(OpConst, &[0]),
+                    make_instructions(OpConst, &[1]),
+                    make_instructions(OpConst, &[2]),
+                    make_instructions(OpConcat, &[3]),
+                    make_instructions(OpPop, &[0]),
+                ],
+            },
+            CompilerTestCase {
+                input: "`${1}${2}`",
+                expected_constants: vec![Object::Integer(1), Object::Integer(2)],
+                expected_instructions: vec![
+                    make_instructions(OpConst, &[0]),
+                    make_instructions(OpConst, &[1]),
+                    make_instructions(OpConcat, &[2]),
+                    make_instructions(OpPop, &[0]),
+                ],
+            },
+        ];
+
+        run_compiler_test(tests);
+    }
+
     #[test]
     fn test_array() {
         let tests = vec![
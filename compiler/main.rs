@@ -3,52 +3,173 @@ use compiler::vm::VM;
 
 use compiler::symbol_table::SymbolTable;
 use object::Object;
-use std::io::stdin;
-use std::io::{self, Write};
+use repl::cli::{self, parse_run_args, read_script, CliError};
+use repl::Backend;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::rc::Rc;
 
+use parser::ast::{Node, Statement};
 use parser::parse;
 
+const USAGE: &str = "\
+usage:
+  monkey-compiler                                      start the REPL
+  monkey-compiler run <file.monkey|-> [-- <args>...]   run a script, `-` reading it from stdin
+  monkey-compiler check <file.monkey>                  type check annotations";
+
 fn main() {
-    println!("Welcome to monkey compiler by gengjiawen");
-    let mut constants = vec![];
-    let mut symbol_table = SymbolTable::new();
-    let null = Rc::new(Object::Null);
-    let mut globals = vec![null; compiler::vm::GLOBAL_SIZE];
-    loop {
-        print!("> ");
-        io::stdout().flush().unwrap();
-        let mut input = String::new();
-        stdin().read_line(&mut input).unwrap();
-
-        if input.trim_end().is_empty() {
-            println!("bye");
-            std::process::exit(0);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (command, rest) = match args.split_first() {
+        None => {
+            repl();
+            return;
+        }
+        Some(split) => split,
+    };
+    let outcome = match command.as_str() {
+        "run" => {
+            // The VM reports runtime errors by panicking; `run_command`
+            // prints them itself.
+            std::panic::set_hook(Box::new(|_| {}));
+            run_command(rest).map(Some)
         }
+        "check" => cli::check_command(rest).map(|()| None),
+        other => Err(CliError::usage(format!("unknown command `{}`", other))),
+    };
+    cli::finish(outcome, USAGE);
+}
 
-        let program = match parse(&input) {
-            Ok(x) => x,
-            Err(e) => {
-                println!("{}", e[0]);
-                continue;
-            }
-        };
+fn repl() {
+    // The VM reports runtime errors by panicking; the backend prints them.
+    std::panic::set_hook(Box::new(|_| {}));
+    repl::run(
+        "Welcome to monkey compiler by gengjiawen",
+        repl::history_file("monkey-compiler"),
+        CompilerBackend::new(),
+    );
+}
+
+struct CompilerBackend {
+    symbol_table: SymbolTable,
+    constants: Vec<Rc<Object>>,
+    globals: Vec<Rc<Object>>,
+}
+
+impl CompilerBackend {
+    fn new() -> Self {
+        // Compiler::new() defines the builtins, and new_with_state replaces
+        // the symbol table wholesale, so start from its table.
+        let compiler = Compiler::new();
+        let null = Rc::new(Object::Null);
+        CompilerBackend {
+            symbol_table: compiler.symbol_table,
+            constants: compiler.constants,
+            globals: vec![null; compiler::vm::GLOBAL_SIZE],
+        }
+    }
+}
+
+impl Backend for CompilerBackend {
+    fn eval(&mut self, source: &str) -> Result<String, String> {
+        let program = parse(source).map_err(|errors| format!("parse error: {}", errors[0]))?;
+        // Compile against clones and commit only after a successful run, so a
+        // failed input cannot leave a half-defined binding behind.
+        let mut compiler =
+            Compiler::new_with_state(self.symbol_table.clone(), self.constants.clone());
+        let bytecode = compiler.compile(&program)?;
+        let mut vm = VM::new_with_global_store(bytecode, self.globals.clone());
+        run_vm(&mut vm)?;
+        let result = result_string(&program, &vm);
+        self.symbol_table = compiler.symbol_table;
+        self.constants = compiler.constants;
+        self.globals = vm.globals;
+        Ok(result)
+    }
 
-        let mut compiler = Compiler::new_with_state(symbol_table, constants);
-
-        match compiler.compile(&program) {
-            Ok(bytecodes) => {
-                let mut vm = VM::new_with_global_store(bytecodes, globals);
-                vm.run();
-                println!("{}", vm.last_popped_stack_elm().unwrap());
-                globals = vm.globals;
-            }
-            Err(e) => {
-                println!("{}", e);
-            }
+    fn reset(&mut self) {
+        *self = CompilerBackend::new();
+    }
+
+    fn env(&self) -> Vec<(String, String)> {
+        self.symbol_table
+            .global_symbols()
+            .into_iter()
+            .map(|(name, index)| (name, self.globals[index].to_string()))
+            .collect()
+    }
+
+    fn bytecode(&self, source: &str) -> Option<Result<String, String>> {
+        let program = match parse(source) {
+            Ok(program) => program,
+            Err(errors) => return Some(Err(format!("parse error: {}", errors[0]))),
         };
+        let mut compiler =
+            Compiler::new_with_state(self.symbol_table.clone(), self.constants.clone());
+        Some(compiler.compile(&program).map(|bytecode| bytecode.string()))
+    }
+}
+
+/// Runs `vm`, turning the panic it reports a runtime error with into an
+/// error message.
+fn run_vm(vm: &mut VM) -> Result<(), String> {
+    catch_unwind(AssertUnwindSafe(|| vm.run())).map_err(|panic| {
+        let message = panic
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| {
+                panic
+                    .downcast_ref::<&str>()
+                    .map(|message| (*message).to_string())
+            })
+            .unwrap_or_else(|| "the VM stopped unexpectedly".to_string());
+        format!("runtime error: {}", message)
+    })
+}
 
-        symbol_table = compiler.symbol_table;
-        constants = compiler.constants;
+/// Compiles and runs a whole script on a fresh VM, with everything after
+/// `--` in the global `args` as strings, and renders the value it ends with.
+fn run_command(args: &[String]) -> Result<String, CliError> {
+    let (input, script_args) = parse_run_args(args)?;
+    let source = read_script(&input)?;
+    let program = parse(&source)
+        .map_err(|errors| CliError::failure(format!("parse error: {}", errors[0])))?;
+    let mut compiler = Compiler::new();
+    let args_symbol = compiler.symbol_table.define("args".to_string());
+    let bytecode = compiler
+        .compile(&program)
+        .map_err(|error| CliError::failure(format!("compile error: {}", error)))?;
+
+    let null = Rc::new(Object::Null);
+    let mut globals = vec![null; compiler::vm::GLOBAL_SIZE];
+    let script_args = script_args
+        .into_iter()
+        .map(|arg| Rc::new(Object::String(arg)))
+        .collect();
+    globals[args_symbol.index] = Rc::new(Object::Array(script_args));
+    let mut vm = VM::new_with_global_store(bytecode, globals);
+    run_vm(&mut vm).map_err(CliError::failure)?;
+    Ok(result_string(&program, &vm))
+}
+
+/// The value `program` ends with: its last statement's when that is an
+/// expression or a `return`, null otherwise. After a trailing `let` the slot
+/// `last_popped_stack_elm` reads still holds whatever an earlier statement
+/// left there.
+fn result_string(program: &Node, vm: &VM) -> String {
+    let last = match program {
+        Node::Program(program) => program.body.last(),
+        _ => None,
+    };
+    match last {
+        Some(Statement::Expr(_) | Statement::Return(_)) => vm
+            .last_popped_stack_elm()
+            .map_or_else(|| Object::Null.to_string(), |value| value.to_string()),
+        _ => Object::Null.to_string(),
     }
 }
+
+#[cfg(test)]
+mod cli_test;
+
+#[cfg(test)]
+mod repl_test;
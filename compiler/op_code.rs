@@ -66,6 +66,14 @@ pub enum Opcode {
     OpNew,
     // Appended to preserve the byte values of all existing opcodes.
     OpLessThan,
+    OpConcat,
+    /// First instruction of a `fn*` body: returns a generator holding the
+    /// frame instead of running the body.
+    OpGenerator,
+    /// Stops a generator's body at the value on top of the stack. The
+    /// operand says how: 0 for `yield`, 1 for `yield*` and 2 for
+    /// `return yield*`.
+    OpYield,
 }
 
 lazy_static! {
@@ -323,6 +331,27 @@ lazy_static! {
                 operand_width: vec![1],
             },
         );
+        m.insert(
+            Opcode::OpConcat,
+            OpcodeDefinition {
+                name: "OpConcat",
+                operand_width: vec![2],
+            },
+        );
+        m.insert(
+            Opcode::OpGenerator,
+            OpcodeDefinition {
+                name: "OpGenerator",
+                operand_width: vec![],
+            },
+        );
+        m.insert(
+            Opcode::OpYield,
+            OpcodeDefinition {
+                name: "OpYield",
+                operand_width: vec![1],
+            },
+        );
         return m;
     };
 }
@@ -0,0 +1,36 @@
+use repl::Backend;
+
+use super::CompilerBackend;
+
+#[test]
+fn builtins_resolve_across_inputs() {
+    let mut backend = CompilerBackend::new();
+    assert_eq!(backend.eval("let xs = [1, 2, 3];").unwrap(), "null");
+    assert_eq!(backend.eval("len(xs)").unwrap(), "3");
+}
+
+#[test]
+fn failed_inputs_define_nothing() {
+    let mut backend = CompilerBackend::new();
+    let error = backend.eval("let x = 1 + true;").unwrap_err();
+    assert_eq!(error, "runtime error: unsupported add for those types");
+    assert!(backend.eval("x").is_err());
+    assert!(backend.env().is_empty());
+}
+
+#[test]
+fn env_lists_globals_and_reset_forgets_them() {
+    let mut backend = CompilerBackend::new();
+    backend.eval("let b = 2; let a = fn() { b };").unwrap();
+    assert_eq!(
+        backend.env(),
+        vec![
+            ("a".to_string(), "[closure function]".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ]
+    );
+    assert!(backend.bytecode("a()").unwrap().unwrap().contains("OpCall"));
+
+    backend.reset();
+    assert!(backend.env().is_empty());
+}
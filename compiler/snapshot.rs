@@ -10,8 +10,8 @@ use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::rc::Rc;
 
-use object::builtins::BuiltIns;
-use object::{CompiledFunction, Object};
+use object::integer::Int;
+use object::{BigInt, CompiledFunction, Object};
 use parser::lexer::token::Span;
 use strum::IntoEnumIterator;
 
@@ -20,7 +20,7 @@ use crate::op_code::{read_operands, Instructions, Opcode, DEFINITIONS};
 
 /// Bump when the container layout changes (header, sections, tags, varint
 /// rules). Bytecode ABI changes are covered by the fingerprint instead.
-pub const FORMAT_VERSION: u8 = 1;
+pub const FORMAT_VERSION: u8 = 4;
 
 pub(crate) const MAGIC: [u8; 4] = *b"MBC\0";
 pub(crate) const FLAG_HAS_DEBUG_INFO: u8 = 0b0000_0001;
@@ -28,11 +28,13 @@ pub(crate) const FLAG_HAS_DEBUG_INFO: u8 = 0b0000_0001;
 pub(crate) const TAG_INTEGER: u8 = 1;
 pub(crate) const TAG_STRING: u8 = 2;
 pub(crate) const TAG_FUNCTION: u8 = 3;
+pub(crate) const TAG_FLOAT: u8 = 4;
+pub(crate) const TAG_BIGINT: u8 = 5;
 
 #[derive(Debug, PartialEq)]
 pub enum SnapshotWriteError {
     /// `Bytecode.constants` is a public field, so the writer cannot assume it
-    /// only holds the three variants the compiler emits.
+    /// only holds the variants the compiler emits.
     UnsupportedConstant { index: usize, kind: String },
 }
 
@@ -75,10 +77,11 @@ lazy_static! {
 }
 
 /// Fingerprint of the bytecode ABI: every opcode (discriminant, name,
-/// operand widths, in enum order) and every builtin (index, name, in table
-/// order — `OpGetBuiltin` operands are indexes into that table). This is a
-/// compatibility sentinel, not integrity protection: safety against forged
-/// headers rests on the L1/L2/L3 checks, not on this value.
+/// operand widths, in enum order). Builtins are not part of it: a snapshot
+/// lists the builtin names its `OpGetBuiltin` operands index, and the VM
+/// resolves them by name. This is a compatibility sentinel, not integrity
+/// protection: safety against forged headers rests on the L1/L2/L3 checks,
+/// not on this value.
 pub fn bytecode_abi_fingerprint() -> u32 {
     *ABI_FINGERPRINT
 }
@@ -95,10 +98,6 @@ fn compute_abi_fingerprint() -> u32 {
             hash.absorb_u64(width as u64);
         }
     }
-    for (index, builtin) in BuiltIns.iter().enumerate() {
-        hash.absorb_u64(index as u64);
-        hash.absorb_bytes(builtin.name.as_bytes());
-    }
     hash.finish()
 }
 
@@ -152,6 +151,10 @@ pub fn write_bytecode(
     out.extend_from_slice(&bytecode_abi_fingerprint().to_le_bytes());
     out.push(if strip_debug { 0 } else { FLAG_HAS_DEBUG_INFO });
 
+    write_uleb128(&mut out, bytecode.builtins.len() as u64);
+    for name in &bytecode.builtins {
+        write_string(&mut out, name);
+    }
     write_bytes(&mut out, &bytecode.instructions.data);
     write_uleb128(&mut out, bytecode.constants.len() as u64);
     for (index, constant) in bytecode.constants.iter().enumerate() {
@@ -185,6 +188,14 @@ fn write_constant(
             out.push(TAG_STRING);
             write_string(out, value);
         }
+        Object::BigInt(value) => {
+            out.push(TAG_BIGINT);
+            write_bytes(out, &value.to_signed_bytes_le());
+        }
+        Object::Float(value) => {
+            out.push(TAG_FLOAT);
+            out.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
         Object::CompiledFunction(function) => {
             out.push(TAG_FUNCTION);
             write_string(out, &function.name);
@@ -248,6 +259,8 @@ pub(crate) fn write_sleb128(out: &mut Vec<u8>, mut value: i64) {
 fn object_kind(object: &Object) -> &'static str {
     match object {
         Object::Integer(_) => "Integer",
+        Object::BigInt(_) => "BigInt",
+        Object::Float(_) => "Float",
         Object::Boolean(_) => "Boolean",
         Object::String(_) => "String",
         Object::Array(_) => "Array",
@@ -255,13 +268,18 @@ fn object_kind(object: &Object) -> &'static str {
         Object::Null => "Null",
         Object::ReturnValue(_) => "ReturnValue",
         Object::Function(..) => "Function",
+        Object::GeneratorFunction(..) => "GeneratorFunction",
         Object::Builtin(_) => "Builtin",
+        Object::Native(_) => "Native",
         Object::Error(_) => "Error",
         Object::CompiledFunction(_) => "CompiledFunction",
         Object::ClosureObj(_) => "Closure",
         Object::Class(_) => "Class",
         Object::Instance(_) => "Instance",
         Object::BoundMethod(_) => "BoundMethod",
+        Object::BoundBuiltin(_) => "BoundBuiltin",
+        Object::Iterator(_) => "Iterator",
+        Object::Generator(_) => "Generator",
     }
 }
 
@@ -296,6 +314,11 @@ pub fn read_bytecode(buf: &[u8]) -> Result<Bytecode, SnapshotError> {
     }
     let has_debug = flags & FLAG_HAS_DEBUG_INFO != 0;
 
+    let builtin_count = reader.read_count()?;
+    let mut builtins = Vec::with_capacity(builtin_count);
+    for _ in 0..builtin_count {
+        builtins.push(reader.read_string()?);
+    }
     let main_instructions = reader.read_length_prefixed_bytes()?.to_vec();
     let constant_count = reader.read_count()?;
     let mut constants: Vec<Rc<Object>> = Vec::with_capacity(constant_count);
@@ -313,13 +336,14 @@ pub fn read_bytecode(buf: &[u8]) -> Result<Bytecode, SnapshotError> {
         return Err(SnapshotError::TrailingBytes);
     }
 
-    validate_instruction_stream("main", &main_instructions, &constants)?;
+    validate_instruction_stream("main", &main_instructions, &constants, builtins.len())?;
     for (index, constant) in constants.iter().enumerate() {
         if let Object::CompiledFunction(function) = constant.as_ref() {
             validate_instruction_stream(
                 &format!("constant {}", index),
                 &function.instructions,
                 &constants,
+                builtins.len(),
             )?;
         }
     }
@@ -331,6 +355,7 @@ pub fn read_bytecode(buf: &[u8]) -> Result<Bytecode, SnapshotError> {
         constants,
         debug_info,
         function_debug_info,
+        builtins,
     })
 }
 
@@ -339,6 +364,11 @@ fn read_constant(reader: &mut Reader) -> Result<Object, SnapshotError> {
     match tag {
         TAG_INTEGER => Ok(Object::Integer(reader.read_sleb128()?)),
         TAG_STRING => Ok(Object::String(reader.read_string()?)),
+        TAG_FLOAT => Ok(Object::Float(reader.read_f64()?)),
+        TAG_BIGINT => {
+            // narrowed, so a value that fits in i64 still loads as an Integer
+            Ok(Object::from(Int::narrow(reader.read_bigint()?)))
+        }
         TAG_FUNCTION => {
             let name = reader.read_string()?;
             let num_locals = reader.read_usize()?;
@@ -421,7 +451,8 @@ fn read_debug_info(
 /// L1 linear scan of one instruction stream (§6 of the design doc): every
 /// opcode is defined, operands are complete, jumps land on instruction
 /// boundaries (or one past the end), and index operands stay inside the
-/// constant pool / builtin table with the constant kind each opcode needs.
+/// constant pool / builtin name list with the constant kind each opcode
+/// needs.
 ///
 /// Deliberately not checked here: stack depth, operand runtime types,
 /// local/free index validity. Those depend on execution state and are the
@@ -430,6 +461,7 @@ fn validate_instruction_stream(
     stream: &str,
     instructions: &[u8],
     constants: &[Rc<Object>],
+    builtin_count: usize,
 ) -> Result<(), SnapshotError> {
     let len = instructions.len();
     let mut is_boundary = vec![false; len + 1];
@@ -489,7 +521,7 @@ fn validate_instruction_stream(
                 }
             }
             Opcode::OpGetBuiltin => {
-                if operands[0] >= BuiltIns.len() {
+                if operands[0] >= builtin_count {
                     return Err(invalid(
                         stream,
                         offset,
@@ -504,6 +536,13 @@ fn validate_instruction_stream(
                     format!("OpHash needs an even element count, got {}", operands[0]),
                 ));
             }
+            Opcode::OpYield if operands[0] > 2 => {
+                return Err(invalid(
+                    stream,
+                    offset,
+                    format!("unknown OpYield kind {}", operands[0]),
+                ));
+            }
             _ => {}
         }
         offset += 1 + operand_len;
@@ -563,6 +602,13 @@ impl<'a> Reader<'a> {
         Ok(slice)
     }
 
+    /// IEEE 754 bits, little-endian. Every bit pattern is accepted, NaN
+    /// payloads included.
+    pub(crate) fn read_f64(&mut self) -> Result<f64, SnapshotError> {
+        let bytes = self.read_exact(8)?;
+        Ok(f64::from_bits(u64::from_le_bytes(bytes.try_into().unwrap())))
+    }
+
     /// Non-canonical encodings are accepted; only length and 64-bit range
     /// are enforced (§4.1 hard rules).
     pub(crate) fn read_uleb128(&mut self) -> Result<u64, SnapshotError> {
@@ -626,6 +672,12 @@ impl<'a> Reader<'a> {
         Ok(count)
     }
 
+    /// Length-prefixed two's-complement bytes, little-endian.
+    pub(crate) fn read_bigint(&mut self) -> Result<BigInt, SnapshotError> {
+        let bytes = self.read_length_prefixed_bytes()?;
+        Ok(BigInt::from_signed_bytes_le(bytes))
+    }
+
     fn read_length_prefixed_bytes(&mut self) -> Result<&'a [u8], SnapshotError> {
         let len = self.read_usize()?;
         if len > self.remaining() {
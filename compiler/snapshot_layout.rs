@@ -14,14 +14,15 @@ use serde::Serialize;
 
 use crate::op_code::{read_operands, Opcode, DEFINITIONS};
 use crate::snapshot::{
-    read_bytecode, Reader, SnapshotError, FLAG_HAS_DEBUG_INFO, TAG_FUNCTION, TAG_INTEGER,
-    TAG_STRING,
+    read_bytecode, Reader, SnapshotError, FLAG_HAS_DEBUG_INFO, TAG_BIGINT, TAG_FLOAT, TAG_FUNCTION,
+    TAG_INTEGER, TAG_STRING,
 };
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SnapshotSection {
     Header,
+    Builtins,
     Main,
     Constants,
     Debug,
@@ -73,7 +74,7 @@ struct Walker<'a> {
 
 impl<'a> Walker<'a> {
     fn walk(mut self) -> Result<SnapshotLayout, SnapshotError> {
-        use SnapshotSection::{Constants, Debug, Header, Main};
+        use SnapshotSection::{Builtins, Constants, Debug, Header, Main};
 
         self.record(
             Header,
@@ -90,10 +91,7 @@ impl<'a> Walker<'a> {
             |r| r.read_exact(4),
             |bytes| {
                 let value = u32::from_le_bytes(bytes[..4].try_into().expect("4-byte slice"));
-                format!(
-                    "0x{:08x} — FNV-1a over the opcode and builtin tables (little-endian)",
-                    value
-                )
+                format!("0x{:08x} — FNV-1a over the opcode table (little-endian)", value)
             },
         )?;
         let abi_fingerprint = format!(
@@ -109,6 +107,14 @@ impl<'a> Walker<'a> {
         })?;
         let has_debug_info = flags & FLAG_HAS_DEBUG_INFO != 0;
 
+        let builtin_count =
+            self.record(Builtins, "builtin count", Reader::read_usize, |count| {
+                format!("{} builtin names, indexed by OpGetBuiltin (ULEB128)", count)
+            })?;
+        for index in 0..builtin_count {
+            self.record_str(Builtins, &format!("builtin[{}]", index))?;
+        }
+
         let main_len = self.record(Main, "main length", Reader::read_usize, |len| {
             format!("{} bytes of main instructions follow (ULEB128)", len)
         })?;
@@ -126,6 +132,8 @@ impl<'a> Walker<'a> {
                 TAG_INTEGER => "TAG_INTEGER (1) — SLEB128 value".to_string(),
                 TAG_STRING => "TAG_STRING (2) — length-prefixed UTF-8".to_string(),
                 TAG_FUNCTION => "TAG_FUNCTION (3) — name, locals, params, body".to_string(),
+                TAG_FLOAT => "TAG_FLOAT (4) — f64 bits, little-endian".to_string(),
+                TAG_BIGINT => "TAG_BIGINT (5) — length-prefixed two's complement".to_string(),
                 other => format!("unknown tag {}", other),
             })?;
             streams.push(format!("const[{}]", index));
@@ -141,6 +149,22 @@ impl<'a> Walker<'a> {
                 TAG_STRING => {
                     self.record_str(Constants, &format!("const[{}] text", index))?;
                 }
+                TAG_FLOAT => {
+                    self.record(
+                        Constants,
+                        format!("const[{}] value", index),
+                        Reader::read_f64,
+                        |value| format!("{} (f64 LE)", object::format_float(*value)),
+                    )?;
+                }
+                TAG_BIGINT => {
+                    self.record(
+                        Constants,
+                        format!("const[{}] value", index),
+                        Reader::read_bigint,
+                        |value| format!("{} (two's complement LE)", value),
+                    )?;
+                }
                 TAG_FUNCTION => {
                     let name = self.record_str(Constants, &format!("const[{}] name", index))?;
                     self.record(
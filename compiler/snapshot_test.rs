@@ -11,7 +11,7 @@ mod tests {
     use crate::snapshot::{
         bytecode_abi_fingerprint, read_bytecode, write_bytecode, write_sleb128, write_uleb128,
         Reader, SnapshotError, SnapshotWriteError, FLAG_HAS_DEBUG_INFO, FORMAT_VERSION, MAGIC,
-        TAG_FUNCTION, TAG_INTEGER, TAG_STRING,
+        TAG_BIGINT, TAG_FLOAT, TAG_FUNCTION, TAG_INTEGER, TAG_STRING,
     };
 
     fn compile(source: &str) -> Bytecode {
@@ -28,11 +28,13 @@ mod tests {
         out
     }
 
-    /// Hand-assemble a file. ULEB128 lengths are written as single bytes,
-    /// which is only correct because every length in these tests is < 128.
-    /// `constants` and `debug` are raw section bytes including their counts.
+    /// Hand-assemble a file whose builtin list is just `len`. ULEB128 lengths
+    /// are written as single bytes, which is only correct because every
+    /// length in these tests is < 128. `constants` and `debug` are raw section
+    /// bytes including their counts.
     fn raw_file(flags: u8, main: &[u8], constants: &[u8], debug: &[u8]) -> Vec<u8> {
         let mut out = header(flags);
+        out.extend_from_slice(&[1, 3, b'l', b'e', b'n']);
         out.push(main.len() as u8);
         out.extend_from_slice(main);
         out.extend_from_slice(constants);
@@ -76,6 +78,7 @@ mod tests {
         assert_eq!(original.constants, restored.constants);
         assert_eq!(original.debug_info, restored.debug_info);
         assert_eq!(original.function_debug_info, restored.function_debug_info);
+        assert_eq!(original.builtins, restored.builtins);
     }
 
     #[test]
@@ -100,6 +103,7 @@ mod tests {
             constants: vec![Rc::new(Object::Integer(1)), Rc::new(Object::Null)],
             debug_info: DebugInfo::default(),
             function_debug_info: HashMap::new(),
+            builtins: Vec::new(),
         };
         assert_eq!(
             write_bytecode(&bytecode, false),
@@ -110,6 +114,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn float_constants_roundtrip_bit_for_bit() {
+        let values = [0.5, -0.0, f64::INFINITY, f64::NAN, f64::MIN_POSITIVE];
+        let bytecode = Bytecode {
+            instructions: Instructions {
+                data: vec![],
+            },
+            constants: values
+                .iter()
+                .map(|value| Rc::new(Object::Float(*value)))
+                .collect(),
+            debug_info: DebugInfo::default(),
+            function_debug_info: HashMap::new(),
+            builtins: Vec::new(),
+        };
+        let restored = read_bytecode(&write_bytecode(&bytecode, false).unwrap()).unwrap();
+        let bits = |constants: &[Rc<Object>]| {
+            constants
+                .iter()
+                .map(|constant| match constant.as_ref() {
+                    Object::Float(value) => value.to_bits(),
+                    other => panic!("expected a float constant, got {:?}", other),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(bits(&bytecode.constants), bits(&restored.constants));
+
+        let compiled = compile("1.5 * 2");
+        let restored = read_bytecode(&write_bytecode(&compiled, false).unwrap()).unwrap();
+        assert_eq!(compiled.constants, restored.constants);
+    }
+
+    #[test]
+    fn rejects_truncated_float_constant() {
+        let blob = raw_file(0, &[], &[1, TAG_FLOAT, 0, 0, 0, 0], &[]);
+        assert_eq!(read_bytecode(&blob), Err(SnapshotError::UnexpectedEof));
+    }
+
+    #[test]
+    fn bigint_constants_roundtrip() {
+        let compiled = compile("100000000000000000000 - 18446744073709551616");
+        assert!(matches!(compiled.constants[0].as_ref(), Object::BigInt(_)));
+        let restored = read_bytecode(&write_bytecode(&compiled, false).unwrap()).unwrap();
+        assert_eq!(compiled.constants, restored.constants);
+    }
+
+    #[test]
+    fn bigint_constant_that_fits_loads_as_integer() {
+        let blob = raw_file(0, &[], &[1, TAG_BIGINT, 1, 0xff], &[]);
+        let bytecode = read_bytecode(&blob).unwrap();
+        assert_eq!(format!("{:?}", bytecode.constants[0]), "Integer(-1)");
+    }
+
     #[test]
     fn serialization_is_deterministic() {
         let source = "let a = fn() { 1 }; let b = fn() { 2 }; a() + b()";
@@ -329,7 +386,16 @@ mod tests {
 
     #[test]
     fn rejects_builtin_index_out_of_range() {
-        assert_invalid_instruction(&raw_file(0, &[Opcode::OpGetBuiltin as u8, 200], &[0], &[]));
+        // The file lists one builtin, so index 1 is past the end even though
+        // the standard library has far more.
+        assert_invalid_instruction(&raw_file(0, &[Opcode::OpGetBuiltin as u8, 1], &[0], &[]));
+    }
+
+    #[test]
+    fn builtin_index_is_checked_against_the_listed_names() {
+        let restored =
+            read_bytecode(&raw_file(0, &[Opcode::OpGetBuiltin as u8, 0], &[0], &[])).unwrap();
+        assert_eq!(restored.builtins, vec!["len".to_string()]);
     }
 
     #[test]
@@ -1,12 +1,24 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::rc::Rc;
 
 use byteorder::{BigEndian, ByteOrder};
-use object::builtins::BuiltIns;
-
+use object::builtins::{builtin_id, builtin_method, random_builtin, BuiltinId};
+use object::generators::{
+    self, new_generator, step, GeneratorHost, GeneratorState, NextError, Resumed, Suspension,
+};
+use object::higher_order::{self, is_higher_order, HigherOrderError, ObjectHost, ObjectValues};
+
+use object::integer::{self, IntegerOp};
+use object::output::{Output, OutputSink};
+use object::random::{is_random_builtin, Random};
+use object::registry::{Builtins, Ctx};
 use object::Object::ClosureObj;
-use object::{BoundMethodObject, BuiltinFunc, ClassObject, Closure, InstanceObject, Object};
+use object::{
+    float_operands, BoundMethodObject, BuiltinFunc, ClassObject, Closure, InstanceObject, Object,
+    OrderedMap, Vector,
+};
 
 use crate::compiler::Bytecode;
 use crate::frame::Frame;
@@ -15,6 +27,10 @@ use crate::op_code::Opcode;
 const STACK_SIZE: usize = 2048;
 pub const GLOBAL_SIZE: usize = 65536;
 const MAX_FRAMES: usize = 1024;
+/// Special methods and higher-order builtin callbacks re-enter the dispatch
+/// loop on the native stack, so their nesting is capped well below
+/// `MAX_FRAMES`.
+const MAX_NESTED_RUN_DEPTH: usize = 64;
 
 pub struct VM {
     constants: Vec<Rc<Object>>,
@@ -26,10 +42,23 @@ pub struct VM {
 
     frames: Vec<Frame>,
     frame_index: usize,
+    nested_run_depth: usize,
+    random: Random,
+    output: Output,
+    /// `Bytecode::builtins` resolved against the registry; `None` where the
+    /// registry has no builtin of that name.
+    builtins: Vec<(String, Option<Rc<Object>>)>,
+    /// Where the generator body being resumed stopped, left by `OpYield`.
+    yielded: Option<Resumed<Suspension, Rc<Object>>>,
 }
 
 impl VM {
     pub fn new(bytecode: Bytecode) -> VM {
+        return VM::with_builtins(bytecode, &Builtins::standard());
+    }
+
+    /// A VM that resolves the program's builtins by name in `builtins`.
+    pub fn with_builtins(bytecode: Bytecode, builtins: &Builtins) -> VM {
         // it's rust, it's verbose. You can't just grow your vector size.
         let empty_frame = Frame::new(
             Closure {
@@ -58,6 +87,16 @@ impl VM {
         let mut frames = vec![empty_frame; MAX_FRAMES];
         frames[0] = main_frame;
 
+        let builtins = bytecode
+            .builtins
+            .into_iter()
+            .map(|name| {
+                let value = builtins
+                    .get(&name)
+                    .map(|builtin| Rc::new(builtin.to_object()));
+                (name, value)
+            })
+            .collect();
         let null = Rc::new(Object::Null);
         return VM {
             constants: bytecode.constants,
@@ -66,6 +105,11 @@ impl VM {
             globals: vec![null; GLOBAL_SIZE],
             frames,
             frame_index: 1,
+            nested_run_depth: 0,
+            random: Random::default(),
+            output: Output::default(),
+            builtins,
+            yielded: None,
         };
     }
 
@@ -75,7 +119,19 @@ impl VM {
         return vm;
     }
 
+    /// Sends what `puts` prints to `output` instead of stdout.
+    pub fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+
     pub fn run(&mut self) {
+        self.execute_frames(0);
+    }
+
+    /// Runs the dispatch loop until the program ends or, for nested calls made
+    /// on behalf of an opcode, until the frame count drops back to
+    /// `stop_depth`.
+    fn execute_frames(&mut self, stop_depth: usize) {
         let mut ip: usize;
         let mut ins: Vec<u8>;
         while self.current_frame().ip
@@ -175,6 +231,9 @@ impl VM {
                     let frame = self.pop_frame();
                     self.sp = frame.base_pointer - 1;
                     self.push(return_value);
+                    if self.frame_index == stop_depth {
+                        return;
+                    }
                 }
                 Opcode::OpReturn => {
                     if self.frame_index == 1 {
@@ -185,6 +244,9 @@ impl VM {
                     let frame = self.pop_frame();
                     self.sp = frame.base_pointer - 1;
                     self.push(Rc::new(object::Object::Null));
+                    if self.frame_index == stop_depth {
+                        return;
+                    }
                 }
                 Opcode::OpCall => {
                     let num_args = ins[ip + 1] as usize;
@@ -206,8 +268,12 @@ impl VM {
                 Opcode::OpGetBuiltin => {
                     let built_index = ins[ip + 1] as usize;
                     self.current_frame().ip += 1;
-                    let definition = BuiltIns.get(built_index).unwrap().function;
-                    self.push(Rc::new(Object::Builtin(definition)));
+                    let builtin = match self.builtins.get(built_index) {
+                        Some((_, Some(builtin))) => Rc::clone(builtin),
+                        Some((name, None)) => panic!("builtin {} is not registered", name),
+                        None => panic!("builtin index {} out of range", built_index),
+                    };
+                    self.push(builtin);
                 }
                 Opcode::OpClosure => {
                     let const_index = BigEndian::read_u16(&ins[ip + 1..ip + 3]) as usize;
@@ -272,6 +338,40 @@ impl VM {
                     self.current_frame().ip += 1;
                     self.execute_new(num_args);
                 }
+                Opcode::OpConcat => {
+                    let count = BigEndian::read_u16(&ins[ip + 1..ip + 3]) as usize;
+                    self.current_frame().ip += 2;
+                    let parts = self.stack[self.sp - count..self.sp].to_vec();
+                    let text = parts
+                        .iter()
+                        .map(|part| self.display_value(part))
+                        .collect::<String>();
+                    self.sp -= count;
+                    self.push(Rc::new(Object::String(text)));
+                }
+                Opcode::OpGenerator => {
+                    let frame = self.pop_frame();
+                    let generator = new_generator(self.suspend(&frame));
+                    self.sp = frame.base_pointer - 1;
+                    self.push(generator);
+                    if self.frame_index == stop_depth {
+                        return;
+                    }
+                }
+                Opcode::OpYield => {
+                    let kind = ins[ip + 1];
+                    self.current_frame().ip += 1;
+                    let value = self.pop();
+                    let frame = self.pop_frame();
+                    self.yielded = Some(match kind {
+                        0 => Resumed::Yield(value, self.suspend(&frame)),
+                        1 => Resumed::Delegate(value, self.suspend(&frame)),
+                        _ => Resumed::Forward(value),
+                    });
+                    self.sp = frame.base_pointer - 1;
+                    // Generator bodies only run under `resume_generator`.
+                    return;
+                }
             }
         }
     }
@@ -279,16 +379,44 @@ impl VM {
     fn execute_binary_operation(&mut self, opcode: Opcode) {
         let right = self.pop();
         let left = self.pop();
+        let method = match opcode {
+            Opcode::OpAdd => "add",
+            Opcode::OpSub => "sub",
+            Opcode::OpMul => "mul",
+            _ => "div",
+        };
+        if let Some(result) = self.call_special_method(&left, method, &[Rc::clone(&right)]) {
+            self.push(result);
+            return;
+        }
         match (left.as_ref(), right.as_ref()) {
-            (Object::Integer(l), Object::Integer(r)) => {
+            (Object::Integer(_) | Object::BigInt(_), Object::Integer(_) | Object::BigInt(_)) => {
+                let op = match opcode {
+                    Opcode::OpAdd => IntegerOp::Add,
+                    Opcode::OpSub => IntegerOp::Sub,
+                    Opcode::OpMul => IntegerOp::Mul,
+                    Opcode::OpDiv => IntegerOp::Div,
+                    _ => panic!("Unknown opcode for int"),
+                };
+                let (l, r) = (left.as_int().unwrap(), right.as_int().unwrap());
+                match integer::apply(op, &l, &r) {
+                    Some(result) => self.push(Rc::from(Object::from(result))),
+                    None => panic!("division by zero"),
+                }
+            }
+            (
+                Object::Integer(_) | Object::BigInt(_) | Object::Float(_),
+                Object::Integer(_) | Object::BigInt(_) | Object::Float(_),
+            ) => {
+                let (l, r) = float_operands(&left, &right).unwrap();
                 let result = match opcode {
                     Opcode::OpAdd => l + r,
                     Opcode::OpSub => l - r,
                     Opcode::OpMul => l * r,
                     Opcode::OpDiv => l / r,
-                    _ => panic!("Unknown opcode for int"),
+                    _ => panic!("Unknown opcode for float"),
                 };
-                self.push(Rc::from(Object::Integer(result)));
+                self.push(Rc::from(Object::Float(result)));
             }
             (Object::String(l), Object::String(r)) => {
                 let result = match opcode {
@@ -307,7 +435,10 @@ impl VM {
         let right = self.pop();
         let left = self.pop();
         if opcode == Opcode::OpEqual || opcode == Opcode::OpNotEqual {
-            let equal = left.as_ref() == right.as_ref();
+            let equal = match self.call_special_method(&left, "eq", &[Rc::clone(&right)]) {
+                Some(result) => self.is_truthy(result),
+                None => left.as_ref() == right.as_ref(),
+            };
             self.push(Rc::new(Object::Boolean(if opcode == Opcode::OpEqual {
                 equal
             } else {
@@ -316,11 +447,24 @@ impl VM {
             return;
         }
         match (left.as_ref(), right.as_ref()) {
-            (Object::Integer(l), Object::Integer(r)) => {
+            (Object::Integer(_) | Object::BigInt(_), Object::Integer(_) | Object::BigInt(_)) => {
+                let ordering = integer::compare(&left.as_int().unwrap(), &right.as_int().unwrap());
+                let result = match opcode {
+                    Opcode::OpGreaterThan => ordering.is_gt(),
+                    Opcode::OpLessThan => ordering.is_lt(),
+                    _ => panic!("Unknown opcode for comparing int"),
+                };
+                self.push(Rc::from(Object::Boolean(result)));
+            }
+            (
+                Object::Integer(_) | Object::BigInt(_) | Object::Float(_),
+                Object::Integer(_) | Object::BigInt(_) | Object::Float(_),
+            ) => {
+                let (l, r) = float_operands(&left, &right).unwrap();
                 let result = match opcode {
                     Opcode::OpGreaterThan => l > r,
                     Opcode::OpLessThan => l < r,
-                    _ => panic!("Unknown opcode for comparing int"),
+                    _ => panic!("Unknown opcode for comparing float"),
                 };
                 self.push(Rc::from(Object::Boolean(result)));
             }
@@ -333,8 +477,12 @@ impl VM {
     fn execute_minus_operation(&mut self, opcode: Opcode) {
         let operand = self.pop();
         match operand.as_ref() {
-            Object::Integer(l) => {
-                self.push(Rc::from(Object::Integer(-*l)));
+            Object::Integer(_) | Object::BigInt(_) => {
+                let negated = integer::negate(&operand.as_int().unwrap());
+                self.push(Rc::from(Object::from(negated)));
+            }
+            Object::Float(l) => {
+                self.push(Rc::from(Object::Float(-*l)));
             }
             _ => {
                 panic!("unsupported types for negation {:?}", opcode)
@@ -377,19 +525,15 @@ impl VM {
             _ => true,
         }
     }
-    fn build_array(&self, start: usize, end: usize) -> Vec<Rc<Object>> {
-        let mut elements = Vec::with_capacity(end - start);
-        for i in start..end {
-            elements.push(Rc::clone(&self.stack[i]));
-        }
-        return elements;
+    fn build_array(&self, start: usize, end: usize) -> Vector<Rc<Object>> {
+        return self.stack[start..end].iter().cloned().collect();
     }
 
     // Object's Hash impl only covers Integer/Boolean/String, which have no
     // interior mutability, so the keys are effectively immutable.
     #[allow(clippy::mutable_key_type)]
-    fn build_hash(&self, start: usize, end: usize) -> HashMap<Rc<Object>, Rc<Object>> {
-        let mut elements = HashMap::new();
+    fn build_hash(&self, start: usize, end: usize) -> OrderedMap<Rc<Object>, Rc<Object>> {
+        let mut elements = OrderedMap::new();
         for i in (start..end).step_by(2) {
             let key = Rc::clone(&self.stack[i]);
             let value = Rc::clone(&self.stack[i + 1]);
@@ -399,6 +543,10 @@ impl VM {
     }
 
     fn execute_index_operation(&mut self, left: Rc<Object>, index: Rc<Object>) {
+        if let Some(result) = self.call_special_method(&left, "get", &[Rc::clone(&index)]) {
+            self.push(result);
+            return;
+        }
         match (left.as_ref(), index.as_ref()) {
             (Object::Array(l), Object::Integer(i)) => {
                 self.execute_array_index(l, *i);
@@ -412,7 +560,7 @@ impl VM {
         }
     }
 
-    fn execute_array_index(&mut self, array: &[Rc<Object>], index: i64) {
+    fn execute_array_index(&mut self, array: &Vector<Rc<Object>>, index: i64) {
         if index < array.len() as i64 && index >= 0 {
             self.push(Rc::clone(&array[index as usize]));
         } else {
@@ -421,9 +569,9 @@ impl VM {
     }
 
     #[allow(clippy::mutable_key_type)]
-    fn execute_hash_index(&mut self, hash: &HashMap<Rc<Object>, Rc<Object>>, index: Rc<Object>) {
+    fn execute_hash_index(&mut self, hash: &OrderedMap<Rc<Object>, Rc<Object>>, index: Rc<Object>) {
         match &*index {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => match hash.get(&index) {
+            key if key.is_hashable() => match hash.get(&index) {
                 Some(el) => {
                     self.push(Rc::clone(el));
                 }
@@ -460,9 +608,20 @@ impl VM {
             Object::Builtin(bt) => {
                 self.call_builtin(*bt, num_args);
             }
+            Object::Native(native) => {
+                let args = &self.stack[self.sp - num_args..self.sp];
+                let result = native.call(&mut Ctx::new(&mut self.random), args);
+                self.sp = self.sp - num_args - 1;
+                self.push(result);
+            }
             Object::BoundMethod(bound) => {
                 self.call_bound_method(bound.clone(), num_args);
             }
+            Object::BoundBuiltin(bound) => {
+                let builtin = Rc::new(Object::Builtin(bound.function));
+                self.rewrite_receiver_call(builtin, Rc::clone(&bound.receiver), num_args);
+                self.call_builtin(bound.function, num_args + 1);
+            }
             Object::Class(class) => {
                 panic!("class {} must be constructed with new", class.borrow().name)
             }
@@ -483,6 +642,64 @@ impl VM {
 
     fn call_builtin(&mut self, bt: BuiltinFunc, num_args: usize) {
         let args = self.stack[self.sp - num_args..self.sp].to_vec();
+        if builtin_id(bt) == Some(BuiltinId::Next) {
+            let result = match generators::next(&mut FrameHost(self), &args) {
+                Ok((value, done)) => step(value, done),
+                Err(NextError::Builtin(error)) => {
+                    Rc::new(Object::Error(error.message().to_string()))
+                }
+                Err(NextError::Resume(never)) => match never {},
+            };
+            self.sp = self.sp - num_args - 1;
+            self.push(result);
+            return;
+        }
+        if let Some(id) = builtin_id(bt).filter(|id| is_higher_order(*id)) {
+            let mut host = ObjectHost(|function: &Rc<Object>, args: &[Rc<Object>]| {
+                return Ok::<_, Infallible>(self.call_value(function, args));
+            });
+            let result = match higher_order::call(&mut host, id, &args) {
+                Ok(result) => result,
+                Err(HigherOrderError::Builtin(error)) => {
+                    Rc::new(Object::Error(error.message().to_string()))
+                }
+                Err(HigherOrderError::Callback(never)) => match never {},
+            };
+            self.sp = self.sp - num_args - 1;
+            self.push(result);
+            return;
+        }
+        if let Some(id) = builtin_id(bt).filter(|id| is_random_builtin(*id)) {
+            let result = random_builtin(&mut self.random, id, &args);
+            self.sp = self.sp - num_args - 1;
+            self.push(result);
+            return;
+        }
+        if builtin_id(bt) == Some(BuiltinId::Puts) {
+            let lines: Vec<String> = args
+                .iter()
+                .map(|arg| match arg.as_ref() {
+                    Object::Instance(_) => self.display_value(arg),
+                    _ => arg.to_string(),
+                })
+                .collect();
+            for line in &lines {
+                self.output.write_line(line);
+            }
+            self.sp = self.sp - num_args - 1;
+            self.push(Rc::new(Object::Null));
+            return;
+        }
+        let args = if builtin_id(bt) == Some(BuiltinId::Str) {
+            args.iter()
+                .map(|arg| match arg.as_ref() {
+                    Object::Instance(_) => Rc::new(Object::String(self.display_value(arg))),
+                    _ => Rc::clone(arg),
+                })
+                .collect()
+        } else {
+            args
+        };
         let result = bt(args);
         self.sp = self.sp - num_args - 1;
         self.push(result);
@@ -517,6 +734,9 @@ impl VM {
     }
 
     fn get_property(&self, receiver: &Rc<Object>, name: &str) -> Rc<Object> {
+        if let Some(method) = builtin_method(receiver, name) {
+            return method;
+        }
         let Object::Instance(instance) = &**receiver else {
             panic!("cannot read property '{}' of {}", name, receiver);
         };
@@ -605,6 +825,114 @@ impl VM {
         self.call_closure(closure, num_args + 1);
     }
 
+    /// Calls `receiver.name(args)` to completion when the receiver is an
+    /// instance whose class defines `name`; operators use this to reach
+    /// special methods. The call runs on a regular frame nested inside the
+    /// current instruction, so the caller sees only the returned value.
+    fn call_special_method(
+        &mut self,
+        receiver: &Rc<Object>,
+        name: &str,
+        args: &[Rc<Object>],
+    ) -> Option<Rc<Object>> {
+        let Object::Instance(instance) = receiver.as_ref() else {
+            return None;
+        };
+        let method = instance
+            .borrow()
+            .class
+            .borrow()
+            .methods
+            .get(name)
+            .cloned()?;
+        if self.nested_run_depth >= MAX_NESTED_RUN_DEPTH {
+            panic!("special method '{}' nested too deeply", name);
+        }
+        let bound = Rc::new(BoundMethodObject {
+            receiver: Rc::clone(instance),
+            method,
+            name: name.to_string(),
+        });
+        return Some(self.call_value(&Rc::new(Object::BoundMethod(bound)), args));
+    }
+
+    /// Calls any callable to completion on behalf of the running instruction,
+    /// running the dispatch loop nested when it needs a frame.
+    fn call_value(&mut self, callee: &Rc<Object>, args: &[Rc<Object>]) -> Rc<Object> {
+        if self.nested_run_depth >= MAX_NESTED_RUN_DEPTH {
+            panic!("builtin callbacks nested too deeply");
+        }
+        self.push(Rc::clone(callee));
+        for arg in args {
+            self.push(Rc::clone(arg));
+        }
+        let depth = self.frame_index;
+        self.execute_call(args.len());
+        if self.frame_index > depth {
+            self.nested_run_depth += 1;
+            self.execute_frames(depth);
+            self.nested_run_depth -= 1;
+        }
+        return self.pop();
+    }
+
+    /// The frame as a generator keeps it: where it stopped and its locals
+    /// and temporaries.
+    fn suspend(&self, frame: &Frame) -> Suspension {
+        return Suspension::Frame {
+            closure: frame.cl.clone(),
+            ip: frame.ip,
+            stack: self.stack[frame.base_pointer..self.sp].to_vec(),
+        };
+    }
+
+    /// Puts a generator's frame back on the stack, with `sent` as the value
+    /// of the instruction it stopped at, and runs it until it yields or
+    /// returns.
+    fn resume_generator(
+        &mut self,
+        suspension: Suspension,
+        sent: Rc<Object>,
+    ) -> Resumed<Suspension, Rc<Object>> {
+        let Suspension::Frame {
+            closure,
+            ip,
+            stack,
+        } = suspension
+        else {
+            unreachable!("VM generators stop in a frame")
+        };
+        if self.nested_run_depth >= MAX_NESTED_RUN_DEPTH {
+            panic!("generators nested too deeply");
+        }
+        let depth = self.frame_index;
+        self.push(Rc::new(Object::ClosureObj(closure.clone())));
+        let base_pointer = self.sp;
+        for value in stack {
+            self.push(value);
+        }
+        self.push(sent);
+        self.push_frame(Frame {
+            cl: closure,
+            ip,
+            base_pointer,
+        });
+        self.nested_run_depth += 1;
+        self.execute_frames(depth);
+        self.nested_run_depth -= 1;
+        match self.yielded.take() {
+            Some(stopped) => return stopped,
+            None => return Resumed::Return(self.pop()),
+        }
+    }
+
+    fn display_value(&mut self, value: &Rc<Object>) -> String {
+        match self.call_special_method(value, "toString", &[]) {
+            Some(shown) => shown.to_string(),
+            None => value.to_string(),
+        }
+    }
+
     fn rewrite_receiver_call(
         &mut self,
         callable: Rc<Object>,
@@ -620,3 +948,34 @@ impl VM {
         self.sp += 1;
     }
 }
+
+/// Runs generator bodies for `next` on the VM's stack.
+struct FrameHost<'a>(&'a mut VM);
+
+impl ObjectValues for FrameHost<'_> {}
+
+impl GeneratorHost for FrameHost<'_> {
+    type Suspension = Suspension;
+    type Error = Infallible;
+
+    fn take_state(
+        &mut self,
+        generator: &Rc<Object>,
+    ) -> Option<GeneratorState<Suspension, Rc<Object>>> {
+        return generators::take_state(generator);
+    }
+
+    fn put_state(&mut self, generator: &Rc<Object>, state: GeneratorState<Suspension, Rc<Object>>) {
+        generators::put_state(generator, state);
+    }
+
+    fn resume(
+        &mut self,
+        suspension: Suspension,
+        sent: Rc<Object>,
+    ) -> Result<Resumed<Suspension, Rc<Object>>, Infallible> {
+        return Ok(self.0.resume_generator(suspension, sent));
+    }
+
+    fn discard(&mut self, _: Suspension) {}
+}
@@ -4,6 +4,15 @@ mod tests {
     use object::Object;
     use std::rc::Rc;
 
+    fn ints(values: &[i64]) -> Object {
+        Object::Array(
+            values
+                .iter()
+                .map(|value| Rc::new(Object::Integer(*value)))
+                .collect(),
+        )
+    }
+
     #[test]
     fn test_function_without_arguments() {
         let tests = vec![
@@ -197,10 +206,9 @@ mod tests {
             },
             VmTestCase {
                 input: "rest([1, 2, 3]);",
-                expected: Object::Array(vec![
-                    Rc::from(Object::Integer(2)),
-                    Rc::from(Object::Integer(3)),
-                ]),
+                expected: Object::Array(
+                    vec![Rc::from(Object::Integer(2)), Rc::from(Object::Integer(3))].into(),
+                ),
             },
             VmTestCase {
                 input: "rest([]);",
@@ -208,9 +216,485 @@ mod tests {
             },
             VmTestCase {
                 input: "push([], 1);",
-                expected: Object::Array(vec![Rc::from(Object::Integer(1))]),
+                expected: Object::Array(vec![Rc::from(Object::Integer(1))].into()),
             },
         ];
         run_vm_tests(tests);
     }
+
+    #[test]
+    fn test_string_builtins() {
+        let tests = vec![
+            VmTestCase {
+                input: "split(\"a,b\", \",\");",
+                expected: Object::Array(
+                    vec![
+                        Rc::from(Object::String("a".to_string())),
+                        Rc::from(Object::String("b".to_string())),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "join([1, \"b\"], \"-\");",
+                expected: Object::String("1-b".to_string()),
+            },
+            VmTestCase {
+                input: "upper(trim(\" hi \")) + substr(\"héllo\", 1, 2);",
+                expected: Object::String("HIél".to_string()),
+            },
+            VmTestCase {
+                input: "contains(replace(\"a-b\", \"-\", \"+\"), \"+\");",
+                expected: Object::Boolean(true),
+            },
+            VmTestCase {
+                input: "index_of(\"héllo\", \"l\") + int(\"40\");",
+                expected: Object::Integer(42),
+            },
+            VmTestCase {
+                input: "int(\"nope\");",
+                expected: Object::Null,
+            },
+            VmTestCase {
+                input: "str([1, 2.5]);",
+                expected: Object::String("[1, 2.5]".to_string()),
+            },
+            VmTestCase {
+                input: "lower(1);",
+                expected: Object::Error("builtin lower not supported for type 1".to_string()),
+            },
+        ];
+        run_vm_tests(tests);
+    }
+
+    #[test]
+    fn test_higher_order_builtins() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: "map([1, 2, 3], fn(x) { x * 2 });",
+                expected: ints(&[2, 4, 6]),
+            },
+            VmTestCase {
+                input: "let limit = 1; filter([3, 1, 2], fn(x) { x > limit });",
+                expected: ints(&[3, 2]),
+            },
+            VmTestCase {
+                input: "reduce([1, 2, 3], fn(acc, x) { acc + x }, 10);",
+                expected: Object::Integer(16),
+            },
+            VmTestCase {
+                input: "sort([3, 1, 2]);",
+                expected: ints(&[1, 2, 3]),
+            },
+            VmTestCase {
+                input: "sort([1, 3, 2], fn(a, b) { b - a });",
+                expected: ints(&[3, 2, 1]),
+            },
+            VmTestCase {
+                input: "find([1, 4, 6], fn(x) { x > 3 });",
+                expected: Object::Integer(4),
+            },
+            VmTestCase {
+                input: "find([1], fn(x) { x > 3 });",
+                expected: Object::Null,
+            },
+            VmTestCase {
+                input: "[any([1, 2], fn(x) { x == 2 }), all([1, 2], fn(x) { x == 2 })];",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::Boolean(true)),
+                        Rc::new(Object::Boolean(false)),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "map([\"a\"], upper);",
+                expected: Object::Array(vec![Rc::new(Object::String("A".to_string()))].into()),
+            },
+            VmTestCase {
+                input: "map([[2, 1]], fn(xs) { sort(xs, fn(a, b) { a - b }) });",
+                expected: Object::Array(vec![Rc::new(ints(&[1, 2]))].into()),
+            },
+            VmTestCase {
+                input: "sort([1, \"a\"]);",
+                expected: Object::Error("builtin sort cannot compare 1 and a".to_string()),
+            },
+            VmTestCase {
+                input: "map(1, fn(x) { x });",
+                expected: Object::Error("builtin map not supported for type 1".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_hash_builtins() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: "keys({\"b\": 1, \"a\": 2, 3: 3});",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::String("b".to_string())),
+                        Rc::new(Object::String("a".to_string())),
+                        Rc::new(Object::Integer(3)),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "values(merge({1: 1, 2: 2}, {3: 3, 1: 4}));",
+                expected: ints(&[4, 2, 3]),
+            },
+            VmTestCase {
+                input: "keys(delete({1: 1, 2: 2, 3: 3}, 2));",
+                expected: ints(&[1, 3]),
+            },
+            VmTestCase {
+                input: "entries({1: 2});",
+                expected: Object::Array(vec![Rc::new(ints(&[1, 2]))].into()),
+            },
+            VmTestCase {
+                input: "let h = {1: 1}; delete(h, 1); [has(h, 1), has(h, 2)];",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::Boolean(true)),
+                        Rc::new(Object::Boolean(false)),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "has({}, [1]);",
+                expected: Object::Error("builtin has not supported for type [1]".to_string()),
+            },
+            VmTestCase {
+                input: "keys(1);",
+                expected: Object::Error("builtin keys not supported for type 1".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_math_builtins() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: "[abs(-5), min(3, 1, 2), pow(2, 10), floor(2.7), round(-2.5)];",
+                expected: ints(&[5, 1, 1024, 2, -3]),
+            },
+            VmTestCase {
+                input: "sqrt(16);",
+                expected: Object::Float(4.0),
+            },
+            VmTestCase {
+                input: "max(1, 2.5, 2);",
+                expected: Object::Float(2.5),
+            },
+            VmTestCase {
+                input: "pow(2, 64);",
+                expected: Object::BigInt("18446744073709551616".parse().unwrap()),
+            },
+            VmTestCase {
+                input: "abs(\"a\");",
+                expected: Object::Error("builtin abs not supported for type a".to_string()),
+            },
+            VmTestCase {
+                input: "min();",
+                expected: Object::Error(
+                    "builtin min expected at least 1 argument, got 0".to_string(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_reflection_builtins() {
+        let strings = |values: &[&str]| {
+            Object::Array(
+                values
+                    .iter()
+                    .map(|v| Rc::new(Object::String(v.to_string())))
+                    .collect(),
+            )
+        };
+        let class = "class Point { constructor(x, y) { this.y = y; this.x = x; } sum() { this.x + this.y } norm() { 0 } } \
+                     class Other {} let p = new Point(1, 2);";
+        let with_class = |rest: &str| format!("{} {}", class, rest);
+        let inputs = [
+            with_class("[type_of(1), type_of(1.5), type_of(true), type_of(\"a\"), type_of([]), type_of({})];"),
+            with_class("[type_of(if (false) { 1 }), type_of(fn() {}), type_of(len)];"),
+            with_class("[type_of(Point), type_of(p), type_of(p.sum)];"),
+            with_class("[instance_of(p, Point), instance_of(p, Other), instance_of(1, Point)];"),
+            with_class("fields(p);"),
+            with_class("methods(Point);"),
+            with_class("fields(Point);"),
+        ];
+        let expected = [
+            strings(&["integer", "float", "boolean", "string", "array", "hash"]),
+            strings(&["null", "closure", "builtin"]),
+            strings(&["class", "instance", "boundMethod"]),
+            Object::Array(
+                vec![
+                    Rc::new(Object::Boolean(true)),
+                    Rc::new(Object::Boolean(false)),
+                    Rc::new(Object::Boolean(false)),
+                ]
+                .into(),
+            ),
+            strings(&["x", "y"]),
+            strings(&["norm", "sum"]),
+            Object::Error("builtin fields not supported for type [class Point]".to_string()),
+        ];
+        run_vm_tests(
+            inputs
+                .iter()
+                .zip(expected)
+                .map(|(input, expected)| VmTestCase {
+                    input,
+                    expected,
+                })
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn test_builtin_methods() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: r#""  monkey ".trim().upper().len();"#,
+                expected: Object::Integer(6),
+            },
+            VmTestCase {
+                input: "[1, 2].push(3).map(fn(x) { x * 2 });",
+                expected: ints(&[2, 4, 6]),
+            },
+            VmTestCase {
+                input: "[3, 1, 2].sort().reduce(fn(acc, x) { acc * 10 + x }, 0);",
+                expected: Object::Integer(123),
+            },
+            VmTestCase {
+                input: r#"let h = {"a": 1}; h.merge({"b": 2}).values();"#,
+                expected: ints(&[1, 2]),
+            },
+            VmTestCase {
+                input: "let push = [1].push; [push(2), [type_of(push)]];",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(ints(&[1, 2])),
+                        Rc::new(Object::Array(
+                            vec![Rc::new(Object::String("boundMethod".to_string()))].into(),
+                        )),
+                    ]
+                    .into(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_arrays_and_hashes_are_values() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: "let a = [1, 2]; let b = push(a, 3); [len(a), len(b), first(rest(b)), len(rest(a))];",
+                expected: ints(&[2, 3, 2, 1]),
+            },
+            VmTestCase {
+                input: r#"let h = {"a": 1, "b": 2, "c": 3}; let d = delete(h, "b"); push(values(merge(d, {"b": 4, "a": 5})), len(keys(h)));"#,
+                expected: ints(&[5, 3, 4, 3]),
+            },
+            VmTestCase {
+                input: "let xs = reduce(to_array(range(50000)), fn(acc, x) { push(acc, x * 2) }, []); [len(xs), xs[49999]];",
+                expected: ints(&[50000, 99998]),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_iterator_builtins() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: "to_array(range(10, 0, -3));",
+                expected: ints(&[10, 7, 4, 1]),
+            },
+            VmTestCase {
+                input: "to_array(take(range(0, 1000000000), 3));",
+                expected: ints(&[0, 1, 2]),
+            },
+            VmTestCase {
+                input: "let r = range(2, 5); [len(r), first(r), len(to_array(rest(r))), len(r)];",
+                expected: ints(&[3, 2, 2, 3]),
+            },
+            VmTestCase {
+                input: "to_array(zip(range(3), enumerate([7, 8])));",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::Array(
+                            vec![Rc::new(Object::Integer(0)), Rc::new(ints(&[0, 7]))].into(),
+                        )),
+                        Rc::new(Object::Array(
+                            vec![Rc::new(Object::Integer(1)), Rc::new(ints(&[1, 8]))].into(),
+                        )),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "type_of(range(1));",
+                expected: Object::String("iterator".to_string()),
+            },
+            VmTestCase {
+                input: "[len(range(0, 9223372036854775807)), len(take(rest(range(0, 9223372036854775807, 2)), 5))];",
+                expected: ints(&[9223372036854775807, 5]),
+            },
+            VmTestCase {
+                input: "to_array(range(0, 9223372036854775807));",
+                expected: Object::Error(
+                    "builtin to_array iterator has more than 16777216 elements".to_string(),
+                ),
+            },
+            VmTestCase {
+                input: "let sum = fn(it) { reduce(to_array(range(20000)), fn(acc, x) { [rest(acc[0]), acc[1] + first(acc[0])] }, [it, 0])[1] }; [sum(range(20000)), sum(take(to_array(range(20000)), 20000))];",
+                expected: ints(&[199990000, 199990000]),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_json_builtins() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: r#"json_encode({"a": [1, 2.5, true], "b": "x", 3: false});"#,
+                expected: Object::String(r#"{"a":[1,2.5,true],"b":"x","3":false}"#.to_string()),
+            },
+            VmTestCase {
+                input: r#"let h = json_decode(`{"b": [1, null], "a": 2.5}`); [keys(h), h["b"][1], h["a"]];"#,
+                expected: Object::Array(vec![
+                    Rc::new(Object::Array(vec![
+                        Rc::new(Object::String("b".to_string())),
+                        Rc::new(Object::String("a".to_string())),
+                    ].into())),
+                    Rc::new(Object::Null),
+                    Rc::new(Object::Float(2.5)),
+                ].into()),
+            },
+            VmTestCase {
+                input: "json_decode(json_encode([1, [2, 3]]))[1][0];",
+                expected: Object::Integer(2),
+            },
+            VmTestCase {
+                input: "json_decode(json_encode(9223372036854775807 * 10));",
+                expected: Object::BigInt("92233720368547758070".parse().unwrap()),
+            },
+            VmTestCase {
+                input: "json_encode([fn(x) { x }]);",
+                expected: Object::Error(
+                    "builtin json_encode cannot encode [closure function]".to_string(),
+                ),
+            },
+            VmTestCase {
+                input: "json_decode(`[1,`);",
+                expected: Object::Error(
+                    "builtin json_decode got invalid JSON: EOF while parsing a value at line 1 column 3"
+                        .to_string(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_random_builtins() {
+        run_vm_tests(vec![
+            VmTestCase {
+                input: "seed(42); [random(100), random(100), random(100)];",
+                expected: ints(&[13, 91, 58]),
+            },
+            VmTestCase {
+                input: "[random(1000), random(1000)];",
+                expected: ints(&[535, 700]),
+            },
+            VmTestCase {
+                input: "let f = fn() { random(1000) }; seed(9); let a = f(); seed(9); a == random(1000);",
+                expected: Object::Boolean(true),
+            },
+            VmTestCase {
+                input: "random(0);",
+                expected: Object::Error(
+                    "builtin random expected a positive bound, got 0".to_string(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn puts_writes_to_the_vm_output() {
+        use crate::compiler::Compiler;
+        use crate::vm::VM;
+        use object::output::Output;
+        use parser::parse;
+        use std::cell::RefCell;
+
+        let input = r#"class P { toString() { "p" } }
+puts(1, "two", new P());
+map([3], puts);
+print(4)"#;
+        let bytecode = Compiler::new().compile(&parse(input).unwrap()).unwrap();
+        let output = Rc::new(RefCell::new(String::new()));
+        let mut vm = VM::new(bytecode);
+        vm.set_output(Output::shared(&output));
+        vm.run();
+        assert_eq!(output.borrow().as_str(), "1\ntwo\np\n3\n4\n");
+        assert_eq!(vm.last_popped_stack_elm(), Some(Rc::new(Object::Null)));
+    }
+
+    #[test]
+    fn generators_keep_their_frame_between_yields() {
+        use crate::compiler::Compiler;
+        use crate::vm::VM;
+        use parser::parse;
+
+        let drain = "let drain = fn(g, acc) { let step = next(g); if (step[\"done\"]) { acc } else { drain(g, push(acc, step[\"value\"])) } };";
+        let tests = [
+            (
+                "let count = fn*(n) { yield n; yield n + 1; n + 2 }; let g = count(1); [next(g), next(g), next(g), next(g)]",
+                "[[value: 1, done: false], [value: 2, done: false], [value: 3, done: true], [value: null, done: true]]",
+            ),
+            (
+                "let sum = fn*() { let a = yield 0; let b = yield a * 2; return a + b; }; let g = sum(); next(g, 100); [next(g, 5)[\"value\"], next(g, 7)[\"value\"]]",
+                "[10, 12]",
+            ),
+            (
+                "let light = fn*(go) { if (go) { yield \"green\"; yield \"yellow\"; } else { yield \"blink\"; } yield \"red\"; }; [drain(light(true), []), drain(light(false), [])]",
+                "[[green, yellow, red], [blink, red]]",
+            ),
+            (
+                "let pair = fn*(a) { yield a; yield a + 1; \"pair\" }; let both = fn*() { let last = yield* pair(1); yield last; yield* pair(10); }; drain(both(), [])",
+                "[1, 2, pair, 10, 11]",
+            ),
+            (
+                "let from = fn*(n) { yield n; return yield* from(n + 1); }; let g = from(0); let skip = fn(n) { if (n == 0) { next(g) } else { next(g); skip(n - 1) } }; skip(500)[\"value\"]",
+                "500",
+            ),
+            (
+                "let deep = fn*(n) { if (n == 0) { yield \"bottom\"; return 0; } let below = yield* deep(n - 1); below + 1 }; let g = deep(2000); [next(g)[\"value\"], next(g)[\"value\"]]",
+                "[bottom, 2000]",
+            ),
+            ("let empty = fn*() {}; next(empty(), 5)", "[value: null, done: true]"),
+            ("let g = fn*() { yield 1 }; [type_of(g), type_of(g())]", "[closure, generator]"),
+            ("next(1)", "builtin next not supported for type 1"),
+            (
+                "let g = fn*() { yield 1 }; [take(g(), 1), to_array(g()), first(g()), len(g())]",
+                "[builtin take not supported for type [generator], builtin to_array not supported for type [generator], builtin first not supported for for type [generator], builtin len not supported for for type [generator]]",
+            ),
+            ("let g = fn*() { yield* 1 }; next(g())", "yield* expected a generator, got 1"),
+            (
+                "class Holder {} let holder = new Holder(); let g = fn*() { yield next(holder.it) }; holder.it = g(); next(holder.it)[\"value\"]",
+                "generator is already running",
+            ),
+        ];
+        for (input, expected) in tests {
+            let program = parse(&format!("{}{}", drain, input)).unwrap();
+            let mut vm = VM::new(Compiler::new().compile(&program).unwrap());
+            vm.run();
+            assert_eq!(vm.last_popped_stack_elm().unwrap().to_string(), expected, "{}", input);
+        }
+    }
 }
@@ -25,8 +25,7 @@ pub fn run_vm_tests(tests: Vec<VmTestCase>) {
 
 #[cfg(test)]
 mod tests {
-    use object::Object;
-    use std::collections::HashMap;
+    use object::{Object, OrderedMap};
     use std::panic::{catch_unwind, AssertUnwindSafe};
     use std::rc::Rc;
 
@@ -344,6 +343,75 @@ mod tests {
         run_vm_tests(tests);
     }
 
+    #[test]
+    fn test_float_arithmetic() {
+        let cases = [
+            ("2.5", 2.5),
+            ("-0.5", -0.5),
+            ("0.5 + 0.25", 0.75),
+            ("1 + 0.5", 1.5),
+            ("7 / 2.0", 3.5),
+            ("2.0 * 3 - 1", 5.0),
+        ];
+        let tests = cases
+            .iter()
+            .map(|(input, expected)| VmTestCase {
+                input,
+                expected: Object::Float(*expected),
+            })
+            .collect();
+        run_vm_tests(tests);
+
+        run_vm_tests(vec![
+            VmTestCase {
+                input: "1 == 1.0",
+                expected: Object::Boolean(true),
+            },
+            VmTestCase {
+                input: "0.5 < 1",
+                expected: Object::Boolean(true),
+            },
+            VmTestCase {
+                input: "2 > 2.5",
+                expected: Object::Boolean(false),
+            },
+        ]);
+
+        // Equality is numeric, so pin the variants: int op int stays int.
+        for (input, expected) in [("7 / 2", "Integer(3)"), ("3 * 1.0", "Float(3.0)")] {
+            let mut compiler = Compiler::new();
+            let mut vm = VM::new(compiler.compile(&parse(input).unwrap()).unwrap());
+            vm.run();
+            assert_eq!(format!("{:?}", vm.last_popped_stack_elm().unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn test_integer_overflow_promotes() {
+        let cases = [
+            ("9223372036854775807 + 1", "BigInt(9223372036854775808)"),
+            ("-9223372036854775808 - 1", "BigInt(-9223372036854775809)"),
+            ("-(-9223372036854775808)", "BigInt(9223372036854775808)"),
+            ("-9223372036854775808 / -1", "BigInt(9223372036854775808)"),
+            ("4294967296 * 4294967296", "BigInt(18446744073709551616)"),
+            // results that fit again narrow back to a plain integer
+            ("(9223372036854775807 + 1) - 1", "Integer(9223372036854775807)"),
+            ("-9223372036854775808", "Integer(-9223372036854775808)"),
+            ("100000000000000000000 > 9223372036854775807", "Boolean(true)"),
+            ("{18446744073709551616: 1}[4294967296 * 4294967296]", "Integer(1)"),
+        ];
+        for (input, expected) in cases {
+            let mut compiler = Compiler::new();
+            let mut vm = VM::new(compiler.compile(&parse(input).unwrap()).unwrap());
+            vm.run();
+            assert_eq!(
+                format!("{:?}", vm.last_popped_stack_elm().unwrap()),
+                expected,
+                "input: {input}"
+            );
+        }
+    }
+
     #[test]
     fn test_strings() {
         let tests = vec![
@@ -364,13 +432,34 @@ mod tests {
         run_vm_tests(tests);
     }
 
+    #[test]
+    fn test_template_literals() {
+        let tests = vec![
+            VmTestCase {
+                input: "``",
+                expected: Object::String("".to_string()),
+            },
+            VmTestCase {
+                input: "let n = 3; `n = ${n}!`",
+                expected: Object::String("n = 3!".to_string()),
+            },
+            VmTestCase {
+                input: r#"`${1 + 1}${true}${[1, "a"]}${if (false) { 1 }}${2.5}`"#,
+                expected: Object::String("2true[1, a]null2.5".to_string()),
+            },
+            VmTestCase {
+                input: r#"let f = fn(x) { `<${x}>` }; `${f(`${f("a")}`)}`"#,
+                expected: Object::String("<<a>>".to_string()),
+            },
+        ];
+
+        run_vm_tests(tests);
+    }
+
     #[test]
     fn test_arrays() {
         fn map_vec_to_object(vec: Vec<i64>) -> Object {
-            let array = vec
-                .iter()
-                .map(|i| Rc::new(Object::Integer(*i)))
-                .collect::<Vec<Rc<Object>>>();
+            let array = vec.iter().map(|i| Rc::new(Object::Integer(*i))).collect();
             return Object::Array(array);
         }
         let tests = vec![
@@ -395,7 +484,7 @@ mod tests {
     fn test_hash() {
         #[allow(clippy::mutable_key_type)]
         fn map_vec_to_object(vec: Vec<(i64, i64)>) -> Object {
-            let hash = vec.iter().fold(HashMap::new(), |mut acc, (k, v)| {
+            let hash = vec.iter().fold(OrderedMap::new(), |mut acc, (k, v)| {
                 acc.insert(Rc::new(Object::Integer(*k)), Rc::new(Object::Integer(*v)));
                 acc
             });
@@ -404,7 +493,7 @@ mod tests {
         let tests = vec![
             VmTestCase {
                 input: "{}",
-                expected: Object::Hash(HashMap::new()),
+                expected: Object::Hash(OrderedMap::new()),
             },
             VmTestCase {
                 input: "{1: 2, 2: 3}",
@@ -540,9 +629,54 @@ mod tests {
                 input: "class Trace { constructor() { this.order = 0; } mark(value) { this.order = this.order * 10 + value; value; } target() { this.mark(1); this; } } class Pair { constructor(left, right) { this.value = left + right; } } let trace = new Trace(); trace.target().value = trace.mark(2); let pair = new Pair(trace.mark(3), trace.mark(4)); trace.order;",
                 expected: Object::Integer(1234),
             },
+            VmTestCase {
+                input: "class Point { x: int; y: int | null; constructor(x: int) { this.x = x; } sum(): int { this.x + 1; } } let make = fn(x: int): Point { new Point(x) }; let point: Point = make(41); point.sum();",
+                expected: Object::Integer(42),
+            },
         ]);
     }
 
+    #[test]
+    fn test_operator_special_methods() {
+        let vector = "class Vec { constructor(x, y) { this.x = x; this.y = y; } add(o) { new Vec(this.x + o.x, this.y + o.y) } div(k) { new Vec(this.x / k, this.y / k) } eq(o) { if (this.x == o.x) { this.y == o.y } else { false } } get(i) { if (i == 0) { this.x } else { this.y } } toString() { `(${this.x}, ${this.y})` } }";
+        let cases = [
+            (
+                format!("{} `${{new Vec(1, 2) + new Vec(3, 4)}}`", vector),
+                Object::String("(4, 6)".to_string()),
+            ),
+            (format!("{} (new Vec(4, 8) / 2)[1]", vector), Object::Integer(4)),
+            (format!("{} new Vec(1, 2) == new Vec(1, 2)", vector), Object::Boolean(true)),
+            (format!("{} new Vec(1, 2) != new Vec(1, 2)", vector), Object::Boolean(false)),
+            (
+                format!("{} let v = new Vec(7, 9); [v[0], v[1]]", vector),
+                Object::Array(
+                    vec![Rc::new(Object::Integer(7)), Rc::new(Object::Integer(9))].into(),
+                ),
+            ),
+            (
+                format!("{} let f = fn(v) {{ v + v }}; f(new Vec(1, 1))[0] + 1", vector),
+                Object::Integer(3),
+            ),
+            ("class A {} `${new A()}`".to_string(), Object::String("[object A]".to_string())),
+            (format!("{} str(new Vec(1, 2))", vector), Object::String("(1, 2)".to_string())),
+        ];
+
+        for (input, expected) in cases {
+            run_vm_tests(vec![VmTestCase {
+                input: &input,
+                expected,
+            }]);
+        }
+        assert_eq!(
+            vm_panic_message("class M { sub() { 0 } } new M() - 1;"),
+            "wrong number of arguments for M.sub: want=0, got=1"
+        );
+        assert_eq!(
+            vm_panic_message("class Spin { add(o) { this + o } } new Spin() + 1;"),
+            "special method 'add' nested too deeply"
+        );
+    }
+
     #[test]
     fn class_runtime_errors_use_user_visible_arity() {
         let cases = [
@@ -562,11 +696,51 @@ mod tests {
             ("let factory = fn() {}; new factory();", "cannot construct [closure function]"),
             ("class Empty {} new Empty().missing;", "property 'missing' does not exist on Empty"),
             ("1.value;", "cannot read property 'value' of 1"),
+            ("\"abc\".keys;", "cannot read property 'keys' of abc"),
             ("1.value = 2;", "cannot set property 'value' of 1"),
+            ("1 / 0;", "division by zero"),
         ];
 
         for (input, expected) in cases {
             assert_eq!(vm_panic_message(input), expected, "input: {input}");
         }
     }
+
+    #[test]
+    fn test_host_registered_builtins() {
+        use crate::snapshot::{read_bytecode, write_bytecode};
+        use object::registry::{Arity, Builtins};
+        use std::cell::Cell;
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        let mut builtins = Builtins::standard();
+        builtins.register("tick", Arity::exactly(0), "Counts calls from the host.", move |_, _| {
+            counter.set(counter.get() + 1);
+            return Ok(Rc::new(Object::Integer(counter.get())));
+        });
+
+        let program = parse("tick(); let f = fn() { tick() }; [f(), tick(1)]").unwrap();
+        let bytecode = Compiler::with_builtins(&builtins)
+            .compile(&program)
+            .unwrap();
+        let blob = write_bytecode(&bytecode, false).unwrap();
+
+        let mut vm = VM::with_builtins(bytecode, &builtins);
+        vm.run();
+        let expected = Object::Array(
+            vec![
+                Rc::new(Object::Integer(2)),
+                Rc::new(Object::Error("builtin tick expected 0 arguments, got 1".to_string())),
+            ]
+            .into(),
+        );
+        assert_eq!(vm.last_popped_stack_elm().unwrap().as_ref(), &expected);
+        assert_eq!(calls.get(), 2);
+
+        // A loaded snapshot finds `tick` by name, and only when it is called.
+        let mut vm = VM::new(read_bytecode(&blob).unwrap());
+        let panic = catch_unwind(AssertUnwindSafe(|| vm.run())).expect_err("VM should panic");
+        assert_eq!(panic.downcast_ref::<String>().unwrap(), "builtin tick is not registered");
+    }
 }
@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 
 use compiler::snapshot::read_bytecode;
 
-use super::{compile_command, run_command};
+use super::{check_command, compile_command, run_command};
 
 /// Scratch file under a shared temp directory. Tests run in parallel, so
 /// every test uses file names unique to itself.
@@ -123,3 +123,31 @@ fn missing_input_files_fail_with_code_one() {
     let error = run_command(&[arg(&missing)]).unwrap_err();
     assert_eq!(error.exit_code, 1);
 }
+
+#[test]
+fn check_refuses_compiled_bytecode() {
+    let source = write_source("typed-bad.monkey", "let x = 1;\nlet s: string = x;\ns");
+    assert_eq!(check_command(&[arg(&source)]).unwrap_err().exit_code, 1);
+    // Annotations never change execution.
+    assert_eq!(run_command(&[arg(&source)]).unwrap(), "1");
+
+    let error = check_command(&["foo.mbc".to_string()]).unwrap_err();
+    assert_eq!(error.exit_code, 2);
+    assert_eq!(error.message, "error: foo.mbc is compiled bytecode, not source");
+}
+
+#[test]
+fn arguments_after_the_separator_reach_the_script() {
+    let source = write_source("args.monkey", "[len(args), args[1]]");
+    let script_args = ["--".to_string(), "-v".to_string(), "two".to_string()];
+    let direct = run_command(&[&[arg(&source)], &script_args[..]].concat()).unwrap();
+    assert_eq!(direct, "[2, two]");
+
+    let output = scratch_path("args.mbc");
+    compile_command(&[arg(&source), "-o".to_string(), arg(&output)]).unwrap();
+    let compiled = run_command(&[&[arg(&output)], &script_args[..]].concat()).unwrap();
+    assert_eq!(compiled, "[2, two]");
+
+    let no_args = run_command(&[arg(&output)]).unwrap();
+    assert_eq!(no_args, "[0, null]");
+}
@@ -1,12 +1,13 @@
 use compiler::compiler::{Bytecode, Compiler};
 use compiler::snapshot::{read_bytecode, write_bytecode};
 use compiler::symbol_table::SymbolTable;
-use gc::runner::{compile_source, run_bytecode};
+use gc::runner::{compile_script, run_script};
+use gc::value::value_to_string;
 use gc::{GcRuntimeError, GcVM};
 use object::Object;
 use parser::parse;
-use std::io::stdin;
-use std::io::{self, Write};
+use repl::cli::{self, CliError};
+use repl::Backend;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
@@ -14,7 +15,8 @@ const USAGE: &str = "\
 usage:
   monkey-gc                                                start the REPL
   monkey-gc compile <file.monkey> [-o <file.mbc>] [--strip]
-  monkey-gc run <file.monkey|file.mbc> [--max-instructions <n>]";
+  monkey-gc run <file.monkey|file.mbc> [--max-instructions <n>] [-- <args>...]
+  monkey-gc check <file.monkey>                            type check annotations";
 
 struct Repl {
     symbol_table: SymbolTable,
@@ -69,77 +71,74 @@ fn main() {
     let outcome = match command.as_str() {
         "compile" => compile_command(rest).map(|()| None),
         "run" => run_command(rest).map(Some),
+        "check" => check_command(rest).map(|()| None),
         other => Err(CliError::usage(format!("unknown command `{}`", other))),
     };
-    match outcome {
-        Ok(Some(result)) => println!("{}", result),
-        Ok(None) => {}
-        Err(error) => {
-            eprintln!("{}", error.message);
-            std::process::exit(error.exit_code);
-        }
-    }
+    cli::finish(outcome, USAGE);
 }
 
 fn repl() {
-    println!("Welcome to monkey gc by gengjiawen");
-    let mut repl = Repl::new();
-
-    loop {
-        print!("> ");
-        io::stdout().flush().unwrap();
-        let mut input = String::new();
-        stdin().read_line(&mut input).unwrap();
-
-        if input.trim_end().is_empty() {
-            println!("bye");
-            std::process::exit(0);
-        }
+    repl::run("Welcome to monkey gc by gengjiawen", repl::history_file("monkey-gc"), Repl::new());
+}
 
-        match repl.eval_line(&input) {
-            Ok(result) => println!("{}", result),
-            Err(error) => eprintln!("{}", error),
-        }
+impl Backend for Repl {
+    fn eval(&mut self, source: &str) -> Result<String, String> {
+        self.eval_line(source)
     }
-}
 
-#[derive(Debug)]
-struct CliError {
-    exit_code: i32,
-    message: String,
-}
+    fn reset(&mut self) {
+        *self = Repl::new();
+    }
 
-impl CliError {
-    /// Bad invocation: report the problem plus usage, exit code 2.
-    fn usage(message: impl std::fmt::Display) -> Self {
-        CliError {
-            exit_code: 2,
-            message: format!("error: {}\n{}", message, USAGE),
-        }
+    fn env(&self) -> Vec<(String, String)> {
+        self.symbol_table
+            .global_symbols()
+            .into_iter()
+            .filter_map(|(name, index)| {
+                let value = self.vm.global(index)?;
+                Some((name, value_to_string(self.vm.heap(), value)))
+            })
+            .collect()
+    }
+
+    fn bytecode(&self, source: &str) -> Option<Result<String, String>> {
+        let program = match parse(source) {
+            Ok(program) => program,
+            Err(errors) => return Some(Err(format!("parse error: {}", errors[0]))),
+        };
+        let mut compiler =
+            Compiler::new_with_state(self.symbol_table.clone(), self.constants.clone());
+        Some(compiler.compile(&program).map(|bytecode| bytecode.string()))
     }
 
-    /// Operational failure (I/O, parse, compile, bad snapshot): exit code 1.
-    fn failure(message: impl std::fmt::Display) -> Self {
-        CliError {
-            exit_code: 1,
-            message: format!("error: {}", message),
+    fn heap(&self) -> Option<String> {
+        let snapshot = self.vm.heap().snapshot();
+        let mut lines = vec![format!(
+            "{} objects, {} bytes tracked",
+            snapshot.object_count, snapshot.tracked_bytes
+        )];
+        for (kind, count) in snapshot.by_value_kind {
+            if count > 0 {
+                lines.push(format!("  {:?}: {}", kind, count));
+            }
         }
+        Some(lines.join("\n"))
     }
+}
 
-    fn runtime(error: &GcRuntimeError) -> Self {
-        // Spans inside an .mbc file are untrusted integers, so they are
-        // printed numerically and never used to slice source text.
-        let message = match &error.span {
-            Some(span) => format!(
-                "runtime error: {} (source offset {}..{})",
-                error.message, span.start, span.end
-            ),
-            None => format!("runtime error: {}", error.message),
-        };
-        CliError {
-            exit_code: 1,
-            message,
+/// A runtime error: exit code 1, with the span as plain offsets.
+fn runtime_error(error: &GcRuntimeError) -> CliError {
+    // Spans inside an .mbc file are untrusted integers, so they are
+    // printed numerically and never used to slice source text.
+    let message = match &error.span {
+        Some(span) => {
+            format!("runtime error: {} (source offset {}..{})", error.message, span.start, span.end)
         }
+        None => format!("runtime error: {}", error.message),
+    };
+    CliError {
+        exit_code: 1,
+        message,
     }
 }
 
@@ -169,7 +168,7 @@ fn compile_command(args: &[String]) -> Result<(), CliError> {
     let source = std::fs::read_to_string(&input).map_err(|error| {
         CliError::failure(format!("cannot read {}: {}", input.display(), error))
     })?;
-    let bytecode = compile_source(&source).map_err(CliError::failure)?;
+    let bytecode = compile_script(&source).map_err(CliError::failure)?;
     let blob = write_bytecode(&bytecode, strip_debug).map_err(|error| {
         CliError::failure(format!("cannot serialize {}: {:?}", input.display(), error))
     })?;
@@ -185,6 +184,7 @@ fn run_command(args: &[String]) -> Result<String, CliError> {
     let mut iter = args.iter();
     while let Some(arg) = iter.next() {
         match arg.as_str() {
+            "--" => break,
             "--max-instructions" => {
                 let value = iter
                     .next()
@@ -198,8 +198,19 @@ fn run_command(args: &[String]) -> Result<String, CliError> {
         }
     }
     let input = input.ok_or_else(|| CliError::usage("run needs an input file"))?;
+    let script_args: Vec<String> = iter.cloned().collect();
     let bytecode = load_bytecode(&input)?;
-    run_bytecode(bytecode, budget).map_err(|error| CliError::runtime(&error))
+    run_script(bytecode, budget, &script_args).map_err(|error| runtime_error(&error))
+}
+
+/// `check` as the other binaries have it, refusing compiled bytecode.
+fn check_command(args: &[String]) -> Result<(), CliError> {
+    if let [path] = args {
+        if has_mbc_extension(Path::new(path)) {
+            return Err(CliError::usage(format!("{} is compiled bytecode, not source", path)));
+        }
+    }
+    cli::check_command(args)
 }
 
 /// Dispatch on the file extension (design doc §7): `.mbc` goes through the
@@ -218,7 +229,7 @@ fn load_bytecode(input: &Path) -> Result<Bytecode, CliError> {
         let source = std::fs::read_to_string(input).map_err(|error| {
             CliError::failure(format!("cannot read {}: {}", input.display(), error))
         })?;
-        compile_source(&source).map_err(CliError::failure)
+        compile_script(&source).map_err(CliError::failure)
     }
 }
 
@@ -1,4 +1,5 @@
 use super::Repl;
+use repl::Backend;
 
 #[test]
 fn repl_resolves_builtins() {
@@ -39,3 +40,26 @@ fn repl_keeps_state_across_successful_lines() {
         .expect("persisted global should resolve");
     assert_eq!(result, "42");
 }
+
+#[test]
+fn repl_lists_globals_and_resets() {
+    let mut repl = Repl::new();
+    repl.eval("let xs = [1, 2];\nlet name = \"gc\";").unwrap();
+    assert_eq!(
+        repl.env(),
+        vec![
+            ("name".to_string(), "gc".to_string()),
+            ("xs".to_string(), "[1, 2]".to_string()),
+        ]
+    );
+    assert!(repl.heap().unwrap().contains("Array: 1"), "got: {:?}", repl.heap());
+    assert!(repl
+        .bytecode("xs")
+        .unwrap()
+        .unwrap()
+        .contains("OpGetGlobal"));
+
+    repl.reset();
+    assert!(repl.env().is_empty());
+    assert!(repl.eval("xs").is_err());
+}
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashSet};
 
+use object::methods::Receiver;
 use serde::Serialize;
 
 use crate::value::{Value, ValueCell, ValueKind};
@@ -15,14 +16,17 @@ pub const MAX_GLOBAL_ROOTS: usize = 500;
 
 pub use crate::value::{EdgeRelation, HashKeyKind};
 
-const VALUE_KINDS: [ValueKind; 14] = [
+const VALUE_KINDS: [ValueKind; 17] = [
     ValueKind::Class,
     ValueKind::Instance,
     ValueKind::BoundMethod,
     ValueKind::Closure,
     ValueKind::Array,
+    ValueKind::Iterator,
+    ValueKind::Generator,
     ValueKind::Hash,
     ValueKind::Integer,
+    ValueKind::Float,
     ValueKind::Boolean,
     ValueKind::String,
     ValueKind::Null,
@@ -89,7 +93,15 @@ impl EdgeRelation {
             } => 7,
             EdgeRelation::BoundMethodReceiver => 8,
             EdgeRelation::BoundMethodFunction => 9,
-            EdgeRelation::Unknown => 10,
+            EdgeRelation::IteratorSource {
+                ..
+            } => 10,
+            EdgeRelation::GeneratorClosure => 11,
+            EdgeRelation::GeneratorStack {
+                ..
+            } => 12,
+            EdgeRelation::GeneratorInner => 13,
+            EdgeRelation::Unknown => 14,
         }
     }
 
@@ -116,6 +128,14 @@ impl EdgeRelation {
             } => RelationSortKey::Name(name),
             EdgeRelation::BoundMethodReceiver => RelationSortKey::None,
             EdgeRelation::BoundMethodFunction => RelationSortKey::None,
+            EdgeRelation::IteratorSource {
+                index,
+            } => RelationSortKey::Index(*index),
+            EdgeRelation::GeneratorClosure => RelationSortKey::None,
+            EdgeRelation::GeneratorStack {
+                index,
+            } => RelationSortKey::Index(*index),
+            EdgeRelation::GeneratorInner => RelationSortKey::None,
             EdgeRelation::Unknown => RelationSortKey::None,
         }
     }
@@ -317,23 +337,26 @@ pub(crate) fn summarize_gc_object(runtime: &GcRuntime, id: GcId) -> GcObjectSumm
         Value::Instance(instance) => {
             format!("Instance({})", class_name(runtime, instance.class))
         }
-        Value::BoundMethod(method) => format!(
-            "BoundMethod({}.{})",
-            instance_class_name(runtime, method.receiver),
-            method.name
-        ),
+        Value::BoundMethod(method) => {
+            format!("BoundMethod({}.{})", receiver_name(runtime, method.receiver), method.name)
+        }
         Value::Closure(closure) => closure_name(runtime, closure.func)
             .map(|name| format!("Closure({})", name))
             .unwrap_or_else(|| "Closure".to_string()),
         Value::Array(_) => "Array".to_string(),
+        Value::Iterator(_) => "Iterator".to_string(),
+        Value::Generator(_) => "Generator".to_string(),
         Value::Hash(_) => "Hash".to_string(),
         Value::Integer(_) => "Integer".to_string(),
+        Value::BigInt(_) => "BigInt".to_string(),
+        Value::Float(_) => "Float".to_string(),
         Value::Boolean(_) => "Boolean".to_string(),
         Value::String(_) => "String".to_string(),
         Value::Null => "Null".to_string(),
         Value::Error(_) => "Error".to_string(),
         Value::CompiledFunction(_) => "CompiledFunction".to_string(),
         Value::Builtin(_) => "Builtin".to_string(),
+        Value::Native(native) => format!("Native({})", native.name),
     };
 
     GcObjectSummary {
@@ -364,11 +387,14 @@ fn closure_name(runtime: &GcRuntime, reference: GcRef) -> Option<&str> {
         })
 }
 
-fn instance_class_name(runtime: &GcRuntime, reference: GcRef) -> &str {
+fn receiver_name(runtime: &GcRuntime, reference: GcRef) -> &str {
     runtime
         .object_downcast::<ValueCell>(reference.0)
         .and_then(|cell| match &cell.value {
             Value::Instance(instance) => Some(class_name(runtime, instance.class)),
+            Value::String(_) => Some(Receiver::String.name()),
+            Value::Array(_) => Some(Receiver::Array.name()),
+            Value::Hash(_) => Some(Receiver::Hash.name()),
             _ => None,
         })
         .unwrap_or("<unknown>")
@@ -1,11 +1,14 @@
 #[cfg(test)]
 mod tests {
+    use object::generators::GeneratorState;
+    use object::iterators::Iter;
+    use object::OrderedMap;
     use std::collections::HashMap;
 
     use crate::report::{EdgeRelation, FinalFate, HashKeyKind, TrialDecision, MAX_EDGE_DETAILS};
     use crate::value::{
         alloc_value, format_hash_key_label, get_value_mut, GcBoundMethod, GcClass, GcClosure,
-        GcInstance, HashKey, Value, ValueKind, MAX_HASH_KEY_LABEL_LEN,
+        GcInstance, GcSuspension, HashKey, Value, ValueKind, MAX_HASH_KEY_LABEL_LEN,
     };
     use crate::{run_source_with_report, GcHeap, GcObject, GcRef};
 
@@ -133,11 +136,11 @@ mod tests {
     #[test]
     fn rooted_nested_array_builds_witness_chain() {
         let mut heap = GcHeap::new();
-        let leaf = alloc_value(&mut heap, Value::Array(vec![]));
+        let leaf = alloc_value(&mut heap, Value::Array(vec![].into()));
         // alloc_value dups child edges, so pass the owned handle directly.
-        let mid = alloc_value(&mut heap, Value::Array(vec![leaf]));
+        let mid = alloc_value(&mut heap, Value::Array(vec![leaf].into()));
         heap.free(leaf);
-        let root = alloc_value(&mut heap, Value::Array(vec![mid]));
+        let root = alloc_value(&mut heap, Value::Array(vec![mid].into()));
         heap.free(mid);
 
         let report = heap.run_gc_with_stats_bundle();
@@ -181,8 +184,8 @@ mod tests {
     #[test]
     fn duplicate_array_refs_count_two_incoming_edges() {
         let mut heap = GcHeap::new();
-        let target = alloc_value(&mut heap, Value::Array(vec![]));
-        let holder = alloc_value(&mut heap, Value::Array(vec![target, target]));
+        let target = alloc_value(&mut heap, Value::Array(vec![].into()));
+        let holder = alloc_value(&mut heap, Value::Array(vec![target, target].into()));
         heap.free(target);
 
         let report = heap.run_gc_with_stats_bundle();
@@ -303,7 +306,7 @@ mod tests {
         let v4 = heap.dup(value);
         let map = alloc_value(
             &mut heap,
-            Value::Hash(HashMap::from([
+            Value::Hash(OrderedMap::from([
                 (HashKey::String("b".to_string()), v1),
                 (HashKey::String("a".to_string()), v2),
                 (HashKey::Integer(1), v3),
@@ -406,8 +409,8 @@ mod tests {
         }
 
         let values = [
-            Value::Array(vec![GcRef(1), GcRef(2)]),
-            Value::Hash(HashMap::from([
+            Value::Array(vec![GcRef(1), GcRef(2)].into()),
+            Value::Hash(OrderedMap::from([
                 (HashKey::Integer(1), GcRef(3)),
                 (HashKey::String("key".to_string()), GcRef(4)),
             ])),
@@ -429,6 +432,12 @@ mod tests {
                 method: GcRef(12),
                 name: "method".to_string(),
             }),
+            Value::Iterator(Iter::Zip(GcRef(13), GcRef(14))),
+            Value::Generator(GeneratorState::Suspended(GcSuspension {
+                closure: GcRef(15),
+                ip: 3,
+                stack: vec![GcRef(16), GcRef(17)],
+            })),
         ];
 
         for value in &values {
@@ -541,7 +550,7 @@ mod tests {
         for _ in 0..(MAX_EDGE_DETAILS + 20) {
             items.push(heap.dup(target));
         }
-        let _holder = alloc_value(&mut heap, Value::Array(items));
+        let _holder = alloc_value(&mut heap, Value::Array(items.into()));
         let report = heap.run_gc_with_stats_bundle();
         assert!(report.phases.trial_deletion.edges_visited > MAX_EDGE_DETAILS);
         assert_eq!(report.phases.trial_deletion.visited_edges.len(), MAX_EDGE_DETAILS);
@@ -6,7 +6,12 @@
 //! `gc::eval_source`: exporting the final value back to an [`object::Object`]
 //! fails for class instances and drops the runtime-error `Span`.
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use compiler::compiler::{Bytecode, Compiler};
+use object::output::Output;
+use object::Object;
 
 use crate::vm::{GcClassifiedRuntimeError, GcRuntimeError, GcVM};
 
@@ -22,6 +27,41 @@ pub fn compile_source(source: &str) -> Result<Bytecode, String> {
     compiler.compile(&program)
 }
 
+/// The global slot a script compiled by [`compile_script`] reads `args` from.
+pub const ARGS_SLOT: usize = 0;
+
+/// [`compile_source`] for a script run from the command line, which sees the
+/// arguments given after `--` as the global `args`.
+pub fn compile_script(source: &str) -> Result<Bytecode, String> {
+    let program = parser::parse(source).map_err(|errors| {
+        errors
+            .first()
+            .cloned()
+            .unwrap_or_else(|| "unknown parse error".to_string())
+    })?;
+    let mut compiler = Compiler::new();
+    let args = compiler.symbol_table.define("args".to_string());
+    debug_assert_eq!(args.index, ARGS_SLOT);
+    compiler.compile(&program)
+}
+
+/// [`run_bytecode`] with `args` bound to `script_args`. Bytecode compiled
+/// without `args` writes that slot before it reads it, so it runs the same.
+pub fn run_script(
+    bytecode: Bytecode,
+    instruction_budget: usize,
+    script_args: &[String],
+) -> Result<String, GcRuntimeError> {
+    let mut vm = GcVM::new(bytecode);
+    let script_args = script_args
+        .iter()
+        .map(|arg| Rc::new(Object::String(arg.clone())))
+        .collect();
+    vm.set_global(ARGS_SLOT, &Object::Array(script_args));
+    vm.run_with_budget(instruction_budget)?;
+    Ok(vm.last_result_string())
+}
+
 /// Execute bytecode on a fresh VM and render the final popped value the way
 /// the REPL does. `instruction_budget` is `usize::MAX` for normal runs; the
 /// CLI's `--max-instructions` threads a finite budget through here.
@@ -40,11 +80,12 @@ pub fn run_bytecode_with_output(
     bytecode: Bytecode,
     instruction_budget: usize,
 ) -> (Result<String, GcClassifiedRuntimeError>, String) {
+    let output = Rc::new(RefCell::new(String::new()));
     let mut vm = GcVM::new(bytecode);
-    vm.set_capture_output(true);
+    vm.set_output(Output::shared(&output));
     let result = vm
         .run_with_budget_classified(instruction_budget)
         .map(|()| vm.last_result_string());
-    let output = vm.take_output();
+    let output = output.take();
     (result, output)
 }
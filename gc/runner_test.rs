@@ -1,9 +1,11 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
 use compiler::compiler::{Bytecode, DebugInfo};
 use compiler::op_code::{Instructions, Opcode};
 use compiler::snapshot::{read_bytecode, write_bytecode};
+use object::output::Output;
 use object::Object;
 
 use crate::runner::{compile_source, run_bytecode, run_bytecode_with_output};
@@ -74,15 +76,16 @@ fn captured_output_survives_a_later_runtime_error() {
 }
 
 #[test]
-fn taking_output_keeps_capture_enabled() {
+fn output_sink_outlives_loaded_programs() {
+    let output = Rc::new(RefCell::new(String::new()));
     let mut vm = GcVM::new(compile_source(r#"puts("one")"#).unwrap());
-    vm.set_capture_output(true);
+    vm.set_output(Output::shared(&output));
     vm.run_with_budget(usize::MAX).unwrap();
-    assert_eq!(vm.take_output(), "one\n");
+    assert_eq!(output.take(), "one\n");
 
     vm.load_bytecode(compile_source(r#"puts("two")"#).unwrap());
     vm.run_with_budget(usize::MAX).unwrap();
-    assert_eq!(vm.take_output(), "two\n");
+    assert_eq!(output.take(), "two\n");
 }
 
 #[test]
@@ -108,6 +111,7 @@ fn hostile_bytecode(instructions: Vec<u8>, constants: Vec<Rc<Object>>) -> Byteco
         constants,
         debug_info: DebugInfo::default(),
         function_debug_info: HashMap::new(),
+        builtins: Vec::new(),
     }
 }
 
@@ -3,8 +3,21 @@ use std::fmt;
 use std::fmt::Write as _;
 use std::rc::Rc;
 
-use object::builtins::{BuiltIns, BuiltinId};
-use object::{Closure, CompiledFunction, Object};
+use object::builtins::{builtin_id, json_object, BuiltIns, BuiltinError, BuiltinId};
+use object::generators::GeneratorState;
+use object::hashes::{self, HashArg, HashResult};
+use object::higher_order::ValueHost;
+use object::integer::Int;
+use object::iterators::{self, Iter, IterHost, IterSource};
+use object::json::{self, JsonArg, JsonView};
+use object::math::{self, NumArg, NumResult};
+use object::methods::Receiver;
+use object::output::{OutputSink, StdoutSink};
+use object::random::{self, Random};
+use object::reflect::{self, ReflectArg, ReflectResult, TypeOf};
+use object::registry::NativeBuiltin;
+use object::strings::{self, TextArg, TextResult};
+use object::{format_float, BigInt, Closure, CompiledFunction, Object, OrderedMap, Vector};
 use serde::Serialize;
 
 use crate::header::GcObjectType;
@@ -14,18 +27,49 @@ use crate::{GcHeap, GcObject, GcRef};
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Integer(i64),
+    // only ever holds values outside the `i64` range, see `object::integer`
+    BigInt(BigInt),
+    Float(f64),
     Boolean(bool),
     String(String),
-    Array(Vec<GcRef>),
-    Hash(HashMap<HashKey, GcRef>),
+    /// Persistent like `Object::Array`, but each array still holds its own
+    /// reference to every element, so copying one into a new array shares
+    /// the structure and only bumps the elements' counts.
+    Array(Vector<GcRef>),
+    /// Entries in insertion order, like `Object::Hash`.
+    Hash(OrderedMap<HashKey, GcRef>),
     Null,
     Error(String),
     CompiledFunction(CompiledFunction),
     Closure(GcClosure),
     Builtin(BuiltinId),
+    /// A host builtin from the VM's registry.
+    Native(Rc<NativeBuiltin>),
     Class(GcClass),
     Instance(GcInstance),
     BoundMethod(GcBoundMethod),
+    /// A lazy sequence, see `object::iterators`.
+    Iterator(Iter<GcRef>),
+    /// A generator, see `object::generators`.
+    Generator(GeneratorState<GcSuspension, GcRef>),
+}
+
+/// Where a generator's frame stopped: the closure value from its callee
+/// slot, its instruction pointer, and the stack from its first local up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcSuspension {
+    pub closure: GcRef,
+    pub ip: i32,
+    pub stack: Vec<GcRef>,
+}
+
+impl GcSuspension {
+    fn for_each_ref(&self, mut visit: impl FnMut(GcRef)) {
+        visit(self.closure);
+        for value in &self.stack {
+            visit(*value);
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -62,8 +106,11 @@ pub enum ValueKind {
     BoundMethod,
     Closure,
     Array,
+    Iterator,
+    Generator,
     Hash,
     Integer,
+    Float,
     Boolean,
     String,
     Null,
@@ -99,6 +146,14 @@ pub enum EdgeRelation {
     },
     BoundMethodReceiver,
     BoundMethodFunction,
+    IteratorSource {
+        index: usize,
+    },
+    GeneratorClosure,
+    GeneratorStack {
+        index: usize,
+    },
+    GeneratorInner,
     Unknown,
 }
 
@@ -115,6 +170,7 @@ pub const MAX_HASH_KEY_LABEL_LEN: usize = 64;
 pub fn format_hash_key_label(key: &HashKey) -> String {
     match key {
         HashKey::Integer(value) => value.to_string(),
+        HashKey::BigInt(value) => value.to_string(),
         HashKey::Boolean(value) => value.to_string(),
         HashKey::String(value) => escape_and_truncate_key(value),
     }
@@ -150,6 +206,7 @@ fn escape_and_truncate_key(value: &str) -> String {
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum HashKey {
     Integer(i64),
+    BigInt(BigInt),
     Boolean(bool),
     String(String),
 }
@@ -157,7 +214,7 @@ pub enum HashKey {
 impl HashKey {
     pub fn kind(&self) -> HashKeyKind {
         match self {
-            HashKey::Integer(_) => HashKeyKind::Integer,
+            HashKey::Integer(_) | HashKey::BigInt(_) => HashKeyKind::Integer,
             HashKey::Boolean(_) => HashKeyKind::Boolean,
             HashKey::String(_) => HashKeyKind::String,
         }
@@ -183,13 +240,17 @@ impl Value {
             Value::Closure(_) => ValueKind::Closure,
             Value::Array(_) => ValueKind::Array,
             Value::Hash(_) => ValueKind::Hash,
-            Value::Integer(_) => ValueKind::Integer,
+            Value::Iterator(_) => ValueKind::Iterator,
+            Value::Generator(_) => ValueKind::Generator,
+            // a BigInt is still an integer to the program; it only differs in storage
+            Value::Integer(_) | Value::BigInt(_) => ValueKind::Integer,
+            Value::Float(_) => ValueKind::Float,
             Value::Boolean(_) => ValueKind::Boolean,
             Value::String(_) => ValueKind::String,
             Value::Null => ValueKind::Null,
             Value::Error(_) => ValueKind::Error,
             Value::CompiledFunction(_) => ValueKind::CompiledFunction,
-            Value::Builtin(_) => ValueKind::Builtin,
+            Value::Builtin(_) | Value::Native(_) => ValueKind::Builtin,
         }
     }
 
@@ -266,15 +327,46 @@ impl Value {
                 visit(EdgeRelation::BoundMethodReceiver, method.receiver);
                 visit(EdgeRelation::BoundMethodFunction, method.method);
             }
+            Value::Iterator(iter) => {
+                let mut index = 0;
+                iter.for_each_source(|source| {
+                    visit(
+                        EdgeRelation::IteratorSource {
+                            index,
+                        },
+                        *source,
+                    );
+                    index += 1;
+                });
+            }
+            Value::Generator(state) => {
+                if let GeneratorState::Suspended(suspension)
+                | GeneratorState::Delegating(suspension, _) = state
+                {
+                    visit(EdgeRelation::GeneratorClosure, suspension.closure);
+                    for (index, value) in suspension.stack.iter().enumerate() {
+                        visit(
+                            EdgeRelation::GeneratorStack {
+                                index,
+                            },
+                            *value,
+                        );
+                    }
+                }
+                state.for_each_inner(|inner| visit(EdgeRelation::GeneratorInner, *inner));
+            }
             // Leaf variants own no GcRef. No catch-all: adding a Value variant
             // must fail to compile until its edges are classified here.
             Value::Integer(_)
+            | Value::BigInt(_)
+            | Value::Float(_)
             | Value::Boolean(_)
             | Value::String(_)
             | Value::Null
             | Value::Error(_)
             | Value::CompiledFunction(_)
-            | Value::Builtin(_) => {}
+            | Value::Builtin(_)
+            | Value::Native(_) => {}
         }
     }
 
@@ -314,23 +406,43 @@ impl Value {
                 visit(method.receiver);
                 visit(method.method);
             }
+            Value::Iterator(iter) => iter.for_each_source(|source| visit(*source)),
+            Value::Generator(state) => {
+                if let GeneratorState::Suspended(suspension)
+                | GeneratorState::Delegating(suspension, _) = state
+                {
+                    suspension.for_each_ref(&mut *visit);
+                }
+                state.for_each_inner(|inner| visit(*inner));
+            }
             // Leaf variants own no GcRef. No catch-all: adding a Value variant
             // must fail to compile until its edges are classified here.
             Value::Integer(_)
+            | Value::BigInt(_)
+            | Value::Float(_)
             | Value::Boolean(_)
             | Value::String(_)
             | Value::Null
             | Value::Error(_)
             | Value::CompiledFunction(_)
-            | Value::Builtin(_) => {}
+            | Value::Builtin(_)
+            | Value::Native(_) => {}
         }
     }
 
     pub fn with_owned_edges(self, heap: &mut GcHeap) -> Self {
         match self {
-            Value::Array(items) => Value::Array(items.into_iter().map(|r| heap.dup(r)).collect()),
+            Value::Array(items) => {
+                for item in items.iter() {
+                    heap.dup(*item);
+                }
+                Value::Array(items)
+            }
             Value::Hash(map) => {
-                Value::Hash(map.into_iter().map(|(k, v)| (k, heap.dup(v))).collect())
+                for value in map.values() {
+                    heap.dup(*value);
+                }
+                Value::Hash(map)
             }
             Value::Closure(mut closure) => {
                 closure.func = heap.dup(closure.func);
@@ -360,10 +472,51 @@ impl Value {
                 method.method = heap.dup(method.method);
                 Value::BoundMethod(method)
             }
+            Value::Iterator(iter) => Value::Iterator(iter.map_sources(|source| heap.dup(*source))),
+            Value::Generator(state) => {
+                if let GeneratorState::Suspended(suspension)
+                | GeneratorState::Delegating(suspension, _) = &state
+                {
+                    suspension.for_each_ref(|reference| {
+                        heap.dup(reference);
+                    });
+                }
+                state.for_each_inner(|inner| {
+                    heap.dup(*inner);
+                });
+                Value::Generator(state)
+            }
             other => other,
         }
     }
 
+    /// Both operands as `f64` when at least one is a float and the other is
+    /// a number; mirrors `object::float_operands`.
+    pub fn float_operands(left: &Value, right: &Value) -> Option<(f64, f64)> {
+        match (left, right) {
+            (Value::Float(l), Value::Float(r)) => Some((*l, *r)),
+            (Value::Float(l), r) => Some((*l, r.as_int()?.to_f64())),
+            (l, Value::Float(r)) => Some((l.as_int()?.to_f64(), *r)),
+            _ => None,
+        }
+    }
+
+    /// The value of an `Integer` or `BigInt`, for `object::integer`.
+    pub fn as_int(&self) -> Option<Int> {
+        match self {
+            Value::Integer(value) => Some(Int::Small(*value)),
+            Value::BigInt(value) => Some(Int::Big(value.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn from_int(value: Int) -> Value {
+        match value {
+            Int::Small(value) => Value::Integer(value),
+            Int::Big(value) => Value::BigInt(value),
+        }
+    }
+
     pub fn edge_refs(&self) -> Vec<GcRef> {
         let mut refs = Vec::new();
         self.trace(&mut |reference| refs.push(reference));
@@ -375,6 +528,7 @@ impl HashKey {
     pub fn from_object(object: &Object) -> Option<HashKey> {
         match object {
             Object::Integer(i) => Some(HashKey::Integer(*i)),
+            Object::BigInt(i) => Some(HashKey::BigInt(i.clone())),
             Object::Boolean(b) => Some(HashKey::Boolean(*b)),
             Object::String(s) => Some(HashKey::String(s.clone())),
             _ => None,
@@ -384,15 +538,26 @@ impl HashKey {
     pub fn from_value(value: &Value) -> Option<HashKey> {
         match value {
             Value::Integer(i) => Some(HashKey::Integer(*i)),
+            Value::BigInt(i) => Some(HashKey::BigInt(i.clone())),
             Value::Boolean(b) => Some(HashKey::Boolean(*b)),
             Value::String(s) => Some(HashKey::String(s.clone())),
             _ => None,
         }
     }
 
+    pub fn to_value(&self) -> Value {
+        match self {
+            HashKey::Integer(i) => Value::Integer(*i),
+            HashKey::BigInt(i) => Value::BigInt(i.clone()),
+            HashKey::Boolean(b) => Value::Boolean(*b),
+            HashKey::String(s) => Value::String(s.clone()),
+        }
+    }
+
     pub fn to_object(&self) -> Object {
         match self {
             HashKey::Integer(i) => Object::Integer(*i),
+            HashKey::BigInt(i) => Object::BigInt(i.clone()),
             HashKey::Boolean(b) => Object::Boolean(*b),
             HashKey::String(s) => Object::String(s.clone()),
         }
@@ -441,6 +606,8 @@ fn format_reference(heap: &GcHeap, reference: GcRef, visited: &mut HashSet<usize
 fn format_value(heap: &GcHeap, value: &Value, visited: &mut HashSet<usize>) -> String {
     match value {
         Value::Integer(i) => i.to_string(),
+        Value::BigInt(i) => i.to_string(),
+        Value::Float(x) => format_float(*x),
         Value::Boolean(b) => b.to_string(),
         Value::String(s) => s.clone(),
         Value::Null => "null".to_string(),
@@ -465,14 +632,16 @@ fn format_value(heap: &GcHeap, value: &Value, visited: &mut HashSet<usize>) -> S
         }
         Value::CompiledFunction(_) => "[compiled function]".to_string(),
         Value::Closure(_) => "[closure function]".to_string(),
-        Value::Builtin(_) => "[builtin function]".to_string(),
+        Value::Builtin(_) | Value::Native(_) => "[builtin function]".to_string(),
         Value::Class(class) => format!("[class {}]", class.name),
         Value::Instance(instance) => {
             format!("[object {}]", class_name(heap, instance.class))
         }
         Value::BoundMethod(method) => {
-            format!("[bound method {}.{}]", instance_class_name(heap, method.receiver), method.name)
+            format!("[bound method {}.{}]", receiver_name(heap, method.receiver), method.name)
         }
+        Value::Iterator(_) => "[iterator]".to_string(),
+        Value::Generator(_) => "[generator]".to_string(),
     }
 }
 
@@ -483,9 +652,14 @@ fn class_name(heap: &GcHeap, class: GcRef) -> String {
     }
 }
 
-fn instance_class_name(heap: &GcHeap, instance: GcRef) -> String {
-    match get_value(heap, instance) {
+/// The class of an instance receiver, or the type of a string, array or hash
+/// one (see `object::methods`).
+fn receiver_name(heap: &GcHeap, receiver: GcRef) -> String {
+    match get_value(heap, receiver) {
         Value::Instance(instance) => class_name(heap, instance.class),
+        Value::String(_) => Receiver::String.name().to_string(),
+        Value::Array(_) => Receiver::Array.name().to_string(),
+        Value::Hash(_) => Receiver::Hash.name().to_string(),
         _ => "<invalid receiver>".to_string(),
     }
 }
@@ -493,6 +667,7 @@ fn instance_class_name(heap: &GcHeap, instance: GcRef) -> String {
 fn format_hash_key(key: &HashKey) -> String {
     match key {
         HashKey::Integer(i) => i.to_string(),
+        HashKey::BigInt(i) => i.to_string(),
         HashKey::Boolean(b) => b.to_string(),
         HashKey::String(s) => s.clone(),
     }
@@ -501,6 +676,8 @@ fn format_hash_key(key: &HashKey) -> String {
 pub fn import_object(heap: &mut GcHeap, object: &Object) -> GcRef {
     let value = match object {
         Object::Integer(i) => Value::Integer(*i),
+        Object::BigInt(i) => Value::BigInt(i.clone()),
+        Object::Float(x) => Value::Float(*x),
         Object::Boolean(b) => Value::Boolean(*b),
         Object::String(s) => Value::String(s.clone()),
         Object::Null => Value::Null,
@@ -539,11 +716,24 @@ pub fn import_object(heap: &mut GcHeap, object: &Object) -> GcRef {
                 .expect("unknown builtin function");
             Value::Builtin(definition.id)
         }
+        Object::Native(native) => Value::Native(Rc::clone(native)),
         Object::ReturnValue(inner) => return import_object(heap, inner),
-        Object::Function(_, _, _) => {
+        Object::Function(_, _, _) | Object::GeneratorFunction(_, _, _) => {
             panic!("interpreter functions cannot be imported into the GC VM")
         }
-        Object::Class(_) | Object::Instance(_) | Object::BoundMethod(_) => {
+        Object::BoundBuiltin(bound) => {
+            let id = builtin_id(bound.function).expect("unknown builtin function");
+            let method = alloc_value(heap, Value::Builtin(id));
+            Value::BoundMethod(GcBoundMethod {
+                receiver: import_object(heap, &bound.receiver),
+                method,
+                name: bound.name.to_string(),
+            })
+        }
+        Object::Iterator(iter) => {
+            Value::Iterator(iter.map_sources(|source| import_object(heap, source)))
+        }
+        Object::Class(_) | Object::Instance(_) | Object::BoundMethod(_) | Object::Generator(_) => {
             panic!("graph values cannot be imported into the GC VM")
         }
     };
@@ -558,14 +748,16 @@ pub fn import_object(heap: &mut GcHeap, object: &Object) -> GcRef {
 pub fn try_export_object(heap: &GcHeap, reference: GcRef) -> Result<Object, String> {
     match get_value(heap, reference) {
         Value::Integer(i) => Ok(Object::Integer(*i)),
+        Value::BigInt(i) => Ok(Object::BigInt(i.clone())),
+        Value::Float(x) => Ok(Object::Float(*x)),
         Value::Boolean(b) => Ok(Object::Boolean(*b)),
         Value::String(s) => Ok(Object::String(s.clone())),
         Value::Null => Ok(Object::Null),
         Value::Error(e) => Ok(Object::Error(e.clone())),
         Value::Array(items) => {
-            let mut exported = Vec::with_capacity(items.len());
+            let mut exported = Vector::new();
             for item in items {
-                exported.push(Rc::new(try_export_object(heap, *item)?));
+                exported.push_back(Rc::new(try_export_object(heap, *item)?));
             }
             Ok(Object::Array(exported))
         }
@@ -573,8 +765,8 @@ pub fn try_export_object(heap: &GcHeap, reference: GcRef) -> Result<Object, Stri
             // Object's Hash impl only covers Integer/Boolean/String, which have no
             // interior mutability, so the keys are effectively immutable.
             #[allow(clippy::mutable_key_type)]
-            let mut exported = HashMap::with_capacity(map.len());
-            for (key, value) in map {
+            let mut exported = OrderedMap::new();
+            for (key, value) in map.iter() {
                 exported
                     .insert(Rc::new(key.to_object()), Rc::new(try_export_object(heap, *value)?));
             }
@@ -602,7 +794,19 @@ pub fn try_export_object(heap: &GcHeap, reference: GcRef) -> Result<Object, Stri
                 .ok_or_else(|| "unknown builtin id".to_string())?;
             Ok(Object::Builtin(definition.function))
         }
-        Value::Class(_) | Value::Instance(_) | Value::BoundMethod(_) => {
+        Value::Native(native) => Ok(Object::Native(Rc::clone(native))),
+        Value::Iterator(iter) => {
+            let mut sources = Vec::new();
+            iter.for_each_source(|source| sources.push(*source));
+            let mut exported = Vec::with_capacity(sources.len());
+            for source in sources {
+                exported.push(Rc::new(try_export_object(heap, source)?));
+            }
+            let mut exported = exported.into_iter();
+            let iter = iter.map_sources(|_| exported.next().expect("one export per source"));
+            Ok(Object::Iterator(Rc::new(iter)))
+        }
+        Value::Class(_) | Value::Instance(_) | Value::BoundMethod(_) | Value::Generator(_) => {
             Err("GC graph values cannot be exported as object::Object".to_string())
         }
     }
@@ -613,16 +817,16 @@ pub fn export_object(heap: &GcHeap, reference: GcRef) -> Object {
 }
 
 pub fn call_builtin(heap: &mut GcHeap, builtin: BuiltinId, args: &[GcRef], null: GcRef) -> GcRef {
-    call_builtin_with_output(heap, builtin, args, null, None)
+    call_builtin_with_output(heap, builtin, args, null, &mut StdoutSink)
 }
 
-/// Invoke a builtin and optionally append its user-visible output to a buffer.
+/// Invoke a builtin, writing its user-visible output to `output`.
 pub fn call_builtin_with_output(
     heap: &mut GcHeap,
     builtin: BuiltinId,
     args: &[GcRef],
     null: GcRef,
-    mut output: Option<&mut String>,
+    output: &mut dyn OutputSink,
 ) -> GcRef {
     match builtin {
         BuiltinId::Len => {
@@ -635,6 +839,7 @@ pub fn call_builtin_with_output(
             match get_value(heap, args[0]) {
                 Value::String(value) => alloc_value(heap, Value::Integer(value.len() as i64)),
                 Value::Array(value) => alloc_value(heap, Value::Integer(value.len() as i64)),
+                Value::Iterator(_) => call_iterator_builtin(heap, builtin, args, null),
                 _ => alloc_value(
                     heap,
                     Value::Error(format!(
@@ -646,12 +851,7 @@ pub fn call_builtin_with_output(
         }
         BuiltinId::Puts => {
             for argument in args {
-                let rendered = value_to_string(heap, *argument);
-                if let Some(output) = output.as_deref_mut() {
-                    writeln!(output, "{}", rendered).expect("writing to a String cannot fail");
-                } else {
-                    println!("{}", rendered);
-                }
+                output.write_line(&value_to_string(heap, *argument));
             }
             heap.dup(null)
         }
@@ -674,6 +874,9 @@ pub fn call_builtin_with_output(
             }
             let items = match get_value(heap, args[0]) {
                 Value::Array(items) => items.clone(),
+                Value::Iterator(_) if builtin != BuiltinId::Last => {
+                    return call_iterator_builtin(heap, builtin, args, null)
+                }
                 _ => {
                     return alloc_value(
                         heap,
@@ -687,18 +890,18 @@ pub fn call_builtin_with_output(
             };
             match builtin {
                 BuiltinId::First => items
-                    .first()
+                    .front()
                     .map(|item| heap.dup(*item))
                     .unwrap_or_else(|| heap.dup(null)),
                 BuiltinId::Last => items
-                    .last()
+                    .back()
                     .map(|item| heap.dup(*item))
                     .unwrap_or_else(|| heap.dup(null)),
                 BuiltinId::Rest => {
                     if items.is_empty() {
                         heap.dup(null)
                     } else {
-                        alloc_value(heap, Value::Array(items[1..].to_vec()))
+                        alloc_value(heap, Value::Array(items.skip(1)))
                     }
                 }
                 _ => unreachable!(),
@@ -723,10 +926,351 @@ pub fn call_builtin_with_output(
                     )
                 }
             };
-            items.push(args[1]);
+            items.push_back(args[1]);
             alloc_value(heap, Value::Array(items))
         }
+        BuiltinId::Split
+        | BuiltinId::Join
+        | BuiltinId::Trim
+        | BuiltinId::Upper
+        | BuiltinId::Lower
+        | BuiltinId::Contains
+        | BuiltinId::StartsWith
+        | BuiltinId::EndsWith
+        | BuiltinId::Replace
+        | BuiltinId::Substr
+        | BuiltinId::IndexOf
+        | BuiltinId::Chars
+        | BuiltinId::Str
+        | BuiltinId::Int => call_text_builtin(heap, builtin, args, null),
+        BuiltinId::Keys
+        | BuiltinId::Values
+        | BuiltinId::Entries
+        | BuiltinId::Has
+        | BuiltinId::Delete
+        | BuiltinId::Merge => call_hash_builtin(heap, builtin, args),
+        BuiltinId::Abs
+        | BuiltinId::Min
+        | BuiltinId::Max
+        | BuiltinId::Pow
+        | BuiltinId::Sqrt
+        | BuiltinId::Floor
+        | BuiltinId::Ceil
+        | BuiltinId::Round => {
+            let result = math::call(builtin, num_args(heap, args));
+            alloc_num_result(heap, result, null)
+        }
+        BuiltinId::JsonEncode | BuiltinId::JsonDecode => call_json_builtin(heap, builtin, args),
+        BuiltinId::TypeOf | BuiltinId::InstanceOf | BuiltinId::Fields | BuiltinId::Methods => {
+            call_reflect_builtin(heap, builtin, args)
+        }
+        BuiltinId::Range
+        | BuiltinId::Take
+        | BuiltinId::Zip
+        | BuiltinId::Enumerate
+        | BuiltinId::ToArray => call_iterator_builtin(heap, builtin, args, null),
+        // Calling back into Monkey code, resuming generators and the random
+        // generator need the VM; `GcVM::call_builtin` runs these itself.
+        BuiltinId::Map
+        | BuiltinId::Filter
+        | BuiltinId::Reduce
+        | BuiltinId::Sort
+        | BuiltinId::Find
+        | BuiltinId::Any
+        | BuiltinId::All
+        | BuiltinId::Random
+        | BuiltinId::Seed
+        | BuiltinId::Next => {
+            let name = BuiltIns
+                .iter()
+                .find(|definition| definition.id == builtin)
+                .map_or("?", |definition| definition.name);
+            alloc_value(
+                heap,
+                Value::Error(format!("builtin {} can only be called from Monkey code", name)),
+            )
+        }
+    }
+}
+
+fn call_text_builtin(heap: &mut GcHeap, builtin: BuiltinId, args: &[GcRef], null: GcRef) -> GcRef {
+    let args = args
+        .iter()
+        .map(|argument| match get_value(heap, *argument) {
+            Value::String(text) => TextArg::Str(text.clone()),
+            Value::Integer(value) => TextArg::Int(Int::Small(*value)),
+            Value::BigInt(value) => TextArg::Int(Int::Big(value.clone())),
+            Value::Float(value) => TextArg::Float(*value),
+            Value::Array(items) => TextArg::Array(
+                items
+                    .iter()
+                    .map(|item| value_to_string(heap, *item))
+                    .collect(),
+            ),
+            _ => TextArg::Other(value_to_string(heap, *argument)),
+        })
+        .collect();
+    match strings::call(builtin, args) {
+        Ok(TextResult::Str(text)) => alloc_value(heap, Value::String(text)),
+        Ok(TextResult::Int(value)) => alloc_value(heap, Value::from_int(value)),
+        Ok(TextResult::Bool(value)) => alloc_value(heap, Value::Boolean(value)),
+        Ok(TextResult::Array(items)) => {
+            let items = items
+                .into_iter()
+                .map(|item| alloc_value(heap, Value::String(item)))
+                .collect();
+            alloc_owned_array(heap, items)
+        }
+        Ok(TextResult::Null) => heap.dup(null),
+        Err(error) => alloc_value(heap, Value::Error(error.message().to_string())),
+    }
+}
+
+fn call_hash_builtin(heap: &mut GcHeap, builtin: BuiltinId, args: &[GcRef]) -> GcRef {
+    let result = {
+        let decoded: Vec<HashArg<'_, HashKey, GcRef>> = args
+            .iter()
+            .map(|argument| match get_value(heap, *argument) {
+                Value::Hash(entries) => HashArg::Hash(entries),
+                value => match HashKey::from_value(value) {
+                    Some(key) => HashArg::Key(key),
+                    None => HashArg::Other,
+                },
+            })
+            .collect();
+        hashes::call(builtin, &decoded, |index| value_to_string(heap, args[index]))
+    };
+    match result {
+        Ok(HashResult::Keys(keys)) => {
+            let keys = keys
+                .iter()
+                .map(|key| alloc_value(heap, key.to_value()))
+                .collect();
+            alloc_owned_array(heap, keys)
+        }
+        Ok(HashResult::Values(values)) => alloc_value(heap, Value::Array(values.into())),
+        Ok(HashResult::Entries(entries)) => {
+            let pairs = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = alloc_value(heap, key.to_value());
+                    let pair = alloc_value(heap, Value::Array(vec![key, value].into()));
+                    heap.free(key);
+                    pair
+                })
+                .collect();
+            alloc_owned_array(heap, pairs)
+        }
+        Ok(HashResult::Bool(value)) => alloc_value(heap, Value::Boolean(value)),
+        Ok(HashResult::Hash(entries)) => alloc_value(heap, Value::Hash(entries)),
+        Err(error) => alloc_value(heap, Value::Error(error.message().to_string())),
+    }
+}
+
+fn call_iterator_builtin(
+    heap: &mut GcHeap,
+    builtin: BuiltinId,
+    args: &[GcRef],
+    null: GcRef,
+) -> GcRef {
+    let mut host = GcIterHost {
+        heap,
+        null,
+    };
+    match iterators::call(&mut host, builtin, args) {
+        Ok(result) => result,
+        Err(error) => alloc_value(host.heap, Value::Error(error.message().to_string())),
+    }
+}
+
+struct GcIterHost<'a> {
+    heap: &'a mut GcHeap,
+    null: GcRef,
+}
+
+impl ValueHost for GcIterHost<'_> {
+    type Value = GcRef;
+
+    fn array(&mut self, elements: &[GcRef]) -> GcRef {
+        alloc_value(self.heap, Value::Array(elements.iter().copied().collect()))
+    }
+
+    fn null(&mut self) -> GcRef {
+        self.heap.dup(self.null)
+    }
+
+    fn retain(&mut self, value: &GcRef) -> GcRef {
+        self.heap.dup(*value)
+    }
+
+    fn release(&mut self, value: GcRef) {
+        self.heap.free(value);
+    }
+
+    fn describe(&self, value: &GcRef) -> String {
+        value_to_string(self.heap, *value)
+    }
+}
+
+impl IterHost for GcIterHost<'_> {
+    fn source(&self, value: &GcRef) -> Option<IterSource<GcRef>> {
+        match get_value(self.heap, *value) {
+            Value::Array(items) => Some(IterSource::Array(items.clone())),
+            Value::Iterator(iter) => Some(IterSource::Iter(iter.clone())),
+            _ => None,
+        }
+    }
+
+    fn integer(&self, value: &GcRef) -> Option<Int> {
+        get_value(self.heap, *value).as_int()
+    }
+
+    fn int(&mut self, value: i64) -> GcRef {
+        alloc_value(self.heap, Value::Integer(value))
+    }
+
+    fn iterator(&mut self, iter: Iter<GcRef>) -> GcRef {
+        alloc_value(self.heap, Value::Iterator(iter))
+    }
+}
+
+fn call_json_builtin(heap: &mut GcHeap, builtin: BuiltinId, args: &[GcRef]) -> GcRef {
+    if builtin == BuiltinId::JsonEncode {
+        let result = json::encode(args, |argument| match get_value(heap, *argument) {
+            Value::Null => JsonView::Null,
+            Value::Boolean(value) => JsonView::Bool(*value),
+            Value::Integer(value) => JsonView::Int(Int::Small(*value)),
+            Value::BigInt(value) => JsonView::Int(Int::Big(value.clone())),
+            Value::Float(value) => JsonView::Float(*value),
+            Value::String(text) => JsonView::Str(text.clone()),
+            Value::Array(items) => JsonView::Array(argument.0, items.iter().copied().collect()),
+            Value::Hash(entries) => JsonView::Hash(
+                argument.0,
+                entries
+                    .iter()
+                    .map(|(key, value)| {
+                        let key = match key {
+                            HashKey::Integer(value) => value.to_string(),
+                            HashKey::BigInt(value) => value.to_string(),
+                            HashKey::Boolean(value) => value.to_string(),
+                            HashKey::String(value) => value.clone(),
+                        };
+                        (key, *value)
+                    })
+                    .collect(),
+            ),
+            _ => JsonView::Other(value_to_string(heap, *argument)),
+        });
+        return match result {
+            Ok(text) => alloc_value(heap, Value::String(text)),
+            Err(error) => alloc_value(heap, Value::Error(error.message().to_string())),
+        };
+    }
+    let result = {
+        let decoded: Vec<JsonArg<'_>> = args
+            .iter()
+            .map(|argument| match get_value(heap, *argument) {
+                Value::String(text) => JsonArg::Str(text),
+                _ => JsonArg::Other(value_to_string(heap, *argument)),
+            })
+            .collect();
+        json::decode(&decoded)
+    };
+    match result {
+        Ok(document) => import_object(heap, &json_object(document)),
+        Err(error) => alloc_value(heap, Value::Error(error.message().to_string())),
+    }
+}
+
+fn call_reflect_builtin(heap: &mut GcHeap, builtin: BuiltinId, args: &[GcRef]) -> GcRef {
+    let decoded = args
+        .iter()
+        .map(|argument| {
+            let kind = match get_value(heap, *argument) {
+                Value::Class(class) => {
+                    return ReflectArg::Class(argument.0, class.methods.keys().cloned().collect());
+                }
+                Value::Instance(instance) => {
+                    return ReflectArg::Instance(
+                        instance.class.0,
+                        instance.fields.keys().cloned().collect(),
+                    );
+                }
+                Value::Integer(_) | Value::BigInt(_) => TypeOf::Integer,
+                Value::Float(_) => TypeOf::Float,
+                Value::Boolean(_) => TypeOf::Boolean,
+                Value::String(_) => TypeOf::String,
+                Value::Null => TypeOf::Null,
+                Value::Array(_) => TypeOf::Array,
+                Value::Hash(_) => TypeOf::Hash,
+                Value::Closure(_) => TypeOf::Closure,
+                Value::Builtin(_) | Value::Native(_) => TypeOf::Builtin,
+                Value::BoundMethod(_) => TypeOf::BoundMethod,
+                Value::Iterator(_) => TypeOf::Iterator,
+                Value::Generator(_) => TypeOf::Generator,
+                Value::Error(_) => TypeOf::Error,
+                Value::CompiledFunction(_) => TypeOf::CompiledFunction,
+            };
+            ReflectArg::Other(kind)
+        })
+        .collect();
+    match reflect::call(builtin, decoded, |index| value_to_string(heap, args[index])) {
+        Ok(ReflectResult::Str(name)) => alloc_value(heap, Value::String(name.to_string())),
+        Ok(ReflectResult::Bool(value)) => alloc_value(heap, Value::Boolean(value)),
+        Ok(ReflectResult::Names(names)) => {
+            let names = names
+                .into_iter()
+                .map(|name| alloc_value(heap, Value::String(name)))
+                .collect();
+            alloc_owned_array(heap, names)
+        }
+        Err(error) => alloc_value(heap, Value::Error(error.message().to_string())),
+    }
+}
+
+/// `random` and `seed`, drawing from the VM's generator.
+pub fn call_random_builtin(
+    heap: &mut GcHeap,
+    random: &mut Random,
+    builtin: BuiltinId,
+    args: &[GcRef],
+    null: GcRef,
+) -> GcRef {
+    let result = random::call(random, builtin, num_args(heap, args));
+    alloc_num_result(heap, result, null)
+}
+
+fn num_args(heap: &GcHeap, args: &[GcRef]) -> Vec<NumArg> {
+    args.iter()
+        .map(|argument| match get_value(heap, *argument) {
+            Value::Integer(value) => NumArg::Int(Int::Small(*value)),
+            Value::BigInt(value) => NumArg::Int(Int::Big(value.clone())),
+            Value::Float(value) => NumArg::Float(*value),
+            _ => NumArg::Other(value_to_string(heap, *argument)),
+        })
+        .collect()
+}
+
+fn alloc_num_result(
+    heap: &mut GcHeap,
+    result: Result<NumResult, BuiltinError>,
+    null: GcRef,
+) -> GcRef {
+    match result {
+        Ok(NumResult::Int(value)) => alloc_value(heap, Value::from_int(value)),
+        Ok(NumResult::Float(value)) => alloc_value(heap, Value::Float(value)),
+        Ok(NumResult::Null) => heap.dup(null),
+        Err(error) => alloc_value(heap, Value::Error(error.message().to_string())),
+    }
+}
+
+/// An array of `items`, taking over the caller's references to them.
+fn alloc_owned_array(heap: &mut GcHeap, items: Vec<GcRef>) -> GcRef {
+    let array = alloc_value(heap, Value::Array(items.iter().copied().collect()));
+    for item in items {
+        heap.free(item);
     }
+    array
 }
 
 impl fmt::Display for Value {
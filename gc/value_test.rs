@@ -4,7 +4,8 @@ mod tests {
     use std::rc::Rc;
 
     use object::builtins::BuiltinId;
-    use object::{CompiledFunction, Object};
+    use object::iterators::Iter;
+    use object::{CompiledFunction, Object, OrderedMap};
 
     use crate::value::{
         alloc_value, call_builtin, export_object, get_value, get_value_mut, import_object, GcClass,
@@ -66,11 +67,14 @@ mod tests {
     #[test]
     fn import_export_array_roundtrip() {
         let mut heap = GcHeap::new();
-        let original = Object::Array(vec![
-            Rc::new(Object::Integer(1)),
-            Rc::new(Object::String("two".to_string())),
-            Rc::new(Object::Boolean(true)),
-        ]);
+        let original = Object::Array(
+            vec![
+                Rc::new(Object::Integer(1)),
+                Rc::new(Object::String("two".to_string())),
+                Rc::new(Object::Boolean(true)),
+            ]
+            .into(),
+        );
         let reference = import_object(&mut heap, &original);
         assert_eq!(export_object(&heap, reference), original);
     }
@@ -87,7 +91,7 @@ mod tests {
                 ),
             ]
             .into_iter()
-            .collect::<HashMap<_, _>>(),
+            .collect::<OrderedMap<_, _>>(),
         );
         let reference = import_object(&mut heap, &original);
         assert_eq!(export_object(&heap, reference), original);
@@ -96,10 +100,12 @@ mod tests {
     #[test]
     fn import_export_nested_array_roundtrip() {
         let mut heap = GcHeap::new();
-        let original = Object::Array(vec![Rc::new(Object::Array(vec![
-            Rc::new(Object::Integer(1)),
-            Rc::new(Object::Integer(2)),
-        ]))]);
+        let original = Object::Array(
+            vec![Rc::new(Object::Array(
+                vec![Rc::new(Object::Integer(1)), Rc::new(Object::Integer(2))].into(),
+            ))]
+            .into(),
+        );
         let reference = import_object(&mut heap, &original);
         assert_eq!(export_object(&heap, reference), original);
     }
@@ -126,7 +132,7 @@ mod tests {
         let child = alloc_value(&mut heap, Value::Integer(1));
         assert_eq!(heap.ref_count(child), 1);
 
-        let parent = alloc_value(&mut heap, Value::Array(vec![child]));
+        let parent = alloc_value(&mut heap, Value::Array(vec![child].into()));
         assert_eq!(heap.ref_count(child), 2);
 
         heap.free(parent);
@@ -136,10 +142,12 @@ mod tests {
     #[test]
     fn import_object_releases_temporary_child_refs() {
         let mut heap = GcHeap::new();
-        let original = Object::Array(vec![Rc::new(Object::Array(vec![
-            Rc::new(Object::Integer(1)),
-            Rc::new(Object::Integer(2)),
-        ]))]);
+        let original = Object::Array(
+            vec![Rc::new(Object::Array(
+                vec![Rc::new(Object::Integer(1)), Rc::new(Object::Integer(2))].into(),
+            ))]
+            .into(),
+        );
 
         let root = import_object(&mut heap, &original);
         let nested = match get_value(&heap, root) {
@@ -165,11 +173,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn iterator_keeps_its_sources_alive() {
+        let mut heap = GcHeap::new();
+        let source = alloc_value(&mut heap, Value::Array(vec![].into()));
+        let iterator = alloc_value(&mut heap, Value::Iterator(Iter::Take(source, 1)));
+        assert_eq!(heap.ref_count(source), 2);
+
+        heap.free(source);
+        assert!(heap.exists(source));
+        heap.free(iterator);
+        assert!(!heap.exists(source));
+    }
+
     #[test]
     fn value_cycle_collected_by_gc() {
         let mut heap = GcHeap::new();
-        let node_a = alloc_value(&mut heap, Value::Array(vec![]));
-        let node_b = alloc_value(&mut heap, Value::Array(vec![node_a]));
+        let node_a = alloc_value(&mut heap, Value::Array(vec![].into()));
+        let node_b = alloc_value(&mut heap, Value::Array(vec![node_a].into()));
 
         let node_b_edge = heap.dup(node_b);
         match &mut heap
@@ -178,7 +199,7 @@ mod tests {
             .expect("node_a should be a ValueCell")
             .value
         {
-            Value::Array(items) => items.push(node_b_edge),
+            Value::Array(items) => items.push_back(node_b_edge),
             other => panic!("expected node_a array, got {:?}", other),
         }
 
@@ -1,10 +1,18 @@
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 use byteorder::{BigEndian, ByteOrder};
 use compiler::compiler::{Bytecode, DebugInfo};
 use compiler::op_code::Opcode;
-use object::builtins::{BuiltIns, BuiltinId};
-use object::Object;
+use object::builtins::BuiltinId;
+use object::generators::{self, GeneratorHost, GeneratorState, NextError, Resumed};
+use object::higher_order::{self, is_higher_order, HigherOrderError, Host, SortKey, ValueHost};
+use object::integer::{self, Int, IntegerOp};
+use object::methods::{self, Receiver};
+use object::output::Output;
+use object::random::{is_random_builtin, Random};
+use object::registry::{BuiltinEntry, Builtins, Ctx, NativeBuiltin};
+use object::{Object, OrderedMap, Vector};
 use parser::lexer::token::Span;
 use serde::Serialize;
 
@@ -14,15 +22,19 @@ use crate::report::{
     GlobalRoot,
 };
 use crate::value::{
-    alloc_value, call_builtin_with_output, export_object, get_value, get_value_mut, import_object,
-    try_export_object, value_to_string, GcBoundMethod, GcClass, GcClosure, GcInstance, HashKey,
-    Value,
+    alloc_value, call_builtin_with_output, call_random_builtin, export_object, get_value,
+    get_value_mut, import_object, try_export_object, value_to_string, GcBoundMethod, GcClass,
+    GcClosure, GcInstance, GcSuspension, HashKey, Value,
 };
 use crate::{GcHeap, GcId, GcRef};
 
 const STACK_SIZE: usize = 2048;
 pub const GLOBAL_SIZE: usize = 65536;
 const MAX_FRAMES: usize = 1024;
+/// Special methods and higher-order builtin callbacks re-enter the dispatch
+/// loop on the native stack, so their nesting is capped well below
+/// `MAX_FRAMES`.
+const MAX_NESTED_RUN_DEPTH: usize = 64;
 pub const DEFAULT_INSTRUCTION_BUDGET: usize = 100_000;
 
 /// Runtime failure returned by the established VM and runner APIs.
@@ -82,6 +94,7 @@ impl GcRuntimeErrorKind {
 enum CalleeKind {
     Closure(GcClosure),
     Builtin(BuiltinId),
+    Native(Rc<NativeBuiltin>),
     BoundMethod(GcBoundMethod),
     Class(String),
     Other(String),
@@ -96,21 +109,39 @@ pub struct GcVM {
     global_names: Vec<(String, usize)>,
     frames: Vec<Frame>,
     frame_index: usize,
+    instruction_budget: usize,
+    executed: usize,
+    nested_run_depth: usize,
+    random: Random,
+    registry: Builtins,
+    /// `Bytecode::builtins` resolved against `registry`; `None` where it has
+    /// no builtin of that name.
+    builtins: Vec<(String, Option<Value>)>,
     null: GcRef,
     last_popped: GcRef,
     main_debug_info: DebugInfo,
     function_debug_info: HashMap<GcRef, DebugInfo>,
-    output: Option<String>,
+    output: Output,
+    /// Where the generator body being resumed stopped, left by `OpYield`.
+    yielded: Option<Resumed<GcSuspension, GcRef>>,
 }
 
 impl GcVM {
     pub fn new(bytecode: Bytecode) -> Self {
+        Self::with_builtins(bytecode, Builtins::standard())
+    }
+
+    /// A VM that resolves the program's builtins by name in `registry`,
+    /// including the programs later loaded with [`GcVM::load_bytecode`].
+    pub fn with_builtins(bytecode: Bytecode, registry: Builtins) -> Self {
         let Bytecode {
             instructions,
             constants: object_constants,
             debug_info: main_debug_info,
             function_debug_info: object_function_debug_info,
+            builtins: builtin_names,
         } = bytecode;
+        let builtins = resolve_builtins(&registry, builtin_names);
         let mut heap = GcHeap::new();
         let null = alloc_value(&mut heap, Value::Null);
         let constants = object_constants
@@ -173,11 +204,18 @@ impl GcVM {
             global_names: Vec::new(),
             frames,
             frame_index: 1,
+            instruction_budget: usize::MAX,
+            executed: 0,
+            nested_run_depth: 0,
+            random: Random::default(),
+            registry,
+            builtins,
             null,
             last_popped,
             main_debug_info,
             function_debug_info,
-            output: None,
+            output: Output::default(),
+            yielded: None,
         }
     }
 
@@ -192,7 +230,9 @@ impl GcVM {
             constants: object_constants,
             debug_info: main_debug_info,
             function_debug_info: object_function_debug_info,
+            builtins: builtin_names,
         } = bytecode;
+        self.builtins = resolve_builtins(&self.registry, builtin_names);
 
         self.clear_stack_range(0, self.sp);
         self.sp = 0;
@@ -264,14 +304,10 @@ impl GcVM {
         &mut self.heap
     }
 
-    /// Capture `puts`/`print` output in-memory instead of writing to stdout.
-    pub fn set_capture_output(&mut self, capture: bool) {
-        self.output = capture.then(String::new);
-    }
-
-    /// Drain captured output while leaving capture enabled.
-    pub fn take_output(&mut self) -> String {
-        self.output.as_mut().map(std::mem::take).unwrap_or_default()
+    /// Send `puts`/`print` output to `output` instead of stdout. The sink
+    /// stays in place across `load_bytecode`.
+    pub fn set_output(&mut self, output: Output) {
+        self.output = output;
     }
 
     /// Record which global slot each source-level global name refers to, so
@@ -371,17 +407,27 @@ impl GcVM {
         &mut self,
         instruction_budget: usize,
     ) -> Result<(), GcClassifiedRuntimeError> {
-        let mut executed = 0;
+        self.instruction_budget = instruction_budget;
+        self.executed = 0;
+        self.nested_run_depth = 0;
+        self.execute_frames(0)
+    }
+
+    /// The dispatch loop. Special-method calls made on behalf of an opcode
+    /// re-enter it with `stop_depth` set to the caller's frame count so it
+    /// returns as soon as the method's frame is popped; the instruction
+    /// budget is shared with the outer run.
+    fn execute_frames(&mut self, stop_depth: usize) -> Result<(), GcClassifiedRuntimeError> {
         while self.current_frame().ip < self.current_frame().instructions.len() as i32 - 1 {
             self.current_frame().ip += 1;
             let ip = self.current_frame().ip as usize;
-            if executed >= instruction_budget {
+            if self.executed >= self.instruction_budget {
                 return Err(self.runtime_error(
                     GcRuntimeErrorKind::ExecutionLimit,
-                    format!("instruction limit exceeded (budget: {})", instruction_budget),
+                    format!("instruction limit exceeded (budget: {})", self.instruction_budget),
                 ));
             }
-            executed += 1;
+            self.executed += 1;
             let ins = self.current_frame().instructions.clone();
             let op = *ins.get(ip).unwrap();
             let opcode = Opcode::from_repr(op).ok_or_else(|| {
@@ -493,6 +539,9 @@ impl GcVM {
                     self.clear_stack_range(new_sp, self.sp);
                     self.sp = new_sp;
                     self.push_raw(return_value)?;
+                    if self.frame_index == stop_depth {
+                        return Ok(());
+                    }
                 }
                 Opcode::OpReturn => {
                     if self.frame_index == 1 {
@@ -507,6 +556,9 @@ impl GcVM {
                     self.clear_stack_range(new_sp, self.sp);
                     self.sp = new_sp;
                     self.dup_and_push(self.null)?;
+                    if self.frame_index == stop_depth {
+                        return Ok(());
+                    }
                 }
                 Opcode::OpCall => {
                     let num_args = ins[ip + 1] as usize;
@@ -532,13 +584,22 @@ impl GcVM {
                 Opcode::OpGetBuiltin => {
                     let built_index = ins[ip + 1] as usize;
                     self.current_frame().ip += 1;
-                    let definition = BuiltIns.get(built_index).ok_or_else(|| {
-                        self.runtime_error(
-                            GcRuntimeErrorKind::InvalidBytecode,
-                            format!("builtin index {} out of range", built_index),
-                        )
-                    })?;
-                    self.alloc_and_push(Value::Builtin(definition.id))?;
+                    let builtin = match self.builtins.get(built_index) {
+                        Some((_, Some(builtin))) => builtin.clone(),
+                        Some((name, None)) => {
+                            return Err(self.runtime_error(
+                                GcRuntimeErrorKind::Call,
+                                format!("builtin {} is not registered", name),
+                            ))
+                        }
+                        None => {
+                            return Err(self.runtime_error(
+                                GcRuntimeErrorKind::InvalidBytecode,
+                                format!("builtin index {} out of range", built_index),
+                            ))
+                        }
+                    };
+                    self.alloc_and_push(builtin)?;
                 }
                 Opcode::OpClosure => {
                     let const_index = BigEndian::read_u16(&ins[ip + 1..ip + 3]) as usize;
@@ -613,11 +674,76 @@ impl GcVM {
                     self.current_frame().ip += 1;
                     self.execute_new(num_args)?;
                 }
+                Opcode::OpConcat => {
+                    let count = BigEndian::read_u16(&ins[ip + 1..ip + 3]) as usize;
+                    self.current_frame().ip += 2;
+                    let start = self.stack_base_for(count)?;
+                    let mut text = String::new();
+                    for slot in start..self.sp {
+                        text.push_str(&self.display_value(self.stack[slot])?);
+                    }
+                    let string = alloc_value(&mut self.heap, Value::String(text));
+                    self.clear_stack_range(start, self.sp);
+                    self.sp = start;
+                    self.push_raw(string)?;
+                }
+                Opcode::OpGenerator => {
+                    if self.frame_index == 1 {
+                        return Err(self.runtime_error(
+                            GcRuntimeErrorKind::InvalidBytecode,
+                            "OpGenerator outside a function",
+                        ));
+                    }
+                    let frame = self.pop_frame();
+                    let suspension = self.suspend(&frame);
+                    let state = GeneratorState::Suspended(suspension.clone());
+                    let generator = alloc_value(&mut self.heap, Value::Generator(state));
+                    self.release_suspension(suspension);
+                    self.push_raw(generator)?;
+                    if self.frame_index == stop_depth {
+                        return Ok(());
+                    }
+                }
+                Opcode::OpYield => {
+                    // Only a frame put back by `resume_generator` can stop.
+                    if stop_depth == 0 || self.frame_index != stop_depth + 1 {
+                        return Err(self.runtime_error(
+                            GcRuntimeErrorKind::InvalidBytecode,
+                            "OpYield outside a generator",
+                        ));
+                    }
+                    let kind = ins[ip + 1];
+                    self.current_frame().ip += 1;
+                    let value = self.pop_owned()?;
+                    let frame = self.pop_frame();
+                    let suspension = self.suspend(&frame);
+                    self.yielded = Some(match kind {
+                        0 => Resumed::Yield(value, suspension),
+                        1 => Resumed::Delegate(value, suspension),
+                        _ => {
+                            self.release_suspension(suspension);
+                            Resumed::Forward(value)
+                        }
+                    });
+                    return Ok(());
+                }
             }
         }
         Ok(())
     }
 
+    /// Puts a copy of `value` in global slot `index`, before a run.
+    pub fn set_global(&mut self, index: usize, value: &Object) {
+        let value = import_object(&mut self.heap, value);
+        let previous = std::mem::replace(&mut self.globals[index], value);
+        self.heap.free(previous);
+    }
+
+    /// The value in global slot `index`, if there is such a slot.
+    pub fn global(&self, index: usize) -> Option<GcRef> {
+        self.globals.get(index).copied()
+    }
+
     pub fn last_popped_stack_elm(&self) -> Option<GcRef> {
         Some(self.last_popped)
     }
@@ -725,23 +851,49 @@ impl GcVM {
 
     fn execute_binary_operation(&mut self, opcode: Opcode) -> Result<(), GcClassifiedRuntimeError> {
         let (right, left) = self.pop_owned_pair()?;
+        let method = match opcode {
+            Opcode::OpAdd => "add",
+            Opcode::OpSub => "sub",
+            Opcode::OpMul => "mul",
+            _ => "div",
+        };
+        match self.call_special_method(left, method, &[right]) {
+            Ok(None) => {}
+            special => {
+                self.heap.free(left);
+                self.heap.free(right);
+                return self.push_raw(special?.expect("special method result"));
+            }
+        }
         let left_value = get_value(&self.heap, left).clone();
         let right_value = get_value(&self.heap, right).clone();
         let result = match (&left_value, &right_value) {
-            (Value::Integer(l), Value::Integer(r)) => match opcode {
-                Opcode::OpAdd => Ok(Value::Integer(l.wrapping_add(*r))),
-                Opcode::OpSub => Ok(Value::Integer(l.wrapping_sub(*r))),
-                Opcode::OpMul => Ok(Value::Integer(l.wrapping_mul(*r))),
-                Opcode::OpDiv if *r != 0 => {
-                    l.checked_div(*r).map(Value::Integer).ok_or_else(|| {
-                        (GcRuntimeErrorKind::Arithmetic, "integer overflow in division".to_string())
-                    })
-                }
-                Opcode::OpDiv => {
-                    Err((GcRuntimeErrorKind::Arithmetic, "division by zero".to_string()))
+            (Value::Integer(_) | Value::BigInt(_), Value::Integer(_) | Value::BigInt(_)) => {
+                let op = match opcode {
+                    Opcode::OpAdd => IntegerOp::Add,
+                    Opcode::OpSub => IntegerOp::Sub,
+                    Opcode::OpMul => IntegerOp::Mul,
+                    Opcode::OpDiv => IntegerOp::Div,
+                    _ => unreachable!(),
+                };
+                let (l, r) = (left_value.as_int().unwrap(), right_value.as_int().unwrap());
+                integer::apply(op, &l, &r)
+                    .map(Value::from_int)
+                    .ok_or_else(|| (GcRuntimeErrorKind::Arithmetic, "division by zero".to_string()))
+            }
+            (
+                Value::Integer(_) | Value::BigInt(_) | Value::Float(_),
+                Value::Integer(_) | Value::BigInt(_) | Value::Float(_),
+            ) => {
+                let (l, r) = Value::float_operands(&left_value, &right_value).unwrap();
+                match opcode {
+                    Opcode::OpAdd => Ok(Value::Float(l + r)),
+                    Opcode::OpSub => Ok(Value::Float(l - r)),
+                    Opcode::OpMul => Ok(Value::Float(l * r)),
+                    Opcode::OpDiv => Ok(Value::Float(l / r)),
+                    _ => unreachable!(),
                 }
-                _ => unreachable!(),
-            },
+            }
             (Value::String(l), Value::String(r)) if opcode == Opcode::OpAdd => {
                 Ok(Value::String(l.to_string() + r))
             }
@@ -764,14 +916,47 @@ impl GcVM {
 
     fn execute_comparison(&mut self, opcode: Opcode) -> Result<(), GcClassifiedRuntimeError> {
         let (right, left) = self.pop_owned_pair()?;
+        if opcode == Opcode::OpEqual || opcode == Opcode::OpNotEqual {
+            match self.call_special_method(left, "eq", &[right]) {
+                Ok(None) => {}
+                special => {
+                    self.heap.free(left);
+                    self.heap.free(right);
+                    let result = special?.expect("special method result");
+                    let equal = is_truthy(&self.heap, result);
+                    self.heap.free(result);
+                    let equal = if opcode == Opcode::OpEqual { equal } else { !equal };
+                    return self.alloc_and_push(Value::Boolean(equal));
+                }
+            }
+        }
         let result = match (get_value(&self.heap, left), get_value(&self.heap, right)) {
-            (Value::Integer(l), Value::Integer(r)) => match opcode {
-                Opcode::OpEqual => Some(l == r),
-                Opcode::OpNotEqual => Some(l != r),
-                Opcode::OpGreaterThan => Some(l > r),
-                Opcode::OpLessThan => Some(l < r),
-                _ => unreachable!(),
-            },
+            (
+                l @ (Value::Integer(_) | Value::BigInt(_)),
+                r @ (Value::Integer(_) | Value::BigInt(_)),
+            ) => {
+                let ordering = integer::compare(&l.as_int().unwrap(), &r.as_int().unwrap());
+                match opcode {
+                    Opcode::OpEqual => Some(ordering.is_eq()),
+                    Opcode::OpNotEqual => Some(ordering.is_ne()),
+                    Opcode::OpGreaterThan => Some(ordering.is_gt()),
+                    Opcode::OpLessThan => Some(ordering.is_lt()),
+                    _ => unreachable!(),
+                }
+            }
+            (
+                l @ (Value::Integer(_) | Value::BigInt(_) | Value::Float(_)),
+                r @ (Value::Integer(_) | Value::BigInt(_) | Value::Float(_)),
+            ) => {
+                let (l, r) = Value::float_operands(l, r).unwrap();
+                match opcode {
+                    Opcode::OpEqual => Some(l == r),
+                    Opcode::OpNotEqual => Some(l != r),
+                    Opcode::OpGreaterThan => Some(l > r),
+                    Opcode::OpLessThan => Some(l < r),
+                    _ => unreachable!(),
+                }
+            }
             (Value::Boolean(l), Value::Boolean(r)) => match opcode {
                 Opcode::OpEqual => Some(l == r),
                 Opcode::OpNotEqual => Some(l != r),
@@ -817,7 +1002,10 @@ impl GcVM {
     fn execute_minus_operation(&mut self) -> Result<(), GcClassifiedRuntimeError> {
         let operand = self.pop_owned()?;
         let negated = match get_value(&self.heap, operand) {
-            Value::Integer(value) => Some(value.wrapping_neg()),
+            value @ (Value::Integer(_) | Value::BigInt(_)) => {
+                Some(Value::from_int(integer::negate(&value.as_int().unwrap())))
+            }
+            Value::Float(value) => Some(Value::Float(-value)),
             _ => None,
         };
         let message = negated.is_none().then(|| {
@@ -825,7 +1013,7 @@ impl GcVM {
         });
         self.heap.free(operand);
         if let Some(negated) = negated {
-            self.alloc_and_push(Value::Integer(negated))
+            self.alloc_and_push(negated)
         } else {
             Err(self.runtime_error(GcRuntimeErrorKind::Type, message.unwrap()))
         }
@@ -841,20 +1029,16 @@ impl GcVM {
         self.alloc_and_push(Value::Boolean(result))
     }
 
-    fn build_array(&mut self, start: usize, end: usize) -> Vec<GcRef> {
-        let mut elements = Vec::with_capacity(end - start);
-        for i in start..end {
-            elements.push(self.stack[i]);
-        }
-        elements
+    fn build_array(&mut self, start: usize, end: usize) -> Vector<GcRef> {
+        self.stack[start..end].iter().copied().collect()
     }
 
     fn build_hash(
         &mut self,
         start: usize,
         end: usize,
-    ) -> Result<HashMap<HashKey, GcRef>, GcClassifiedRuntimeError> {
-        let mut elements = HashMap::new();
+    ) -> Result<OrderedMap<HashKey, GcRef>, GcClassifiedRuntimeError> {
+        let mut elements = OrderedMap::new();
         for i in (start..end).step_by(2) {
             let key_ref = self.stack[i];
             let key = HashKey::from_value(get_value(&self.heap, key_ref)).ok_or_else(|| {
@@ -876,6 +1060,9 @@ impl GcVM {
         left: GcRef,
         index: GcRef,
     ) -> Result<(), GcClassifiedRuntimeError> {
+        if let Some(result) = self.call_special_method(left, "get", &[index])? {
+            return self.push_raw(result);
+        }
         let left_value = get_value(&self.heap, left).clone();
         let index_value = get_value(&self.heap, index).clone();
         match (&left_value, &index_value) {
@@ -894,7 +1081,7 @@ impl GcVM {
 
     fn execute_array_index(
         &mut self,
-        array: &[GcRef],
+        array: &Vector<GcRef>,
         index: i64,
     ) -> Result<(), GcClassifiedRuntimeError> {
         if index < array.len() as i64 && index >= 0 {
@@ -906,7 +1093,7 @@ impl GcVM {
 
     fn execute_hash_index(
         &mut self,
-        hash: &HashMap<HashKey, GcRef>,
+        hash: &OrderedMap<HashKey, GcRef>,
         index: &Value,
     ) -> Result<(), GcClassifiedRuntimeError> {
         let key = HashKey::from_value(index).ok_or_else(|| {
@@ -942,6 +1129,7 @@ impl GcVM {
         match callee_kind(&self.heap, callee) {
             CalleeKind::Closure(closure) => self.call_closure(closure, num_args),
             CalleeKind::Builtin(builtin) => self.call_builtin(builtin, num_args),
+            CalleeKind::Native(native) => self.call_native(&native, num_args),
             CalleeKind::BoundMethod(bound) => self.call_bound_method(bound, num_args),
             CalleeKind::Class(name) => Err(self.runtime_error(
                 GcRuntimeErrorKind::Call,
@@ -995,14 +1183,101 @@ impl GcVM {
         num_args: usize,
     ) -> Result<(), GcClassifiedRuntimeError> {
         let base = self.sp - num_args - 1;
-        let args = self.stack[self.sp - num_args..self.sp].to_vec();
-        let result = call_builtin_with_output(
-            &mut self.heap,
-            builtin,
-            &args,
-            self.null,
-            self.output.as_mut(),
-        );
+        let mut args = self.stack[self.sp - num_args..self.sp].to_vec();
+        if builtin == BuiltinId::Next {
+            let result = match generators::next(self, &args) {
+                Ok((value, done)) => self.step(value, done),
+                Err(NextError::Builtin(error)) => {
+                    alloc_value(&mut self.heap, Value::Error(error.message().to_string()))
+                }
+                Err(NextError::Resume(error)) => return Err(error),
+            };
+            self.clear_stack_range(base, self.sp);
+            self.sp = base;
+            return self.push_raw(result);
+        }
+        if is_higher_order(builtin) {
+            let result = match higher_order::call(self, builtin, &args) {
+                Ok(result) => result,
+                Err(HigherOrderError::Builtin(error)) => {
+                    alloc_value(&mut self.heap, Value::Error(error.message().to_string()))
+                }
+                Err(HigherOrderError::Callback(error)) => return Err(error),
+            };
+            self.clear_stack_range(base, self.sp);
+            self.sp = base;
+            return self.push_raw(result);
+        }
+        if is_random_builtin(builtin) {
+            let result =
+                call_random_builtin(&mut self.heap, &mut self.random, builtin, &args, self.null);
+            self.clear_stack_range(base, self.sp);
+            self.sp = base;
+            return self.push_raw(result);
+        }
+        let mut rendered = Vec::new();
+        if matches!(builtin, BuiltinId::Puts | BuiltinId::Str) {
+            for arg in args.iter_mut() {
+                if matches!(get_value(&self.heap, *arg), Value::Instance(_)) {
+                    let text = match self.display_value(*arg) {
+                        Ok(text) => text,
+                        Err(error) => {
+                            for string in rendered {
+                                self.heap.free(string);
+                            }
+                            return Err(error);
+                        }
+                    };
+                    *arg = alloc_value(&mut self.heap, Value::String(text));
+                    rendered.push(*arg);
+                }
+            }
+        }
+        let result =
+            call_builtin_with_output(&mut self.heap, builtin, &args, self.null, &mut self.output);
+        for string in rendered {
+            self.heap.free(string);
+        }
+        self.clear_stack_range(base, self.sp);
+        self.sp = base;
+        self.push_raw(result)
+    }
+
+    /// Natives work on `object::Object`, so the arguments are exported from
+    /// the heap and the result imported back.
+    fn call_native(
+        &mut self,
+        native: &NativeBuiltin,
+        num_args: usize,
+    ) -> Result<(), GcClassifiedRuntimeError> {
+        let base = self.sp - num_args - 1;
+        let mut args = Vec::with_capacity(num_args);
+        for reference in &self.stack[self.sp - num_args..self.sp] {
+            match try_export_object(&self.heap, *reference) {
+                Ok(arg) => args.push(Rc::new(arg)),
+                Err(_) => {
+                    return Err(self.runtime_error(
+                        GcRuntimeErrorKind::Type,
+                        format!(
+                            "builtin {} not supported for type {}",
+                            native.name,
+                            self.describe(reference)
+                        ),
+                    ))
+                }
+            }
+        }
+        let result = native.call(&mut Ctx::new(&mut self.random), &args);
+        if matches!(
+            *result,
+            Object::Function(..) | Object::Class(_) | Object::Instance(_) | Object::BoundMethod(_)
+        ) {
+            return Err(self.runtime_error(
+                GcRuntimeErrorKind::Type,
+                format!("builtin {} returned {}, which the GC VM cannot hold", native.name, result),
+            ));
+        }
+        let result = import_object(&mut self.heap, &result);
         self.clear_stack_range(base, self.sp);
         self.sp = base;
         self.push_raw(result)
@@ -1094,6 +1369,9 @@ impl GcVM {
     ) -> Result<GcRef, GcClassifiedRuntimeError> {
         let (class, field) = match get_value(&self.heap, receiver) {
             Value::Instance(instance) => (instance.class, instance.fields.get(name).copied()),
+            Value::String(_) | Value::Array(_) | Value::Hash(_) => {
+                return self.get_builtin_method(receiver, name)
+            }
             _ => {
                 return Err(self.runtime_error(
                     GcRuntimeErrorKind::Property,
@@ -1134,6 +1412,41 @@ impl GcVM {
         }
     }
 
+    /// A string, array or hash method: the builtin from `methods`, bound to
+    /// `receiver` the same way a class method is bound to an instance.
+    fn get_builtin_method(
+        &mut self,
+        receiver: GcRef,
+        name: &str,
+    ) -> Result<GcRef, GcClassifiedRuntimeError> {
+        let kind = match get_value(&self.heap, receiver) {
+            Value::String(_) => Receiver::String,
+            Value::Array(_) => Receiver::Array,
+            _ => Receiver::Hash,
+        };
+        let Some(id) = methods::lookup(kind, name) else {
+            return Err(self.runtime_error(
+                GcRuntimeErrorKind::Property,
+                format!(
+                    "cannot read property '{}' of {}",
+                    name,
+                    value_to_string(&self.heap, receiver)
+                ),
+            ));
+        };
+        let method = alloc_value(&mut self.heap, Value::Builtin(id));
+        let bound = alloc_value(
+            &mut self.heap,
+            Value::BoundMethod(GcBoundMethod {
+                receiver,
+                method,
+                name: name.to_string(),
+            }),
+        );
+        self.heap.free(method);
+        Ok(bound)
+    }
+
     fn set_property(
         &mut self,
         receiver: GcRef,
@@ -1243,6 +1556,12 @@ impl GcVM {
     ) -> Result<(), GcClassifiedRuntimeError> {
         let closure = match get_value(&self.heap, bound.method) {
             Value::Closure(closure) => closure.clone(),
+            Value::Builtin(builtin) => {
+                let builtin = *builtin;
+                let receiver = self.heap.dup(bound.receiver);
+                self.rewrite_receiver_call(bound.method, receiver, num_args)?;
+                return self.call_builtin(builtin, num_args + 1);
+            }
             _ => {
                 return Err(self.runtime_error(
                     GcRuntimeErrorKind::InvalidBytecode,
@@ -1281,6 +1600,170 @@ impl GcVM {
         self.call_closure(closure, num_args + 1)
     }
 
+    /// Runs `receiver.name(args)` to completion when `receiver` is an instance
+    /// whose class defines `name`, returning the owned result. Arguments stay
+    /// owned by the caller; the nested frame works on duplicates.
+    fn call_special_method(
+        &mut self,
+        receiver: GcRef,
+        name: &str,
+        args: &[GcRef],
+    ) -> Result<Option<GcRef>, GcClassifiedRuntimeError> {
+        let class = match get_value(&self.heap, receiver) {
+            Value::Instance(instance) => instance.class,
+            _ => return Ok(None),
+        };
+        let method = match get_value(&self.heap, class) {
+            Value::Class(class) => class.methods.get(name).copied(),
+            _ => None,
+        };
+        let Some(method) = method else {
+            return Ok(None);
+        };
+        if self.nested_run_depth >= MAX_NESTED_RUN_DEPTH {
+            return Err(self.runtime_error(
+                GcRuntimeErrorKind::Stack,
+                format!("special method '{}' nested too deeply", name),
+            ));
+        }
+        let bound = alloc_value(
+            &mut self.heap,
+            Value::BoundMethod(GcBoundMethod {
+                receiver,
+                method,
+                name: name.to_string(),
+            }),
+        );
+        let result = self.call_value(bound, args);
+        self.heap.free(bound);
+        result.map(Some)
+    }
+
+    /// Calls any callable to completion on behalf of the running instruction
+    /// and returns the owned result, nesting the dispatch loop when the call
+    /// needs a frame. `callee` and `args` stay owned by the caller.
+    fn call_value(
+        &mut self,
+        callee: GcRef,
+        args: &[GcRef],
+    ) -> Result<GcRef, GcClassifiedRuntimeError> {
+        if self.nested_run_depth >= MAX_NESTED_RUN_DEPTH {
+            return Err(self
+                .runtime_error(GcRuntimeErrorKind::Stack, "builtin callbacks nested too deeply"));
+        }
+        self.dup_and_push(callee)?;
+        for arg in args {
+            self.dup_and_push(*arg)?;
+        }
+        let depth = self.frame_index;
+        self.execute_call(args.len())?;
+        if self.frame_index > depth {
+            self.nested_run_depth += 1;
+            self.execute_frames(depth)?;
+            self.nested_run_depth -= 1;
+        }
+        self.pop_owned()
+    }
+
+    /// Moves a popped frame's callee slot and stack into a suspension,
+    /// dropping the stack back to below the callee.
+    fn suspend(&mut self, frame: &Frame) -> GcSuspension {
+        let callee = frame.base_pointer - 1;
+        let mut taken = Vec::with_capacity(self.sp - callee);
+        for index in callee..self.sp {
+            let null = self.heap.dup(self.null);
+            taken.push(std::mem::replace(&mut self.stack[index], null));
+        }
+        self.sp = callee;
+        let stack = taken.split_off(1);
+        GcSuspension {
+            closure: taken[0],
+            ip: frame.ip,
+            stack,
+        }
+    }
+
+    fn release_suspension(&mut self, suspension: GcSuspension) {
+        self.heap.free(suspension.closure);
+        for value in suspension.stack {
+            self.heap.free(value);
+        }
+    }
+
+    /// Puts a generator's frame back on the stack, with `sent` as the value
+    /// of the instruction it stopped at, and runs it until it yields or
+    /// returns. Takes over both references.
+    fn resume_generator(
+        &mut self,
+        suspension: GcSuspension,
+        sent: GcRef,
+    ) -> Result<Resumed<GcSuspension, GcRef>, GcClassifiedRuntimeError> {
+        let error = if self.nested_run_depth >= MAX_NESTED_RUN_DEPTH {
+            Some(self.runtime_error(GcRuntimeErrorKind::Stack, "generators nested too deeply"))
+        } else if self.sp + suspension.stack.len() + 2 > STACK_SIZE {
+            Some(self.runtime_error(GcRuntimeErrorKind::Stack, "stack limit exceeded"))
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            self.release_suspension(suspension);
+            self.heap.free(sent);
+            return Err(error);
+        }
+        let closure = match get_value(&self.heap, suspension.closure) {
+            Value::Closure(closure) => closure.clone(),
+            _ => unreachable!("generators suspend closure frames"),
+        };
+        let instructions = compiled_instructions(&self.heap, closure.func);
+        let depth = self.frame_index;
+        self.push_raw(suspension.closure)?;
+        let base_pointer = self.sp;
+        for value in suspension.stack {
+            self.push_raw(value)?;
+        }
+        self.push_raw(sent)?;
+        self.push_frame(Frame {
+            cl: closure,
+            ip: suspension.ip,
+            base_pointer,
+            instructions,
+        })?;
+        self.nested_run_depth += 1;
+        let result = self.execute_frames(depth);
+        self.nested_run_depth -= 1;
+        result?;
+        match self.yielded.take() {
+            Some(stopped) => Ok(stopped),
+            None => Ok(Resumed::Return(self.pop_owned()?)),
+        }
+    }
+
+    /// The `{"value": value, "done": done}` hash `next` returns, taking over
+    /// `value`.
+    fn step(&mut self, value: GcRef, done: bool) -> GcRef {
+        let done = alloc_value(&mut self.heap, Value::Boolean(done));
+        let mut entries = OrderedMap::new();
+        entries.insert(HashKey::String("value".to_string()), value);
+        entries.insert(HashKey::String("done".to_string()), done);
+        let step = alloc_value(&mut self.heap, Value::Hash(entries));
+        self.heap.free(value);
+        self.heap.free(done);
+        step
+    }
+
+    /// Text shown by `puts` and template literals, using a class-defined
+    /// `toString` when there is one.
+    fn display_value(&mut self, value: GcRef) -> Result<String, GcClassifiedRuntimeError> {
+        match self.call_special_method(value, "toString", &[])? {
+            Some(shown) => {
+                let text = value_to_string(&self.heap, shown);
+                self.heap.free(shown);
+                Ok(text)
+            }
+            None => Ok(value_to_string(&self.heap, value)),
+        }
+    }
+
     /// Takes ownership of `receiver` and frees it if the stack cannot hold
     /// the rewritten call layout.
     fn rewrite_receiver_call(
@@ -1312,6 +1795,97 @@ impl GcVM {
     }
 }
 
+impl ValueHost for GcVM {
+    type Value = GcRef;
+
+    fn array(&mut self, elements: &[GcRef]) -> GcRef {
+        alloc_value(&mut self.heap, Value::Array(elements.iter().copied().collect()))
+    }
+
+    fn null(&mut self) -> GcRef {
+        self.heap.dup(self.null)
+    }
+
+    fn retain(&mut self, value: &GcRef) -> GcRef {
+        self.heap.dup(*value)
+    }
+
+    fn release(&mut self, value: GcRef) {
+        self.heap.free(value);
+    }
+
+    fn describe(&self, value: &GcRef) -> String {
+        value_to_string(&self.heap, *value)
+    }
+}
+
+/// Lets the higher-order builtins call back into the running VM. The
+/// builtin's own arguments stay on the stack until it returns, so the element
+/// refs it borrows from its array stay alive.
+impl Host for GcVM {
+    type Error = GcClassifiedRuntimeError;
+
+    fn call(&mut self, function: &GcRef, args: &[GcRef]) -> Result<GcRef, Self::Error> {
+        self.call_value(*function, args)
+    }
+
+    fn elements(&self, value: &GcRef) -> Option<Vec<GcRef>> {
+        match get_value(&self.heap, *value) {
+            Value::Array(items) => Some(items.iter().copied().collect()),
+            _ => None,
+        }
+    }
+
+    fn boolean(&mut self, value: bool) -> GcRef {
+        alloc_value(&mut self.heap, Value::Boolean(value))
+    }
+
+    fn truthy(&self, value: &GcRef) -> bool {
+        is_truthy(&self.heap, *value)
+    }
+
+    fn sort_key(&self, value: &GcRef) -> Option<SortKey> {
+        match get_value(&self.heap, *value) {
+            Value::Integer(value) => Some(SortKey::Int(Int::Small(*value))),
+            Value::BigInt(value) => Some(SortKey::Int(Int::Big(value.clone()))),
+            Value::Float(value) => Some(SortKey::Float(*value)),
+            Value::String(value) => Some(SortKey::Str(value.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Runs generator bodies for `next` on the VM's stack.
+impl GeneratorHost for GcVM {
+    type Suspension = GcSuspension;
+    type Error = GcClassifiedRuntimeError;
+
+    fn take_state(&mut self, generator: &GcRef) -> Option<GeneratorState<GcSuspension, GcRef>> {
+        match get_value_mut(&mut self.heap, *generator) {
+            Value::Generator(state) => Some(std::mem::replace(state, GeneratorState::Running)),
+            _ => None,
+        }
+    }
+
+    fn put_state(&mut self, generator: &GcRef, state: GeneratorState<GcSuspension, GcRef>) {
+        if let Value::Generator(slot) = get_value_mut(&mut self.heap, *generator) {
+            *slot = state;
+        }
+    }
+
+    fn resume(
+        &mut self,
+        suspension: GcSuspension,
+        sent: GcRef,
+    ) -> Result<Resumed<GcSuspension, GcRef>, GcClassifiedRuntimeError> {
+        self.resume_generator(suspension, sent)
+    }
+
+    fn discard(&mut self, suspension: GcSuspension) {
+        self.release_suspension(suspension);
+    }
+}
+
 fn is_truthy(heap: &GcHeap, condition: GcRef) -> bool {
     match get_value(heap, condition) {
         Value::Boolean(b) => *b,
@@ -1324,12 +1898,26 @@ fn callee_kind(heap: &GcHeap, reference: GcRef) -> CalleeKind {
     match get_value(heap, reference) {
         Value::Closure(closure) => CalleeKind::Closure(closure.clone()),
         Value::Builtin(builtin) => CalleeKind::Builtin(*builtin),
+        Value::Native(native) => CalleeKind::Native(Rc::clone(native)),
         Value::BoundMethod(bound) => CalleeKind::BoundMethod(bound.clone()),
         Value::Class(class) => CalleeKind::Class(class.name.clone()),
         _ => CalleeKind::Other(value_to_string(heap, reference)),
     }
 }
 
+fn resolve_builtins(registry: &Builtins, names: Vec<String>) -> Vec<(String, Option<Value>)> {
+    names
+        .into_iter()
+        .map(|name| {
+            let value = registry.get(&name).map(|builtin| match builtin {
+                BuiltinEntry::Standard(definition) => Value::Builtin(definition.id),
+                BuiltinEntry::Native(native) => Value::Native(Rc::clone(native)),
+            });
+            (name, value)
+        })
+        .collect()
+}
+
 fn compiled_instructions(heap: &GcHeap, func: GcRef) -> Vec<u8> {
     match get_value(heap, func) {
         Value::CompiledFunction(f) => f.instructions.clone(),
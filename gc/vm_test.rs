@@ -1,7 +1,6 @@
 use compiler::compiler::Compiler;
-use object::Object;
+use object::{Object, OrderedMap};
 use parser::parse;
-use std::collections::HashMap;
 use std::rc::Rc;
 
 use crate::GcVM;
@@ -50,6 +49,7 @@ fn int_hash(pairs: &[(i64, i64)]) -> Object {
 mod tests {
     use super::*;
     use crate::value::{alloc_value, get_value_mut, value_to_string, Value, ValueCell, ValueKind};
+    use object::BigInt;
 
     #[test]
     fn test_integer_arithmetic() {
@@ -80,19 +80,19 @@ mod tests {
             },
             VmTestCase {
                 input: "9223372036854775807 + 1",
-                expected: Object::Integer(i64::MIN),
+                expected: Object::BigInt(BigInt::from(i64::MAX) + 1),
             },
             VmTestCase {
                 input: "(0 - 9223372036854775807 - 1) - 1",
-                expected: Object::Integer(i64::MAX),
+                expected: Object::BigInt(BigInt::from(i64::MIN) - 1),
             },
             VmTestCase {
                 input: "9223372036854775807 * 2",
-                expected: Object::Integer(-2),
+                expected: Object::BigInt(BigInt::from(i64::MAX) * 2),
             },
             VmTestCase {
                 input: "-(0 - 9223372036854775807 - 1)",
-                expected: Object::Integer(i64::MIN),
+                expected: Object::BigInt(-BigInt::from(i64::MIN)),
             },
             VmTestCase {
                 input: "5 + 5 + 5 + 5 - 10",
@@ -269,6 +269,54 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_float_arithmetic() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "1 + 0.5",
+                expected: Object::Float(1.5),
+            },
+            VmTestCase {
+                input: "-(7 / 2.0)",
+                expected: Object::Float(-3.5),
+            },
+            VmTestCase {
+                input: "1.0 / 0",
+                expected: Object::Float(f64::INFINITY),
+            },
+            VmTestCase {
+                input: "[1 == 1.0, 0.5 < 1, 2 > 2.5, 1.5 != 1.5]",
+                expected: Object::Array(
+                    [true, true, false, false]
+                        .iter()
+                        .map(|value| Rc::new(Object::Boolean(*value)))
+                        .collect(),
+                ),
+            },
+        ]);
+        assert_eq!(crate::eval_source("[7 / 2, 3 * 1.0]").unwrap().to_string(), "[3, 3.0]");
+
+        let key_error = crate::run_source_with_report_classified("{1.5: 1}", 100).unwrap_err();
+        assert_eq!(key_error.kind, "index");
+    }
+
+    #[test]
+    fn test_integer_overflow_promotes() {
+        for (input, expected) in [
+            ("-9223372036854775808 - 1", "-9223372036854775809"),
+            ("4294967296 * 4294967296", "18446744073709551616"),
+            ("100000000000000000000 > 9223372036854775807", "true"),
+            ("100000000000000000000 == 100000000000000000000", "true"),
+            ("{18446744073709551616: 1}[4294967296 * 4294967296]", "1"),
+        ] {
+            assert_eq!(crate::eval_source(input).unwrap().to_string(), expected, "input: {input}");
+        }
+        assert_eq!(
+            format!("{:?}", crate::eval_source("(9223372036854775807 + 1) - 1").unwrap()),
+            "Integer(9223372036854775807)"
+        );
+    }
+
     #[test]
     fn test_strings() {
         run_gc_vm_tests(vec![
@@ -287,6 +335,28 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_template_literals() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "``",
+                expected: Object::String("".to_string()),
+            },
+            VmTestCase {
+                input: "let n = 3; `n = ${n}!`",
+                expected: Object::String("n = 3!".to_string()),
+            },
+            VmTestCase {
+                input: r#"`${1 + 1}${true}${[1, "a"]}${if (false) { 1 }}${2.5}`"#,
+                expected: Object::String("2true[1, a]null2.5".to_string()),
+            },
+            VmTestCase {
+                input: r#"let f = fn(x) { `<${x}>` }; `${f(`${f("a")}`)}`"#,
+                expected: Object::String("<<a>>".to_string()),
+            },
+        ]);
+    }
+
     #[test]
     fn test_arrays() {
         run_gc_vm_tests(vec![
@@ -310,7 +380,7 @@ mod tests {
         run_gc_vm_tests(vec![
             VmTestCase {
                 input: "{}",
-                expected: Object::Hash(HashMap::new()),
+                expected: Object::Hash(OrderedMap::new()),
             },
             VmTestCase {
                 input: "{1: 2, 2: 3}",
@@ -532,6 +602,499 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_string_builtins() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "split(\"a,b\", \",\");",
+                expected: Object::Array(
+                    vec![
+                        Rc::from(Object::String("a".to_string())),
+                        Rc::from(Object::String("b".to_string())),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "join([1, \"b\"], \"-\");",
+                expected: Object::String("1-b".to_string()),
+            },
+            VmTestCase {
+                input: "upper(trim(\" hi \")) + substr(\"héllo\", 1, 2);",
+                expected: Object::String("HIél".to_string()),
+            },
+            VmTestCase {
+                input: "contains(replace(\"a-b\", \"-\", \"+\"), \"+\");",
+                expected: Object::Boolean(true),
+            },
+            VmTestCase {
+                input: "index_of(\"héllo\", \"l\") + int(\"40\");",
+                expected: Object::Integer(42),
+            },
+            VmTestCase {
+                input: "int(\"nope\");",
+                expected: Object::Null,
+            },
+            VmTestCase {
+                input: "str([1, 2.5]);",
+                expected: Object::String("[1, 2.5]".to_string()),
+            },
+            VmTestCase {
+                input: "lower(1);",
+                expected: Object::Error("builtin lower not supported for type 1".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_higher_order_builtins() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "map([1, 2, 3], fn(x) { x * 2 });",
+                expected: int_array(&[2, 4, 6]),
+            },
+            VmTestCase {
+                input: "let limit = 1; filter([3, 1, 2], fn(x) { x > limit });",
+                expected: int_array(&[3, 2]),
+            },
+            VmTestCase {
+                input: "reduce([1, 2, 3], fn(acc, x) { acc + x }, 10);",
+                expected: Object::Integer(16),
+            },
+            VmTestCase {
+                input: "sort([3, 1, 2]);",
+                expected: int_array(&[1, 2, 3]),
+            },
+            VmTestCase {
+                input: "sort([1, 3, 2], fn(a, b) { b - a });",
+                expected: int_array(&[3, 2, 1]),
+            },
+            VmTestCase {
+                input: "find([1, 4, 6], fn(x) { x > 3 });",
+                expected: Object::Integer(4),
+            },
+            VmTestCase {
+                input: "find([1], fn(x) { x > 3 });",
+                expected: Object::Null,
+            },
+            VmTestCase {
+                input: "[any([1, 2], fn(x) { x == 2 }), all([1, 2], fn(x) { x == 2 })];",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::Boolean(true)),
+                        Rc::new(Object::Boolean(false)),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "map([\"a\"], upper);",
+                expected: Object::Array(vec![Rc::new(Object::String("A".to_string()))].into()),
+            },
+            VmTestCase {
+                input: "map([[2, 1]], fn(xs) { sort(xs, fn(a, b) { a - b }) });",
+                expected: Object::Array(vec![Rc::new(int_array(&[1, 2]))].into()),
+            },
+            VmTestCase {
+                input: "sort([1, \"a\"]);",
+                expected: Object::Error("builtin sort cannot compare 1 and a".to_string()),
+            },
+            VmTestCase {
+                input: "map(1, fn(x) { x });",
+                expected: Object::Error("builtin map not supported for type 1".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_hash_builtins() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "keys({\"b\": 1, \"a\": 2, 3: 3});",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::String("b".to_string())),
+                        Rc::new(Object::String("a".to_string())),
+                        Rc::new(Object::Integer(3)),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "values(merge({1: 1, 2: 2}, {3: 3, 1: 4}));",
+                expected: int_array(&[4, 2, 3]),
+            },
+            VmTestCase {
+                input: "keys(delete({1: 1, 2: 2, 3: 3}, 2));",
+                expected: int_array(&[1, 3]),
+            },
+            VmTestCase {
+                input: "entries({1: 2});",
+                expected: Object::Array(vec![Rc::new(int_array(&[1, 2]))].into()),
+            },
+            VmTestCase {
+                input: "let h = {1: 1}; delete(h, 1); [has(h, 1), has(h, 2)];",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::Boolean(true)),
+                        Rc::new(Object::Boolean(false)),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "has({}, [1]);",
+                expected: Object::Error("builtin has not supported for type [1]".to_string()),
+            },
+            VmTestCase {
+                input: "keys(1);",
+                expected: Object::Error("builtin keys not supported for type 1".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_math_builtins() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "[abs(-5), min(3, 1, 2), pow(2, 10), floor(2.7), round(-2.5)];",
+                expected: int_array(&[5, 1, 1024, 2, -3]),
+            },
+            VmTestCase {
+                input: "sqrt(16);",
+                expected: Object::Float(4.0),
+            },
+            VmTestCase {
+                input: "max(1, 2.5, 2);",
+                expected: Object::Float(2.5),
+            },
+            VmTestCase {
+                input: "pow(2, 64);",
+                expected: Object::BigInt("18446744073709551616".parse().unwrap()),
+            },
+            VmTestCase {
+                input: "abs(\"a\");",
+                expected: Object::Error("builtin abs not supported for type a".to_string()),
+            },
+            VmTestCase {
+                input: "min();",
+                expected: Object::Error(
+                    "builtin min expected at least 1 argument, got 0".to_string(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_reflection_builtins() {
+        let strings = |values: &[&str]| {
+            Object::Array(
+                values
+                    .iter()
+                    .map(|v| Rc::new(Object::String(v.to_string())))
+                    .collect(),
+            )
+        };
+        let class = "class Point { constructor(x, y) { this.y = y; this.x = x; } sum() { this.x + this.y } norm() { 0 } } \
+                     class Other {} let p = new Point(1, 2);";
+        let with_class = |rest: &str| format!("{} {}", class, rest);
+        let inputs = [
+            with_class("[type_of(1), type_of(1.5), type_of(true), type_of(\"a\"), type_of([]), type_of({})];"),
+            with_class("[type_of(if (false) { 1 }), type_of(fn() {}), type_of(len)];"),
+            with_class("[type_of(Point), type_of(p), type_of(p.sum)];"),
+            with_class("[instance_of(p, Point), instance_of(p, Other), instance_of(1, Point)];"),
+            with_class("fields(p);"),
+            with_class("methods(Point);"),
+            with_class("fields(Point);"),
+        ];
+        let expected = [
+            strings(&["integer", "float", "boolean", "string", "array", "hash"]),
+            strings(&["null", "closure", "builtin"]),
+            strings(&["class", "instance", "boundMethod"]),
+            Object::Array(
+                vec![
+                    Rc::new(Object::Boolean(true)),
+                    Rc::new(Object::Boolean(false)),
+                    Rc::new(Object::Boolean(false)),
+                ]
+                .into(),
+            ),
+            strings(&["x", "y"]),
+            strings(&["norm", "sum"]),
+            Object::Error("builtin fields not supported for type [class Point]".to_string()),
+        ];
+        run_gc_vm_tests(
+            inputs
+                .iter()
+                .zip(expected)
+                .map(|(input, expected)| VmTestCase {
+                    input,
+                    expected,
+                })
+                .collect(),
+        );
+    }
+
+    #[test]
+    fn test_builtin_methods() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: r#""  monkey ".trim().upper().len();"#,
+                expected: Object::Integer(6),
+            },
+            VmTestCase {
+                input: "[1, 2].push(3).map(fn(x) { x * 2 });",
+                expected: int_array(&[2, 4, 6]),
+            },
+            VmTestCase {
+                input: "[3, 1, 2].sort().reduce(fn(acc, x) { acc * 10 + x }, 0);",
+                expected: Object::Integer(123),
+            },
+            VmTestCase {
+                input: r#"let h = {"a": 1}; h.merge({"b": 2}).values();"#,
+                expected: int_array(&[1, 2]),
+            },
+            VmTestCase {
+                input: "let push = [1].push; [push(2), [type_of(push)]];",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(int_array(&[1, 2])),
+                        Rc::new(Object::Array(
+                            vec![Rc::new(Object::String("boundMethod".to_string()))].into(),
+                        )),
+                    ]
+                    .into(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_arrays_and_hashes_are_values() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "let a = [1, 2]; let b = push(a, 3); [len(a), len(b), first(rest(b)), len(rest(a))];",
+                expected: int_array(&[2, 3, 2, 1]),
+            },
+            VmTestCase {
+                input: r#"let h = {"a": 1, "b": 2, "c": 3}; let d = delete(h, "b"); push(values(merge(d, {"b": 4, "a": 5})), len(keys(h)));"#,
+                expected: int_array(&[5, 3, 4, 3]),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_iterator_builtins() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "to_array(range(10, 0, -3));",
+                expected: int_array(&[10, 7, 4, 1]),
+            },
+            VmTestCase {
+                input: "to_array(take(range(0, 1000000000), 3));",
+                expected: int_array(&[0, 1, 2]),
+            },
+            VmTestCase {
+                input: "let r = range(2, 5); [len(r), first(r), len(to_array(rest(r))), len(r)];",
+                expected: int_array(&[3, 2, 2, 3]),
+            },
+            VmTestCase {
+                input: "to_array(zip(range(3), enumerate([7, 8])));",
+                expected: Object::Array(
+                    vec![
+                        Rc::new(Object::Array(
+                            vec![Rc::new(Object::Integer(0)), Rc::new(int_array(&[0, 7]))].into(),
+                        )),
+                        Rc::new(Object::Array(
+                            vec![Rc::new(Object::Integer(1)), Rc::new(int_array(&[1, 8]))].into(),
+                        )),
+                    ]
+                    .into(),
+                ),
+            },
+            VmTestCase {
+                input: "type_of(range(1));",
+                expected: Object::String("iterator".to_string()),
+            },
+            VmTestCase {
+                input: "[len(range(0, 9223372036854775807)), len(take(rest(range(0, 9223372036854775807, 2)), 5))];",
+                expected: int_array(&[9223372036854775807, 5]),
+            },
+            VmTestCase {
+                input: "to_array(range(0, 9223372036854775807));",
+                expected: Object::Error(
+                    "builtin to_array iterator has more than 16777216 elements".to_string(),
+                ),
+            },
+            VmTestCase {
+                input: "let sum = fn(it) { reduce(to_array(range(20000)), fn(acc, x) { [rest(acc[0]), acc[1] + first(acc[0])] }, [it, 0])[1] }; [sum(range(20000)), sum(take(to_array(range(20000)), 20000))];",
+                expected: int_array(&[199990000, 199990000]),
+            },
+        ]);
+    }
+
+    #[test]
+    fn generators_keep_their_frame_between_yields() {
+        let drain = "let drain = fn(g, acc) { let step = next(g); if (step[\"done\"]) { acc } else { drain(g, push(acc, step[\"value\"])) } };";
+        let tests = [
+            (
+                "let count = fn*(n) { yield n; yield n + 1; n + 2 }; let g = count(1); [next(g), next(g), next(g), next(g)]",
+                "[{value: 1, done: false}, {value: 2, done: false}, {value: 3, done: true}, {value: null, done: true}]",
+            ),
+            (
+                "let sum = fn*() { let a = yield 0; let b = yield a * 2; return a + b; }; let g = sum(); next(g, 100); [next(g, 5)[\"value\"], next(g, 7)[\"value\"]]",
+                "[10, 12]",
+            ),
+            (
+                "let pair = fn*(a) { yield a; yield a + 1; \"pair\" }; let both = fn*() { let last = yield* pair(1); yield last; yield* pair(10); }; drain(both(), [])",
+                "[1, 2, pair, 10, 11]",
+            ),
+            (
+                "let from = fn*(n) { yield n; return yield* from(n + 1); }; let g = from(0); let skip = fn(n) { if (n == 0) { next(g) } else { next(g); skip(n - 1) } }; skip(500)[\"value\"]",
+                "500",
+            ),
+            (
+                "let deep = fn*(n) { if (n == 0) { yield \"bottom\"; return 0; } let below = yield* deep(n - 1); below + 1 }; let g = deep(2000); [next(g)[\"value\"], next(g)[\"value\"]]",
+                "[bottom, 2000]",
+            ),
+            ("let g = fn*() { yield 1 }; [type_of(g), type_of(g()), g()]", "[closure, generator, [generator]]"),
+            ("next(1)", "builtin next not supported for type 1"),
+            (
+                "let g = fn*() { yield 1 }; [take(g(), 1), to_array(g()), first(g()), len(g())]",
+                "[builtin take not supported for type [generator], builtin to_array not supported for type [generator], builtin first not supported for for type [generator], builtin len not supported for for type [generator]]",
+            ),
+            ("let g = fn*() { yield* 1 }; next(g())", "yield* expected a generator, got 1"),
+        ];
+        for (input, expected) in tests {
+            let source = format!("{}{}", drain, input);
+            let mut vm = GcVM::new(Compiler::new().compile(&parse(&source).unwrap()).unwrap());
+            vm.run();
+            assert_eq!(vm.last_result_string(), expected, "{}", input);
+        }
+
+        let error = crate::run_source_with_report_classified(
+            "let g = fn*() { yield 1; 1 / 0 }; let it = g(); next(it); next(it);",
+            1_000,
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, "arithmetic");
+    }
+
+    #[test]
+    fn test_json_builtins() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: r#"json_encode({"a": [1, 2.5, true], "b": "x", 3: false});"#,
+                expected: Object::String(r#"{"a":[1,2.5,true],"b":"x","3":false}"#.to_string()),
+            },
+            VmTestCase {
+                input: r#"let h = json_decode(`{"b": [1, null], "a": 2.5}`); [keys(h), h["b"][1], h["a"]];"#,
+                expected: Object::Array(vec![
+                    Rc::new(Object::Array(vec![
+                        Rc::new(Object::String("b".to_string())),
+                        Rc::new(Object::String("a".to_string())),
+                    ].into())),
+                    Rc::new(Object::Null),
+                    Rc::new(Object::Float(2.5)),
+                ].into()),
+            },
+            VmTestCase {
+                input: "json_decode(json_encode([1, [2, 3]]))[1][0];",
+                expected: Object::Integer(2),
+            },
+            VmTestCase {
+                input: "json_decode(json_encode(9223372036854775807 * 10));",
+                expected: Object::BigInt("92233720368547758070".parse().unwrap()),
+            },
+            VmTestCase {
+                input: "json_encode([fn(x) { x }]);",
+                expected: Object::Error(
+                    "builtin json_encode cannot encode [closure function]".to_string(),
+                ),
+            },
+            VmTestCase {
+                input: "json_decode(`[1,`);",
+                expected: Object::Error(
+                    "builtin json_decode got invalid JSON: EOF while parsing a value at line 1 column 3"
+                        .to_string(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_random_builtins() {
+        run_gc_vm_tests(vec![
+            VmTestCase {
+                input: "seed(42); [random(100), random(100), random(100)];",
+                expected: int_array(&[13, 91, 58]),
+            },
+            VmTestCase {
+                input: "[random(1000), random(1000)];",
+                expected: int_array(&[535, 700]),
+            },
+            VmTestCase {
+                input: "let f = fn() { random(1000) }; seed(9); let a = f(); seed(9); a == random(1000);",
+                expected: Object::Boolean(true),
+            },
+            VmTestCase {
+                input: "random(0);",
+                expected: Object::Error(
+                    "builtin random expected a positive bound, got 0".to_string(),
+                ),
+            },
+        ]);
+    }
+
+    #[test]
+    fn host_registered_builtins_resolve_by_name() {
+        use object::registry::{Arity, Builtins};
+        use std::cell::Cell;
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        let mut builtins = Builtins::standard();
+        builtins.register("tick", Arity::exactly(0), "Counts calls from the host.", move |_, _| {
+            counter.set(counter.get() + 1);
+            return Ok(Rc::new(Object::Array(
+                vec![Rc::new(Object::Integer(counter.get()))].into(),
+            )));
+        });
+
+        let program = parse("tick(); let f = fn() { tick() }; [f(), tick(1)]").unwrap();
+        let compile = || {
+            Compiler::with_builtins(&builtins)
+                .compile(&program)
+                .unwrap()
+        };
+
+        let mut vm = GcVM::with_builtins(compile(), builtins.clone());
+        vm.run();
+        let expected = Object::Array(
+            vec![
+                Rc::new(int_array(&[2])),
+                Rc::new(Object::Error("builtin tick expected 0 arguments, got 1".to_string())),
+            ]
+            .into(),
+        );
+        assert_eq!(vm.export_last_result().unwrap(), expected);
+        assert_eq!(calls.get(), 2);
+
+        let mut vm = GcVM::new(compile());
+        let error = vm.run_with_budget(1_000).unwrap_err();
+        assert_eq!(error.message, "builtin tick is not registered");
+    }
+
+    #[test]
+    fn higher_order_callback_errors_abort_the_program() {
+        let error =
+            crate::run_source_with_report_classified("map([1], fn(x) { x.missing });", 1_000)
+                .unwrap_err();
+        assert!(error
+            .message
+            .contains("cannot read property 'missing' of 1"));
+    }
+
     #[test]
     fn builtin_call_releases_callee_args_and_stack_temporaries() {
         let program = parse("len([1, 2, 3]);").unwrap();
@@ -732,6 +1295,74 @@ mod tests {
         ]);
     }
 
+    const VECTOR_CLASS: &str = r#"
+        class Vec {
+          constructor(x, y) { this.x = x; this.y = y; }
+          add(o) { new Vec(this.x + o.x, this.y + o.y) }
+          sub(o) { new Vec(this.x - o.x, this.y - o.y) }
+          eq(o) { if (this.x == o.x) { this.y == o.y } else { false } }
+          get(i) { if (i == 0) { this.x } else { this.y } }
+          toString() { `(${this.x}, ${this.y})` }
+        }
+    "#;
+
+    #[test]
+    fn operators_dispatch_to_special_methods() {
+        let cases = [
+            ("`${new Vec(1, 2) + new Vec(3, 4)}`", Object::String("(4, 6)".to_string())),
+            ("(new Vec(5, 5) - new Vec(1, 2))[1]", Object::Integer(3)),
+            ("new Vec(1, 2) == new Vec(1, 2)", Object::Boolean(true)),
+            ("new Vec(1, 2) != new Vec(1, 2)", Object::Boolean(false)),
+            ("let f = fn(v) { v[0] + v[1] }; f(new Vec(40, 2))", Object::Integer(42)),
+            ("str(new Vec(1, 2))", Object::String("(1, 2)".to_string())),
+        ];
+        for (input, expected) in cases {
+            let source = format!("{}{}", VECTOR_CLASS, input);
+            run_gc_vm_tests(vec![VmTestCase {
+                input: &source,
+                expected,
+            }]);
+        }
+
+        let printed = format!(
+            "{}puts(new Vec(1, 2), [new Vec(3, 4)]); new Vec(0, 0) + new Vec(0, 0);",
+            VECTOR_CLASS
+        );
+        let mut vm = GcVM::new(Compiler::new().compile(&parse(&printed).unwrap()).unwrap());
+        let output = Rc::new(std::cell::RefCell::new(String::new()));
+        vm.set_output(object::output::Output::shared(&output));
+        vm.run();
+        assert_eq!(output.borrow().as_str(), "(1, 2)\n[[object Vec]]\n");
+        let report = vm.collect_garbage();
+        assert_eq!(
+            report
+                .after
+                .by_value_kind
+                .get(&ValueKind::Instance)
+                .copied()
+                .unwrap_or(0),
+            1
+        );
+
+        let error =
+            crate::run_source_with_report_classified("class M { sub() { 0 } } new M() - 1;", 1_000)
+                .unwrap_err();
+        assert_eq!(error.kind, "call");
+        assert!(error
+            .message
+            .contains("wrong number of arguments for M.sub: want=0, got=1"));
+
+        let limit = crate::run_source_with_report_classified(
+            "class Spin { add(o) { this + o } } new Spin() + 1;",
+            usize::MAX,
+        )
+        .unwrap_err();
+        assert_eq!(limit.kind, "stack");
+        assert!(limit
+            .message
+            .contains("special method 'add' nested too deeply"));
+    }
+
     fn cycle_vm(source: &str) -> GcVM {
         let program = parse(source).unwrap();
         let mut compiler = Compiler::new();
@@ -903,10 +1534,10 @@ mod tests {
         // Seed an unreachable self-cycle. The threshold below is calibrated so
         // the class and its two closures allocate first, then the instance
         // allocation runs GC while its receiver exists only as an owned local.
-        let cycle = alloc_value(vm.heap_mut(), Value::Array(vec![]));
+        let cycle = alloc_value(vm.heap_mut(), Value::Array(vec![].into()));
         let cycle_edge = vm.heap_mut().dup(cycle);
         match get_value_mut(vm.heap_mut(), cycle) {
-            Value::Array(items) => items.push(cycle_edge),
+            Value::Array(items) => items.push_back(cycle_edge),
             _ => unreachable!(),
         }
         vm.heap_mut().free(cycle);
@@ -960,6 +1591,29 @@ mod tests {
         assert_eq!(value_to_string(bound.heap(), bound.last_popped_stack_elm().unwrap()), "42");
     }
 
+    #[test]
+    fn generator_holding_its_owner_is_collected() {
+        let mut vm = cycle_vm(
+            r#"
+                class Box {}
+                let kept = fn*() { yield 1; }();
+                let makeCycle = fn() {
+                  let box = new Box();
+                  let hold = fn*(b) { yield b; };
+                  box.it = hold(box);
+                  next(box.it);
+                };
+                makeCycle();
+                next(kept)["value"];
+            "#,
+        );
+        let report = vm.collect_garbage();
+        assert_eq!(report.before.by_value_kind[&ValueKind::Generator], 2);
+        assert_eq!(report.after.by_value_kind[&ValueKind::Generator], 1);
+        assert_eq!(report.after.by_value_kind[&ValueKind::Instance], 0);
+        assert_eq!(vm.last_result_string(), "1");
+    }
+
     #[test]
     fn instance_bound_method_cycle_is_collected() {
         let mut vm = cycle_vm(
@@ -1073,10 +1727,7 @@ mod tests {
 
         let division_overflow =
             crate::run_source_with_report("(0 - 9223372036854775807 - 1) / (0 - 1);", 10_000)
-                .unwrap_err();
-        assert_eq!(division_overflow.stage, crate::GcRunStage::Runtime);
-        assert!(division_overflow
-            .message
-            .contains("integer overflow in division"));
+                .unwrap();
+        assert_eq!(division_overflow.result, "9223372036854775808");
     }
 }
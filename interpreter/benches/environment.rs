@@ -0,0 +1,119 @@
+//! Compares looking names up by string in each scope's map, as the
+//! interpreter did before validation resolved them, with the slot lookups it
+//! does now, on a chain of scopes shaped like a closure three calls deep.
+//! Also times whole programs that lean on name lookup.
+//!
+//! Run with `cargo bench -p monkey-interpreter`.
+
+use std::cell::RefCell;
+use std::hint::black_box;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use interpreter::eval;
+use object::environment::{Env, Environment};
+use object::Object;
+
+const DEPTH: usize = 3;
+const NAMES_PER_SCOPE: usize = 4;
+const LOOKUPS: usize = 1_000_000;
+
+fn main() {
+    lookups();
+    programs();
+}
+
+fn lookups() {
+    let root: Env = Rc::new(RefCell::new(Environment::default()));
+    let mut scope = Rc::clone(&root);
+    for depth in 0..DEPTH {
+        let mut inner = Environment::new_enclosed_environment(&scope);
+        for index in 0..NAMES_PER_SCOPE {
+            let value = Rc::new(Object::Integer(index as i64));
+            inner.set(name(depth, index), Rc::clone(&value));
+            inner.set_local(index, value);
+        }
+        scope = Rc::new(RefCell::new(inner));
+    }
+    // Every name in the chain, looked up from the innermost scope.
+    let by_name: Vec<String> = (0..DEPTH)
+        .flat_map(|depth| (0..NAMES_PER_SCOPE).map(move |index| name(depth, index)))
+        .collect();
+    let by_slot: Vec<(usize, usize)> = (0..DEPTH)
+        .flat_map(|depth| (0..NAMES_PER_SCOPE).map(move |index| (DEPTH - 1 - depth, index)))
+        .collect();
+
+    report("get by name", LOOKUPS, || {
+        let env = scope.borrow();
+        for i in 0..LOOKUPS {
+            black_box(env.get(&by_name[i % by_name.len()]));
+        }
+    });
+    report("get by slot", LOOKUPS, || {
+        let env = scope.borrow();
+        for i in 0..LOOKUPS {
+            let (depth, index) = by_slot[i % by_slot.len()];
+            black_box(env.get_local(depth, index));
+        }
+    });
+    let value = Rc::new(Object::Integer(0));
+    report("set by name", LOOKUPS, || {
+        let mut env = scope.borrow_mut();
+        for i in 0..LOOKUPS {
+            env.set(name(0, i % NAMES_PER_SCOPE), Rc::clone(&value));
+        }
+    });
+    report("set by slot", LOOKUPS, || {
+        let mut env = scope.borrow_mut();
+        for i in 0..LOOKUPS {
+            env.set_local(i % NAMES_PER_SCOPE, Rc::clone(&value));
+        }
+    });
+}
+
+fn programs() {
+    let programs = [
+        (
+            "fib(20)",
+            "let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }; fib(20)",
+        ),
+        (
+            "tail count to 1M",
+            "let count = fn(n, acc) { if (n == 0) { acc } else { count(n - 1, acc + 1) } }; count(1000000, 0)",
+        ),
+        (
+            "closure sum",
+            "let add = fn(a) { fn(b) { fn(c) { a + b + c } } }; let loop = fn(n, acc) { if (n == 0) { acc } else { loop(n - 1, add(n)(1)(acc)) } }; loop(100000, 0)",
+        ),
+    ];
+    for (label, source) in programs.iter() {
+        report(label, 1, || {
+            let env: Env = Rc::new(RefCell::new(Environment::default()));
+            let node = parser::parse(source).expect("benchmark programs parse");
+            black_box(eval(node, &env).expect("benchmark programs run"));
+        });
+    }
+}
+
+fn name(depth: usize, index: usize) -> String {
+    return format!("name_{}_{}", depth, index);
+}
+
+/// Runs `run` once to warm up, then five times, and prints the fastest run.
+fn report(label: &str, operations: usize, mut run: impl FnMut()) {
+    run();
+    let fastest = (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+    if operations > 1 {
+        let per_operation = fastest.as_nanos() as f64 / operations as f64;
+        println!("{:<20} {:>10.2?} ({:.1} ns each)", label, fastest, per_operation);
+    } else {
+        println!("{:<20} {:>10.2?}", label, fastest);
+    }
+}
@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::run_command;
+
+/// Scratch file under a shared temp directory. Tests run in parallel, so
+/// every test uses file names unique to itself.
+fn write_source(name: &str, source: &str) -> String {
+    let dir = std::env::temp_dir().join("monkey-interpreter-cli-tests");
+    fs::create_dir_all(&dir).unwrap();
+    let path: PathBuf = dir.join(name);
+    fs::write(&path, source).unwrap();
+    path.display().to_string()
+}
+
+#[test]
+fn run_evaluates_a_multi_line_script() {
+    let source =
+        write_source("multi-line.monkey", "let add = fn(a, b) {\n  a + b\n};\n\nadd(20, 22)\n");
+    assert_eq!(run_command(&[source]).unwrap(), "42");
+}
+
+#[test]
+fn arguments_after_the_separator_reach_the_script() {
+    let source = write_source("args.monkey", "[len(args), args[1]]");
+    let outcome = run_command(&[
+        source,
+        "--".to_string(),
+        "-v".to_string(),
+        "two".to_string(),
+    ]);
+    assert_eq!(outcome.unwrap(), "[2, two]");
+}
+
+#[test]
+fn script_errors_exit_with_code_one() {
+    let parse_error = run_command(&[write_source("parse-error.monkey", "let = 1;")]).unwrap_err();
+    assert_eq!(parse_error.exit_code, 1);
+    assert!(parse_error.message.starts_with("error: parse error:"), "got: {}", parse_error.message);
+
+    let runtime = run_command(&[write_source(
+        "runtime-error.monkey",
+        "let f = fn() { 1 + true }; f()",
+    )])
+    .unwrap_err();
+    assert_eq!(runtime.exit_code, 1);
+    assert!(runtime.message.contains("source offset"), "got: {}", runtime.message);
+    assert!(runtime.message.contains("in f called at"), "got: {}", runtime.message);
+}
+
+#[test]
+fn annotations_never_change_execution() {
+    let source = write_source("typed-bad.monkey", "let x = 1;\nlet s: string = x;\ns");
+    assert_eq!(run_command(&[source]).unwrap(), "1");
+}
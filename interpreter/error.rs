@@ -0,0 +1,124 @@
+use std::fmt;
+
+use parser::lexer::token::Span;
+use serde::Serialize;
+
+/// How many calls [`EvalError::render`] shows from each end of a deep stack.
+const RENDERED_FRAMES: usize = 10;
+
+/// What went wrong, in the categories the GC VM reports
+/// (`GcRuntimeErrorKind`), so the same program fails the same way on both.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EvalErrorKind {
+    /// The program failed the checks the compiler also runs, before any of
+    /// it was evaluated, or named something that does not exist.
+    Compile,
+    Arithmetic,
+    Call,
+    ExecutionLimit,
+    Index,
+    Property,
+    Stack,
+    Type,
+}
+
+impl EvalErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Compile => "compile",
+            Self::Arithmetic => "arithmetic",
+            Self::Call => "call",
+            Self::ExecutionLimit => "executionLimit",
+            Self::Index => "index",
+            Self::Property => "property",
+            Self::Stack => "stack",
+            Self::Type => "type",
+        }
+    }
+}
+
+/// A Monkey function that was running when the error happened.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// The name it was called by: the identifier, `Class.method`, or
+    /// `<anonymous>` when the callee was some other expression.
+    pub function: String,
+    /// The call expression that entered it.
+    pub span: Span,
+}
+
+/// A failed evaluation. `Display` shows only the message; [`EvalError::render`]
+/// adds the kind, location and call stack.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalError {
+    pub kind: EvalErrorKind,
+    pub message: String,
+    /// The innermost expression that failed.
+    pub span: Option<Span>,
+    /// The Monkey calls the error passed through, innermost first.
+    pub call_stack: Vec<CallFrame>,
+}
+
+impl EvalError {
+    pub fn new(kind: EvalErrorKind, message: impl Into<String>) -> EvalError {
+        return EvalError {
+            kind,
+            message: message.into(),
+            span: None,
+            call_stack: Vec::new(),
+        };
+    }
+
+    /// Blames `span` unless a nested expression already took the blame.
+    pub(crate) fn at(mut self, span: &Span) -> EvalError {
+        if self.span.is_none() {
+            self.span = Some(span.clone());
+        }
+        return self;
+    }
+
+    /// Records that the error left the body of `function`, called at `span`.
+    pub(crate) fn called_from(mut self, function: &str, span: &Span) -> EvalError {
+        self.call_stack.push(CallFrame {
+            function: function.to_string(),
+            span: span.clone(),
+        });
+        return self;
+    }
+
+    /// The error as the `monkey-interpreter` REPL prints it: the kind, the
+    /// message and where it happened, then one line per active call. Deep
+    /// stacks keep only their innermost and outermost calls.
+    pub fn render(&self) -> String {
+        let mut rendered = format!("{} error: {}", self.kind.as_str(), self.message);
+        if let Some(span) = &self.span {
+            rendered.push_str(&format!(" (source offset {}..{})", span.start, span.end));
+        }
+        let frames = self.call_stack.len();
+        for (index, frame) in self.call_stack.iter().enumerate() {
+            if frames > 2 * RENDERED_FRAMES && index == RENDERED_FRAMES {
+                let hidden = frames - 2 * RENDERED_FRAMES;
+                rendered.push_str(&format!("\n    ... {} more calls", hidden));
+            }
+            if frames > 2 * RENDERED_FRAMES
+                && (RENDERED_FRAMES..frames - RENDERED_FRAMES).contains(&index)
+            {
+                continue;
+            }
+            rendered.push_str(&format!(
+                "\n    in {} called at source offset {}..{}",
+                frame.function, frame.span.start, frame.span.end
+            ));
+        }
+        return rendered;
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,286 @@
+//! Running `fn*` bodies a piece at a time. Validation only lets a `yield`
+//! stop a whole statement, directly in the body or in `if`s used as
+//! statements, so where a body stopped is a path through its statements:
+//! the index of a statement, then for an `if` the branch taken and the index
+//! in that branch, and so on down to the statement with the `yield`.
+//! Resuming follows the path without evaluating anything on it again.
+
+use std::rc::Rc;
+
+use object::environment::Env;
+use object::generators::{
+    self, new_generator, step, GeneratorHost, GeneratorState, NextError, Resumed, Suspension,
+};
+use object::higher_order::ObjectValues;
+use object::Object;
+use parser::ast::*;
+use parser::lexer::token::TokenKind;
+
+use crate::error::EvalError;
+use crate::limits::EvalState;
+use crate::{bind, eval_expression, eval_statement, is_truthy, unwrap_return, CallSite};
+
+/// How a run of a body, or of a block in it, stopped. Paths lead to the
+/// statement that stopped it.
+enum Stop {
+    Yield(Rc<Object>, Vec<usize>),
+    Delegate(Rc<Object>, Vec<usize>),
+    /// At a `return yield*`.
+    Forward(Rc<Object>),
+    /// With the block's value, wrapped in `Object::ReturnValue` if it came
+    /// from a `return`.
+    Done(Rc<Object>),
+}
+
+/// A generator for a call of the `fn*` function `function`, whose body will
+/// run in `env`.
+pub(crate) fn generator(function: &Rc<Object>, env: Env) -> Rc<Object> {
+    return new_generator(Suspension::Body {
+        function: Rc::clone(function),
+        env,
+        resume_at: Vec::new(),
+    });
+}
+
+/// The `next` builtin. Bad arguments are `Error` values like those of any
+/// builtin; an error in the body ends the generator and the run.
+pub(crate) fn next(
+    args: &[Rc<Object>],
+    site: &CallSite,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
+    let mut host = Host {
+        site,
+        state,
+    };
+    match generators::next(&mut host, args) {
+        Ok((value, done)) => return Ok(step(value, done)),
+        Err(NextError::Builtin(error)) => {
+            return Ok(Rc::new(Object::Error(error.message().to_string())))
+        }
+        Err(NextError::Resume(error)) => return Err(error),
+    }
+}
+
+/// Runs bodies on behalf of the `next` call at `site`.
+struct Host<'a, 's, 'o> {
+    site: &'a CallSite<'s>,
+    state: &'a EvalState<'o>,
+}
+
+impl ObjectValues for Host<'_, '_, '_> {}
+
+impl GeneratorHost for Host<'_, '_, '_> {
+    type Suspension = Suspension;
+    type Error = EvalError;
+
+    fn take_state(
+        &mut self,
+        generator: &Rc<Object>,
+    ) -> Option<GeneratorState<Suspension, Rc<Object>>> {
+        return generators::take_state(generator);
+    }
+
+    fn put_state(&mut self, generator: &Rc<Object>, state: GeneratorState<Suspension, Rc<Object>>) {
+        generators::put_state(generator, state);
+    }
+
+    fn resume(
+        &mut self,
+        suspension: Suspension,
+        sent: Rc<Object>,
+    ) -> Result<Resumed<Suspension, Rc<Object>>, EvalError> {
+        let Suspension::Body {
+            function,
+            env,
+            resume_at,
+        } = suspension
+        else {
+            unreachable!("interpreter generators stop in a body")
+        };
+        let Object::GeneratorFunction(_, body, _) = &*function else {
+            unreachable!("interpreter generators run a generator function")
+        };
+        let (site, state) = (self.site, self.state);
+        let stop = state.nested(|| {
+            resume_block(&body.body, &resume_at, sent, &env, state)
+                .map_err(|error| error.called_from(site.name, site.span))
+        })?;
+        let suspended = |resume_at| Suspension::Body {
+            function: Rc::clone(&function),
+            env: Rc::clone(&env),
+            resume_at,
+        };
+        match stop {
+            Stop::Yield(value, resume_at) => {
+                return Ok(Resumed::Yield(value, suspended(resume_at)))
+            }
+            Stop::Delegate(inner, resume_at) => {
+                return Ok(Resumed::Delegate(inner, suspended(resume_at)))
+            }
+            Stop::Forward(inner) => return Ok(Resumed::Forward(inner)),
+            Stop::Done(value) => return Ok(Resumed::Return(unwrap_return(value)?)),
+        }
+    }
+
+    fn discard(&mut self, _: Suspension) {}
+}
+
+/// Runs `statements` from the start, or with `sent` as the value of the
+/// `yield` at `resume_at` and on from there.
+fn resume_block(
+    statements: &[Statement],
+    resume_at: &[usize],
+    sent: Rc<Object>,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Stop, EvalError> {
+    let mut result = Rc::new(Object::Null);
+    let mut next = 0;
+    if let Some((&index, inner)) = resume_at.split_first() {
+        match finish_statement(&statements[index], inner, index, sent, env, state)? {
+            Stop::Done(value) if matches!(*value, Object::ReturnValue(_)) => {
+                return Ok(Stop::Done(value))
+            }
+            Stop::Done(value) => result = value,
+            yielded => return Ok(yielded),
+        }
+        next = index + 1;
+    }
+
+    for (index, statement) in statements.iter().enumerate().skip(next) {
+        match run_statement(statement, index, env, state)? {
+            Stop::Done(value) if matches!(*value, Object::ReturnValue(_)) => {
+                return Ok(Stop::Done(value))
+            }
+            Stop::Done(value) => result = value,
+            yielded => return Ok(yielded),
+        }
+    }
+    return Ok(Stop::Done(result));
+}
+
+/// Runs the statement at `index` from its start.
+fn run_statement(
+    statement: &Statement,
+    index: usize,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Stop, EvalError> {
+    let yielded = match statement {
+        Statement::Expr(Expression::Yield(expression))
+        | Statement::Let(Let {
+            expr: Expression::Yield(expression),
+            ..
+        })
+        | Statement::Return(ReturnStatement {
+            argument: Expression::Yield(expression),
+            ..
+        }) => expression,
+        Statement::Expr(Expression::IF(expression)) if if_yields(expression) => {
+            let condition = eval_expression(&expression.condition, env, state)?;
+            let (branch, block) = if is_truthy(&condition) {
+                (0, Some(&expression.consequent))
+            } else {
+                (1, expression.alternate.as_ref())
+            };
+            let Some(block) = block else {
+                return Ok(Stop::Done(Rc::new(Object::Null)));
+            };
+            let resumed = resume_block(&block.body, &[], Rc::new(Object::Null), env, state)?;
+            return Ok(prefix_path(resumed, &[index, branch]));
+        }
+        statement => return eval_statement(statement, env, state).map(Stop::Done),
+    };
+    let value = match &yielded.argument {
+        Some(argument) => eval_expression(argument, env, state)?,
+        None => Rc::new(Object::Null),
+    };
+    if !yielded.delegate {
+        return Ok(Stop::Yield(value, vec![index]));
+    }
+    if let Statement::Return(_) = statement {
+        return Ok(Stop::Forward(value));
+    }
+    return Ok(Stop::Delegate(value, vec![index]));
+}
+
+/// Goes on with the statement at `index` given `sent`: the `yield` in it
+/// when `inner` is empty, else the branch of the `if` it names.
+fn finish_statement(
+    statement: &Statement,
+    inner: &[usize],
+    index: usize,
+    sent: Rc<Object>,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Stop, EvalError> {
+    match statement {
+        Statement::Expr(Expression::Yield(_)) => return Ok(Stop::Done(sent)),
+        Statement::Return(_) => return Ok(Stop::Done(Rc::new(Object::ReturnValue(sent)))),
+        Statement::Let(Let {
+            identifier,
+            resolution,
+            ..
+        }) => {
+            if let TokenKind::IDENTIFIER {
+                name,
+            } = &identifier.kind
+            {
+                bind(env, resolution, name, &identifier.span, sent, state);
+            }
+            return Ok(Stop::Done(Rc::new(Object::Null)));
+        }
+        Statement::Expr(Expression::IF(expression)) => {
+            let (branch, inner) = inner
+                .split_first()
+                .expect("a path into an if names a branch");
+            let block = if *branch == 0 {
+                &expression.consequent
+            } else {
+                expression
+                    .alternate
+                    .as_ref()
+                    .expect("the path took an existing branch")
+            };
+            let resumed = resume_block(&block.body, inner, sent, env, state)?;
+            return Ok(prefix_path(resumed, &[index, *branch]));
+        }
+        _ => unreachable!("a generator only stops at a yield"),
+    }
+}
+
+fn prefix_path(stop: Stop, prefix: &[usize]) -> Stop {
+    let prefixed = |path| {
+        let mut full = prefix.to_vec();
+        full.extend(path);
+        return full;
+    };
+    match stop {
+        Stop::Yield(value, path) => return Stop::Yield(value, prefixed(path)),
+        Stop::Delegate(inner, path) => return Stop::Delegate(inner, prefixed(path)),
+        stop => return stop,
+    }
+}
+
+/// Whether a branch of the `if` can stop at a `yield`.
+fn if_yields(expression: &IF) -> bool {
+    return block_yields(&expression.consequent)
+        || expression.alternate.as_ref().is_some_and(block_yields);
+}
+
+fn block_yields(block: &BlockStatement) -> bool {
+    return block.body.iter().any(|statement| match statement {
+        Statement::Expr(Expression::Yield(_)) => true,
+        Statement::Let(Let {
+            expr: Expression::Yield(_),
+            ..
+        }) => true,
+        Statement::Return(ReturnStatement {
+            argument: Expression::Yield(_),
+            ..
+        }) => true,
+        Statement::Expr(Expression::IF(expression)) => if_yields(expression),
+        _ => false,
+    });
+}
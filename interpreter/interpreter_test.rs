@@ -33,6 +33,48 @@ mod tests {
         apply_test(&test_case);
     }
 
+    #[test]
+    fn test_float_expressions() {
+        let test_case = [
+            ("1.5", "1.5"),
+            ("-2.25", "-2.25"),
+            ("0.5 + 0.25", "0.75"),
+            ("1 + 0.5", "1.5"),
+            ("3 * 1.0", "3.0"),
+            ("7 / 2", "3"),
+            ("7 / 2.0", "3.5"),
+            ("1.0 / 0", "inf"),
+            ("0.5 < 1", "true"),
+            ("2 > 2.5", "false"),
+            ("1 == 1.0", "true"),
+            ("0.1 + 0.2 == 0.3", "false"),
+            ("[1.5] == [1.5]", "true"),
+            ("if (0.0) { 1 } else { 2 }", "1"),
+            ("{1.5: 1}", "key 1.5 is not hashable"),
+            ("{1: 2}[1.0]", "not a valid hash key"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_integer_overflow_promotes() {
+        let test_case = [
+            ("9223372036854775807 + 1", "9223372036854775808"),
+            ("-9223372036854775808 - 1", "-9223372036854775809"),
+            ("-9223372036854775808", "-9223372036854775808"),
+            ("-(-9223372036854775808)", "9223372036854775808"),
+            ("-9223372036854775808 / -1", "9223372036854775808"),
+            ("4294967296 * 4294967296", "18446744073709551616"),
+            ("(9223372036854775807 + 1) - 1", "9223372036854775807"),
+            ("(9223372036854775807 + 1) - 1 == 9223372036854775807", "true"),
+            ("100000000000000000000 > 9223372036854775807", "true"),
+            ("100000000000000000000 * 0.5", "5e19"),
+            ("{18446744073709551616: 1}[4294967296 * 4294967296]", "1"),
+            ("1 / 0", "division by zero"),
+        ];
+        apply_test(&test_case);
+    }
+
     #[test]
     fn test_boolean_expressions() {
         let test_case = [
@@ -164,6 +206,18 @@ mod tests {
         apply_test(&test_case);
     }
 
+    #[test]
+    fn test_template_literals() {
+        let test_case = [
+            ("`plain`", "plain"),
+            ("let n = 3; `n = ${n}!`", "n = 3!"),
+            ("`${1 + 1}${true}${[1, \"a\"]}${if (false) { 1 }}`", "2true[1, a]null"),
+            (r#"let h = {"k": "v"}; `${ h["k"] }${`${1.5}`}`"#, "v1.5"),
+            ("`${9223372036854775807 + 1}`", "9223372036854775808"),
+        ];
+        apply_test(&test_case);
+    }
+
     #[test]
     fn test_builtin_functions() {
         let test_case = [
@@ -174,6 +228,20 @@ mod tests {
         apply_test(&test_case);
     }
 
+    #[test]
+    fn test_runtime_builtins_called_directly() {
+        for name in ["puts", "map", "random", "next"] {
+            let definition = object::builtins::BuiltIns
+                .iter()
+                .find(|definition| definition.name == name)
+                .unwrap();
+            assert_eq!(
+                (definition.function)(vec![]).to_string(),
+                format!("builtin {} can only be called from Monkey code", name)
+            );
+        }
+    }
+
     #[test]
     fn test_array_literals() {
         let test_case = [("[1, 2 * 2, 3 + 3]", "[1, 4, 6]")];
@@ -218,6 +286,251 @@ mod tests {
         // ];
     }
 
+    #[test]
+    fn test_string_builtin_functions() {
+        let test_case = [
+            (r#"split("a,b,,c", ",")"#, "[a, b, , c]"),
+            (r#"split("héllo", "")"#, "[h, é, l, l, o]"),
+            (r#"join([1, "b", [2]], "-")"#, "1-b-[2]"),
+            (r#"trim("  padded  ")"#, "padded"),
+            (r#"upper("MiXed") + lower("MiXed")"#, "MIXEDmixed"),
+            (r#"contains("haystack", "st")"#, "true"),
+            (r#"starts_with("haystack", "hay")"#, "true"),
+            (r#"ends_with("haystack", "hay")"#, "false"),
+            (r#"replace("a-b-c", "-", "+")"#, "a+b+c"),
+            (r#"substr("héllo", 1, 3)"#, "éll"),
+            (r#"substr("hello", 3)"#, "lo"),
+            (r#"substr("hello", 9)"#, ""),
+            (r#"index_of("héllo", "l")"#, "2"),
+            (r#"index_of("hello", "z")"#, "-1"),
+            (r#"chars("abc")"#, "[a, b, c]"),
+            (r#"str(1.5) + str([1, true])"#, "1.5[1, true]"),
+            (r#"int(" -42 ") + int(7.9) + int(3)"#, "-32"),
+            (r#"int("99999999999999999999")"#, "99999999999999999999"),
+            (r#"int("4x")"#, "null"),
+            (r#"upper(1)"#, "builtin upper not supported for type 1"),
+            (r#"int(true)"#, "builtin int not supported for type true"),
+            (r#"split("a")"#, "builtin split expected 2 arguments, got 1"),
+            (r#"substr("a", 1, 2, 3)"#, "builtin substr expected 2 or 3 arguments, got 4"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_higher_order_builtin_functions() {
+        let test_case = [
+            ("map([1, 2, 3], fn(x) { x * 2 })", "[2, 4, 6]"),
+            ("map([], fn(x) { x })", "[]"),
+            ("filter([1, 2, 3, 4], fn(x) { x > 2 })", "[3, 4]"),
+            ("reduce([1, 2, 3], fn(acc, x) { acc + x }, 10)", "16"),
+            ("reduce([], fn(acc, x) { acc + x }, 0)", "0"),
+            ("find([1, 2, 3], fn(x) { x > 1 })", "2"),
+            ("find([1, 2, 3], fn(x) { x > 5 })", "null"),
+            ("any([1, 2], fn(x) { x == 2 })", "true"),
+            ("all([1, 2], fn(x) { x == 2 })", "false"),
+            ("all([], fn(x) { false })", "true"),
+            ("sort([3, 1.5, 2])", "[1.5, 2, 3]"),
+            (r#"sort(["b", "a", "c"])"#, "[a, b, c]"),
+            ("sort([3, 1, 2], fn(a, b) { b - a })", "[3, 2, 1]"),
+            (r#"map(["a", "b"], upper)"#, "[A, B]"),
+            ("map([[2, 1], [1]], sort)", "[[1, 2], [1]]"),
+            ("let n = 0; map([1, 2], fn(x) { let n = n + x; n })", "[1, 2]"),
+            ("sort([1, \"a\"])", "builtin sort cannot compare 1 and a"),
+            (
+                "sort([2, 1], fn(a, b) { true })",
+                "builtin sort comparator must return a number, got true",
+            ),
+            ("map(1, fn(x) { x })", "builtin map not supported for type 1"),
+            ("reduce([1], fn(acc, x) { acc })", "builtin reduce expected 3 arguments, got 2"),
+            ("map([1], fn(a, b) { a })", "wrong number of arguments: want=2, got=1"),
+            (
+                "map([1], fn(x) { x + true })",
+                "eval infix error for op: start: 19, end: 20, kind: +, left: 1, right: true",
+            ),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_hash_builtin_functions() {
+        let test_case = [
+            (r#"{"b": 1, "a": 2, 3: 3}"#, "[b: 1, a: 2, 3: 3]"),
+            (r#"keys({"b": 1, "a": 2, true: 3})"#, "[b, a, true]"),
+            (r#"values({"b": 1, "a": 2})"#, "[1, 2]"),
+            (r#"entries({"b": 1, 2: "x"})"#, "[[b, 1], [2, x]]"),
+            (r#"has({"a": 1}, "a")"#, "true"),
+            (r#"has({"a": 1}, 1)"#, "false"),
+            (r#"delete({"a": 1, "b": 2, "c": 3}, "b")"#, "[a: 1, c: 3]"),
+            (r#"let h = {"a": 1}; delete(h, "a"); h"#, "[a: 1]"),
+            (r#"merge({"a": 1, "b": 2}, {"c": 3, "a": 4})"#, "[a: 4, b: 2, c: 3]"),
+            ("keys({})", "[]"),
+            ("keys([1])", "builtin keys not supported for type [1]"),
+            (r#"has({"a": 1}, [1])"#, "builtin has not supported for type [1]"),
+            ("merge({}, 1)", "builtin merge not supported for type 1"),
+            ("delete({})", "builtin delete expected 2 arguments, got 1"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_math_builtin_functions() {
+        let test_case = [
+            ("abs(-5)", "5"),
+            ("abs(-2.5)", "2.5"),
+            ("min(3, 1, 2)", "1"),
+            ("max(1, 2.5, 2)", "2.5"),
+            ("max(7)", "7"),
+            ("pow(2, 10)", "1024"),
+            ("pow(2, 64)", "18446744073709551616"),
+            ("pow(2, -1)", "0.5"),
+            ("pow(0, 0)", "1"),
+            ("sqrt(16)", "4.0"),
+            ("floor(2.7)", "2"),
+            ("ceil(2.1)", "3"),
+            ("round(-2.5)", "-3"),
+            ("floor(5)", "5"),
+            ("floor(pow(2.0, 1000.0) / 0.0)", "null"),
+            ("min()", "builtin min expected at least 1 argument, got 0"),
+            (r#"abs("a")"#, "builtin abs not supported for type a"),
+            ("pow(2)", "builtin pow expected 2 arguments, got 1"),
+            ("pow(10, 1000000000)", "builtin pow result is too large"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_random_builtin_functions() {
+        let test_case = [
+            // the cases share one environment, so this draws from a fresh generator
+            ("[random(1000), random(1000)]", "[535, 700]"),
+            ("seed(42)", "null"),
+            ("seed(42); [random(100), random(100), random(100)]", "[13, 91, 58]"),
+            ("seed(1); let a = random(1000); seed(1); a == random(1000)", "true"),
+            (
+                "let f = fn() { random(1000) }; seed(9); let a = f(); seed(9); a == random(1000)",
+                "true",
+            ),
+            ("random(1)", "0"),
+            ("random(0)", "builtin random expected a positive bound, got 0"),
+            ("random(1.5)", "builtin random not supported for type 1.5"),
+            ("seed()", "builtin seed expected 1 argument, got 0"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_json_builtin_functions() {
+        let test_case = [
+            (r#"json_encode({"a": [1, 2.5, true], "b": "x", 3: false})"#, r#"{"a":[1,2.5,true],"b":"x","3":false}"#),
+            ("json_encode(if (false) { 1 })", "null"),
+            ("json_encode(9223372036854775807 + 1)", "9223372036854775808"),
+            (r#"json_decode(`{"b": [1, 2.5, null], "a": {"c": "d"}}`)"#, "[b: [1, 2.5, null], a: [c: d]]"),
+            ("json_decode(json_encode([1, [2, 3]]))[1][0]", "2"),
+            ("json_decode(`18446744073709551615`)", "18446744073709551615"),
+            ("json_decode(json_encode(9223372036854775807 * 10))", "92233720368547758070"),
+            ("type_of(json_decode(json_encode(9223372036854775807 * 10)))", "integer"),
+            ("json_decode(`[-92233720368547758070, 1e2, 3]`)", "[-92233720368547758070, 100.0, 3]"),
+            ("json_encode(fn(x) { x })", "builtin json_encode cannot encode fn(x) { x }"),
+            ("class Point {} json_encode([new Point()])", "builtin json_encode cannot encode [object Point]"),
+            ("json_encode(0.0 / 0.0)", "builtin json_encode cannot encode NaN"),
+            ("json_decode(`[1,`)", "builtin json_decode got invalid JSON: EOF while parsing a value at line 1 column 3"),
+            ("json_decode(1)", "builtin json_decode not supported for type 1"),
+            ("json_encode()", "builtin json_encode expected 1 argument, got 0"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_reflection_builtin_functions() {
+        let class = "class Point { constructor(x, y) { this.y = y; this.x = x; } sum() { this.x + this.y } norm() { 0 } }";
+        let test_case = [
+            (r#"[type_of(1), type_of(9223372036854775807 + 1), type_of(1.5), type_of(true), type_of("a")]"#, "[integer, integer, float, boolean, string]"),
+            ("[type_of([]), type_of({}), type_of(if (false) { 1 }), type_of(fn() {}), type_of(len)]", "[array, hash, null, closure, builtin]"),
+            (class, "null"),
+            ("let p = new Point(1, 2); [type_of(Point), type_of(p), type_of(p.sum)]", "[class, instance, boundMethod]"),
+            ("class Other {} [instance_of(p, Point), instance_of(p, Other), instance_of(1, Point)]", "[true, false, false]"),
+            ("[fields(p), methods(Point)]", "[[x, y], [norm, sum]]"),
+            ("fields(Point)", "builtin fields not supported for type [class Point]"),
+            ("methods(p)", "builtin methods not supported for type [object Point]"),
+            ("instance_of(p, p)", "builtin instance_of not supported for type [object Point]"),
+            ("type_of()", "builtin type_of expected 1 argument, got 0"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_builtin_methods() {
+        let test_case = [
+            (r#""a,b,c".split(",")"#, "[a, b, c]"),
+            (r#""  monkey ".trim().upper().len()"#, "6"),
+            ("[1, 2].push(3).map(fn(x) { x * 2 })", "[2, 4, 6]"),
+            ("[3, 1, 2].sort().first()", "1"),
+            ("[1, 2, 3].reduce(fn(acc, x) { acc + x }, 0)", "6"),
+            (
+                r#"let h = {"a": 1}; [h.keys(), h.has("a"), h.merge({"b": 2}).values()]"#,
+                "[[a], true, [1, 2]]",
+            ),
+            (
+                "let push = [1].push; [push(2), push == push, type_of(push)]",
+                "[[1, 2], true, boundMethod]",
+            ),
+            ("[1].push", "[bound method array.push]"),
+            (r#""abc".keys"#, "cannot read property 'keys' of abc"),
+            ("{}.len", "cannot read property 'len' of []"),
+            (r#""abc".substr()"#, "builtin substr expected 2 or 3 arguments, got 1"),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_arrays_and_hashes_are_values() {
+        let test_case = [
+            ("let a = [1, 2]; let b = push(a, 3); [a, b, rest(b), rest(a)]", "[[1, 2], [1, 2, 3], [2, 3], [2]]"),
+            (
+                r#"let h = {"a": 1, "b": 2, "c": 3}; let d = delete(h, "b"); [h, d, merge(d, {"b": 4, "a": 5})]"#,
+                "[[a: 1, b: 2, c: 3], [a: 1, c: 3], [a: 5, c: 3, b: 4]]",
+            ),
+            (r#"{"a": 1, "b": 2} == {"b": 2, "a": 1}"#, "true"),
+            // Pushing onto a large array shares its structure instead of
+            // copying it, so this stays linear.
+            (
+                "let xs = reduce(to_array(range(50000)), fn(acc, x) { push(acc, x * 2) }, []); [len(xs), xs[49999], len(rest(xs))]",
+                "[50000, 99998, 49999]",
+            ),
+        ];
+        apply_test(&test_case);
+    }
+
+    #[test]
+    fn test_iterator_builtins() {
+        let test_case = [
+            ("to_array(range(5))", "[0, 1, 2, 3, 4]"),
+            ("to_array(range(10, 0, -3))", "[10, 7, 4, 1]"),
+            ("to_array(take(range(0, 1000000000), 3))", "[0, 1, 2]"),
+            (r#"to_array(zip(range(3), ["a", "b"]))"#, "[[0, a], [1, b]]"),
+            (r#"to_array(enumerate(["a", "b"]))"#, "[[0, a], [1, b]]"),
+            (
+                "let r = range(2, 5); [len(r), first(r), to_array(rest(r)), len(r)]",
+                "[3, 2, [3, 4], 3]",
+            ),
+            ("[first(range(0)), rest(range(0)), type_of(range(1))]", "[null, null, iterator]"),
+            ("range(3)", "[iterator]"),
+            ("range(0, 3, 0)", "builtin range step must not be zero"),
+            ("take(range(3), -1)", "builtin take not supported for type -1"),
+            ("to_array(1)", "builtin to_array not supported for type 1"),
+            ("len(range(0, 9223372036854775807))", "9223372036854775807"),
+            ("len(take(rest(range(0, 9223372036854775807, 2)), 5))", "5"),
+            ("len(zip(range(10, 0, -3), rest(rest(range(100)))))", "4"),
+            ("len(range(-9223372036854775807 - 1, 9223372036854775807))", "builtin len iterator has too many elements to count"),
+            ("to_array(range(0, 9223372036854775807))", "builtin to_array iterator has more than 16777216 elements"),
+            (
+                "let sum = fn(it, acc) { if (len(it) == 0) { acc } else { sum(rest(it), acc + first(it)) } }; [sum(range(20000), 0), sum(take(to_array(range(20000)), 20000), 0)]",
+                "[199990000, 199990000]",
+            ),
+        ];
+        apply_test(&test_case);
+    }
+
     #[test]
     fn test_hash_index_expressions() {
         let test_case = [
@@ -317,6 +630,39 @@ reader()();"#,
         apply_test(&test_case);
     }
 
+    #[test]
+    fn test_operator_special_methods() {
+        let vector = r#"class Vec {
+  constructor(x, y) { this.x = x; this.y = y; }
+  add(other) { new Vec(this.x + other.x, this.y + other.y) }
+  mul(k) { new Vec(this.x * k, this.y * k) }
+  eq(other) { if (this.x == other.x) { this.y == other.y } else { false } }
+  get(i) { if (i == 0) { this.x } else { this.y } }
+  toString() { `(${this.x}, ${this.y})` }
+}
+"#;
+        let test_case = [
+            (format!("{}`${{new Vec(1, 2) + new Vec(3, 4)}}`", vector), "(4, 6)"),
+            (format!("{}(new Vec(1, 2) * 3)[1]", vector), "6"),
+            (format!("{}new Vec(1, 2) == new Vec(1, 2)", vector), "true"),
+            (format!("{}new Vec(1, 2) != new Vec(1, 2)", vector), "false"),
+            (format!("{}new Vec(1, 2) != new Vec(2, 1)", vector), "true"),
+            (format!("{}new Vec(5, 6)[0]", vector), "5"),
+            (format!("{}str(new Vec(1, 2)) + \"!\"", vector), "(1, 2)!"),
+            ("class A {} str(new A())".to_string(), "[object A]"),
+            ("class A {} let a = new A(); `${a}`".to_string(), "[object A]"),
+            ("class A { eq(o) { 1 } } new A() == 2".to_string(), "true"),
+            ("class A { eq(o) { if (false) { 1 } } } new A() != 2".to_string(), "true"),
+            (
+                "class M { sub() { 0 } } new M() - 1".to_string(),
+                "wrong number of arguments for M.sub: want=0, got=1",
+            ),
+        ];
+        for (input, expected) in &test_case {
+            apply_test(&[(input.as_str(), *expected)]);
+        }
+    }
+
     #[test]
     fn test_class_errors_and_strict_arity() {
         let test_case = [
@@ -346,6 +692,24 @@ reader()();"#,
         )]);
     }
 
+    #[test]
+    fn type_annotations_do_not_change_evaluation() {
+        apply_test(&[
+            (
+                r#"class Point {
+  x: int;
+  constructor(x: int) { this.x = x; }
+  double(): int { this.x * 2 }
+}
+let add = fn(a: int, b: int | null): int { a + b };
+let p: Point = new Point(add(1, 2));
+p.double()"#,
+                "6",
+            ),
+            ("let f = fn(g: fn(int): int, xs: [int]): [int] { [g(first(xs))] }; f(fn(x) { x }, [1])", "[1]"),
+        ]);
+    }
+
     #[test]
     fn validation_accepts_bindings_from_previous_eval() {
         let env: Env = Rc::new(RefCell::new(Default::default()));
@@ -354,4 +718,415 @@ reader()();"#,
         let result = eval(parse("answer + 1;").unwrap(), &env).unwrap();
         assert_eq!(result.as_ref(), &object::Object::Integer(42));
     }
+
+    #[test]
+    fn host_registered_builtins_keep_their_state() {
+        use object::builtins::BuiltinError;
+        use object::registry::{Arity, Builtins};
+        use object::Object;
+        use std::cell::Cell;
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&calls);
+        let mut builtins = Builtins::standard();
+        builtins
+            .register("tick", Arity::exactly(0), "Counts calls from the host.", move |_, _| {
+                counter.set(counter.get() + 1);
+                return Ok(Rc::new(Object::Integer(counter.get())));
+            })
+            .register("len", Arity::exactly(1), "Always 7.", |_, _| Ok(Rc::new(Object::Integer(7))))
+            .register("fail", Arity::between(0, 1), "Always fails.", |_, _| {
+                Err(BuiltinError::Type("fail is not implemented".to_string()))
+            });
+        let env: Env = Rc::new(RefCell::new(Environment::with_builtins(builtins)));
+        let run = |input: &str| format!("{}", eval(parse(input).unwrap(), &env).unwrap());
+
+        assert_eq!(run("tick(); tick(); let f = fn() { tick() }; f()"), "3");
+        assert_eq!(calls.get(), 3);
+        assert_eq!(run("len([1])"), "7");
+        assert_eq!(run("tick(1)"), "builtin tick expected 0 arguments, got 1");
+        assert_eq!(run("fail()"), "fail is not implemented");
+        assert_eq!(run("puts"), "[builtin function]");
+    }
+
+    fn eval_error(input: &str) -> crate::EvalError {
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        return eval(parse(input).unwrap(), &env).unwrap_err();
+    }
+
+    fn slice<'a>(input: &'a str, span: &parser::lexer::token::Span) -> &'a str {
+        return &input[span.start..span.end];
+    }
+
+    #[test]
+    fn errors_carry_kind_span_and_call_stack() {
+        use crate::EvalErrorKind;
+
+        let input = "let inner = fn(x) { x / 0 };\nlet outer = fn() { inner(1) + 0 };\nouter();";
+        let error = eval_error(input);
+        assert_eq!(error.kind, EvalErrorKind::Arithmetic);
+        assert_eq!(slice(input, error.span.as_ref().unwrap()), "x / 0");
+        let frames: Vec<(&str, &str)> = error
+            .call_stack
+            .iter()
+            .map(|frame| (frame.function.as_str(), slice(input, &frame.span)))
+            .collect();
+        assert_eq!(frames, vec![("inner", "inner(1)"), ("outer", "outer()")]);
+        assert_eq!(
+            error.render(),
+            "arithmetic error: division by zero (source offset 20..25)\n    \
+             in inner called at source offset 48..56\n    \
+             in outer called at source offset 64..71"
+        );
+
+        let input = "let negate = fn(b) { -b }; map([true], negate)";
+        let error = eval_error(input);
+        assert_eq!(error.kind, EvalErrorKind::Type);
+        assert_eq!(slice(input, error.span.as_ref().unwrap()), "-b");
+        assert_eq!(error.call_stack.len(), 1);
+        assert_eq!(error.call_stack[0].function, "<callback>");
+        assert_eq!(slice(input, &error.call_stack[0].span), "map([true], negate)");
+
+        let input = "class Box { get(i) { [][i.x] } }\nlet b = new Box(); fn() { b[0] }()";
+        let error = eval_error(input);
+        assert_eq!(error.kind, EvalErrorKind::Property);
+        assert_eq!(slice(input, error.span.as_ref().unwrap()), "i.x");
+        let frames: Vec<(&str, &str)> = error
+            .call_stack
+            .iter()
+            .map(|frame| (frame.function.as_str(), slice(input, &frame.span)))
+            .collect();
+        assert_eq!(frames, vec![("Box.get", "b[0]"), ("<anonymous>", "fn() { b[0] }()")]);
+
+        let cases = [
+            ("missing", EvalErrorKind::Compile, "missing"),
+            ("let f = fn(a) { a }; f(1, 2)", EvalErrorKind::Call, "f(1, 2)"),
+            ("{}[fn() {}]", EvalErrorKind::Index, "{}[fn() {}]"),
+            ("let p = 1; p.x = 2;", EvalErrorKind::Property, "p.x = 2;"),
+        ];
+        for (input, kind, blamed) in cases {
+            let error = eval_error(input);
+            assert_eq!(error.kind, kind, "{}", input);
+            assert_eq!(slice(input, error.span.as_ref().unwrap()), blamed, "{}", input);
+            assert!(error.call_stack.is_empty());
+        }
+    }
+
+    #[test]
+    fn eval_options_bound_steps_depth_and_interrupts() {
+        use crate::{eval_with_options, EvalErrorKind, EvalOptions};
+        use std::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+
+        let run = |input: &str, options: &EvalOptions| {
+            let env: Env = Rc::new(RefCell::new(Default::default()));
+            return eval_with_options(parse(input).unwrap(), &env, options);
+        };
+        let countdown = "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } };";
+        let explode = "let f = fn(n) { if (n == 0) { 0 } else { f(n - 1) + f(n - 1) } }; f(40)";
+
+        let budget = EvalOptions {
+            step_budget: 10_000,
+            ..EvalOptions::default()
+        };
+        let error = run(explode, &budget).unwrap_err();
+        assert_eq!(error.kind, EvalErrorKind::ExecutionLimit);
+        assert_eq!(error.message, "step limit exceeded (budget: 10000)");
+        assert_eq!(run("1 + 2", &budget).unwrap().to_string(), "3");
+
+        let error =
+            run("let f = fn(n) { 1 + f(n + 1) }; f(0)", &EvalOptions::default()).unwrap_err();
+        assert_eq!(error.kind, EvalErrorKind::Stack);
+        assert_eq!(error.message, "call depth limit exceeded (max: 1024)");
+        assert_eq!(error.call_stack.len(), 1024);
+
+        let deep = EvalOptions {
+            max_call_depth: 50_000,
+            ..EvalOptions::default()
+        };
+        let result = run(&format!("{} f(20000)", countdown), &deep).unwrap();
+        assert_eq!(result.to_string(), "20000");
+
+        let interrupted = EvalOptions {
+            interrupt: Some(Arc::new(AtomicBool::new(true))),
+            ..EvalOptions::default()
+        };
+        let error = run(explode, &interrupted).unwrap_err();
+        assert_eq!(error.kind, EvalErrorKind::ExecutionLimit);
+        assert_eq!(error.message, "interrupted");
+        assert_eq!(run("1 + 2", &interrupted).unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn deep_call_stacks_render_their_ends() {
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        let input = "let f = fn(n) { if (n == 0) { 1 / 0 } else { f(n - 1) + 0 } }; f(30)";
+        let rendered = eval(parse(input).unwrap(), &env).unwrap_err().render();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 1 + 10 + 1 + 10);
+        assert_eq!(lines[11], "    ... 11 more calls");
+        assert!(lines[1..]
+            .iter()
+            .enumerate()
+            .all(|(i, line)| i == 10 || line.contains("in f")));
+    }
+
+    #[test]
+    fn tail_calls_run_in_constant_stack() {
+        apply_test(&[
+            (
+                "let count = fn(n, acc) { if (n == 0) { acc } else { count(n - 1, acc + 1) } }; count(1000000, 0)",
+                "1000000",
+            ),
+            (
+                "let down = fn(n) { if (n == 0) { return \"done\"; } return down(n - 1); }; down(100000)",
+                "done",
+            ),
+            (
+                r#"let reduce = fn(arr, initial, f) {
+  let iter = fn(arr, result) {
+    if (len(arr) == 0) { result } else { iter(rest(arr), f(result, first(arr))) }
+  };
+  iter(arr, initial);
+};
+let map = fn(arr, f) { reduce(arr, [], fn(acc, x) { push(acc, f(x)) }) };
+let xs = to_array(range(20000));
+len(map(xs, fn(x) { x * 2 }))"#,
+                "20000",
+            ),
+            (
+                r#"class Counter { run(n, acc) { if (n == 0) { acc } else { this.run(n - 1, acc + 1) } } }
+let c = new Counter();
+c.run(100000, 0)"#,
+                "100000",
+            ),
+        ]);
+
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        let input = "let fail = fn() { 1 / 0 }; let f = fn(n) { if (n == 0) { fail() } else { f(n - 1) } }; f(3) + 1";
+        let error = eval(parse(input).unwrap(), &env).unwrap_err();
+        let frames: Vec<&str> = error
+            .call_stack
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect();
+        assert_eq!(frames, vec!["fail"]);
+        assert_eq!(&input[error.call_stack[0].span.start..error.call_stack[0].span.end], "fail()");
+    }
+
+    #[test]
+    fn names_resolve_to_their_binding() {
+        apply_test(&[
+            ("let g = 10;", "null"),
+            ("g * 2", "20"),
+            ("let add = fn(x) { fn(y) { fn(z) { x + y + z + g } } }; add(1)(2)(3)", "16"),
+            ("let f = fn(f) { f + 1 }; f(1)", "2"),
+            ("let x = 1; let pick = fn(c) { if (c) { let x = 2; } x }; [pick(true), pick(false)]", "[2, 1]"),
+            ("let y = 1; let shadow = fn() { let y = y + 1; y }; [shadow(), y]", "[2, 1]"),
+            ("let g = 5; add(1)(2)(3)", "11"),
+            // A closure sees the binding in scope where it is written, not a
+            // `let` that runs later in the enclosing function. Looking names
+            // up by string at call time used to give 7 here.
+            ("let x = 5; let f = fn() { let g = fn() { x }; let x = 7; g() }; f()", "5"),
+        ]);
+    }
+
+    /// Writes down every event, with the source each node spans.
+    struct Recorder<'a> {
+        source: &'a str,
+        events: Vec<String>,
+    }
+
+    impl Recorder<'_> {
+        fn describe(outcome: crate::Outcome) -> String {
+            match outcome {
+                crate::Outcome::Value(value) => value.to_string(),
+                crate::Outcome::Error(error) => format!("error {}", error),
+                crate::Outcome::TailCall => "tail call".to_string(),
+            }
+        }
+    }
+
+    impl crate::EvalObserver for Recorder<'_> {
+        fn enter_statement(&mut self, statement: &ast::Statement) {
+            let span = statement.span();
+            self.events
+                .push(format!("stmt {}", &self.source[span.start..span.end]));
+        }
+
+        fn leave_statement(&mut self, _: &ast::Statement, outcome: crate::Outcome) {
+            self.events
+                .push(format!("/stmt {}", Self::describe(outcome)));
+        }
+
+        fn enter_expression(&mut self, expression: &ast::Expression) {
+            let span = expression.span();
+            self.events
+                .push(format!("expr {}", &self.source[span.start..span.end]));
+        }
+
+        fn leave_expression(&mut self, _: &ast::Expression, outcome: crate::Outcome) {
+            self.events
+                .push(format!("/expr {}", Self::describe(outcome)));
+        }
+
+        fn enter_call(
+            &mut self,
+            function: &str,
+            args: &[Rc<object::Object>],
+            _: &lexer::token::Span,
+        ) {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            self.events
+                .push(format!("call {}({})", function, args.join(", ")));
+        }
+
+        fn leave_call(&mut self, function: &str, _: &lexer::token::Span, outcome: crate::Outcome) {
+            self.events
+                .push(format!("/call {} {}", function, Self::describe(outcome)));
+        }
+
+        fn bind(&mut self, name: &str, value: &Rc<object::Object>, _: &lexer::token::Span) {
+            self.events.push(format!("bind {} = {}", name, value));
+        }
+    }
+
+    fn observe(source: &str) -> Vec<String> {
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        let mut recorder = Recorder {
+            source,
+            events: Vec::new(),
+        };
+        let options = crate::EvalOptions::default();
+        let _ = crate::eval_with_observer(parse(source).unwrap(), &env, &options, &mut recorder);
+        return recorder.events;
+    }
+
+    #[test]
+    fn observers_see_nested_evaluation_events() {
+        assert_eq!(
+            observe("let x = 1 + 2; x"),
+            vec![
+                "stmt let x = 1 + 2;",
+                "expr 1 + 2",
+                "expr 1",
+                "/expr 1",
+                "expr 2",
+                "/expr 2",
+                "/expr 3",
+                "bind x = 3",
+                "/stmt null",
+                "stmt x",
+                "expr x",
+                "/expr 3",
+                "/stmt 3",
+            ]
+        );
+
+        let events = observe("let f = fn(n) { if (n == 0) { return 0; } f(n - 1) }; f(1) + 1");
+        let calls: Vec<&String> = events
+            .iter()
+            .filter(|event| event.contains("call") || event.starts_with("bind n"))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                "call f(1)",
+                "bind n = 1",
+                "/expr tail call",
+                "/stmt tail call",
+                "/call f tail call",
+                "call f(0)",
+                "bind n = 0",
+                "/call f 0",
+            ]
+        );
+        assert_eq!(events.last().map(String::as_str), Some("/stmt 1"));
+
+        let events = observe("let fail = fn() { 1 / 0 }; fail()");
+        assert!(events.contains(&"/call fail error division by zero".to_string()));
+        assert_eq!(events.last().map(String::as_str), Some("/stmt error division by zero"));
+    }
+
+    #[test]
+    fn puts_writes_to_the_environment_output() {
+        let output = Rc::new(RefCell::new(String::new()));
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        env.borrow_mut()
+            .set_output(object::output::Output::shared(&output));
+        let input = r#"class P { toString() { "p" } }
+let show = fn(x) { puts(x) };
+puts(1, "two", new P());
+map([3], puts);
+show(4);
+"#;
+        let result = eval(parse(input).unwrap(), &env).unwrap();
+        assert_eq!(result.to_string(), "null");
+        assert_eq!(output.take(), "1\ntwo\np\n3\n4\n");
+
+        eval(parse("print(5)").unwrap(), &env).unwrap();
+        assert_eq!(output.take(), "5\n");
+    }
+
+    #[test]
+    fn generators_run_their_bodies_a_yield_at_a_time() {
+        let test_case = [
+            (
+                "let count = fn*(n) { yield n; yield n + 1; n + 2 }; let g = count(1); [next(g), next(g), next(g), next(g)]",
+                "[[value: 1, done: false], [value: 2, done: false], [value: 3, done: true], [value: null, done: true]]",
+            ),
+            (
+                "let sum = fn*() { let a = yield 0; let b = yield a * 2; return a + b; }; let g = sum(); next(g, 100); [next(g, 5)[\"value\"], next(g, 7)[\"value\"]]",
+                "[10, 12]",
+            ),
+            (
+                "let drain = fn(g, acc) { let step = next(g); if (step[\"done\"]) { acc } else { drain(g, push(acc, step[\"value\"])) } };",
+                "null",
+            ),
+            (
+                "let light = fn*(go) { if (go) { yield \"green\"; yield \"yellow\"; } else { yield \"blink\"; } yield \"red\"; }; [drain(light(true), []), drain(light(false), [])]",
+                "[[green, yellow, red], [blink, red]]",
+            ),
+            (
+                "let pair = fn*(a) { yield a; yield a + 1; \"pair\" }; let both = fn*() { let last = yield* pair(1); yield last; yield* pair(10); }; drain(both(), [])",
+                "[1, 2, pair, 10, 11]",
+            ),
+            (
+                "let from = fn*(n) { yield n; return yield* from(n + 1); }; let g = from(0); let skip = fn(n) { if (n == 0) { next(g) } else { next(g); skip(n - 1) } }; skip(10000)[\"value\"]",
+                "10000",
+            ),
+            (
+                "let deep = fn*(n) { if (n == 0) { yield \"bottom\"; return 0; } let below = yield* deep(n - 1); below + 1 }; let g = deep(2000); [next(g)[\"value\"], next(g)[\"value\"]]",
+                "[bottom, 2000]",
+            ),
+            ("let g = fn*() { yield 1 }; [type_of(g), type_of(g()), g()]", "[closure, generator, [generator]]"),
+            ("next(1)", "builtin next not supported for type 1"),
+            ("let g = fn*() { yield* 1 }; next(g())", "yield* expected a generator, got 1"),
+            (
+                "class Holder {} let holder = new Holder(); let g = fn*() { yield next(holder.it) }; holder.it = g(); next(holder.it)[\"value\"]",
+                "generator is already running",
+            ),
+            ("next()", "builtin next expected 1 or 2 arguments, got 0"),
+            (
+                "let g = fn*() { yield 1 }; [take(g(), 1), to_array(g()), first(g()), len(g())]",
+                "[builtin take not supported for type [generator], builtin to_array not supported for type [generator], builtin first not supported for for type [generator], builtin len not supported for for type [generator]]",
+            ),
+        ];
+        apply_test(&test_case);
+
+        let input = "let g = fn*() { yield 1; 1 / 0 }; let it = g(); next(it); let failed = fn() { next(it)[\"value\"] }; failed()";
+        let error = eval_error(input);
+        assert_eq!(error.kind, crate::EvalErrorKind::Arithmetic);
+        let frames: Vec<&str> = error
+            .call_stack
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect();
+        assert_eq!(frames, vec!["next", "failed"]);
+
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        eval(parse(input).unwrap(), &env).unwrap_err();
+        let done = eval(parse("next(it)").unwrap(), &env).unwrap();
+        assert_eq!(done.to_string(), "[value: null, done: true]");
+    }
 }
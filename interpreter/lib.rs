@@ -4,34 +4,116 @@ use std::rc::Rc;
 
 use object::builtins::*;
 use object::environment::*;
-use object::{BoundMethodObject, ClassObject, EvalError, InstanceObject, InstanceRef, Object};
+use object::higher_order::{self, is_higher_order, HigherOrderError, ObjectHost};
+use object::integer::{self, Int, IntegerOp};
+use object::output::OutputSink;
+use object::random::is_random_builtin;
+use object::registry::Ctx;
+use object::{
+    float_operands, BigInt, BoundMethodObject, ClassObject, InstanceObject, InstanceRef, Object,
+    OrderedMap,
+};
 use parser::ast::*;
-use parser::lexer::token::{Token, TokenKind};
+use parser::lexer::token::{Span, Token, TokenKind};
 use parser::validation::validate_program;
 
+pub mod error;
+mod generator;
 mod interpreter_test;
+pub mod limits;
+pub mod observer;
+pub mod session;
+mod session_test;
+
+pub use error::{CallFrame, EvalError, EvalErrorKind};
+pub use limits::EvalOptions;
+pub use observer::{EvalObserver, Outcome};
+
+use limits::EvalState;
+
+/// A call expression, for the call stack of errors raised inside the
+/// function it calls.
+struct CallSite<'a> {
+    name: &'a str,
+    span: &'a Span,
+}
+
+/// How a function body ended when evaluated by [`eval_tail_block`].
+enum Tail {
+    /// With a value, wrapped in `Object::ReturnValue` if it came from a
+    /// `return`.
+    Done(Rc<Object>),
+    /// With a call in tail position, which the caller's loop makes instead
+    /// of the body nesting it.
+    Call(TailCall),
+}
+
+struct TailCall {
+    function: Rc<Object>,
+    args: Vec<Rc<Object>>,
+    /// The environment of the body that made the call.
+    caller: Env,
+    name: String,
+    span: Span,
+}
 
 pub fn eval(node: Node, env: &Env) -> Result<Rc<Object>, EvalError> {
-    match node {
-        Node::Program(p) => {
-            let mut predefined_names = env.borrow().visible_names();
-            predefined_names.extend(BuiltIns.iter().map(|builtin| builtin.name.to_string()));
-            let predefined_names = predefined_names
-                .iter()
-                .map(String::as_str)
-                .collect::<Vec<_>>();
-            validate_program(&p, &predefined_names).map_err(|error| error.message)?;
-            eval_block_statements(&p.body, env)
-        }
-        Node::Statement(statements) => eval_statement(&statements, env),
-        Node::Expression(expression) => eval_expression(&expression, env),
-    }
+    return eval_with_options(node, env, &EvalOptions::default());
+}
+
+/// [`eval`] within the step budget, call depth and interrupt flag of
+/// `options`.
+pub fn eval_with_options(
+    node: Node,
+    env: &Env,
+    options: &EvalOptions,
+) -> Result<Rc<Object>, EvalError> {
+    return run(node, env, &EvalState::new(options, None));
+}
+
+/// [`eval_with_options`], telling `observer` about each step of the run.
+pub fn eval_with_observer(
+    node: Node,
+    env: &Env,
+    options: &EvalOptions,
+    observer: &mut dyn EvalObserver,
+) -> Result<Rc<Object>, EvalError> {
+    return run(node, env, &EvalState::new(options, Some(observer)));
 }
 
-fn eval_block_statements(statements: &Vec<Statement>, env: &Env) -> Result<Rc<Object>, EvalError> {
+fn run(node: Node, env: &Env, state: &EvalState) -> Result<Rc<Object>, EvalError> {
+    // Validation also resolves every name, which evaluation relies on, so a
+    // lone statement or expression is checked as a program of its own.
+    let program = match node {
+        Node::Program(program) => program,
+        Node::Statement(statement) => Program {
+            span: statement.span().clone(),
+            body: vec![statement],
+        },
+        Node::Expression(expression) => Program {
+            span: expression.span().clone(),
+            body: vec![Statement::Expr(expression)],
+        },
+    };
+    let mut predefined_names = env.borrow().visible_names();
+    predefined_names.extend(env.borrow().builtins().names());
+    let predefined_names = predefined_names
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    validate_program(&program, &predefined_names)
+        .map_err(|error| EvalError::new(EvalErrorKind::Compile, error.message).at(&error.span))?;
+    eval_block_statements(&program.body, env, state)
+}
+
+fn eval_block_statements(
+    statements: &Vec<Statement>,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
     let mut result = Rc::new(Object::Null);
     for statement in statements {
-        let val = eval_statement(statement, &Rc::clone(env))?;
+        let val = eval_statement(statement, &Rc::clone(env), state)?;
         match *val {
             Object::ReturnValue(_) => return Ok(val),
             _ => {
@@ -43,36 +125,52 @@ fn eval_block_statements(statements: &Vec<Statement>, env: &Env) -> Result<Rc<Ob
     return Ok(result);
 }
 
-fn eval_statement(statement: &Statement, env: &Env) -> Result<Rc<Object>, EvalError> {
+fn eval_statement(
+    statement: &Statement,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
+    state.observe(|observer| observer.enter_statement(statement));
+    let result = eval_statement_node(statement, env, state);
+    state.observe(|observer| observer.leave_statement(statement, outcome(&result)));
+    return result;
+}
+
+fn eval_statement_node(
+    statement: &Statement,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
     match statement {
-        Statement::Expr(expr) => eval_expression(expr, env),
+        Statement::Expr(expr) => eval_expression(expr, env, state),
         Statement::Return(ReturnStatement {
             argument,
             ..
         }) => {
-            let val = eval_expression(argument, env)?;
+            let val = eval_expression(argument, env, state)?;
             return Ok(Rc::new(Object::ReturnValue(val)));
         }
         Statement::Let(Let {
             identifier: id,
             expr,
+            resolution,
             ..
         }) => {
-            let val = eval_expression(expr, &Rc::clone(env))?;
-            let obj: Rc<Object> = Rc::clone(&val);
+            let val = eval_expression(expr, &Rc::clone(env), state)?;
             if let TokenKind::IDENTIFIER {
                 name,
             } = &id.kind
             {
-                env.borrow_mut().set(name.clone(), obj);
+                bind(env, resolution, name, &id.span, val, state);
             }
             return Ok(Rc::new(Object::Null));
         }
-        Statement::Class(class) => eval_class_declaration(class, env),
+        Statement::Class(class) => eval_class_declaration(class, env, state),
         Statement::SetProperty(statement) => {
-            let receiver = eval_expression(&statement.object, env)?;
-            let value = eval_expression(&statement.value, env)?;
-            set_property(&receiver, statement.property.name.clone(), value)?;
+            let receiver = eval_expression(&statement.object, env, state)?;
+            let value = eval_expression(&statement.value, env, state)?;
+            set_property(&receiver, statement.property.name.clone(), value)
+                .map_err(|error| error.at(&statement.span))?;
             Ok(Rc::new(Object::Null))
         }
     }
@@ -81,6 +179,7 @@ fn eval_statement(statement: &Statement, env: &Env) -> Result<Rc<Object>, EvalEr
 fn eval_class_declaration(
     declaration: &ClassDeclaration,
     env: &Env,
+    state: &EvalState,
 ) -> Result<Rc<Object>, EvalError> {
     let mut constructor = None;
     let mut methods = HashMap::new();
@@ -100,11 +199,154 @@ fn eval_class_declaration(
         constructor,
         methods,
     }));
-    env.borrow_mut()
-        .set(declaration.name.name.clone(), Rc::new(Object::Class(class)));
+    let name = &declaration.name;
+    let class = Rc::new(Object::Class(class));
+    bind(env, &name.resolution, &name.name, &name.span, class, state);
     Ok(Rc::new(Object::Null))
 }
 
+/// Sets the binding a declaration at `span` resolved to, which is always in
+/// `env` itself.
+fn bind(
+    env: &Env,
+    resolution: &Resolution,
+    name: &str,
+    span: &Span,
+    value: Rc<Object>,
+    state: &EvalState,
+) {
+    state.observe(|observer| observer.bind(name, &value, span));
+    match resolution.bindings().first() {
+        Some(Binding::Local {
+            index,
+            ..
+        }) => env.borrow_mut().set_local(*index, value),
+        _ => env.borrow_mut().set(name.to_string(), value),
+    }
+}
+
+/// [`eval_block_statements`] for the blocks of a function body, where a
+/// `return` leaves the function: calls in tail position come back as
+/// [`Tail::Call`] instead of being made. `tail` says whether the value of the
+/// block is also the value of the function.
+fn eval_tail_block(
+    statements: &[Statement],
+    env: &Env,
+    state: &EvalState,
+    tail: bool,
+) -> Result<Tail, EvalError> {
+    let mut result = Rc::new(Object::Null);
+    for (index, statement) in statements.iter().enumerate() {
+        state.observe(|observer| observer.enter_statement(statement));
+        let outcome = match statement {
+            Statement::Return(ReturnStatement {
+                argument,
+                ..
+            }) => match eval_tail_expression(argument, env, state, true) {
+                Ok(Tail::Done(value)) => Ok(Tail::Done(Rc::new(Object::ReturnValue(value)))),
+                outcome => outcome,
+            },
+            Statement::Expr(expression) => {
+                let last = index + 1 == statements.len();
+                eval_tail_expression(expression, env, state, tail && last)
+            }
+            statement => eval_statement_node(statement, env, state).map(Tail::Done),
+        };
+        state.observe(|observer| observer.leave_statement(statement, tail_outcome(&outcome)));
+        match outcome? {
+            Tail::Done(value) if matches!(*value, Object::ReturnValue(_)) => {
+                return Ok(Tail::Done(value))
+            }
+            Tail::Done(value) => result = value,
+            call => return Ok(call),
+        }
+    }
+
+    Ok(Tail::Done(result))
+}
+
+/// [`eval_expression`] for an expression statement of a function body. Only
+/// calls with `tail` set are left to the caller, but the branches of an `if`
+/// are followed either way, for the `return`s in them.
+fn eval_tail_expression(
+    expression: &Expression,
+    env: &Env,
+    state: &EvalState,
+    tail: bool,
+) -> Result<Tail, EvalError> {
+    state.step().map_err(|error| error.at(expression.span()))?;
+    state.observe(|observer| observer.enter_expression(expression));
+    let outcome = match expression {
+        Expression::FunctionCall(FunctionCall {
+            callee,
+            arguments,
+            span,
+        }) if tail => {
+            let function = eval_expression(callee, env, state)?;
+            let args = eval_expressions(arguments, env, state)?;
+            Ok(Tail::Call(TailCall {
+                function,
+                args,
+                caller: Rc::clone(env),
+                name: callee_name(callee).to_string(),
+                span: span.clone(),
+            }))
+        }
+        Expression::IF(IF {
+            condition,
+            consequent,
+            alternate,
+            ..
+        }) => {
+            let condition = eval_expression(condition, env, state)?;
+            if is_truthy(&condition) {
+                eval_tail_block(&consequent.body, env, state, tail)
+            } else {
+                match alternate {
+                    Some(alt) => eval_tail_block(&alt.body, env, state, tail),
+                    None => Ok(Tail::Done(Rc::new(Object::Null))),
+                }
+            }
+        }
+        _ => eval_expression_node(expression, env, state).map(Tail::Done),
+    };
+    let outcome = outcome.map_err(|error| error.at(expression.span()));
+    state.observe(|observer| observer.leave_expression(expression, tail_outcome(&outcome)));
+    return outcome;
+}
+
+/// How an evaluation ended, as an [`EvalObserver`] is told.
+fn outcome(result: &Result<Rc<Object>, EvalError>) -> Outcome<'_> {
+    match result {
+        Ok(value) => match &**value {
+            Object::ReturnValue(value) => Outcome::Value(value),
+            _ => Outcome::Value(value),
+        },
+        Err(error) => Outcome::Error(error),
+    }
+}
+
+/// [`outcome`] for an evaluation that may end in a tail call.
+fn tail_outcome(result: &Result<Tail, EvalError>) -> Outcome<'_> {
+    match result {
+        Ok(Tail::Done(value)) => match &**value {
+            Object::ReturnValue(value) => Outcome::Value(value),
+            _ => Outcome::Value(value),
+        },
+        Ok(Tail::Call(_)) => Outcome::TailCall,
+        Err(error) => Outcome::Error(error),
+    }
+}
+
+/// The name a call shows with in the call stack of an error.
+fn callee_name(callee: &Expression) -> &str {
+    match callee {
+        Expression::IDENTIFIER(identifier) => identifier.name.as_str(),
+        Expression::Property(property) => property.property.name.as_str(),
+        _ => "<anonymous>",
+    }
+}
+
 fn is_truthy(obj: &Object) -> bool {
     match obj {
         Object::Null => return false,
@@ -113,25 +355,45 @@ fn is_truthy(obj: &Object) -> bool {
     }
 }
 
-fn eval_expression(expression: &Expression, env: &Env) -> Result<Rc<Object>, EvalError> {
+fn eval_expression(
+    expression: &Expression,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
+    state.step().map_err(|error| error.at(expression.span()))?;
+    state.observe(|observer| observer.enter_expression(expression));
+    let result =
+        eval_expression_node(expression, env, state).map_err(|error| error.at(expression.span()));
+    state.observe(|observer| observer.leave_expression(expression, outcome(&result)));
+    return result;
+}
+
+fn eval_expression_node(
+    expression: &Expression,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
     match expression {
-        Expression::LITERAL(literal) => eval_literal(literal, env),
+        Expression::LITERAL(literal) => eval_literal(literal, env, state),
         Expression::PREFIX(UnaryExpression {
             op,
             operand: expr,
             ..
         }) => {
-            let right = eval_expression(expr, &Rc::clone(env))?;
+            let right = eval_expression(expr, &Rc::clone(env), state)?;
             return eval_prefix(op, &right);
         }
         Expression::INFIX(BinaryExpression {
             op,
             left,
             right,
-            ..
+            span,
         }) => {
-            let left = eval_expression(left, &Rc::clone(env))?;
-            let right = eval_expression(right, &Rc::clone(env))?;
+            let left = eval_expression(left, &Rc::clone(env), state)?;
+            let right = eval_expression(right, &Rc::clone(env), state)?;
+            if let Some(result) = eval_instance_infix(op, &left, &right, span, state) {
+                return result;
+            }
             return eval_infix(op, &left, &right);
         }
         Expression::IF(IF {
@@ -140,61 +402,94 @@ fn eval_expression(expression: &Expression, env: &Env) -> Result<Rc<Object>, Eva
             alternate,
             ..
         }) => {
-            let condition = eval_expression(condition, &Rc::clone(env))?;
+            let condition = eval_expression(condition, &Rc::clone(env), state)?;
             if is_truthy(&condition) {
-                eval_block_statements(&(consequent.body), env)
+                eval_block_statements(&(consequent.body), env, state)
             } else {
                 match alternate {
-                    Some(alt) => eval_block_statements(&(alt.body), env),
+                    Some(alt) => eval_block_statements(&(alt.body), env, state),
                     None => Ok(Rc::new(Object::Null)),
                 }
             }
         }
         Expression::IDENTIFIER(IDENTIFIER {
-            name: id,
+            name,
+            resolution,
             ..
-        }) => eval_identifier(id, env),
+        }) => eval_identifier(name, resolution, env),
         Expression::FUNCTION(FunctionDeclaration {
             params,
             body,
+            generator,
             ..
         }) => {
-            return Ok(Rc::new(Object::Function(params.clone(), body.clone(), Rc::clone(env))));
+            let (params, body, env) = (params.clone(), body.clone(), Rc::clone(env));
+            if *generator {
+                return Ok(Rc::new(Object::GeneratorFunction(params, body, env)));
+            }
+            return Ok(Rc::new(Object::Function(params, body, env)));
         }
         Expression::FunctionCall(FunctionCall {
             callee,
             arguments,
-            ..
+            span,
         }) => {
-            let func = eval_expression(callee, &Rc::clone(env))?;
-            let args = eval_expressions(arguments, env)?;
-            apply_function(&func, &args)
+            let func = eval_expression(callee, &Rc::clone(env), state)?;
+            let args = eval_expressions(arguments, env, state)?;
+            apply_function(
+                &func,
+                &args,
+                env,
+                &CallSite {
+                    name: callee_name(callee),
+                    span,
+                },
+                state,
+            )
         }
         Expression::Index(Index {
             object: left,
             index,
-            ..
+            span,
         }) => {
-            let literal = eval_expression(left, &Rc::clone(env))?;
-            let index = eval_expression(index, env)?;
-            eval_index_expression(&literal, &index)
+            let literal = eval_expression(left, &Rc::clone(env), state)?;
+            let index = eval_expression(index, env, state)?;
+            eval_index_expression(&literal, &index, span, state)
         }
-        Expression::This(_) => eval_identifier("this", env),
+        Expression::This(this) => eval_identifier("this", &this.resolution, env),
         Expression::Property(property) => {
-            let receiver = eval_expression(&property.object, env)?;
+            let receiver = eval_expression(&property.object, env, state)?;
             get_property(&receiver, &property.property.name)
         }
         Expression::New(new_expression) => {
-            let class = eval_identifier(&new_expression.callee.name, env)?;
-            let arguments = eval_expressions(&new_expression.arguments, env)?;
-            construct_instance(&class, &arguments)
+            let callee = &new_expression.callee;
+            let class = eval_identifier(&callee.name, &callee.resolution, env)?;
+            let arguments = eval_expressions(&new_expression.arguments, env, state)?;
+            construct_instance(&class, &arguments, &new_expression.span, state)
+        }
+        Expression::Template(template) => {
+            let values = eval_expressions(&template.expressions, env, state)?;
+            let mut text = template.quasis[0].clone();
+            for (value, quasi) in values.iter().zip(&template.quasis[1..]) {
+                text.push_str(&display_value(value, &template.span, state)?);
+                text.push_str(quasi);
+            }
+            Ok(Rc::new(Object::String(text)))
+        }
+        // Generator bodies run their yields themselves, see `generator`.
+        Expression::Yield(_) => {
+            Err(EvalError::new(EvalErrorKind::Compile, "yield outside a generator function"))
         }
     }
 }
 
 fn get_property(receiver: &Rc<Object>, name: &str) -> Result<Rc<Object>, EvalError> {
+    if let Some(method) = builtin_method(receiver, name) {
+        return Ok(method);
+    }
     let Object::Instance(instance) = &**receiver else {
-        return Err(format!("cannot read property '{}' of {}", name, receiver));
+        let message = format!("cannot read property '{}' of {}", name, receiver);
+        return Err(EvalError::new(EvalErrorKind::Property, message));
     };
 
     if let Some(value) = instance.borrow().fields.get(name).cloned() {
@@ -214,12 +509,14 @@ fn get_property(receiver: &Rc<Object>, name: &str) -> Result<Rc<Object>, EvalErr
         }))));
     }
 
-    Err(format!("property '{}' does not exist on {}", name, class_name))
+    let message = format!("property '{}' does not exist on {}", name, class_name);
+    Err(EvalError::new(EvalErrorKind::Property, message))
 }
 
 fn set_property(receiver: &Rc<Object>, name: String, value: Rc<Object>) -> Result<(), EvalError> {
     let Object::Instance(instance) = &**receiver else {
-        return Err(format!("cannot set property '{}' of {}", name, receiver));
+        let message = format!("cannot set property '{}' of {}", name, receiver);
+        return Err(EvalError::new(EvalErrorKind::Property, message));
     };
     instance.borrow_mut().fields.insert(name, value);
     Ok(())
@@ -228,9 +525,12 @@ fn set_property(receiver: &Rc<Object>, name: String, value: Rc<Object>) -> Resul
 fn construct_instance(
     class_value: &Rc<Object>,
     args: &[Rc<Object>],
+    span: &Span,
+    state: &EvalState,
 ) -> Result<Rc<Object>, EvalError> {
     let Object::Class(class) = &**class_value else {
-        return Err(format!("cannot construct {}", class_value));
+        let message = format!("cannot construct {}", class_value);
+        return Err(EvalError::new(EvalErrorKind::Call, message));
     };
     let instance = Rc::new(RefCell::new(InstanceObject {
         class: Rc::clone(class),
@@ -239,23 +539,103 @@ fn construct_instance(
     let instance_value = Rc::new(Object::Instance(Rc::clone(&instance)));
     let constructor = class.borrow().constructor.clone();
     if let Some(constructor) = constructor {
+        let name = format!("{}.constructor", class.borrow().name);
         apply_method(
             &constructor,
             &instance,
             args,
-            &format!("{}.constructor", class.borrow().name),
+            &CallSite {
+                name: &name,
+                span,
+            },
+            state,
         )?;
     } else if !args.is_empty() {
-        return Err(format!(
+        let message = format!(
             "wrong number of arguments for {}.constructor: want=0, got={}",
             class.borrow().name,
             args.len()
-        ));
+        );
+        return Err(EvalError::new(EvalErrorKind::Call, message));
     }
     Ok(instance_value)
 }
 
-fn eval_index_expression(left: &Rc<Object>, index: &Rc<Object>) -> Result<Rc<Object>, EvalError> {
+/// Runs `receiver.name(args)` when the receiver is an instance whose class
+/// defines `name`. Only class methods count: a field of the same name does not
+/// change how operators behave on the instance. `span` is the expression that
+/// made the call.
+fn call_special_method(
+    receiver: &Rc<Object>,
+    name: &str,
+    args: &[Rc<Object>],
+    span: &Span,
+    state: &EvalState,
+) -> Option<Result<Rc<Object>, EvalError>> {
+    let Object::Instance(instance) = &**receiver else {
+        return None;
+    };
+    let (class_name, method) = {
+        let instance = instance.borrow();
+        let class = instance.class.borrow();
+        (class.name.clone(), class.methods.get(name).cloned()?)
+    };
+    let name = format!("{}.{}", class_name, name);
+    Some(apply_method(
+        &method,
+        instance,
+        args,
+        &CallSite {
+            name: &name,
+            span,
+        },
+        state,
+    ))
+}
+
+fn eval_instance_infix(
+    op: &Token,
+    left: &Rc<Object>,
+    right: &Rc<Object>,
+    span: &Span,
+    state: &EvalState,
+) -> Option<Result<Rc<Object>, EvalError>> {
+    let name = match op.kind {
+        TokenKind::PLUS => "add",
+        TokenKind::MINUS => "sub",
+        TokenKind::ASTERISK => "mul",
+        TokenKind::SLASH => "div",
+        TokenKind::EQ | TokenKind::NotEq => "eq",
+        _ => return None,
+    };
+    let result = call_special_method(left, name, &[Rc::clone(right)], span, state)?;
+    if name != "eq" {
+        return Some(result);
+    }
+    Some(result.map(|equal| {
+        let equal = is_truthy(&equal);
+        Rc::new(Object::Boolean(if op.kind == TokenKind::EQ { equal } else { !equal }))
+    }))
+}
+
+/// Text of a value as `puts` and template literals show it, honouring a
+/// class-defined `toString`.
+fn display_value(value: &Rc<Object>, span: &Span, state: &EvalState) -> Result<String, EvalError> {
+    match call_special_method(value, "toString", &[], span, state) {
+        Some(shown) => Ok(shown?.to_string()),
+        None => Ok(value.to_string()),
+    }
+}
+
+fn eval_index_expression(
+    left: &Rc<Object>,
+    index: &Rc<Object>,
+    span: &Span,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
+    if let Some(result) = call_special_method(left, "get", &[Rc::clone(index)], span, state) {
+        return result;
+    }
     match (&**left, &**index) {
         (Object::Array(arr), Object::Integer(idx)) => match arr.get(*idx as usize) {
             Some(obj) => return Ok(Rc::clone(obj)),
@@ -263,7 +643,7 @@ fn eval_index_expression(left: &Rc<Object>, index: &Rc<Object>) -> Result<Rc<Obj
         },
         (Object::Hash(map), key) => {
             if !(key.is_hashable()) {
-                return Err("not a valid hash key".to_string());
+                return Err(EvalError::new(EvalErrorKind::Index, "not a valid hash key"));
             }
 
             match map.get(key) {
@@ -271,65 +651,240 @@ fn eval_index_expression(left: &Rc<Object>, index: &Rc<Object>) -> Result<Rc<Obj
                 None => return Ok(Rc::new(Object::Null)),
             }
         }
-        _ => return Err(format!("index operator not supported for {}", left)),
+        _ => {
+            let message = format!("index operator not supported for {}", left);
+            return Err(EvalError::new(EvalErrorKind::Index, message));
+        }
     }
 }
 
-fn apply_function(function: &Rc<Object>, args: &[Rc<Object>]) -> Result<Rc<Object>, EvalError> {
-    match &**function {
-        Object::Function(params, body, env) => {
-            if params.len() != args.len() {
-                return Err(format!(
-                    "wrong number of arguments: want={}, got={}",
-                    params.len(),
-                    args.len()
-                ));
+/// `caller` is the environment of the call site; builtins that keep
+/// per-program state (`random`, `seed`, host natives) find it there.
+fn apply_function(
+    function: &Rc<Object>,
+    args: &[Rc<Object>],
+    caller: &Env,
+    site: &CallSite,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
+    let tail = enter_function(function, args, caller, site, state)?;
+    return finish_tail(tail, state);
+}
+
+/// Makes the calls that function bodies leave in tail position until one
+/// of them ends with a value, so tail recursion runs in constant native
+/// stack. A tail call replaces its caller, so only the function running
+/// when an error happens is on its call stack, not the ones that tail
+/// called it.
+fn finish_tail(mut tail: Tail, state: &EvalState) -> Result<Rc<Object>, EvalError> {
+    loop {
+        match tail {
+            Tail::Done(value) => return unwrap_return(value),
+            Tail::Call(call) => {
+                let site = CallSite {
+                    name: &call.name,
+                    span: &call.span,
+                };
+                tail = enter_function(&call.function, &call.args, &call.caller, &site, state)
+                    .map_err(|error| error.at(&call.span))?;
             }
-            let mut env = Environment::new_enclosed_environment(env);
+        }
+    }
+}
 
-            params.iter().enumerate().for_each(|(i, param)| {
-                env.set(param.name.clone(), args[i].clone());
+/// Starts a call: Monkey functions and methods stop at a call in tail
+/// position, anything else runs to its value.
+fn enter_function(
+    function: &Rc<Object>,
+    args: &[Rc<Object>],
+    caller: &Env,
+    site: &CallSite,
+    state: &EvalState,
+) -> Result<Tail, EvalError> {
+    if let Object::BoundMethod(bound) = &**function {
+        let site = CallSite {
+            name: &bound.name,
+            span: site.span,
+        };
+        return enter_method(&bound.method, &bound.receiver, args, &site, state);
+    }
+    return observe_call(args, site, state, || match &**function {
+        Object::Function(params, body, env) => {
+            let env = call_env(params, args, env, state)?;
+            return state.nested(|| {
+                eval_tail_block(&body.body, &env, state, true)
+                    .map_err(|error| error.called_from(site.name, site.span))
             });
+        }
+        Object::GeneratorFunction(params, _, env) => {
+            let env = call_env(params, args, env, state)?;
+            return Ok(Tail::Done(generator::generator(function, env)));
+        }
+        _ => return apply_builtin(function, args, caller, site, state).map(Tail::Done),
+    });
+}
 
-            let evaluated = eval_block_statements(&body.body, &Rc::new(RefCell::new(env)))?;
-            return unwrap_return(evaluated);
+/// The scope of a function body, enclosed in `env`, with `args` bound to
+/// `params`.
+fn call_env(
+    params: &[IDENTIFIER],
+    args: &[Rc<Object>],
+    env: &Env,
+    state: &EvalState,
+) -> Result<Env, EvalError> {
+    if params.len() != args.len() {
+        let message =
+            format!("wrong number of arguments: want={}, got={}", params.len(), args.len());
+        return Err(EvalError::new(EvalErrorKind::Call, message));
+    }
+    let mut env = Environment::new_enclosed_environment(env);
+    for (index, (parameter, argument)) in params.iter().zip(args).enumerate() {
+        state.observe(|observer| observer.bind(&parameter.name, argument, &parameter.span));
+        env.set_local(index, Rc::clone(argument));
+    }
+    return Ok(Rc::new(RefCell::new(env)));
+}
+
+/// Runs `call` between the [`EvalObserver`] events for it.
+fn observe_call(
+    args: &[Rc<Object>],
+    site: &CallSite,
+    state: &EvalState,
+    call: impl FnOnce() -> Result<Tail, EvalError>,
+) -> Result<Tail, EvalError> {
+    state.observe(|observer| observer.enter_call(site.name, args, site.span));
+    let result = call();
+    state.observe(|observer| observer.leave_call(site.name, site.span, tail_outcome(&result)));
+    return result;
+}
+
+/// Calls anything that is not a Monkey function or method.
+fn apply_builtin(
+    function: &Rc<Object>,
+    args: &[Rc<Object>],
+    caller: &Env,
+    site: &CallSite,
+    state: &EvalState,
+) -> Result<Rc<Object>, EvalError> {
+    match &**function {
+        Object::Builtin(b) if builtin_id(*b) == Some(BuiltinId::Puts) => {
+            let mut lines = Vec::with_capacity(args.len());
+            for arg in args {
+                match &**arg {
+                    Object::Instance(_) => lines.push(display_value(arg, site.span, state)?),
+                    _ => lines.push(arg.to_string()),
+                }
+            }
+            let mut output = caller.borrow().output();
+            for line in &lines {
+                output.write_line(line);
+            }
+            Ok(Rc::new(Object::Null))
+        }
+        Object::Builtin(b) => match builtin_id(*b) {
+            Some(BuiltinId::Next) => generator::next(args, site, state),
+            Some(BuiltinId::Str) => {
+                let mut shown = Vec::with_capacity(args.len());
+                for arg in args {
+                    match &**arg {
+                        Object::Instance(_) => shown
+                            .push(Rc::new(Object::String(display_value(arg, site.span, state)?))),
+                        _ => shown.push(Rc::clone(arg)),
+                    }
+                }
+                Ok(b(shown))
+            }
+            Some(id) if is_higher_order(id) => {
+                // The callback has no name of its own at the builtin's call site.
+                let callback = CallSite {
+                    name: "<callback>",
+                    span: site.span,
+                };
+                let mut host = ObjectHost(|function: &Rc<Object>, args: &[Rc<Object>]| {
+                    return apply_function(function, args, caller, &callback, state);
+                });
+                match higher_order::call(&mut host, id, args) {
+                    Ok(result) => Ok(result),
+                    Err(HigherOrderError::Builtin(error)) => {
+                        Ok(Rc::new(Object::Error(error.message().to_string())))
+                    }
+                    Err(HigherOrderError::Callback(error)) => Err(error),
+                }
+            }
+            Some(id) if is_random_builtin(id) => {
+                let random = caller.borrow().random();
+                let result = random_builtin(&mut random.borrow_mut(), id, args);
+                Ok(result)
+            }
+            _ => Ok(b(args.to_vec())),
+        },
+        Object::Native(native) => {
+            let random = caller.borrow().random();
+            let mut random = random.borrow_mut();
+            Ok(native.call(&mut Ctx::new(&mut random), args))
         }
-        Object::Builtin(b) => Ok(b(args.to_vec())),
-        Object::BoundMethod(bound) => {
-            apply_method(&bound.method, &bound.receiver, args, &bound.name)
+        Object::BoundBuiltin(bound) => {
+            let builtin = Rc::new(Object::Builtin(bound.function));
+            let mut bound_args = vec![Rc::clone(&bound.receiver)];
+            bound_args.extend_from_slice(args);
+            apply_builtin(&builtin, &bound_args, caller, site, state)
         }
         Object::Class(class) => {
-            Err(format!("class {} must be constructed with new", class.borrow().name))
+            let message = format!("class {} must be constructed with new", class.borrow().name);
+            Err(EvalError::new(EvalErrorKind::Call, message))
         }
-        f => Err(format!("expected {} to be a function", f)),
+        f => Err(EvalError::new(EvalErrorKind::Call, format!("expected {} to be a function", f))),
     }
 }
 
+/// `site.name` is how the method shows in messages, `Class.name` or the
+/// name it was bound by.
 fn apply_method(
     method: &Rc<Object>,
     receiver: &InstanceRef,
     args: &[Rc<Object>],
-    display_name: &str,
+    site: &CallSite,
+    state: &EvalState,
 ) -> Result<Rc<Object>, EvalError> {
-    let Object::Function(params, body, declaration_env) = &**method else {
-        return Err(format!("{} is not a method", display_name));
-    };
-    if params.len() != args.len() {
-        return Err(format!(
-            "wrong number of arguments for {}: want={}, got={}",
-            display_name,
-            params.len(),
-            args.len()
-        ));
-    }
+    let tail = enter_method(method, receiver, args, site, state)?;
+    return finish_tail(tail, state);
+}
 
-    let mut call_env = Environment::new_enclosed_environment(declaration_env);
-    call_env.set("this".to_string(), Rc::new(Object::Instance(Rc::clone(receiver))));
-    for (parameter, argument) in params.iter().zip(args) {
-        call_env.set(parameter.name.clone(), Rc::clone(argument));
-    }
-    let evaluated = eval_block_statements(&body.body, &Rc::new(RefCell::new(call_env)))?;
-    unwrap_return(evaluated)
+/// [`enter_function`] for a method, with `this` bound to `receiver`.
+fn enter_method(
+    method: &Rc<Object>,
+    receiver: &InstanceRef,
+    args: &[Rc<Object>],
+    site: &CallSite,
+    state: &EvalState,
+) -> Result<Tail, EvalError> {
+    observe_call(args, site, state, || {
+        let Object::Function(params, body, declaration_env) = &**method else {
+            let message = format!("{} is not a method", site.name);
+            return Err(EvalError::new(EvalErrorKind::Call, message));
+        };
+        if params.len() != args.len() {
+            let message = format!(
+                "wrong number of arguments for {}: want={}, got={}",
+                site.name,
+                params.len(),
+                args.len()
+            );
+            return Err(EvalError::new(EvalErrorKind::Call, message));
+        }
+
+        let mut call_env = Environment::new_enclosed_environment(declaration_env);
+        for (index, (parameter, argument)) in params.iter().zip(args).enumerate() {
+            state.observe(|observer| observer.bind(&parameter.name, argument, &parameter.span));
+            call_env.set_local(index, Rc::clone(argument));
+        }
+        call_env.set_local(params.len(), Rc::new(Object::Instance(Rc::clone(receiver))));
+        let call_env = Rc::new(RefCell::new(call_env));
+        state.nested(|| {
+            eval_tail_block(&body.body, &call_env, state, true)
+                .map_err(|error| error.called_from(site.name, site.span))
+        })
+    })
 }
 
 fn unwrap_return(obj: Rc<Object>) -> Result<Rc<Object>, EvalError> {
@@ -340,23 +895,46 @@ fn unwrap_return(obj: Rc<Object>) -> Result<Rc<Object>, EvalError> {
     }
 }
 
-fn eval_expressions(exprs: &Vec<Expression>, env: &Env) -> Result<Vec<Rc<Object>>, EvalError> {
+fn eval_expressions(
+    exprs: &Vec<Expression>,
+    env: &Env,
+    state: &EvalState,
+) -> Result<Vec<Rc<Object>>, EvalError> {
     let mut list = Vec::new();
     for expr in exprs {
-        let val = eval_expression(expr, &Rc::clone(env))?;
+        let val = eval_expression(expr, &Rc::clone(env), state)?;
         list.push(val);
     }
 
     Ok(list)
 }
 
-fn eval_identifier(identifier: &str, env: &Env) -> Result<Rc<Object>, EvalError> {
-    match env.borrow().get(identifier) {
-        Some(obj) => Ok(obj.clone()),
-        None => match BuiltIns.iter().find(|builtin| builtin.name == identifier) {
-            Some(obj) => Ok(Rc::new(Object::Builtin(obj.function))),
-            None => Err(format!("unknown identifier {}", identifier)),
-        },
+fn eval_identifier(
+    identifier: &str,
+    resolution: &Resolution,
+    env: &Env,
+) -> Result<Rc<Object>, EvalError> {
+    let env = env.borrow();
+    for binding in resolution.bindings().iter() {
+        let value = match *binding {
+            Binding::Local {
+                depth,
+                index,
+            } => env.get_local(depth, index),
+            Binding::Global {
+                depth,
+            } => env.get_global(depth, identifier),
+        };
+        if let Some(value) = value {
+            return Ok(value);
+        }
+    }
+    match env.builtins().get(identifier) {
+        Some(builtin) => Ok(Rc::new(builtin.to_object())),
+        None => Err(EvalError::new(
+            EvalErrorKind::Compile,
+            format!("unknown identifier {}", identifier),
+        )),
     }
 }
 
@@ -364,7 +942,7 @@ fn eval_prefix(op: &Token, right: &Object) -> Result<Rc<Object>, EvalError> {
     match op.kind {
         TokenKind::BANG => eval_prefix_bang(right),
         TokenKind::MINUS => eval_prefix_minus(right),
-        _ => Err(format!("unknown prefix operator: {}", op)),
+        _ => Err(EvalError::new(EvalErrorKind::Type, format!("unknown prefix operator: {}", op))),
     }
 }
 
@@ -378,8 +956,14 @@ fn eval_prefix_bang(expr: &Object) -> Result<Rc<Object>, EvalError> {
 
 fn eval_prefix_minus(expr: &Object) -> Result<Rc<Object>, EvalError> {
     match *expr {
-        Object::Integer(i) => Ok(Rc::from(Object::Integer(-i))),
-        _ => Err(format!("can't apply prefix minus operator: {}", expr)),
+        Object::Integer(_) | Object::BigInt(_) => {
+            Ok(Rc::from(Object::from(integer::negate(&expr.as_int().unwrap()))))
+        }
+        Object::Float(x) => Ok(Rc::from(Object::Float(-x))),
+        _ => Err(EvalError::new(
+            EvalErrorKind::Type,
+            format!("can't apply prefix minus operator: {}", expr),
+        )),
     }
 }
 
@@ -389,8 +973,15 @@ fn eval_infix(op: &Token, left: &Object, right: &Object) -> Result<Rc<Object>, E
         return Ok(Rc::new(Object::Boolean(if op.kind == TokenKind::EQ { equal } else { !equal })));
     }
     match (left, right) {
-        (Object::Integer(left), Object::Integer(right)) => {
-            return eval_integer_infix(op, *left, *right);
+        (Object::Integer(_) | Object::BigInt(_), Object::Integer(_) | Object::BigInt(_)) => {
+            return eval_integer_infix(op, &left.as_int().unwrap(), &right.as_int().unwrap());
+        }
+        (
+            Object::Integer(_) | Object::BigInt(_) | Object::Float(_),
+            Object::Integer(_) | Object::BigInt(_) | Object::Float(_),
+        ) => {
+            let (left, right) = float_operands(left, right).unwrap();
+            return eval_float_infix(op, left, right);
         }
         (Object::Boolean(left), Object::Boolean(right)) => {
             return eval_boolean_infix(op, *left, *right);
@@ -398,21 +989,52 @@ fn eval_infix(op: &Token, left: &Object, right: &Object) -> Result<Rc<Object>, E
         (Object::String(left), Object::String(right)) => {
             return eval_string_infix(op, left.to_string(), right.to_string());
         }
-        _ => Err(format!("eval infix error for op: {}, left: {}, right: {}", op, left, right)),
+        _ => {
+            let message =
+                format!("eval infix error for op: {}, left: {}, right: {}", op, left, right);
+            Err(EvalError::new(EvalErrorKind::Type, message))
+        }
     }
 }
 
-fn eval_integer_infix(op: &Token, left: i64, right: i64) -> Result<Rc<Object>, EvalError> {
+fn eval_integer_infix(op: &Token, left: &Int, right: &Int) -> Result<Rc<Object>, EvalError> {
+    let arith = match &op.kind {
+        TokenKind::PLUS => IntegerOp::Add,
+        TokenKind::MINUS => IntegerOp::Sub,
+        TokenKind::ASTERISK => IntegerOp::Mul,
+        TokenKind::SLASH => IntegerOp::Div,
+        TokenKind::LT => {
+            return Ok(Rc::from(Object::Boolean(integer::compare(left, right).is_lt())))
+        }
+        TokenKind::GT => {
+            return Ok(Rc::from(Object::Boolean(integer::compare(left, right).is_gt())))
+        }
+        TokenKind::EQ => return Ok(Rc::from(Object::Boolean(left == right))),
+        TokenKind::NotEq => return Ok(Rc::from(Object::Boolean(left != right))),
+        op => {
+            let message = format!("Invalid infix operator {} for int", op);
+            return Err(EvalError::new(EvalErrorKind::Type, message));
+        }
+    };
+
+    match integer::apply(arith, left, right) {
+        Some(result) => Ok(Rc::from(Object::from(result))),
+        None => Err(EvalError::new(EvalErrorKind::Arithmetic, "division by zero")),
+    }
+}
+
+fn eval_float_infix(op: &Token, left: f64, right: f64) -> Result<Rc<Object>, EvalError> {
     let result = match &op.kind {
-        TokenKind::PLUS => Object::Integer(left + right),
-        TokenKind::MINUS => Object::Integer(left - right),
-        TokenKind::ASTERISK => Object::Integer(left * right),
-        TokenKind::SLASH => Object::Integer(left / right),
+        TokenKind::PLUS => Object::Float(left + right),
+        TokenKind::MINUS => Object::Float(left - right),
+        TokenKind::ASTERISK => Object::Float(left * right),
+        TokenKind::SLASH => Object::Float(left / right),
         TokenKind::LT => Object::Boolean(left < right),
         TokenKind::GT => Object::Boolean(left > right),
-        TokenKind::EQ => Object::Boolean(left == right),
-        TokenKind::NotEq => Object::Boolean(left != right),
-        op => return Err(format!("Invalid infix operator {} for int", op)),
+        op => {
+            let message = format!("Invalid infix operator {} for float", op);
+            return Err(EvalError::new(EvalErrorKind::Type, message));
+        }
     };
 
     Ok(Rc::from(result))
@@ -422,7 +1044,10 @@ fn eval_boolean_infix(op: &Token, left: bool, right: bool) -> Result<Rc<Object>,
     let result = match &op.kind {
         TokenKind::EQ => Object::Boolean(left == right),
         TokenKind::NotEq => Object::Boolean(left != right),
-        op => return Err(format!("Invalid infix operator for boolean: {}", op)),
+        op => {
+            let message = format!("Invalid infix operator for boolean: {}", op);
+            return Err(EvalError::new(EvalErrorKind::Type, message));
+        }
     };
 
     Ok(Rc::from(result))
@@ -433,18 +1058,35 @@ fn eval_string_infix(op: &Token, left: String, right: String) -> Result<Rc<Objec
         TokenKind::EQ => Object::Boolean(left == right),
         TokenKind::NotEq => Object::Boolean(left != right),
         TokenKind::PLUS => Object::String(format!("{}{}", left, right)),
-        op => return Err(format!("Invalid infix {} operator for string", op)),
+        op => {
+            let message = format!("Invalid infix {} operator for string", op);
+            return Err(EvalError::new(EvalErrorKind::Type, message));
+        }
     };
 
     Ok(Rc::from(result))
 }
 
-fn eval_literal(literal: &Literal, env: &Env) -> Result<Rc<Object>, EvalError> {
+fn eval_literal(literal: &Literal, env: &Env, state: &EvalState) -> Result<Rc<Object>, EvalError> {
     match literal {
         Literal::Integer(Integer {
             raw: i,
             ..
         }) => Ok(Rc::from(Object::Integer(*i))),
+        Literal::BigInt(BigInteger {
+            raw: digits,
+            ..
+        }) => match digits.parse::<BigInt>() {
+            Ok(value) => Ok(Rc::from(Object::from(Int::narrow(value)))),
+            Err(_) => Err(EvalError::new(
+                EvalErrorKind::Type,
+                format!("invalid integer literal {}", digits),
+            )),
+        },
+        Literal::Float(Float {
+            raw: x,
+            ..
+        }) => Ok(Rc::from(Object::Float(x.0))),
         Literal::Boolean(Boolean {
             raw: b,
             ..
@@ -457,8 +1099,8 @@ fn eval_literal(literal: &Literal, env: &Env) -> Result<Rc<Object>, EvalError> {
             elements,
             ..
         }) => {
-            let list = eval_expressions(elements, env)?;
-            return Ok(Rc::from(Object::Array(list)));
+            let list = eval_expressions(elements, env, state)?;
+            return Ok(Rc::from(Object::Array(list.into())));
         }
         Literal::Hash(Hash {
             elements: map,
@@ -467,14 +1109,15 @@ fn eval_literal(literal: &Literal, env: &Env) -> Result<Rc<Object>, EvalError> {
             // Object's Hash impl only covers Integer/Boolean/String, which have no
             // interior mutability; keys are checked with is_hashable() before insert.
             #[allow(clippy::mutable_key_type)]
-            let mut hash_map = HashMap::new();
+            let mut hash_map = OrderedMap::new();
 
             for (k, v) in map {
-                let key = eval_expression(k, env)?;
+                let key = eval_expression(k, env, state)?;
                 if !key.is_hashable() {
-                    return Err(format!("key {} is not hashable", key));
+                    let message = format!("key {} is not hashable", key);
+                    return Err(EvalError::new(EvalErrorKind::Index, message));
                 }
-                let value = eval_expression(v, env)?;
+                let value = eval_expression(v, env, state)?;
                 hash_map.insert(key, value);
             }
 
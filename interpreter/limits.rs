@@ -0,0 +1,106 @@
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::{EvalError, EvalErrorKind};
+use crate::observer::EvalObserver;
+
+/// How many steps pass between two looks at the interrupt flag.
+const INTERRUPT_CHECK_INTERVAL: usize = 1024;
+/// A Monkey call takes a few kilobytes of native stack in release builds and
+/// tens of kilobytes in debug builds. Calls move to a fresh segment of
+/// `STACK_SEGMENT` bytes once less than `STACK_RED_ZONE` is left, so only
+/// `max_call_depth` bounds recursion, whatever stack the host thread has.
+const STACK_RED_ZONE: usize = 128 * 1024;
+const STACK_SEGMENT: usize = 2 * 1024 * 1024;
+
+/// Limits for one [`eval_with_options`](crate::eval_with_options), so
+/// untrusted snippets fail with an error instead of running forever or
+/// overflowing the native stack.
+#[derive(Clone, Debug)]
+pub struct EvalOptions {
+    /// How many expressions may be evaluated before the run fails with an
+    /// `executionLimit` error. Unlimited by default.
+    pub step_budget: usize,
+    /// How many Monkey calls may be active at once before the run fails
+    /// with a `stack` error.
+    pub max_call_depth: usize,
+    /// Set to `true` from any thread to stop the run with an
+    /// `executionLimit` error at its next check.
+    pub interrupt: Option<Arc<AtomicBool>>,
+}
+
+impl Default for EvalOptions {
+    fn default() -> EvalOptions {
+        return EvalOptions {
+            step_budget: usize::MAX,
+            max_call_depth: 1024,
+            interrupt: None,
+        };
+    }
+}
+
+/// How far one run has got towards the limits of its [`EvalOptions`], and
+/// who is watching it.
+pub(crate) struct EvalState<'a> {
+    options: &'a EvalOptions,
+    steps: Cell<usize>,
+    depth: Cell<usize>,
+    observer: Option<RefCell<&'a mut dyn EvalObserver>>,
+}
+
+impl<'a> EvalState<'a> {
+    pub(crate) fn new(
+        options: &'a EvalOptions,
+        observer: Option<&'a mut dyn EvalObserver>,
+    ) -> EvalState<'a> {
+        return EvalState {
+            options,
+            steps: Cell::new(0),
+            depth: Cell::new(0),
+            observer: observer.map(RefCell::new),
+        };
+    }
+
+    /// Tells the observer, if there is one, about an event.
+    pub(crate) fn observe(&self, event: impl FnOnce(&mut dyn EvalObserver)) {
+        if let Some(observer) = &self.observer {
+            event(&mut **observer.borrow_mut());
+        }
+    }
+
+    /// Counts one evaluated expression.
+    pub(crate) fn step(&self) -> Result<(), EvalError> {
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+        if steps > self.options.step_budget {
+            let message = format!("step limit exceeded (budget: {})", self.options.step_budget);
+            return Err(EvalError::new(EvalErrorKind::ExecutionLimit, message));
+        }
+        if steps.is_multiple_of(INTERRUPT_CHECK_INTERVAL) {
+            if let Some(interrupt) = &self.options.interrupt {
+                if interrupt.load(Ordering::Relaxed) {
+                    return Err(EvalError::new(EvalErrorKind::ExecutionLimit, "interrupted"));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    /// Runs `call` as one more active Monkey call.
+    pub(crate) fn nested<T>(
+        &self,
+        call: impl FnOnce() -> Result<T, EvalError>,
+    ) -> Result<T, EvalError> {
+        let depth = self.depth.get();
+        if depth >= self.options.max_call_depth {
+            let message =
+                format!("call depth limit exceeded (max: {})", self.options.max_call_depth);
+            return Err(EvalError::new(EvalErrorKind::Stack, message));
+        }
+        self.depth.set(depth + 1);
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, call);
+        self.depth.set(depth);
+        return result;
+    }
+}
@@ -1,28 +1,154 @@
 use interpreter::eval;
+use interpreter::session::{load_session, save_session};
 use object::environment::Env;
+use object::Object;
 use parser::parse;
+use repl::cli::{self, parse_run_args, read_script, CliError};
+use repl::Backend;
 use std::cell::RefCell;
-use std::io::stdin;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+const USAGE: &str = "\
+usage:
+  monkey-interpreter                                   start the REPL
+  monkey-interpreter --session <state.json>            start the REPL where the saved session left off
+  monkey-interpreter run <file.monkey|-> [-- <args>...]  run a script, `-` reading it from stdin
+  monkey-interpreter check <file.monkey>               type check annotations";
+
 fn main() {
-    println!("Welcome to monkey interpreter by gengjiawen");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let outcome = match args.split_first() {
+        Some((command, rest)) if command == "run" => run_command(rest).map(Some),
+        Some((command, rest)) if command == "check" => cli::check_command(rest).map(|()| None),
+        _ => match parse_repl_args(&args) {
+            Ok(session) => {
+                repl(session);
+                return;
+            }
+            Err(error) => Err(error),
+        },
+    };
+    cli::finish(outcome, USAGE);
+}
+
+fn repl(session: Option<PathBuf>) {
     let env: Env = Rc::new(RefCell::new(Default::default()));
-    loop {
-        let mut input = String::new();
-        stdin().read_line(&mut input).unwrap();
+    if let Some(path) = &session {
+        if let Err(message) = resume(path, &env) {
+            eprintln!("error: {}", message);
+            std::process::exit(1);
+        }
+    }
+    let backend = InterpreterBackend {
+        env,
+        session,
+    };
+    repl::run(
+        "Welcome to monkey interpreter by gengjiawen",
+        repl::history_file("monkey-interpreter"),
+        backend,
+    );
+}
 
-        if input.trim_end().is_empty() {
-            println!("bye");
-            std::process::exit(0)
+struct InterpreterBackend {
+    env: Env,
+    /// Where every change is saved, with `--session`.
+    session: Option<PathBuf>,
+}
+
+impl InterpreterBackend {
+    fn save(&self) {
+        // A failed save leaves the last good file in place, so keep going.
+        if let Some(path) = &self.session {
+            if let Err(message) = save(path, &self.env) {
+                eprintln!("warning: session not saved: {}", message);
+            }
         }
+    }
+}
+
+impl Backend for InterpreterBackend {
+    fn eval(&mut self, source: &str) -> Result<String, String> {
+        let node = parse(source).map_err(|errors| format!("parse error: {}", errors[0]))?;
+        let result = eval(node, &self.env).map(|evaluated| evaluated.to_string());
+        self.save();
+        result.map_err(|error| error.render())
+    }
+
+    fn reset(&mut self) {
+        self.env = Rc::new(RefCell::new(Default::default()));
+        self.save();
+    }
+
+    fn env(&self) -> Vec<(String, String)> {
+        let env = self.env.borrow();
+        let mut bindings: Vec<(String, String)> = env
+            .bindings()
+            .map(|(name, value)| (name.clone(), value.to_string()))
+            .collect();
+        bindings.sort();
+        bindings
+    }
+}
 
-        match parse(&input) {
-            Ok(node) => match eval(node, &env) {
-                Ok(evaluated) => println!("{}", evaluated),
-                Err(e) => eprintln!("{}", e),
-            },
-            Err(e) => eprintln!("parse error: {}", e[0]),
+fn parse_repl_args(args: &[String]) -> Result<Option<PathBuf>, CliError> {
+    let mut session = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--session" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| CliError::usage("--session needs a file"))?;
+                session = Some(PathBuf::from(path));
+            }
+            other => return Err(CliError::usage(format!("unexpected argument `{}`", other))),
         }
     }
+    Ok(session)
 }
+
+/// Loads the session at `path` into `env`; a file that isn't there yet is a
+/// new session.
+fn resume(path: &Path, env: &Env) -> Result<(), String> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("cannot read {}: {}", path.display(), e)),
+    };
+    load_session(&json, env).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Writes beside `path` and renames over it, so an interrupted save never
+/// leaves half a session behind.
+fn save(path: &Path, env: &Env) -> Result<(), String> {
+    let json = save_session(env).map_err(|e| e.to_string())?;
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    std::fs::write(&temporary, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&temporary, path).map_err(|e| e.to_string())
+}
+
+/// Runs a whole script in a fresh environment, with everything after `--`
+/// bound to `args` as strings, and renders the value it ends with.
+fn run_command(args: &[String]) -> Result<String, CliError> {
+    let (input, script_args) = parse_run_args(args)?;
+    let source = read_script(&input)?;
+    let program = parse(&source)
+        .map_err(|errors| CliError::failure(format!("parse error: {}", errors[0])))?;
+    let env: Env = Rc::new(RefCell::new(Default::default()));
+    let script_args = script_args
+        .into_iter()
+        .map(|arg| Rc::new(Object::String(arg)))
+        .collect();
+    env.borrow_mut()
+        .set("args".to_string(), Rc::new(Object::Array(script_args)));
+    match eval(program, &env) {
+        Ok(evaluated) => Ok(evaluated.to_string()),
+        Err(error) => Err(CliError::failure(error.render())),
+    }
+}
+
+#[cfg(test)]
+mod cli_test;
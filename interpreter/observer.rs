@@ -0,0 +1,43 @@
+use std::rc::Rc;
+
+use object::Object;
+use parser::ast::{Expression, Statement};
+use parser::lexer::token::Span;
+
+use crate::error::EvalError;
+
+/// Watches a run of [`eval_with_observer`](crate::eval_with_observer), for
+/// tracers, step debuggers and coverage tools. Every method does nothing
+/// unless overridden. Statements and expressions know their own `span()`.
+///
+/// Events nest: every `enter_*` is matched by the `leave_*` of the same
+/// node or call, after those of everything evaluated inside it. An observer
+/// runs on the interpreter's thread, so a debugger can pause the run by not
+/// returning.
+pub trait EvalObserver {
+    fn enter_statement(&mut self, _statement: &Statement) {}
+    fn leave_statement(&mut self, _statement: &Statement, _outcome: Outcome) {}
+    fn enter_expression(&mut self, _expression: &Expression) {}
+    fn leave_expression(&mut self, _expression: &Expression, _outcome: Outcome) {}
+    /// A call of any function, builtin or method, made by the call
+    /// expression at `span`. `function` is the name it shows with in call
+    /// stacks.
+    fn enter_call(&mut self, _function: &str, _args: &[Rc<Object>], _span: &Span) {}
+    fn leave_call(&mut self, _function: &str, _span: &Span, _outcome: Outcome) {}
+    /// A `let`, class declaration or parameter, declared at `span`, taking
+    /// its value.
+    fn bind(&mut self, _name: &str, _value: &Rc<Object>, _span: &Span) {}
+}
+
+/// How a statement, expression or call ended.
+#[derive(Clone, Copy, Debug)]
+pub enum Outcome<'a> {
+    /// With a value; a `return` shows the value it returns.
+    Value(&'a Rc<Object>),
+    Error(&'a EvalError),
+    /// With a call in tail position. The function that made the call ends
+    /// here and the call takes its place, so the call's own
+    /// [`EvalObserver::enter_call`] comes next and its value is the one the
+    /// original call expression gets.
+    TailCall,
+}
@@ -0,0 +1,997 @@
+//! Saving what a session has defined: the values bound in its top-level
+//! scope, written as JSON and loaded into the top-level scope of another
+//! session, for `monkey-interpreter --session`.
+//!
+//! Values, scopes, classes, instances and generators are each numbered in a
+//! table and refer to one another by number, so a value reachable along two
+//! paths is still one value after loading, and the cycles a program can make
+//! (a function stored in the scope it closes over, an instance in one of its
+//! own fields) come back as cycles. A value only refers to values before it
+//! in its table. Values on their own can not form a cycle; every cycle goes
+//! through a scope, class, instance or generator, which loading makes empty
+//! first and fills in once all the values exist.
+//!
+//! Function bodies are stored as their AST. The bindings validation resolved
+//! in them are not part of the AST's JSON, so each body carries them too, in
+//! the order [`for_each_resolution`] visits them.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::rc::Rc;
+
+use object::builtins::{builtin_method, BuiltIns};
+use object::environment::{Env, Environment};
+use object::generators::{GeneratorRef, GeneratorState, Suspension};
+use object::iterators::Iter;
+use object::random::Random;
+use object::{
+    BigInt, BoundMethodObject, ClassObject, ClassRef, InstanceObject, InstanceRef, Object,
+    OrderedMap, Vector,
+};
+use parser::ast::*;
+use serde::{Deserialize, Serialize};
+
+/// Bump when the layout of the file changes.
+pub const SESSION_VERSION: u32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum SessionError {
+    /// A value an interpreter session can not hold on to, such as a
+    /// generator in the middle of a `next` call.
+    Unsupported(String),
+    /// The text is not a session file, or refers to things it does not have.
+    Malformed(String),
+    UnsupportedVersion {
+        found: u32,
+        expected: u32,
+    },
+    /// The session used a builtin this interpreter does not have.
+    UnknownBuiltin(String),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::Unsupported(what) => write!(f, "can not save {}", what),
+            SessionError::Malformed(message) => write!(f, "malformed session: {}", message),
+            SessionError::UnsupportedVersion {
+                found,
+                expected,
+            } => write!(f, "session version {} is not supported, expected {}", found, expected),
+            SessionError::UnknownBuiltin(name) => write!(f, "unknown builtin {}", name),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionFile {
+    version: u32,
+    /// Where the `random` sequence is.
+    random: u64,
+    /// The top-level scope comes first.
+    scopes: Vec<ScopeData>,
+    values: Vec<ValueData>,
+    classes: Vec<ClassData>,
+    instances: Vec<InstanceData>,
+    generators: Vec<GeneratorData>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ScopeData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    outer: Option<usize>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    names: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    slots: Vec<Option<usize>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum ValueData {
+    Integer {
+        value: i64,
+    },
+    BigInt {
+        digits: String,
+    },
+    /// As Rust prints it, which reads back to the same number, NaN and the
+    /// infinities included.
+    Float {
+        value: String,
+    },
+    Boolean {
+        value: bool,
+    },
+    String {
+        value: String,
+    },
+    Null,
+    Error {
+        message: String,
+    },
+    Array {
+        elements: Vec<usize>,
+    },
+    Hash {
+        entries: Vec<(usize, usize)>,
+    },
+    Function(FunctionData),
+    GeneratorFunction(FunctionData),
+    /// A standard or registered builtin, found again by name.
+    Builtin {
+        name: String,
+    },
+    Class {
+        class: usize,
+    },
+    Instance {
+        instance: usize,
+    },
+    BoundMethod {
+        instance: usize,
+        method: usize,
+        name: String,
+    },
+    BoundBuiltin {
+        receiver: usize,
+        name: String,
+    },
+    Range {
+        start: i64,
+        end: i64,
+        step: i64,
+    },
+    Take {
+        source: usize,
+        count: usize,
+    },
+    Skip {
+        source: usize,
+        count: usize,
+    },
+    Zip {
+        left: usize,
+        right: usize,
+    },
+    Enumerate {
+        source: usize,
+    },
+    Generator {
+        generator: usize,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct FunctionData {
+    params: Vec<IDENTIFIER>,
+    body: BlockStatement,
+    resolutions: Vec<Vec<Binding>>,
+    scope: usize,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct ClassData {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    constructor: Option<usize>,
+    methods: BTreeMap<String, usize>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct InstanceData {
+    class: usize,
+    fields: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "state")]
+enum GeneratorData {
+    Suspended(SuspensionData),
+    Delegating { suspension: SuspensionData, inner: usize },
+    Forwarding { inner: usize },
+    Done,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SuspensionData {
+    function: usize,
+    scope: usize,
+    resume_at: Vec<usize>,
+}
+
+/// The session whose top-level scope is `env`, as JSON.
+pub fn save_session(env: &Env) -> Result<String, SessionError> {
+    let mut writer = Writer::default();
+    writer.scope(env)?;
+    while let Some(pending) = writer.pending.pop() {
+        writer.write(pending)?;
+    }
+    let file = SessionFile {
+        version: SESSION_VERSION,
+        random: env.borrow().random().borrow().state(),
+        scopes: writer.scopes,
+        values: writer.values,
+        classes: writer.classes,
+        instances: writer.instances,
+        generators: writer.generators,
+    };
+    return Ok(serde_json::to_string_pretty(&file).expect("a session serializes to JSON"));
+}
+
+/// Binds the names saved in `json` in the top-level scope `env`, which keeps
+/// its own builtins and output. Nothing is bound if the session can not be
+/// loaded.
+pub fn load_session(json: &str, env: &Env) -> Result<(), SessionError> {
+    let file: SessionFile =
+        serde_json::from_str(json).map_err(|error| SessionError::Malformed(error.to_string()))?;
+    if file.version != SESSION_VERSION {
+        return Err(SessionError::UnsupportedVersion {
+            found: file.version,
+            expected: SESSION_VERSION,
+        });
+    }
+    if file.scopes.is_empty() {
+        return Err(malformed("no top-level scope"));
+    }
+
+    let mut reader = Reader {
+        scopes: vec![Rc::clone(env)],
+        classes: Vec::new(),
+        instances: Vec::new(),
+        generators: Vec::new(),
+        values: Vec::new(),
+    };
+    for (index, scope) in file.scopes.iter().enumerate().skip(1) {
+        let outer = match scope.outer {
+            Some(outer) if outer < index => &reader.scopes[outer],
+            _ => return Err(malformed(format!("scope {} is not inside an earlier scope", index))),
+        };
+        let scope = Environment::new_enclosed_environment(outer);
+        reader.scopes.push(Rc::new(RefCell::new(scope)));
+    }
+    for class in &file.classes {
+        reader.classes.push(Rc::new(RefCell::new(ClassObject {
+            name: class.name.clone(),
+            constructor: None,
+            methods: HashMap::new(),
+        })));
+    }
+    for instance in &file.instances {
+        let class = Rc::clone(lookup(&reader.classes, instance.class, "class")?);
+        reader.instances.push(Rc::new(RefCell::new(InstanceObject {
+            class,
+            fields: HashMap::new(),
+        })));
+    }
+    for _ in &file.generators {
+        reader
+            .generators
+            .push(Rc::new(RefCell::new(GeneratorState::Done)));
+    }
+    let builtins = env.borrow().builtins();
+    for data in &file.values {
+        let value = reader.read_value(data, |name| {
+            return match builtins.get(name) {
+                Some(builtin) => Ok(builtin.to_object()),
+                None => Err(SessionError::UnknownBuiltin(name.to_string())),
+            };
+        })?;
+        reader.values.push(Rc::new(value));
+    }
+
+    for (class, data) in reader.classes.iter().zip(&file.classes) {
+        let constructor = match data.constructor {
+            Some(constructor) => Some(Rc::clone(reader.value(constructor)?)),
+            None => None,
+        };
+        let methods = reader.read_names(&data.methods)?;
+        let mut class = class.borrow_mut();
+        class.constructor = constructor;
+        class.methods = methods;
+    }
+    for (instance, data) in reader.instances.iter().zip(&file.instances) {
+        instance.borrow_mut().fields = reader.read_names(&data.fields)?;
+    }
+    for (generator, data) in reader.generators.iter().zip(&file.generators) {
+        *generator.borrow_mut() = reader.read_generator(data)?;
+    }
+    let mut bindings = Vec::new();
+    for (index, data) in file.scopes.iter().enumerate() {
+        let names = reader.read_names(&data.names)?;
+        let mut slots = Vec::new();
+        for (slot, value) in data.slots.iter().enumerate() {
+            if let Some(value) = value {
+                slots.push((slot, Rc::clone(reader.value(*value)?)));
+            }
+        }
+        bindings.push((Rc::clone(&reader.scopes[index]), names, slots));
+    }
+
+    // Everything checks out, so the top-level scope can change now.
+    for (scope, names, slots) in bindings {
+        let mut scope = scope.borrow_mut();
+        for (name, value) in names {
+            scope.set(name, value);
+        }
+        for (slot, value) in slots {
+            scope.set_local(slot, value);
+        }
+    }
+    *env.borrow().random().borrow_mut() = Random::new(file.random);
+    return Ok(());
+}
+
+enum Pending {
+    Scope(usize, Env),
+    Class(usize, ClassRef),
+    Instance(usize, InstanceRef),
+    Generator(usize, GeneratorRef),
+}
+
+/// Numbers what a session reaches. Tables are keyed by address, which stays
+/// put because everything numbered is reachable from the scope being saved.
+#[derive(Default)]
+struct Writer {
+    scopes: Vec<ScopeData>,
+    scope_ids: HashMap<*const RefCell<Environment>, usize>,
+    values: Vec<ValueData>,
+    value_ids: HashMap<*const Object, usize>,
+    classes: Vec<ClassData>,
+    class_ids: HashMap<*const RefCell<ClassObject>, usize>,
+    instances: Vec<InstanceData>,
+    instance_ids: HashMap<*const RefCell<InstanceObject>, usize>,
+    generators: Vec<GeneratorData>,
+    generator_ids: HashMap<*const RefCell<GeneratorState<Suspension, Rc<Object>>>, usize>,
+    /// Numbered but not written yet.
+    pending: Vec<Pending>,
+}
+
+impl Writer {
+    /// The number of `env`, whose scopes out are numbered before it. Only the
+    /// scope being saved can be outermost.
+    fn scope(&mut self, env: &Env) -> Result<usize, SessionError> {
+        if let Some(id) = self.scope_ids.get(&Rc::as_ptr(env)) {
+            return Ok(*id);
+        }
+        let outer = if self.scopes.is_empty() {
+            None
+        } else {
+            match env.borrow().outer() {
+                Some(outer) => Some(self.scope(&outer)?),
+                None => return Err(unsupported("a function defined in another session")),
+            }
+        };
+        let id = self.scopes.len();
+        self.scopes.push(ScopeData {
+            outer,
+            ..Default::default()
+        });
+        self.scope_ids.insert(Rc::as_ptr(env), id);
+        self.pending.push(Pending::Scope(id, Rc::clone(env)));
+        return Ok(id);
+    }
+
+    fn class(&mut self, class: &ClassRef) -> usize {
+        if let Some(id) = self.class_ids.get(&Rc::as_ptr(class)) {
+            return *id;
+        }
+        let id = self.classes.len();
+        self.classes.push(ClassData::default());
+        self.class_ids.insert(Rc::as_ptr(class), id);
+        self.pending.push(Pending::Class(id, Rc::clone(class)));
+        return id;
+    }
+
+    fn instance(&mut self, instance: &InstanceRef) -> usize {
+        if let Some(id) = self.instance_ids.get(&Rc::as_ptr(instance)) {
+            return *id;
+        }
+        let id = self.instances.len();
+        self.instances.push(InstanceData::default());
+        self.instance_ids.insert(Rc::as_ptr(instance), id);
+        self.pending
+            .push(Pending::Instance(id, Rc::clone(instance)));
+        return id;
+    }
+
+    fn generator(&mut self, generator: &GeneratorRef) -> usize {
+        if let Some(id) = self.generator_ids.get(&Rc::as_ptr(generator)) {
+            return *id;
+        }
+        let id = self.generators.len();
+        self.generators.push(GeneratorData::Done);
+        self.generator_ids.insert(Rc::as_ptr(generator), id);
+        self.pending
+            .push(Pending::Generator(id, Rc::clone(generator)));
+        return id;
+    }
+
+    /// The number of `root`, numbering the values it holds first. Nested
+    /// arrays can be deeper than the native stack, so this keeps its own.
+    fn value(&mut self, root: &Rc<Object>) -> Result<usize, SessionError> {
+        let mut stack = vec![(Rc::clone(root), false)];
+        while let Some((value, children_numbered)) = stack.pop() {
+            if self.value_ids.contains_key(&Rc::as_ptr(&value)) {
+                continue;
+            }
+            if !children_numbered {
+                stack.push((Rc::clone(&value), true));
+                let ids = &self.value_ids;
+                for_each_child(&value, |child| {
+                    if !ids.contains_key(&Rc::as_ptr(child)) {
+                        stack.push((Rc::clone(child), false));
+                    }
+                });
+                continue;
+            }
+            let data = self.value_data(&value)?;
+            self.value_ids.insert(Rc::as_ptr(&value), self.values.len());
+            self.values.push(data);
+        }
+        return Ok(self.value_ids[&Rc::as_ptr(root)]);
+    }
+
+    /// An already numbered value.
+    fn id(&self, value: &Rc<Object>) -> usize {
+        return self.value_ids[&Rc::as_ptr(value)];
+    }
+
+    /// `value`, whose children are numbered.
+    fn value_data(&mut self, value: &Object) -> Result<ValueData, SessionError> {
+        let data = match value {
+            Object::Integer(value) => ValueData::Integer {
+                value: *value,
+            },
+            Object::BigInt(value) => ValueData::BigInt {
+                digits: value.to_string(),
+            },
+            Object::Float(value) => ValueData::Float {
+                value: value.to_string(),
+            },
+            Object::Boolean(value) => ValueData::Boolean {
+                value: *value,
+            },
+            Object::String(value) => ValueData::String {
+                value: value.clone(),
+            },
+            Object::Null => ValueData::Null,
+            Object::Error(message) => ValueData::Error {
+                message: message.clone(),
+            },
+            Object::Array(elements) => ValueData::Array {
+                elements: elements.iter().map(|element| self.id(element)).collect(),
+            },
+            Object::Hash(map) => ValueData::Hash {
+                entries: map
+                    .iter()
+                    .map(|(key, value)| (self.id(key), self.id(value)))
+                    .collect(),
+            },
+            Object::Function(params, body, env) => {
+                ValueData::Function(self.function_data(params, body, env)?)
+            }
+            Object::GeneratorFunction(params, body, env) => {
+                ValueData::GeneratorFunction(self.function_data(params, body, env)?)
+            }
+            Object::Builtin(function) => {
+                match BuiltIns
+                    .iter()
+                    .find(|definition| std::ptr::fn_addr_eq(definition.function, *function))
+                {
+                    Some(definition) => ValueData::Builtin {
+                        name: definition.name.to_string(),
+                    },
+                    None => return Err(unsupported("a builtin outside the standard library")),
+                }
+            }
+            Object::Native(native) => ValueData::Builtin {
+                name: native.name.clone(),
+            },
+            Object::Class(class) => ValueData::Class {
+                class: self.class(class),
+            },
+            Object::Instance(instance) => ValueData::Instance {
+                instance: self.instance(instance),
+            },
+            Object::BoundMethod(method) => ValueData::BoundMethod {
+                instance: self.instance(&method.receiver),
+                method: self.id(&method.method),
+                name: method.name.clone(),
+            },
+            Object::BoundBuiltin(method) => ValueData::BoundBuiltin {
+                receiver: self.id(&method.receiver),
+                name: method.name.to_string(),
+            },
+            Object::Iterator(iter) => match &**iter {
+                Iter::Range(start, end, step) => ValueData::Range {
+                    start: *start,
+                    end: *end,
+                    step: *step,
+                },
+                Iter::Take(source, count) => ValueData::Take {
+                    source: self.id(source),
+                    count: *count,
+                },
+                Iter::Skip(source, count) => ValueData::Skip {
+                    source: self.id(source),
+                    count: *count,
+                },
+                Iter::Zip(left, right) => ValueData::Zip {
+                    left: self.id(left),
+                    right: self.id(right),
+                },
+                Iter::Enumerate(source) => ValueData::Enumerate {
+                    source: self.id(source),
+                },
+            },
+            Object::Generator(generator) => ValueData::Generator {
+                generator: self.generator(generator),
+            },
+            Object::ReturnValue(_) | Object::CompiledFunction(_) | Object::ClosureObj(_) => {
+                return Err(unsupported("a value of the compiler's VM"))
+            }
+        };
+        return Ok(data);
+    }
+
+    fn function_data(
+        &mut self,
+        params: &[IDENTIFIER],
+        body: &BlockStatement,
+        env: &Env,
+    ) -> Result<FunctionData, SessionError> {
+        let mut resolutions = Vec::new();
+        for_each_resolution(params, body, &mut |resolution| {
+            resolutions.push(resolution.bindings().to_vec());
+        });
+        return Ok(FunctionData {
+            params: params.to_vec(),
+            body: body.clone(),
+            resolutions,
+            scope: self.scope(env)?,
+        });
+    }
+
+    fn names(
+        &mut self,
+        names: &HashMap<String, Rc<Object>>,
+    ) -> Result<BTreeMap<String, usize>, SessionError> {
+        let mut ids = BTreeMap::new();
+        for (name, value) in names {
+            ids.insert(name.clone(), self.value(value)?);
+        }
+        return Ok(ids);
+    }
+
+    fn write(&mut self, pending: Pending) -> Result<(), SessionError> {
+        match pending {
+            Pending::Scope(id, env) => {
+                let env = env.borrow();
+                for (name, value) in env.bindings() {
+                    let value = self.value(value)?;
+                    self.scopes[id].names.insert(name.clone(), value);
+                }
+                for slot in env.slots() {
+                    let value = match slot {
+                        Some(value) => Some(self.value(value)?),
+                        None => None,
+                    };
+                    self.scopes[id].slots.push(value);
+                }
+            }
+            Pending::Class(id, class) => {
+                let class = class.borrow();
+                let constructor = match &class.constructor {
+                    Some(constructor) => Some(self.value(constructor)?),
+                    None => None,
+                };
+                self.classes[id] = ClassData {
+                    name: class.name.clone(),
+                    constructor,
+                    methods: self.names(&class.methods)?,
+                };
+            }
+            Pending::Instance(id, instance) => {
+                let instance = instance.borrow();
+                self.instances[id] = InstanceData {
+                    class: self.class(&instance.class),
+                    fields: self.names(&instance.fields)?,
+                };
+            }
+            Pending::Generator(id, generator) => {
+                self.generators[id] = match &*generator.borrow() {
+                    GeneratorState::Suspended(suspension) => {
+                        GeneratorData::Suspended(self.suspension(suspension)?)
+                    }
+                    GeneratorState::Delegating(suspension, inner) => GeneratorData::Delegating {
+                        suspension: self.suspension(suspension)?,
+                        inner: self.value(inner)?,
+                    },
+                    GeneratorState::Forwarding(inner) => GeneratorData::Forwarding {
+                        inner: self.value(inner)?,
+                    },
+                    GeneratorState::Running => {
+                        return Err(unsupported("a generator in the middle of a next call"))
+                    }
+                    GeneratorState::Done => GeneratorData::Done,
+                };
+            }
+        }
+        return Ok(());
+    }
+
+    fn suspension(&mut self, suspension: &Suspension) -> Result<SuspensionData, SessionError> {
+        match suspension {
+            Suspension::Body {
+                function,
+                env,
+                resume_at,
+            } => {
+                return Ok(SuspensionData {
+                    function: self.value(function)?,
+                    scope: self.scope(env)?,
+                    resume_at: resume_at.clone(),
+                })
+            }
+            Suspension::Frame {
+                ..
+            } => return Err(unsupported("a generator of the compiler's VM")),
+        }
+    }
+}
+
+/// The values `value` holds directly. Functions, classes, instances and
+/// generators hold theirs through a table of their own.
+fn for_each_child(value: &Object, mut visit: impl FnMut(&Rc<Object>)) {
+    match value {
+        Object::Array(elements) => elements.iter().for_each(visit),
+        Object::Hash(map) => {
+            for (key, value) in map.iter() {
+                visit(key);
+                visit(value);
+            }
+        }
+        Object::BoundMethod(method) => visit(&method.method),
+        Object::BoundBuiltin(method) => visit(&method.receiver),
+        Object::Iterator(iter) => iter.for_each_source(visit),
+        _ => {}
+    }
+}
+
+/// What loading has made so far.
+struct Reader {
+    scopes: Vec<Env>,
+    classes: Vec<ClassRef>,
+    instances: Vec<InstanceRef>,
+    generators: Vec<GeneratorRef>,
+    values: Vec<Rc<Object>>,
+}
+
+impl Reader {
+    fn value(&self, id: usize) -> Result<&Rc<Object>, SessionError> {
+        return lookup(&self.values, id, "value");
+    }
+
+    /// The value `data` describes, given the values before it.
+    fn read_value(
+        &self,
+        data: &ValueData,
+        builtin: impl Fn(&str) -> Result<Object, SessionError>,
+    ) -> Result<Object, SessionError> {
+        let value = match data {
+            ValueData::Integer {
+                value,
+            } => Object::Integer(*value),
+            ValueData::BigInt {
+                digits,
+            } => match digits.parse::<BigInt>() {
+                Ok(value) => Object::BigInt(value),
+                Err(_) => return Err(malformed(format!("bad integer {}", digits))),
+            },
+            ValueData::Float {
+                value,
+            } => match value.parse::<f64>() {
+                Ok(value) => Object::Float(value),
+                Err(_) => return Err(malformed(format!("bad float {}", value))),
+            },
+            ValueData::Boolean {
+                value,
+            } => Object::Boolean(*value),
+            ValueData::String {
+                value,
+            } => Object::String(value.clone()),
+            ValueData::Null => Object::Null,
+            ValueData::Error {
+                message,
+            } => Object::Error(message.clone()),
+            ValueData::Array {
+                elements,
+            } => {
+                let mut array = Vector::new();
+                for element in elements {
+                    array.push_back(Rc::clone(self.value(*element)?));
+                }
+                Object::Array(array)
+            }
+            ValueData::Hash {
+                entries,
+            } => {
+                let mut map = OrderedMap::new();
+                for (key, value) in entries {
+                    map.insert(Rc::clone(self.value(*key)?), Rc::clone(self.value(*value)?));
+                }
+                Object::Hash(map)
+            }
+            ValueData::Function(function) => {
+                let (params, body, env) = self.read_function(function)?;
+                Object::Function(params, body, env)
+            }
+            ValueData::GeneratorFunction(function) => {
+                let (params, body, env) = self.read_function(function)?;
+                Object::GeneratorFunction(params, body, env)
+            }
+            ValueData::Builtin {
+                name,
+            } => builtin(name)?,
+            ValueData::Class {
+                class,
+            } => Object::Class(Rc::clone(lookup(&self.classes, *class, "class")?)),
+            ValueData::Instance {
+                instance,
+            } => Object::Instance(Rc::clone(lookup(&self.instances, *instance, "instance")?)),
+            ValueData::BoundMethod {
+                instance,
+                method,
+                name,
+            } => Object::BoundMethod(Rc::new(BoundMethodObject {
+                receiver: Rc::clone(lookup(&self.instances, *instance, "instance")?),
+                method: Rc::clone(self.value(*method)?),
+                name: name.clone(),
+            })),
+            ValueData::BoundBuiltin {
+                receiver,
+                name,
+            } => match builtin_method(self.value(*receiver)?, name) {
+                Some(method) => return Ok((*method).clone()),
+                None => return Err(malformed(format!("no method {} on value {}", name, receiver))),
+            },
+            ValueData::Range {
+                start,
+                end,
+                step,
+            } => {
+                if *step == 0 {
+                    return Err(malformed("range with a step of 0"));
+                }
+                Object::Iterator(Rc::new(Iter::Range(*start, *end, *step)))
+            }
+            ValueData::Take {
+                source,
+                count,
+            } => self.iterator(Iter::Take(*source, *count))?,
+            ValueData::Skip {
+                source,
+                count,
+            } => self.iterator(Iter::Skip(*source, *count))?,
+            ValueData::Zip {
+                left,
+                right,
+            } => self.iterator(Iter::Zip(*left, *right))?,
+            ValueData::Enumerate {
+                source,
+            } => self.iterator(Iter::Enumerate(*source))?,
+            ValueData::Generator {
+                generator,
+            } => Object::Generator(Rc::clone(lookup(&self.generators, *generator, "generator")?)),
+        };
+        return Ok(value);
+    }
+
+    /// An iterator over the sources numbered in `iter`, which must be arrays
+    /// or iterators.
+    fn iterator(&self, iter: Iter<usize>) -> Result<Object, SessionError> {
+        let mut sources = Vec::new();
+        iter.for_each_source(|source| sources.push(*source));
+        for source in sources {
+            if !matches!(&**self.value(source)?, Object::Array(_) | Object::Iterator(_)) {
+                return Err(malformed(format!("value {} can not be iterated", source)));
+            }
+        }
+        let iter = iter.map_sources(|source| Rc::clone(&self.values[*source]));
+        return Ok(Object::Iterator(Rc::new(iter)));
+    }
+
+    fn read_function(
+        &self,
+        function: &FunctionData,
+    ) -> Result<(Vec<IDENTIFIER>, BlockStatement, Env), SessionError> {
+        let mut resolutions = Vec::new();
+        for_each_resolution(&function.params, &function.body, &mut |resolution| {
+            resolutions.push(resolution);
+        });
+        if resolutions.len() != function.resolutions.len() {
+            return Err(malformed("a function's resolutions do not match its body"));
+        }
+        for (resolution, bindings) in resolutions.into_iter().zip(&function.resolutions) {
+            resolution.set(bindings.clone());
+        }
+        let env = Rc::clone(lookup(&self.scopes, function.scope, "scope")?);
+        return Ok((function.params.clone(), function.body.clone(), env));
+    }
+
+    fn read_names(
+        &self,
+        names: &BTreeMap<String, usize>,
+    ) -> Result<HashMap<String, Rc<Object>>, SessionError> {
+        let mut values = HashMap::new();
+        for (name, id) in names {
+            values.insert(name.clone(), Rc::clone(self.value(*id)?));
+        }
+        return Ok(values);
+    }
+
+    fn read_generator(
+        &self,
+        data: &GeneratorData,
+    ) -> Result<GeneratorState<Suspension, Rc<Object>>, SessionError> {
+        match data {
+            GeneratorData::Suspended(suspension) => {
+                return Ok(GeneratorState::Suspended(self.read_suspension(suspension)?))
+            }
+            GeneratorData::Delegating {
+                suspension,
+                inner,
+            } => {
+                return Ok(GeneratorState::Delegating(
+                    self.read_suspension(suspension)?,
+                    self.read_inner(*inner)?,
+                ))
+            }
+            GeneratorData::Forwarding {
+                inner,
+            } => return Ok(GeneratorState::Forwarding(self.read_inner(*inner)?)),
+            GeneratorData::Done => return Ok(GeneratorState::Done),
+        }
+    }
+
+    fn read_suspension(&self, suspension: &SuspensionData) -> Result<Suspension, SessionError> {
+        let function = self.value(suspension.function)?;
+        if !matches!(&**function, Object::GeneratorFunction(..)) {
+            return Err(malformed(format!(
+                "value {} is not a generator function",
+                suspension.function
+            )));
+        }
+        return Ok(Suspension::Body {
+            function: Rc::clone(function),
+            env: Rc::clone(lookup(&self.scopes, suspension.scope, "scope")?),
+            resume_at: suspension.resume_at.clone(),
+        });
+    }
+
+    fn read_inner(&self, inner: usize) -> Result<Rc<Object>, SessionError> {
+        let value = self.value(inner)?;
+        if !matches!(&**value, Object::Generator(_)) {
+            return Err(malformed(format!("value {} is not a generator", inner)));
+        }
+        return Ok(Rc::clone(value));
+    }
+}
+
+fn lookup<'a, T>(table: &'a [T], id: usize, what: &str) -> Result<&'a T, SessionError> {
+    match table.get(id) {
+        Some(item) => return Ok(item),
+        None => return Err(malformed(format!("no {} {}", what, id))),
+    }
+}
+
+fn malformed(message: impl Into<String>) -> SessionError {
+    return SessionError::Malformed(message.into());
+}
+
+fn unsupported(what: &str) -> SessionError {
+    return SessionError::Unsupported(what.to_string());
+}
+
+/// Visits every resolution in a function's parameters and body, always in
+/// the same order.
+fn for_each_resolution<'a>(
+    params: &'a [IDENTIFIER],
+    body: &'a BlockStatement,
+    visit: &mut dyn FnMut(&'a Resolution),
+) {
+    for param in params {
+        visit(&param.resolution);
+    }
+    visit_block(body, visit);
+}
+
+fn visit_block<'a>(block: &'a BlockStatement, visit: &mut dyn FnMut(&'a Resolution)) {
+    for statement in &block.body {
+        visit_statement(statement, visit);
+    }
+}
+
+fn visit_statement<'a>(statement: &'a Statement, visit: &mut dyn FnMut(&'a Resolution)) {
+    match statement {
+        Statement::Let(statement) => {
+            visit(&statement.resolution);
+            visit_expression(&statement.expr, visit);
+        }
+        Statement::Return(statement) => visit_expression(&statement.argument, visit),
+        Statement::Class(class) => {
+            visit(&class.name.resolution);
+            for method in &class.methods {
+                for_each_resolution(&method.params, &method.body, visit);
+            }
+        }
+        Statement::SetProperty(statement) => {
+            visit_expression(&statement.object, visit);
+            visit_expression(&statement.value, visit);
+        }
+        Statement::Expr(expression) => visit_expression(expression, visit),
+    }
+}
+
+fn visit_expression<'a>(expression: &'a Expression, visit: &mut dyn FnMut(&'a Resolution)) {
+    match expression {
+        Expression::IDENTIFIER(identifier) => visit(&identifier.resolution),
+        Expression::LITERAL(Literal::Array(array)) => {
+            for element in &array.elements {
+                visit_expression(element, visit);
+            }
+        }
+        Expression::LITERAL(Literal::Hash(hash)) => {
+            for (key, value) in &hash.elements {
+                visit_expression(key, visit);
+                visit_expression(value, visit);
+            }
+        }
+        Expression::LITERAL(_) => {}
+        Expression::PREFIX(expression) => visit_expression(&expression.operand, visit),
+        Expression::INFIX(expression) => {
+            visit_expression(&expression.left, visit);
+            visit_expression(&expression.right, visit);
+        }
+        Expression::IF(expression) => {
+            visit_expression(&expression.condition, visit);
+            visit_block(&expression.consequent, visit);
+            if let Some(alternate) = &expression.alternate {
+                visit_block(alternate, visit);
+            }
+        }
+        Expression::FUNCTION(function) => {
+            for_each_resolution(&function.params, &function.body, visit)
+        }
+        Expression::FunctionCall(call) => {
+            visit_expression(&call.callee, visit);
+            for argument in &call.arguments {
+                visit_expression(argument, visit);
+            }
+        }
+        Expression::Index(index) => {
+            visit_expression(&index.object, visit);
+            visit_expression(&index.index, visit);
+        }
+        Expression::This(this) => visit(&this.resolution),
+        Expression::Property(property) => visit_expression(&property.object, visit),
+        Expression::New(expression) => {
+            visit(&expression.callee.resolution);
+            for argument in &expression.arguments {
+                visit_expression(argument, visit);
+            }
+        }
+        Expression::Template(template) => {
+            for expression in &template.expressions {
+                visit_expression(expression, visit);
+            }
+        }
+        Expression::Yield(expression) => {
+            if let Some(argument) = &expression.argument {
+                visit_expression(argument, visit);
+            }
+        }
+    }
+}
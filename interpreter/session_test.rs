@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use object::environment::*;
+    use parser::*;
+
+    use crate::eval;
+    use crate::session::{load_session, save_session, SessionError, SESSION_VERSION};
+
+    fn run(env: &Env, input: &str) -> String {
+        match parse(input) {
+            Ok(node) => match eval(node, env) {
+                Ok(evaluated) => return format!("{}", evaluated),
+                Err(e) => return e.to_string(),
+            },
+            Err(e) => panic!("parse error: {}", e[0]),
+        }
+    }
+
+    /// Runs `setup` in one session, then each check in a session loaded from
+    /// what the first one saved.
+    fn apply_test(setup: &str, checks: &[(&str, &str)]) {
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        run(&env, setup);
+        let json = save_session(&env).unwrap();
+
+        let resumed: Env = Rc::new(RefCell::new(Default::default()));
+        load_session(&json, &resumed).unwrap();
+        for (input, expected) in checks {
+            assert_eq!(&run(&resumed, input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn values_and_closures_survive_a_reload() {
+        apply_test(
+            "let n = 2; let xs = [1, 2.5, \"three\", true, if (false) { 1 }, {\"k\": [n]}]; let big = 9223372036854775807 + 1; \
+             let adder = fn(a) { fn(b) { a + b + n } }; let add3 = adder(1); \
+             let fib = fn(x) { if (x < 2) { x } else { fib(x - 1) + fib(x - 2) } }; let f = len;",
+            &[
+                ("xs", "[1, 2.5, three, true, null, [k: [2]]]"),
+                ("big", "9223372036854775808"),
+                ("add3(4)", "7"),
+                ("fib(15)", "610"),
+                ("f(xs)", "6"),
+                ("let n = 10; add3(4)", "15"),
+            ],
+        );
+    }
+
+    #[test]
+    fn shared_values_and_cycles_stay_shared() {
+        apply_test(
+            "class Node { constructor(v) { this.v = v; this.next = 0; } value() { this.v } } \
+             let a = new Node(1); let b = new Node(2); a.next = b; b.next = a; \
+             let get = a.value; let pair = [a, a];",
+            &[
+                ("a.next.next.v", "1"),
+                ("b.v = 5; a.next.v", "5"),
+                ("pair[0].v = 7; [pair[1].v, get(), instance_of(b, Node)]", "[7, 7, true]"),
+                ("new Node(3).value()", "3"),
+            ],
+        );
+    }
+
+    #[test]
+    fn generators_resume_where_they_were() {
+        apply_test(
+            "let count = fn*(n) { yield n; yield n + 1; n + 2 }; let g = count(1); next(g); \
+             let outer = fn*() { yield* count(10); yield 0; }; let h = outer(); next(h); \
+             let it = take(range(0, 10), 3);",
+            &[
+                (
+                    "[next(g), next(g), next(g)]",
+                    "[[value: 2, done: false], [value: 3, done: true], [value: null, done: true]]",
+                ),
+                ("[next(h)[\"value\"], next(h)[\"value\"], next(h)[\"value\"]]", "[11, 0, null]"),
+                ("to_array(it)", "[0, 1, 2]"),
+            ],
+        );
+    }
+
+    #[test]
+    fn random_carries_on_from_where_it_was() {
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        run(&env, "seed(42); random(100)");
+        let json = save_session(&env).unwrap();
+        let expected = run(&env, "[random(100), random(100)]");
+
+        let resumed: Env = Rc::new(RefCell::new(Default::default()));
+        load_session(&json, &resumed).unwrap();
+        assert_eq!(run(&resumed, "[random(100), random(100)]"), expected);
+    }
+
+    #[test]
+    fn bad_sessions_are_rejected_without_binding_anything() {
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        run(&env, "let xs = [1, 2];");
+        let json = save_session(&env).unwrap();
+
+        let resumed: Env = Rc::new(RefCell::new(Default::default()));
+        let bad = json.replacen("\"xs\": 2", "\"xs\": 9", 1);
+        assert_eq!(
+            load_session(&bad, &resumed),
+            Err(SessionError::Malformed("no value 9".to_string()))
+        );
+        assert_eq!(run(&resumed, "xs"), "undefined variable 'xs'");
+
+        let newer = json.replacen(
+            &format!("\"version\": {}", SESSION_VERSION),
+            &format!("\"version\": {}", SESSION_VERSION + 1),
+            1,
+        );
+        assert_eq!(
+            load_session(&newer, &resumed),
+            Err(SessionError::UnsupportedVersion {
+                found: SESSION_VERSION + 1,
+                expected: SESSION_VERSION,
+            })
+        );
+    }
+
+    #[test]
+    fn running_generators_can_not_be_saved() {
+        let env: Env = Rc::new(RefCell::new(Default::default()));
+        run(&env, "let g = fn*() { yield 1; }; let it = g();");
+        // Nothing can save a session from inside `next`, so set the state by hand.
+        let it = env.borrow().get("it").unwrap();
+        if let object::Object::Generator(generator) = &*it {
+            *generator.borrow_mut() = object::generators::GeneratorState::Running;
+        }
+        assert_eq!(
+            save_session(&env),
+            Err(SessionError::Unsupported("a generator in the middle of a next call".to_string()))
+        );
+    }
+}
@@ -70,6 +70,26 @@ mod tests {
         test_lexer_common("hash", r#"{"one": 1, "two": 2, "three": 3}"#);
     }
 
+    #[test]
+    fn test_lexer_type_annotation() {
+        test_lexer_common("type_annotation", "let x: int | null = 5");
+    }
+
+    #[test]
+    fn test_lexer_float() {
+        test_lexer_common("float", "let pi = 3.25; 1.len");
+    }
+
+    #[test]
+    fn test_lexer_bigint() {
+        test_lexer_common("bigint", "9223372036854775807 + 9223372036854775808");
+    }
+
+    #[test]
+    fn test_lexer_template() {
+        test_lexer_common("template", "`a${x}b${ {\"k\": `${1}`}[\"k\"] }c` + `plain`");
+    }
+
     #[test]
     fn test_lexer_bool() {
         test_lexer_common("bool", "let y=true");
@@ -181,4 +201,23 @@ if (5 < 10) {
             );
         }
     }
+
+    #[test]
+    fn generator_tokens() {
+        let mut lexer = Lexer::new("fn*() { yield yielded; }");
+        let kinds = test_token_set(&mut lexer)
+            .into_iter()
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+
+        assert_eq!(kinds[0], TokenKind::FUNCTION);
+        assert_eq!(kinds[1], TokenKind::ASTERISK);
+        assert_eq!(kinds[5], TokenKind::YIELD);
+        assert_eq!(
+            kinds[6],
+            TokenKind::IDENTIFIER {
+                name: "yielded".to_string()
+            }
+        );
+    }
 }
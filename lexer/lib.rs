@@ -1,4 +1,4 @@
-use crate::token::{lookup_identifier, Span, Token, TokenKind};
+use crate::token::{lookup_identifier, Float64, Span, Token, TokenKind};
 
 mod lexer_test;
 pub mod token;
@@ -8,6 +8,9 @@ pub struct Lexer<'a> {
     position: usize,
     read_position: usize,
     ch: char,
+    // One entry per open `${` substitution, counting the `{` opened inside it
+    // so the matching `}` is known to resume the template text.
+    template_braces: Vec<usize>,
 }
 
 impl<'a> Lexer<'a> {
@@ -17,6 +20,7 @@ impl<'a> Lexer<'a> {
             position: 0,
             read_position: 0,
             ch: '\0',
+            template_braces: Vec::new(),
         };
 
         l.read_char();
@@ -85,11 +89,38 @@ impl<'a> Lexer<'a> {
             '/' => TokenKind::SLASH,
             '<' => TokenKind::LT,
             '>' => TokenKind::GT,
-            '{' => TokenKind::LBRACE,
-            '}' => TokenKind::RBRACE,
+            '{' => {
+                if let Some(depth) = self.template_braces.last_mut() {
+                    *depth += 1;
+                }
+                TokenKind::LBRACE
+            }
+            '}' => {
+                if self.template_braces.last() == Some(&0) {
+                    self.template_braces.pop();
+                    let (end, text, head) = self.read_template_text();
+                    let kind = if head {
+                        TokenKind::TemplateMiddle(text)
+                    } else {
+                        TokenKind::TemplateTail(text)
+                    };
+                    return Token {
+                        span: Span {
+                            start,
+                            end,
+                        },
+                        kind,
+                    };
+                }
+                if let Some(depth) = self.template_braces.last_mut() {
+                    *depth -= 1;
+                }
+                TokenKind::RBRACE
+            }
             '[' => TokenKind::LBRACKET,
             ':' => TokenKind::COLON,
             '.' => TokenKind::DOT,
+            '|' => TokenKind::PIPE,
             ']' => TokenKind::RBRACKET,
             '"' => {
                 let (start, end, string) = self.read_string();
@@ -101,6 +132,21 @@ impl<'a> Lexer<'a> {
                     kind: TokenKind::STRING(string),
                 };
             }
+            '`' => {
+                let (end, text, head) = self.read_template_text();
+                let kind = if head {
+                    TokenKind::TemplateHead(text)
+                } else {
+                    TokenKind::TemplateString(text)
+                };
+                return Token {
+                    span: Span {
+                        start,
+                        end,
+                    },
+                    kind,
+                };
+            }
             _ => {
                 if is_letter(self.ch) {
                     let (start, end, identifier) = self.read_identifier();
@@ -112,13 +158,13 @@ impl<'a> Lexer<'a> {
                         kind: lookup_identifier(&identifier),
                     };
                 } else if is_digit(self.ch) {
-                    let (start, end, num) = self.read_number();
+                    let (start, end, kind) = self.read_number();
                     return Token {
                         span: Span {
                             start,
                             end,
                         },
-                        kind: TokenKind::INT(num),
+                        kind,
                     };
                 } else {
                     TokenKind::ILLEGAL
@@ -181,15 +227,29 @@ impl<'a> Lexer<'a> {
         return (pos, self.position, x);
     }
 
-    fn read_number(&mut self) -> (usize, usize, i64) {
+    fn read_number(&mut self) -> (usize, usize, TokenKind) {
         let pos = self.position;
         while is_digit(self.ch) {
             self.read_char();
         }
 
-        let x = self.input[pos..self.position].parse().unwrap();
+        // `1.5` is a float, but `1.len` stays an integer followed by a dot
+        if self.ch == '.' && is_digit(self.peek_char()) {
+            self.read_char();
+            while is_digit(self.ch) {
+                self.read_char();
+            }
+            let x = self.input[pos..self.position].parse().unwrap();
+            return (pos, self.position, TokenKind::FLOAT(Float64(x)));
+        }
+
+        let digits = &self.input[pos..self.position];
+        let kind = match digits.parse() {
+            Ok(x) => TokenKind::INT(x),
+            Err(_) => TokenKind::BIGINT(digits.to_string()),
+        };
 
-        return (pos, self.position, x);
+        return (pos, self.position, kind);
     }
 
     fn read_string(&mut self) -> (usize, usize, String) {
@@ -209,6 +269,33 @@ impl<'a> Lexer<'a> {
         }
         return (pos - 1, self.position, x);
     }
+
+    /// Reads template text after the opening `` ` `` or a substitution's
+    /// closing `}`, up to and including the `${` or `` ` `` that ends it.
+    /// Returns the end offset, the text, and whether a substitution follows.
+    fn read_template_text(&mut self) -> (usize, String, bool) {
+        let pos = self.position + 1;
+        loop {
+            self.read_char();
+            if self.ch == '`' || self.ch == '\u{0}' || (self.ch == '$' && self.peek_char() == '{') {
+                break;
+            }
+        }
+
+        let x = self.input[pos..self.position].to_string();
+
+        if self.ch == '$' {
+            self.read_char();
+            self.read_char();
+            self.template_braces.push(0);
+            return (self.position, x, true);
+        }
+        // consume the closing `
+        if self.ch == '`' {
+            self.read_char();
+        }
+        return (self.position, x, false);
+    }
 }
 
 fn is_letter(c: char) -> bool {
@@ -29,7 +29,16 @@ pub enum TokenKind {
     // Identifiers + literals
     IDENTIFIER { name: String },
     INT(i64),
+    BIGINT(String), // integer literal too wide for i64, kept as its digits
+    FLOAT(Float64),
     STRING(String),
+    // Template literal pieces, split at each `${ ... }` substitution:
+    // `a` is a TemplateString, `a${ is a TemplateHead, }b${ a TemplateMiddle
+    // and }b` a TemplateTail.
+    TemplateString(String),
+    TemplateHead(String),
+    TemplateMiddle(String),
+    TemplateTail(String),
 
     // Operators
     ASSIGN,   // =
@@ -50,6 +59,7 @@ pub enum TokenKind {
     SEMICOLON,
     COLON,
     DOT,
+    PIPE, // | (type unions)
 
     LPAREN,
     RPAREN,
@@ -69,6 +79,7 @@ pub enum TokenKind {
     CLASS,
     THIS,
     NEW,
+    YIELD,
 }
 
 pub fn lookup_identifier(identifier: &str) -> TokenKind {
@@ -83,6 +94,7 @@ pub fn lookup_identifier(identifier: &str) -> TokenKind {
         "class" => TokenKind::CLASS,
         "this" => TokenKind::THIS,
         "new" => TokenKind::NEW,
+        "yield" => TokenKind::YIELD,
         _ => TokenKind::IDENTIFIER {
             name: identifier.to_string(),
         },
@@ -96,7 +108,13 @@ impl fmt::Display for TokenKind {
                 name,
             } => write!(f, "{}", name),
             TokenKind::INT(i) => write!(f, "{}", i),
+            TokenKind::BIGINT(digits) => write!(f, "{}", digits),
+            TokenKind::FLOAT(x) => write!(f, "{}", x),
             TokenKind::STRING(s) => write!(f, "{}", s),
+            TokenKind::TemplateString(s) => write!(f, "`{}`", s),
+            TokenKind::TemplateHead(s) => write!(f, "`{}${{", s),
+            TokenKind::TemplateMiddle(s) => write!(f, "}}{}${{", s),
+            TokenKind::TemplateTail(s) => write!(f, "}}{}`", s),
             TokenKind::ASSIGN => write!(f, "="),
             TokenKind::PLUS => write!(f, "+"),
             TokenKind::MINUS => write!(f, "-"),
@@ -126,9 +144,50 @@ impl fmt::Display for TokenKind {
             TokenKind::EOF => write!(f, "EOF"),
             TokenKind::COLON => write!(f, ":"),
             TokenKind::DOT => write!(f, "."),
+            TokenKind::PIPE => write!(f, "|"),
             TokenKind::CLASS => write!(f, "class"),
             TokenKind::THIS => write!(f, "this"),
             TokenKind::NEW => write!(f, "new"),
+            TokenKind::YIELD => write!(f, "yield"),
         }
     }
 }
+
+/// An `f64` that can live inside the `Eq`/`Hash`/`Ord` token and AST types.
+/// Equality and hashing are bitwise and ordering is `f64::total_cmp`, so this
+/// identifies a literal's spelling rather than implementing IEEE comparison.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Float64(pub f64);
+
+impl PartialEq for Float64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for Float64 {}
+
+impl std::hash::Hash for Float64 {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl PartialOrd for Float64 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Float64 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl fmt::Display for Float64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
@@ -1,4 +1,16 @@
-use crate::{BuiltinFunc, Object};
+use crate::hashes::{self, HashArg, HashResult};
+use crate::higher_order::ObjectValues;
+use crate::integer::Int;
+use crate::iterators::{self, Iter, IterHost, IterSource};
+use crate::json::{self, Json, JsonArg, JsonView};
+use crate::math::{self, NumArg, NumResult};
+use crate::methods::{self, Receiver};
+use crate::random::{self, Random};
+use crate::reflect::{self, ReflectArg, ReflectResult, TypeOf};
+use crate::registry::Arity;
+use crate::strings::{self, TextArg, TextResult};
+use crate::{BoundBuiltinObject, BuiltinFunc, Object};
+use std::fmt;
 use std::rc::Rc;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -9,6 +21,94 @@ pub enum BuiltinId {
     Last,
     Rest,
     Push,
+    Split,
+    Join,
+    Trim,
+    Upper,
+    Lower,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Replace,
+    Substr,
+    IndexOf,
+    Chars,
+    Str,
+    Int,
+    Map,
+    Filter,
+    Reduce,
+    Sort,
+    Find,
+    Any,
+    All,
+    Keys,
+    Values,
+    Entries,
+    Has,
+    Delete,
+    Merge,
+    Abs,
+    Min,
+    Max,
+    Pow,
+    Sqrt,
+    Floor,
+    Ceil,
+    Round,
+    Random,
+    Seed,
+    JsonEncode,
+    JsonDecode,
+    TypeOf,
+    InstanceOf,
+    Fields,
+    Methods,
+    Range,
+    Take,
+    Zip,
+    Enumerate,
+    ToArray,
+    Next,
+}
+
+/// Bad arguments to a builtin. Backends report these the way they report any
+/// other builtin failure: an `Error` value in the interpreter and VMs, a
+/// terminating runtime error in asm.
+#[derive(Debug, PartialEq)]
+pub enum BuiltinError {
+    Arity(String),
+    Type(String),
+}
+
+impl BuiltinError {
+    pub fn message(&self) -> &str {
+        match self {
+            BuiltinError::Arity(message) | BuiltinError::Type(message) => return message,
+        }
+    }
+}
+
+pub(crate) fn check_arity(
+    name: &str,
+    min: usize,
+    max: usize,
+    got: usize,
+) -> Result<(), BuiltinError> {
+    if got >= min && got <= max {
+        return Ok(());
+    }
+    let expected = if min == max {
+        format!("{} argument{}", min, if min == 1 { "" } else { "s" })
+    } else if max == usize::MAX {
+        format!("at least {} argument{}", min, if min == 1 { "" } else { "s" })
+    } else {
+        format!("{} or {} arguments", min, max)
+    };
+    return Err(BuiltinError::Arity(format!(
+        "builtin {} expected {}, got {}",
+        name, expected, got
+    )));
 }
 
 #[derive(Clone, Copy)]
@@ -16,44 +116,421 @@ pub struct BuiltinDefinition {
     pub name: &'static str,
     pub id: BuiltinId,
     pub function: BuiltinFunc,
+    pub arity: Arity,
+    pub doc: &'static str,
 }
 
+impl fmt::Debug for BuiltinDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuiltinDefinition")
+            .field("name", &self.name)
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `print` and `puts` share an id, so the name is part of the identity.
+impl PartialEq for BuiltinDefinition {
+    fn eq(&self, other: &Self) -> bool {
+        return self.name == other.name && self.id == other.id;
+    }
+}
+
+impl Eq for BuiltinDefinition {}
+
 lazy_static! {
     pub static ref BuiltIns: Vec<BuiltinDefinition> = vec![
         BuiltinDefinition {
             name: "len",
             id: BuiltinId::Len,
-            function: len
+            function: len,
+            arity: Arity::exactly(1),
+            doc: "Length of a string in bytes or of an array.",
         },
         BuiltinDefinition {
             name: "puts",
             id: BuiltinId::Puts,
-            function: puts
+            function: puts,
+            arity: Arity::at_least(0),
+            doc: "Prints each argument on its own line and returns null.",
         },
         BuiltinDefinition {
             name: "first",
             id: BuiltinId::First,
-            function: first
+            function: first,
+            arity: Arity::exactly(1),
+            doc: "First element of an array, or null if it is empty.",
         },
         BuiltinDefinition {
             name: "last",
             id: BuiltinId::Last,
-            function: last
+            function: last,
+            arity: Arity::exactly(1),
+            doc: "Last element of an array, or null if it is empty.",
         },
         BuiltinDefinition {
             name: "rest",
             id: BuiltinId::Rest,
-            function: rest
+            function: rest,
+            arity: Arity::exactly(1),
+            doc: "Every element of an array but the first, or null if it is empty.",
         },
         BuiltinDefinition {
             name: "push",
             id: BuiltinId::Push,
-            function: push
+            function: push,
+            arity: Arity::exactly(2),
+            doc: "A copy of an array with a value appended.",
         },
         BuiltinDefinition {
             name: "print",
             id: BuiltinId::Puts,
-            function: puts
+            function: puts,
+            arity: Arity::at_least(0),
+            doc: "Alias of puts.",
+        },
+        BuiltinDefinition {
+            name: "split",
+            id: BuiltinId::Split,
+            function: split,
+            arity: Arity::exactly(2),
+            doc: "Splits a string on a separator.",
+        },
+        BuiltinDefinition {
+            name: "join",
+            id: BuiltinId::Join,
+            function: join,
+            arity: Arity::exactly(2),
+            doc: "Joins the elements of an array with a separator.",
+        },
+        BuiltinDefinition {
+            name: "trim",
+            id: BuiltinId::Trim,
+            function: trim,
+            arity: Arity::exactly(1),
+            doc: "A string without leading and trailing whitespace.",
+        },
+        BuiltinDefinition {
+            name: "upper",
+            id: BuiltinId::Upper,
+            function: upper,
+            arity: Arity::exactly(1),
+            doc: "A string in upper case.",
+        },
+        BuiltinDefinition {
+            name: "lower",
+            id: BuiltinId::Lower,
+            function: lower,
+            arity: Arity::exactly(1),
+            doc: "A string in lower case.",
+        },
+        BuiltinDefinition {
+            name: "contains",
+            id: BuiltinId::Contains,
+            function: contains,
+            arity: Arity::exactly(2),
+            doc: "Whether a string contains another.",
+        },
+        BuiltinDefinition {
+            name: "starts_with",
+            id: BuiltinId::StartsWith,
+            function: starts_with,
+            arity: Arity::exactly(2),
+            doc: "Whether a string starts with another.",
+        },
+        BuiltinDefinition {
+            name: "ends_with",
+            id: BuiltinId::EndsWith,
+            function: ends_with,
+            arity: Arity::exactly(2),
+            doc: "Whether a string ends with another.",
+        },
+        BuiltinDefinition {
+            name: "replace",
+            id: BuiltinId::Replace,
+            function: replace,
+            arity: Arity::exactly(3),
+            doc: "Replaces every occurrence of a substring.",
+        },
+        BuiltinDefinition {
+            name: "substr",
+            id: BuiltinId::Substr,
+            function: substr,
+            arity: Arity::between(2, 3),
+            doc: "The characters from a start index, optionally limited to a count.",
+        },
+        BuiltinDefinition {
+            name: "index_of",
+            id: BuiltinId::IndexOf,
+            function: index_of,
+            arity: Arity::exactly(2),
+            doc: "Character index of the first occurrence of a substring, or -1.",
+        },
+        BuiltinDefinition {
+            name: "chars",
+            id: BuiltinId::Chars,
+            function: chars,
+            arity: Arity::exactly(1),
+            doc: "The characters of a string as an array.",
+        },
+        BuiltinDefinition {
+            name: "str",
+            id: BuiltinId::Str,
+            function: str,
+            arity: Arity::exactly(1),
+            doc: "Any value rendered the way puts shows it.",
+        },
+        BuiltinDefinition {
+            name: "int",
+            id: BuiltinId::Int,
+            function: int,
+            arity: Arity::exactly(1),
+            doc: "Parses a string or truncates a float; null when that fails.",
+        },
+        BuiltinDefinition {
+            name: "map",
+            id: BuiltinId::Map,
+            function: map,
+            arity: Arity::exactly(2),
+            doc: "Applies a function to every element of an array.",
+        },
+        BuiltinDefinition {
+            name: "filter",
+            id: BuiltinId::Filter,
+            function: filter,
+            arity: Arity::exactly(2),
+            doc: "The elements of an array a function accepts.",
+        },
+        BuiltinDefinition {
+            name: "reduce",
+            id: BuiltinId::Reduce,
+            function: reduce,
+            arity: Arity::exactly(3),
+            doc: "Folds an array with a function, starting from an initial value.",
+        },
+        BuiltinDefinition {
+            name: "sort",
+            id: BuiltinId::Sort,
+            function: sort,
+            arity: Arity::between(1, 2),
+            doc: "A sorted copy of an array, optionally ordered by a comparator.",
+        },
+        BuiltinDefinition {
+            name: "find",
+            id: BuiltinId::Find,
+            function: find,
+            arity: Arity::exactly(2),
+            doc: "The first element a function accepts, or null.",
+        },
+        BuiltinDefinition {
+            name: "any",
+            id: BuiltinId::Any,
+            function: any,
+            arity: Arity::exactly(2),
+            doc: "Whether a function accepts some element of an array.",
+        },
+        BuiltinDefinition {
+            name: "all",
+            id: BuiltinId::All,
+            function: all,
+            arity: Arity::exactly(2),
+            doc: "Whether a function accepts every element of an array.",
+        },
+        BuiltinDefinition {
+            name: "keys",
+            id: BuiltinId::Keys,
+            function: keys,
+            arity: Arity::exactly(1),
+            doc: "The keys of a hash in insertion order.",
+        },
+        BuiltinDefinition {
+            name: "values",
+            id: BuiltinId::Values,
+            function: values,
+            arity: Arity::exactly(1),
+            doc: "The values of a hash in insertion order.",
+        },
+        BuiltinDefinition {
+            name: "entries",
+            id: BuiltinId::Entries,
+            function: entries,
+            arity: Arity::exactly(1),
+            doc: "The [key, value] pairs of a hash in insertion order.",
+        },
+        BuiltinDefinition {
+            name: "has",
+            id: BuiltinId::Has,
+            function: has,
+            arity: Arity::exactly(2),
+            doc: "Whether a hash has a key.",
+        },
+        BuiltinDefinition {
+            name: "delete",
+            id: BuiltinId::Delete,
+            function: delete,
+            arity: Arity::exactly(2),
+            doc: "A copy of a hash without a key.",
+        },
+        BuiltinDefinition {
+            name: "merge",
+            id: BuiltinId::Merge,
+            function: merge,
+            arity: Arity::exactly(2),
+            doc: "A copy of a hash with the entries of another added.",
+        },
+        BuiltinDefinition {
+            name: "abs",
+            id: BuiltinId::Abs,
+            function: abs,
+            arity: Arity::exactly(1),
+            doc: "Absolute value of a number.",
+        },
+        BuiltinDefinition {
+            name: "min",
+            id: BuiltinId::Min,
+            function: min,
+            arity: Arity::at_least(1),
+            doc: "The smallest of its arguments.",
+        },
+        BuiltinDefinition {
+            name: "max",
+            id: BuiltinId::Max,
+            function: max,
+            arity: Arity::at_least(1),
+            doc: "The largest of its arguments.",
+        },
+        BuiltinDefinition {
+            name: "pow",
+            id: BuiltinId::Pow,
+            function: pow,
+            arity: Arity::exactly(2),
+            doc: "A number raised to a power.",
+        },
+        BuiltinDefinition {
+            name: "sqrt",
+            id: BuiltinId::Sqrt,
+            function: sqrt,
+            arity: Arity::exactly(1),
+            doc: "Square root of a number, as a float.",
+        },
+        BuiltinDefinition {
+            name: "floor",
+            id: BuiltinId::Floor,
+            function: floor,
+            arity: Arity::exactly(1),
+            doc: "A number rounded down to an integer.",
+        },
+        BuiltinDefinition {
+            name: "ceil",
+            id: BuiltinId::Ceil,
+            function: ceil,
+            arity: Arity::exactly(1),
+            doc: "A number rounded up to an integer.",
+        },
+        BuiltinDefinition {
+            name: "round",
+            id: BuiltinId::Round,
+            function: round,
+            arity: Arity::exactly(1),
+            doc: "A number rounded to the nearest integer, halves away from zero.",
+        },
+        BuiltinDefinition {
+            name: "random",
+            id: BuiltinId::Random,
+            function: random,
+            arity: Arity::exactly(1),
+            doc: "A pseudo-random integer from 0 up to a bound.",
+        },
+        BuiltinDefinition {
+            name: "seed",
+            id: BuiltinId::Seed,
+            function: seed,
+            arity: Arity::exactly(1),
+            doc: "Restarts the random sequence from a seed.",
+        },
+        BuiltinDefinition {
+            name: "json_encode",
+            id: BuiltinId::JsonEncode,
+            function: json_encode,
+            arity: Arity::exactly(1),
+            doc: "The compact JSON text for a value.",
+        },
+        BuiltinDefinition {
+            name: "json_decode",
+            id: BuiltinId::JsonDecode,
+            function: json_decode,
+            arity: Arity::exactly(1),
+            doc: "The value a JSON text describes.",
+        },
+        BuiltinDefinition {
+            name: "type_of",
+            id: BuiltinId::TypeOf,
+            function: type_of,
+            arity: Arity::exactly(1),
+            doc: "The name of a value's type, such as \"integer\" or \"instance\".",
+        },
+        BuiltinDefinition {
+            name: "instance_of",
+            id: BuiltinId::InstanceOf,
+            function: instance_of,
+            arity: Arity::exactly(2),
+            doc: "Whether a value is an instance of a class.",
+        },
+        BuiltinDefinition {
+            name: "fields",
+            id: BuiltinId::Fields,
+            function: fields,
+            arity: Arity::exactly(1),
+            doc: "The sorted field names of an instance.",
+        },
+        BuiltinDefinition {
+            name: "methods",
+            id: BuiltinId::Methods,
+            function: methods,
+            arity: Arity::exactly(1),
+            doc: "The sorted method names of a class.",
+        },
+        BuiltinDefinition {
+            name: "range",
+            id: BuiltinId::Range,
+            function: range,
+            arity: Arity::between(1, 3),
+            doc: "A lazy iterator over the integers from start up to end, by step.",
+        },
+        BuiltinDefinition {
+            name: "take",
+            id: BuiltinId::Take,
+            function: take,
+            arity: Arity::exactly(2),
+            doc: "A lazy iterator over at most n elements of an array or iterator.",
+        },
+        BuiltinDefinition {
+            name: "zip",
+            id: BuiltinId::Zip,
+            function: zip,
+            arity: Arity::exactly(2),
+            doc: "A lazy iterator over [a, b] pairs from two arrays or iterators.",
+        },
+        BuiltinDefinition {
+            name: "enumerate",
+            id: BuiltinId::Enumerate,
+            function: enumerate,
+            arity: Arity::exactly(1),
+            doc: "A lazy iterator over [index, element] pairs.",
+        },
+        BuiltinDefinition {
+            name: "to_array",
+            id: BuiltinId::ToArray,
+            function: to_array,
+            arity: Arity::exactly(1),
+            doc: "The elements of an array or iterator as a new array.",
+        },
+        BuiltinDefinition {
+            name: "next",
+            id: BuiltinId::Next,
+            function: next,
+            arity: Arity::between(1, 2),
+            doc: "Runs a generator to its next yield; returns {\"value\": v, \"done\": bool}.",
         },
     ];
 }
@@ -72,20 +549,22 @@ pub fn len(args: Vec<Rc<Object>>) -> Rc<Object> {
         )));
     }
     Rc::from(match &*args[0] {
+        Object::Iterator(_) => return iterator_builtin(BuiltinId::Len, args),
         Object::String(s) => Object::Integer(s.len() as i64),
         Object::Array(a) => Object::Integer(a.len() as i64),
         o => Object::Error(format!("builtin len not supported for for type {}", o)),
     })
 }
 
+/// Each runtime writes the lines to its own `OutputSink`.
 pub fn puts(args: Vec<Rc<Object>>) -> Rc<Object> {
-    args.iter().for_each(|obj| println!("{}", obj));
-    Rc::from(Object::Null)
+    return needs_host("puts", args);
 }
 
 pub fn first(args: Vec<Rc<Object>>) -> Rc<Object> {
     match &*args[0] {
-        Object::Array(s) => match s.first() {
+        Object::Iterator(_) => iterator_builtin(BuiltinId::First, args),
+        Object::Array(s) => match s.front() {
             Some(obj) => Rc::clone(obj),
             None => Rc::new(Object::Null),
         },
@@ -95,7 +574,7 @@ pub fn first(args: Vec<Rc<Object>>) -> Rc<Object> {
 
 pub fn last(args: Vec<Rc<Object>>) -> Rc<Object> {
     match &*args[0] {
-        Object::Array(s) => match s.last() {
+        Object::Array(s) => match s.back() {
             Some(obj) => Rc::clone(obj),
             None => Rc::new(Object::Null),
         },
@@ -105,13 +584,12 @@ pub fn last(args: Vec<Rc<Object>>) -> Rc<Object> {
 
 pub fn rest(args: Vec<Rc<Object>>) -> Rc<Object> {
     match &*args[0] {
+        Object::Iterator(_) => iterator_builtin(BuiltinId::Rest, args),
         Object::Array(s) => {
-            let len = s.len();
-            if len > 0 {
-                let new_array = s[1..len].to_vec();
-                return Rc::new(Object::Array(new_array));
+            if s.is_empty() {
+                return Rc::new(Object::Null);
             }
-            return Rc::new(Object::Null);
+            return Rc::new(Object::Array(s.skip(1)));
         }
         o => Rc::new(Object::Error(format!("builtin rest not supported for for type {}", o))),
     }
@@ -123,9 +601,463 @@ pub fn push(args: Vec<Rc<Object>>) -> Rc<Object> {
     match &**array {
         Object::Array(s) => {
             let mut new_array = s.clone();
-            new_array.push(obj);
+            new_array.push_back(obj);
             return Rc::new(Object::Array(new_array));
         }
         o => Rc::new(Object::Error(format!("builtin push not supported for for type {}", o))),
     }
 }
+
+pub fn split(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Split, args);
+}
+
+pub fn join(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Join, args);
+}
+
+pub fn trim(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Trim, args);
+}
+
+pub fn upper(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Upper, args);
+}
+
+pub fn lower(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Lower, args);
+}
+
+pub fn contains(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Contains, args);
+}
+
+pub fn starts_with(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::StartsWith, args);
+}
+
+pub fn ends_with(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::EndsWith, args);
+}
+
+pub fn replace(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Replace, args);
+}
+
+pub fn substr(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Substr, args);
+}
+
+pub fn index_of(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::IndexOf, args);
+}
+
+pub fn chars(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Chars, args);
+}
+
+pub fn str(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Str, args);
+}
+
+pub fn int(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return text_builtin(BuiltinId::Int, args);
+}
+
+fn text_builtin(id: BuiltinId, args: Vec<Rc<Object>>) -> Rc<Object> {
+    let args = args
+        .iter()
+        .map(|arg| match &**arg {
+            Object::String(text) => TextArg::Str(text.clone()),
+            Object::Integer(value) => TextArg::Int(Int::Small(*value)),
+            Object::BigInt(value) => TextArg::Int(Int::Big(value.clone())),
+            Object::Float(value) => TextArg::Float(*value),
+            Object::Array(items) => {
+                TextArg::Array(items.iter().map(|item| item.to_string()).collect())
+            }
+            other => TextArg::Other(other.to_string()),
+        })
+        .collect();
+    let result = match strings::call(id, args) {
+        Ok(TextResult::Str(text)) => Object::String(text),
+        Ok(TextResult::Int(value)) => Object::from(value),
+        Ok(TextResult::Bool(value)) => Object::Boolean(value),
+        Ok(TextResult::Array(items)) => Object::Array(
+            items
+                .into_iter()
+                .map(|item| Rc::new(Object::String(item)))
+                .collect(),
+        ),
+        Ok(TextResult::Null) => Object::Null,
+        Err(error) => Object::Error(error.message().to_string()),
+    };
+    return Rc::new(result);
+}
+
+pub fn keys(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return hash_builtin(BuiltinId::Keys, args);
+}
+
+pub fn values(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return hash_builtin(BuiltinId::Values, args);
+}
+
+pub fn entries(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return hash_builtin(BuiltinId::Entries, args);
+}
+
+pub fn has(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return hash_builtin(BuiltinId::Has, args);
+}
+
+pub fn delete(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return hash_builtin(BuiltinId::Delete, args);
+}
+
+pub fn merge(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return hash_builtin(BuiltinId::Merge, args);
+}
+
+fn hash_builtin(id: BuiltinId, args: Vec<Rc<Object>>) -> Rc<Object> {
+    let decoded: Vec<HashArg<'_, Rc<Object>, Rc<Object>>> = args
+        .iter()
+        .map(|arg| match &**arg {
+            Object::Hash(entries) => HashArg::Hash(entries),
+            other if other.is_hashable() => HashArg::Key(Rc::clone(arg)),
+            _ => HashArg::Other,
+        })
+        .collect();
+    let result = match hashes::call(id, &decoded, |index| args[index].to_string()) {
+        Ok(HashResult::Keys(items)) | Ok(HashResult::Values(items)) => Object::Array(items.into()),
+        Ok(HashResult::Entries(entries)) => Object::Array(
+            entries
+                .into_iter()
+                .map(|(key, value)| Rc::new(Object::Array(vec![key, value].into())))
+                .collect(),
+        ),
+        Ok(HashResult::Bool(value)) => Object::Boolean(value),
+        Ok(HashResult::Hash(entries)) => Object::Hash(entries),
+        Err(error) => Object::Error(error.message().to_string()),
+    };
+    return Rc::new(result);
+}
+
+pub fn abs(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Abs, args);
+}
+
+pub fn min(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Min, args);
+}
+
+pub fn max(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Max, args);
+}
+
+pub fn pow(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Pow, args);
+}
+
+pub fn sqrt(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Sqrt, args);
+}
+
+pub fn floor(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Floor, args);
+}
+
+pub fn ceil(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Ceil, args);
+}
+
+pub fn round(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return math_builtin(BuiltinId::Round, args);
+}
+
+fn math_builtin(id: BuiltinId, args: Vec<Rc<Object>>) -> Rc<Object> {
+    let args = args.iter().map(|arg| num_arg(arg)).collect();
+    return Rc::new(num_result(math::call(id, args)));
+}
+
+pub fn random(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("random", args);
+}
+
+pub fn seed(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("seed", args);
+}
+
+/// `random` and `seed` for the backends whose values are `Rc<Object>`; the
+/// caller owns the generator (see `random`).
+pub fn random_builtin(random: &mut Random, id: BuiltinId, args: &[Rc<Object>]) -> Rc<Object> {
+    let args = args.iter().map(|arg| num_arg(arg)).collect();
+    return Rc::new(num_result(random::call(random, id, args)));
+}
+
+fn num_arg(arg: &Object) -> NumArg {
+    match arg {
+        Object::Integer(value) => return NumArg::Int(Int::Small(*value)),
+        Object::BigInt(value) => return NumArg::Int(Int::Big(value.clone())),
+        Object::Float(value) => return NumArg::Float(*value),
+        other => return NumArg::Other(other.to_string()),
+    }
+}
+
+fn num_result(result: Result<NumResult, BuiltinError>) -> Object {
+    match result {
+        Ok(NumResult::Int(value)) => return Object::from(value),
+        Ok(NumResult::Float(value)) => return Object::Float(value),
+        Ok(NumResult::Null) => return Object::Null,
+        Err(error) => return Object::Error(error.message().to_string()),
+    }
+}
+
+pub fn json_encode(args: Vec<Rc<Object>>) -> Rc<Object> {
+    let result = json::encode(&args, |value| match &**value {
+        Object::Null => JsonView::Null,
+        Object::Boolean(value) => JsonView::Bool(*value),
+        Object::Integer(value) => JsonView::Int(Int::Small(*value)),
+        Object::BigInt(value) => JsonView::Int(Int::Big(value.clone())),
+        Object::Float(value) => JsonView::Float(*value),
+        Object::String(text) => JsonView::Str(text.clone()),
+        Object::Array(items) => {
+            JsonView::Array(Rc::as_ptr(value) as usize, items.iter().cloned().collect())
+        }
+        Object::Hash(entries) => JsonView::Hash(
+            Rc::as_ptr(value) as usize,
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), Rc::clone(value)))
+                .collect(),
+        ),
+        other => JsonView::Other(other.to_string()),
+    });
+    match result {
+        Ok(text) => return Rc::new(Object::String(text)),
+        Err(error) => return Rc::new(Object::Error(error.message().to_string())),
+    }
+}
+
+pub fn json_decode(args: Vec<Rc<Object>>) -> Rc<Object> {
+    let args: Vec<JsonArg<'_>> = args
+        .iter()
+        .map(|arg| match &**arg {
+            Object::String(text) => JsonArg::Str(text),
+            other => JsonArg::Other(other.to_string()),
+        })
+        .collect();
+    match json::decode(&args) {
+        Ok(document) => return json_object(document),
+        Err(error) => return Rc::new(Object::Error(error.message().to_string())),
+    }
+}
+
+/// A decoded document as `Object`s, for the backends that use them (the gc
+/// VM imports it from here too).
+pub fn json_object(document: Json) -> Rc<Object> {
+    let object = match document {
+        Json::Null => Object::Null,
+        Json::Bool(value) => Object::Boolean(value),
+        Json::Int(value) => Object::from(value),
+        Json::Float(value) => Object::Float(value),
+        Json::Str(text) => Object::String(text),
+        Json::Array(items) => Object::Array(items.into_iter().map(json_object).collect()),
+        Json::Hash(entries) => Object::Hash(
+            entries
+                .into_iter()
+                .map(|(key, value)| (Rc::new(Object::String(key)), json_object(value)))
+                .collect(),
+        ),
+    };
+    return Rc::new(object);
+}
+
+pub fn type_of(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return reflect_builtin(BuiltinId::TypeOf, args);
+}
+
+pub fn instance_of(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return reflect_builtin(BuiltinId::InstanceOf, args);
+}
+
+pub fn fields(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return reflect_builtin(BuiltinId::Fields, args);
+}
+
+pub fn methods(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return reflect_builtin(BuiltinId::Methods, args);
+}
+
+fn reflect_builtin(id: BuiltinId, args: Vec<Rc<Object>>) -> Rc<Object> {
+    let decoded = args.iter().map(|arg| reflect_arg(arg)).collect();
+    let result = match reflect::call(id, decoded, |index| args[index].to_string()) {
+        Ok(ReflectResult::Str(name)) => Object::String(name.to_string()),
+        Ok(ReflectResult::Bool(value)) => Object::Boolean(value),
+        Ok(ReflectResult::Names(names)) => Object::Array(
+            names
+                .into_iter()
+                .map(|name| Rc::new(Object::String(name)))
+                .collect(),
+        ),
+        Err(error) => Object::Error(error.message().to_string()),
+    };
+    return Rc::new(result);
+}
+
+fn reflect_arg(arg: &Object) -> ReflectArg {
+    let kind = match arg {
+        Object::Class(class) => {
+            return ReflectArg::Class(
+                Rc::as_ptr(class) as usize,
+                class.borrow().methods.keys().cloned().collect(),
+            );
+        }
+        Object::Instance(instance) => {
+            let instance = instance.borrow();
+            return ReflectArg::Instance(
+                Rc::as_ptr(&instance.class) as usize,
+                instance.fields.keys().cloned().collect(),
+            );
+        }
+        Object::ReturnValue(value) => return reflect_arg(value),
+        Object::Integer(_) | Object::BigInt(_) => TypeOf::Integer,
+        Object::Float(_) => TypeOf::Float,
+        Object::Boolean(_) => TypeOf::Boolean,
+        Object::String(_) => TypeOf::String,
+        Object::Null => TypeOf::Null,
+        Object::Array(_) => TypeOf::Array,
+        Object::Hash(_) => TypeOf::Hash,
+        Object::Function(..) | Object::GeneratorFunction(..) | Object::ClosureObj(_) => {
+            TypeOf::Closure
+        }
+        Object::Builtin(_) | Object::Native(_) => TypeOf::Builtin,
+        Object::BoundMethod(_) | Object::BoundBuiltin(_) => TypeOf::BoundMethod,
+        Object::Iterator(_) => TypeOf::Iterator,
+        Object::Generator(_) => TypeOf::Generator,
+        Object::Error(_) => TypeOf::Error,
+        Object::CompiledFunction(_) => TypeOf::CompiledFunction,
+    };
+    return ReflectArg::Other(kind);
+}
+
+pub fn range(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return iterator_builtin(BuiltinId::Range, args);
+}
+
+pub fn take(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return iterator_builtin(BuiltinId::Take, args);
+}
+
+pub fn zip(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return iterator_builtin(BuiltinId::Zip, args);
+}
+
+pub fn enumerate(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return iterator_builtin(BuiltinId::Enumerate, args);
+}
+
+pub fn to_array(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return iterator_builtin(BuiltinId::ToArray, args);
+}
+
+fn iterator_builtin(id: BuiltinId, args: Vec<Rc<Object>>) -> Rc<Object> {
+    match iterators::call(&mut ObjectIterHost, id, &args) {
+        Ok(result) => return result,
+        Err(error) => return Rc::new(Object::Error(error.message().to_string())),
+    }
+}
+
+struct ObjectIterHost;
+
+impl ObjectValues for ObjectIterHost {}
+
+impl IterHost for ObjectIterHost {
+    fn source(&self, value: &Rc<Object>) -> Option<IterSource<Rc<Object>>> {
+        match &**value {
+            Object::Array(elements) => return Some(IterSource::Array(elements.clone())),
+            Object::Iterator(iter) => return Some(IterSource::Iter((**iter).clone())),
+            _ => return None,
+        }
+    }
+
+    fn integer(&self, value: &Rc<Object>) -> Option<Int> {
+        return value.as_int();
+    }
+
+    fn int(&mut self, value: i64) -> Rc<Object> {
+        return Rc::new(Object::Integer(value));
+    }
+
+    fn iterator(&mut self, iter: Iter<Rc<Object>>) -> Rc<Object> {
+        return Rc::new(Object::Iterator(Rc::new(iter)));
+    }
+}
+
+pub fn map(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("map", args);
+}
+
+pub fn filter(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("filter", args);
+}
+
+pub fn reduce(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("reduce", args);
+}
+
+pub fn sort(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("sort", args);
+}
+
+pub fn find(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("find", args);
+}
+
+pub fn any(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("any", args);
+}
+
+pub fn all(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("all", args);
+}
+
+/// Resuming a generator runs its body, which only a runtime can do.
+pub fn next(args: Vec<Rc<Object>>) -> Rc<Object> {
+    return needs_host("next", args);
+}
+
+/// The higher-order builtins call back into Monkey code, `random`/`seed`
+/// use the generator of the running program and `puts` its output sink,
+/// none of which a plain `BuiltinFunc` can reach. Backends recognise them
+/// with [`builtin_id`] and run them themselves; this is only reached when
+/// something calls the function pointer directly.
+fn needs_host(name: &str, _args: Vec<Rc<Object>>) -> Rc<Object> {
+    return Rc::new(Object::Error(format!("builtin {} can only be called from Monkey code", name)));
+}
+
+/// The id of a builtin function value, found by identity in [`BuiltIns`].
+pub fn builtin_id(function: BuiltinFunc) -> Option<BuiltinId> {
+    return BuiltIns
+        .iter()
+        .find(|definition| std::ptr::fn_addr_eq(definition.function, function))
+        .map(|definition| definition.id);
+}
+
+/// `receiver.name` for a string, array or hash: the builtin `methods` binds
+/// the name to, with `receiver` as its first argument.
+pub fn builtin_method(receiver: &Rc<Object>, name: &str) -> Option<Rc<Object>> {
+    let kind = match &**receiver {
+        Object::String(_) => Receiver::String,
+        Object::Array(_) => Receiver::Array,
+        Object::Hash(_) => Receiver::Hash,
+        _ => return None,
+    };
+    let id = methods::lookup(kind, name)?;
+    let definition = BuiltIns.iter().find(|definition| definition.id == id)?;
+    return Some(Rc::new(Object::BoundBuiltin(Rc::new(BoundBuiltinObject {
+        receiver: Rc::clone(receiver),
+        kind,
+        name: definition.name,
+        function: definition.function,
+    }))));
+}
@@ -1,3 +1,6 @@
+use crate::output::Output;
+use crate::random::Random;
+use crate::registry::Builtins;
 use crate::Object;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -5,20 +8,63 @@ use std::rc::Rc;
 
 pub type Env = Rc<RefCell<Environment>>;
 
+/// One scope of a running program. The top level binds names in `store`, so
+/// later programs can add to it; function and method scopes use `slots`,
+/// numbered by validation (`parser::ast::Binding`).
 #[derive(Debug, Default, Eq, Clone, PartialEq)]
 pub struct Environment {
     store: HashMap<String, Rc<Object>>,
+    /// `None` for a slot whose `let` has not run.
+    slots: Vec<Option<Rc<Object>>>,
     outer: Option<Env>,
+    /// One generator per program, shared by every scope enclosed in the root.
+    random: Rc<RefCell<Random>>,
+    /// The builtins the program can call, also shared from the root.
+    builtins: Rc<Builtins>,
+    /// Where `puts` writes, also shared from the root.
+    output: Output,
 }
 
 impl Environment {
+    /// A root environment whose programs see `builtins` instead of the
+    /// standard set.
+    pub fn with_builtins(builtins: Builtins) -> Self {
+        return Environment {
+            builtins: Rc::new(builtins),
+            ..Default::default()
+        };
+    }
+
     pub fn new_enclosed_environment(outer: &Env) -> Self {
+        let outer_env = outer.borrow();
         return Environment {
+            store: HashMap::new(),
+            slots: Vec::new(),
             outer: Some(Rc::clone(outer)),
-            ..Default::default()
+            random: Rc::clone(&outer_env.random),
+            builtins: Rc::clone(&outer_env.builtins),
+            output: outer_env.output.clone(),
         };
     }
 
+    pub fn random(&self) -> Rc<RefCell<Random>> {
+        return Rc::clone(&self.random);
+    }
+
+    pub fn builtins(&self) -> Rc<Builtins> {
+        return Rc::clone(&self.builtins);
+    }
+
+    pub fn output(&self) -> Output {
+        return self.output.clone();
+    }
+
+    /// Sends the output of programs run in this environment, and in the
+    /// scopes enclosed in it from now on, to `output`.
+    pub fn set_output(&mut self, output: Output) {
+        self.output = output;
+    }
+
     pub fn get(&self, name: &str) -> Option<Rc<Object>> {
         match self.store.get(name) {
             Some(obj) => Some(Rc::clone(obj)),
@@ -36,6 +82,42 @@ impl Environment {
         self.store.insert(name, val);
     }
 
+    /// Slot `index` of the scope `depth` scopes out.
+    pub fn get_local(&self, depth: usize, index: usize) -> Option<Rc<Object>> {
+        if depth == 0 {
+            return self.slots.get(index).cloned().flatten();
+        }
+        return self.outer.as_ref()?.borrow().get_local(depth - 1, index);
+    }
+
+    /// [`Environment::get`] starting `depth` scopes out.
+    pub fn get_global(&self, depth: usize, name: &str) -> Option<Rc<Object>> {
+        if depth == 0 {
+            return self.get(name);
+        }
+        return self.outer.as_ref()?.borrow().get_global(depth - 1, name);
+    }
+
+    pub fn set_local(&mut self, index: usize, val: Rc<Object>) {
+        if index >= self.slots.len() {
+            self.slots.resize(index + 1, None);
+        }
+        self.slots[index] = Some(val);
+    }
+
+    /// The names bound by name in this scope itself.
+    pub fn bindings(&self) -> impl Iterator<Item = (&String, &Rc<Object>)> {
+        return self.store.iter();
+    }
+
+    pub fn slots(&self) -> &[Option<Rc<Object>>] {
+        return &self.slots;
+    }
+
+    pub fn outer(&self) -> Option<Env> {
+        return self.outer.clone();
+    }
+
     pub fn visible_names(&self) -> Vec<String> {
         let mut names = self
             .outer
@@ -0,0 +1,371 @@
+//! Generators. Calling a `fn*` function binds its parameters and returns a
+//! generator without running the body; each `next(generator)` runs the body
+//! up to its next `yield` and returns `{"value": v, "done": false}` with the
+//! value yielded. When the body returns, `next` gives the returned value with
+//! `done` set, and from then on `{"value": null, "done": true}`.
+//!
+//! `next(generator, sent)` hands `sent` back to the body as the value of the
+//! `yield` it stopped at; the first call, which starts the body, drops it.
+//! `yield* inner` yields everything `inner` does, passing sent values on,
+//! and is worth the value `inner` returns. `return yield* inner` hands the
+//! generator over to `inner` for good, so a sequence that ends by
+//! continuing with another generator, like
+//! `let from = fn*(n) { yield n; return yield* from(n + 1); }`, runs on in
+//! constant space however long it gets.
+//!
+//! A generator is read with `next` alone. The sequence builtins (`take`,
+//! `to_array`, `first`, `rest`, `len` and the rest of [`crate::iterators`])
+//! refuse it: an iterator can be read again and again, a generator is used
+//! up by reading it. A `yield` has to be a whole statement, a `let`
+//! initializer or a return value, so `let y = 1 + yield x;` is rejected
+//! when the program is validated. The asm backend has no generators at all:
+//! it rejects `fn*` and `yield`, and its `next` always fails.
+//!
+//! How a generator remembers where it stopped is up to the backend: the
+//! interpreter keeps the position in the body, the VMs keep the frame and
+//! its part of the stack. `next` itself, and how it follows `yield*`, is
+//! written once against [`GeneratorHost`].
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::builtins::{check_arity, BuiltinError};
+use crate::environment::Env;
+use crate::higher_order::ValueHost;
+use crate::{Closure, Object, OrderedMap};
+
+/// A generator. `S` is where a backend's bodies stop, `V` its values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeneratorState<S, V> {
+    /// Not started, or stopped at a `yield`.
+    Suspended(S),
+    /// Stopped at a `yield*` over the generator `V`.
+    Delegating(S, V),
+    /// Returned `yield*` over the generator `V`, which makes the rest of the
+    /// sequence.
+    Forwarding(V),
+    /// Inside a `next` call.
+    Running,
+    /// Returned, or stopped by an error.
+    Done,
+}
+
+impl<S, V> GeneratorState<S, V> {
+    /// Visits the generator this one delegates or forwards to, for tracing.
+    pub fn for_each_inner(&self, mut visit: impl FnMut(&V)) {
+        match self {
+            GeneratorState::Delegating(_, inner) | GeneratorState::Forwarding(inner) => {
+                visit(inner)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// How a run of a generator's body stopped.
+pub enum Resumed<S, V> {
+    /// At a `yield` of the value.
+    Yield(V, S),
+    /// At a `yield*` over the value, which has not been started.
+    Delegate(V, S),
+    /// At a `return yield*` over the value.
+    Forward(V),
+    /// With the value the body returned.
+    Return(V),
+}
+
+/// A backend's generators and how to run their bodies. States and
+/// suspensions own what they hold.
+pub trait GeneratorHost: ValueHost {
+    type Suspension;
+    type Error;
+
+    /// Takes the state out of a generator, leaving it
+    /// [`Running`](GeneratorState::Running); `None` for any other value.
+    fn take_state(
+        &mut self,
+        generator: &Self::Value,
+    ) -> Option<GeneratorState<Self::Suspension, Self::Value>>;
+    fn put_state(
+        &mut self,
+        generator: &Self::Value,
+        state: GeneratorState<Self::Suspension, Self::Value>,
+    );
+    /// Runs the body from where it stopped, with `sent` as the value of the
+    /// `yield` there.
+    fn resume(
+        &mut self,
+        suspension: Self::Suspension,
+        sent: Self::Value,
+    ) -> Result<Resumed<Self::Suspension, Self::Value>, Self::Error>;
+    /// Lets go of a body that will not run again.
+    fn discard(&mut self, suspension: Self::Suspension);
+}
+
+pub enum NextError<E> {
+    /// Bad arguments, or a generator resumed from its own body.
+    Builtin(BuiltinError),
+    /// The body failed; the generator is done.
+    Resume(E),
+}
+
+/// Runs `next(args...)`, returning the value and whether the generator is
+/// done.
+pub fn next<H: GeneratorHost>(
+    host: &mut H,
+    args: &[H::Value],
+) -> Result<(H::Value, bool), NextError<H::Error>> {
+    check_arity("next", 1, 2, args.len()).map_err(NextError::Builtin)?;
+    let sent = match args.get(1) {
+        Some(sent) => host.retain(sent),
+        None => host.null(),
+    };
+    return run(host, &args[0], sent);
+}
+
+/// A generator waiting on the one `next` is running, and what it needs once
+/// that one stops.
+enum Waiting<S, V> {
+    /// Stopped at a `yield*` over it, to resume with what it returns.
+    Delegating(V, S),
+    /// Forwarding to the second generator, which gets its state back.
+    Forwarding(V, V),
+}
+
+/// Where a `next` call is in the chain of generators it runs.
+enum Step<S, V, E> {
+    /// The current generator is sent the value.
+    Send(V),
+    /// Its body stopped.
+    Stopped(Result<Resumed<S, V>, E>),
+    /// It gives this result to the generator waiting on it, if any.
+    Finished(Result<(V, bool), NextError<E>>),
+}
+
+type HostStep<H> =
+    Step<<H as GeneratorHost>::Suspension, <H as ValueHost>::Value, <H as GeneratorHost>::Error>;
+type HostWaiting<H> = Waiting<<H as GeneratorHost>::Suspension, <H as ValueHost>::Value>;
+
+/// `yield*` chains can be any length, so the generators waiting on the one
+/// running are kept in `waiting` instead of on the native stack.
+fn run<H: GeneratorHost>(
+    host: &mut H,
+    generator: &H::Value,
+    sent: H::Value,
+) -> Result<(H::Value, bool), NextError<H::Error>> {
+    let mut current = host.retain(generator);
+    let mut waiting: Vec<HostWaiting<H>> = Vec::new();
+    let mut step = Step::Send(sent);
+    loop {
+        step = match step {
+            Step::Send(sent) => send(host, &mut current, &mut waiting, sent),
+            Step::Stopped(resumed) => stopped(host, &mut current, &mut waiting, resumed),
+            Step::Finished(result) => match waiting.pop() {
+                Some(outer) => finish(host, &mut current, outer, result),
+                None => {
+                    host.release(current);
+                    return result;
+                }
+            },
+        };
+    }
+}
+
+fn send<H: GeneratorHost>(
+    host: &mut H,
+    current: &mut H::Value,
+    waiting: &mut Vec<HostWaiting<H>>,
+    sent: H::Value,
+) -> HostStep<H> {
+    let Some(state) = host.take_state(current) else {
+        host.release(sent);
+        let message = format!("builtin next not supported for type {}", host.describe(current));
+        return Step::Finished(Err(NextError::Builtin(BuiltinError::Type(message))));
+    };
+    match state {
+        GeneratorState::Suspended(suspension) => {
+            return Step::Stopped(host.resume(suspension, sent));
+        }
+        GeneratorState::Delegating(suspension, inner) => {
+            let outer = std::mem::replace(current, inner);
+            waiting.push(Waiting::Delegating(outer, suspension));
+            return Step::Send(sent);
+        }
+        GeneratorState::Forwarding(inner) => {
+            let inner = skip_forwarding(host, inner);
+            let target = host.retain(&inner);
+            let outer = std::mem::replace(current, target);
+            waiting.push(Waiting::Forwarding(outer, inner));
+            return Step::Send(sent);
+        }
+        GeneratorState::Running => {
+            host.release(sent);
+            host.put_state(current, GeneratorState::Running);
+            let error = BuiltinError::Type("generator is already running".to_string());
+            return Step::Finished(Err(NextError::Builtin(error)));
+        }
+        GeneratorState::Done => {
+            host.release(sent);
+            host.put_state(current, GeneratorState::Done);
+            return Step::Finished(Ok((host.null(), true)));
+        }
+    }
+}
+
+/// Follows generators that forward on to the one that does not, so that
+/// forwarding chains stay short.
+fn skip_forwarding<H: GeneratorHost>(host: &mut H, mut inner: H::Value) -> H::Value {
+    loop {
+        match host.take_state(&inner) {
+            Some(GeneratorState::Forwarding(next)) => {
+                let target = host.retain(&next);
+                host.put_state(&inner, GeneratorState::Forwarding(next));
+                host.release(inner);
+                inner = target;
+            }
+            Some(state) => {
+                host.put_state(&inner, state);
+                return inner;
+            }
+            None => return inner,
+        }
+    }
+}
+
+/// Records where the body of the current generator stopped and what it
+/// gives back, or moves on to the generator it now delegates to.
+fn stopped<H: GeneratorHost>(
+    host: &mut H,
+    current: &mut H::Value,
+    waiting: &mut Vec<HostWaiting<H>>,
+    resumed: Result<Resumed<H::Suspension, H::Value>, H::Error>,
+) -> HostStep<H> {
+    match resumed {
+        Ok(Resumed::Yield(value, suspension)) => {
+            host.put_state(current, GeneratorState::Suspended(suspension));
+            return Step::Finished(Ok((value, false)));
+        }
+        Ok(Resumed::Return(value)) => {
+            host.put_state(current, GeneratorState::Done);
+            return Step::Finished(Ok((value, true)));
+        }
+        Ok(Resumed::Delegate(inner, suspension)) => {
+            if let Err(error) = expect_generator(host, &inner) {
+                host.release(inner);
+                host.discard(suspension);
+                host.put_state(current, GeneratorState::Done);
+                return Step::Finished(Err(error));
+            }
+            let outer = std::mem::replace(current, inner);
+            waiting.push(Waiting::Delegating(outer, suspension));
+            return Step::Send(host.null());
+        }
+        Ok(Resumed::Forward(inner)) => {
+            if let Err(error) = expect_generator(host, &inner) {
+                host.release(inner);
+                host.put_state(current, GeneratorState::Done);
+                return Step::Finished(Err(error));
+            }
+            host.put_state(current, GeneratorState::Forwarding(inner));
+            return Step::Send(host.null());
+        }
+        Err(error) => {
+            host.put_state(current, GeneratorState::Done);
+            return Step::Finished(Err(NextError::Resume(error)));
+        }
+    }
+}
+
+/// Hands what the current generator gave to `outer`, which becomes current.
+fn finish<H: GeneratorHost>(
+    host: &mut H,
+    current: &mut H::Value,
+    outer: HostWaiting<H>,
+    result: Result<(H::Value, bool), NextError<H::Error>>,
+) -> HostStep<H> {
+    match outer {
+        Waiting::Delegating(outer, suspension) => {
+            let inner = std::mem::replace(current, outer);
+            match result {
+                Ok((value, false)) => {
+                    host.put_state(current, GeneratorState::Delegating(suspension, inner));
+                    return Step::Finished(Ok((value, false)));
+                }
+                Ok((returned, true)) => {
+                    host.release(inner);
+                    return Step::Stopped(host.resume(suspension, returned));
+                }
+                Err(error) => {
+                    host.release(inner);
+                    host.discard(suspension);
+                    host.put_state(current, GeneratorState::Done);
+                    return Step::Finished(Err(error));
+                }
+            }
+        }
+        Waiting::Forwarding(outer, inner) => {
+            let target = std::mem::replace(current, outer);
+            host.release(target);
+            host.put_state(current, GeneratorState::Forwarding(inner));
+            return Step::Finished(result);
+        }
+    }
+}
+
+fn expect_generator<H: GeneratorHost>(
+    host: &mut H,
+    value: &H::Value,
+) -> Result<(), NextError<H::Error>> {
+    match host.take_state(value) {
+        Some(state) => {
+            host.put_state(value, state);
+            return Ok(());
+        }
+        None => {
+            let message = format!("yield* expected a generator, got {}", host.describe(value));
+            return Err(NextError::Builtin(BuiltinError::Type(message)));
+        }
+    }
+}
+
+/// Where a generator of the interpreter or the compiler's VM stopped.
+pub enum Suspension {
+    /// The interpreter's: the generator function, the scope of its body and
+    /// the position of the `yield` in it, empty before the body starts.
+    Body { function: Rc<Object>, env: Env, resume_at: Vec<usize> },
+    /// The VM's: the frame's closure and instruction pointer, and the stack
+    /// from its first local up.
+    Frame { closure: Closure, ip: i32, stack: Vec<Rc<Object>> },
+}
+
+pub type GeneratorRef = Rc<RefCell<GeneratorState<Suspension, Rc<Object>>>>;
+
+pub fn new_generator(suspension: Suspension) -> Rc<Object> {
+    let state = GeneratorState::Suspended(suspension);
+    return Rc::new(Object::Generator(Rc::new(RefCell::new(state))));
+}
+
+/// Takes the state out of a generator object, for the object backends'
+/// [`GeneratorHost::take_state`].
+pub fn take_state(generator: &Object) -> Option<GeneratorState<Suspension, Rc<Object>>> {
+    match generator {
+        Object::Generator(generator) => {
+            return Some(std::mem::replace(&mut *generator.borrow_mut(), GeneratorState::Running));
+        }
+        _ => return None,
+    }
+}
+
+pub fn put_state(generator: &Object, state: GeneratorState<Suspension, Rc<Object>>) {
+    if let Object::Generator(generator) = generator {
+        *generator.borrow_mut() = state;
+    }
+}
+
+/// The hash `next` returns.
+pub fn step(value: Rc<Object>, done: bool) -> Rc<Object> {
+    let mut result = OrderedMap::new();
+    result.insert(Rc::new(Object::String("value".to_string())), value);
+    result.insert(Rc::new(Object::String("done".to_string())), Rc::new(Object::Boolean(done)));
+    return Rc::new(Object::Hash(result));
+}
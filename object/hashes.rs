@@ -0,0 +1,106 @@
+//! The hash builtins (`keys`, `values`, `entries`, `has`, `delete`, `merge`).
+//! Like the string library, every backend decodes its values into
+//! [`HashArg`], calls [`call`] and encodes the [`HashResult`] back.
+//!
+//! Hashes keep their insertion order, so `keys`, `values` and `entries` list
+//! entries in the order they were first added. `delete` and `merge` return a
+//! new hash and leave their arguments untouched: `delete` keeps the order of
+//! the remaining entries, and `merge` overwrites keys of the left hash in
+//! place and appends the keys only the right hash has.
+
+use std::hash::Hash;
+
+use crate::persistent::OrderedMap;
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+
+pub enum HashArg<'a, K, V> {
+    Hash(&'a OrderedMap<K, V>),
+    /// A value that can be used as a hash key.
+    Key(K),
+    Other,
+}
+
+pub enum HashResult<K, V> {
+    Keys(Vec<K>),
+    Values(Vec<V>),
+    Entries(Vec<(K, V)>),
+    Bool(bool),
+    Hash(OrderedMap<K, V>),
+}
+
+pub fn is_hash_builtin(id: BuiltinId) -> bool {
+    return name(id).is_some();
+}
+
+fn name(id: BuiltinId) -> Option<&'static str> {
+    let name = match id {
+        BuiltinId::Keys => "keys",
+        BuiltinId::Values => "values",
+        BuiltinId::Entries => "entries",
+        BuiltinId::Has => "has",
+        BuiltinId::Delete => "delete",
+        BuiltinId::Merge => "merge",
+        _ => return None,
+    };
+    return Some(name);
+}
+
+/// Runs the hash builtin `id`. `describe(i)` renders argument `i` the way
+/// `puts` shows it, for error messages. Panics if `id` is not one (see
+/// [`is_hash_builtin`]).
+pub fn call<K, V>(
+    id: BuiltinId,
+    args: &[HashArg<'_, K, V>],
+    describe: impl Fn(usize) -> String,
+) -> Result<HashResult<K, V>, BuiltinError>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    let name = name(id).expect("not a hash builtin");
+    let arity = match id {
+        BuiltinId::Keys | BuiltinId::Values | BuiltinId::Entries => 1,
+        _ => 2,
+    };
+    check_arity(name, arity, arity, args.len())?;
+    let unsupported = |index: usize| {
+        BuiltinError::Type(format!("builtin {} not supported for type {}", name, describe(index)))
+    };
+    let HashArg::Hash(hash) = &args[0] else {
+        return Err(unsupported(0));
+    };
+
+    let result = match id {
+        BuiltinId::Keys => HashResult::Keys(hash.keys().cloned().collect()),
+        BuiltinId::Values => HashResult::Values(hash.values().cloned().collect()),
+        BuiltinId::Entries => HashResult::Entries(
+            hash.iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        BuiltinId::Merge => {
+            let HashArg::Hash(other) = &args[1] else {
+                return Err(unsupported(1));
+            };
+            let mut merged = (*hash).clone();
+            for (key, value) in other.iter() {
+                merged.insert(key.clone(), value.clone());
+            }
+            HashResult::Hash(merged)
+        }
+        _ => {
+            let HashArg::Key(key) = &args[1] else {
+                return Err(unsupported(1));
+            };
+            if id == BuiltinId::Has {
+                HashResult::Bool(hash.contains_key(key))
+            } else {
+                let mut remaining = (*hash).clone();
+                remaining.remove(key);
+                HashResult::Hash(remaining)
+            }
+        }
+    };
+    return Ok(result);
+}
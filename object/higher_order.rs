@@ -0,0 +1,340 @@
+//! Builtins that take a function: `map`, `filter`, `reduce`, `sort`, `find`,
+//! `any` and `all`. A [`BuiltinFunc`](crate::BuiltinFunc) cannot run Monkey
+//! code, so these are written once against [`Host`], which every backend
+//! implements by re-entering its own interpreter or VM. The order and number
+//! of callback invocations is part of the contract: side effects in a
+//! callback happen identically everywhere.
+//!
+//! Callbacks receive the element only (`reduce` gets the accumulator first,
+//! a `sort` comparator gets both elements and returns a number that is
+//! negative, zero or positive).
+
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+use crate::integer::{self, Int};
+use crate::Object;
+
+/// How a backend makes, shares and frees its values; the part every builtin
+/// host has in common.
+///
+/// Ownership follows the reference-counted backends: values passed *to* the
+/// host are borrowed, values it returns are owned and go back to either
+/// [`ValueHost::release`] or the caller of the builtin.
+pub trait ValueHost {
+    type Value: Clone;
+
+    fn array(&mut self, elements: &[Self::Value]) -> Self::Value;
+    fn null(&mut self) -> Self::Value;
+    /// An owned reference to a borrowed value.
+    fn retain(&mut self, value: &Self::Value) -> Self::Value;
+    fn release(&mut self, value: Self::Value);
+    /// The value as `puts` shows it, for error messages.
+    fn describe(&self, value: &Self::Value) -> String;
+}
+
+/// A host whose values are `Rc<Object>` (the interpreter and the Rc VM).
+/// Dropping is all the bookkeeping they need, so [`ValueHost`] comes with it.
+pub trait ObjectValues {}
+
+impl<T: ObjectValues> ValueHost for T {
+    type Value = Rc<Object>;
+
+    fn array(&mut self, elements: &[Rc<Object>]) -> Rc<Object> {
+        return Rc::new(Object::Array(elements.iter().cloned().collect()));
+    }
+
+    fn null(&mut self) -> Rc<Object> {
+        return Rc::new(Object::Null);
+    }
+
+    fn retain(&mut self, value: &Rc<Object>) -> Rc<Object> {
+        return Rc::clone(value);
+    }
+
+    fn release(&mut self, _value: Rc<Object>) {}
+
+    fn describe(&self, value: &Rc<Object>) -> String {
+        return value.to_string();
+    }
+}
+
+/// A backend's view of its values for the higher-order builtins.
+pub trait Host: ValueHost {
+    type Error;
+
+    /// Calls any callable value, the way a Monkey call expression would.
+    fn call(
+        &mut self,
+        function: &Self::Value,
+        args: &[Self::Value],
+    ) -> Result<Self::Value, Self::Error>;
+    /// The elements of an array, borrowed from it; `None` for other values.
+    fn elements(&self, value: &Self::Value) -> Option<Vec<Self::Value>>;
+    fn boolean(&mut self, value: bool) -> Self::Value;
+    fn truthy(&self, value: &Self::Value) -> bool;
+    /// Numbers and strings; `None` for values `sort` cannot order.
+    fn sort_key(&self, value: &Self::Value) -> Option<SortKey>;
+}
+
+/// [`Host`] for the backends whose values are `Rc<Object>` (the interpreter
+/// and the Rc VM); they differ only in how a function gets called and what
+/// error a failed call carries.
+pub struct ObjectHost<F>(pub F);
+
+impl<F> ObjectValues for ObjectHost<F> {}
+
+impl<F, E> Host for ObjectHost<F>
+where
+    F: FnMut(&Rc<Object>, &[Rc<Object>]) -> Result<Rc<Object>, E>,
+{
+    type Error = E;
+
+    fn call(&mut self, function: &Rc<Object>, args: &[Rc<Object>]) -> Result<Rc<Object>, E> {
+        return (self.0)(function, args);
+    }
+
+    fn elements(&self, value: &Rc<Object>) -> Option<Vec<Rc<Object>>> {
+        match &**value {
+            Object::Array(elements) => return Some(elements.iter().cloned().collect()),
+            _ => return None,
+        }
+    }
+
+    fn boolean(&mut self, value: bool) -> Rc<Object> {
+        return Rc::new(Object::Boolean(value));
+    }
+
+    fn truthy(&self, value: &Rc<Object>) -> bool {
+        return !matches!(**value, Object::Null | Object::Boolean(false));
+    }
+
+    fn sort_key(&self, value: &Rc<Object>) -> Option<SortKey> {
+        match &**value {
+            Object::Integer(value) => return Some(SortKey::Int(Int::Small(*value))),
+            Object::BigInt(value) => return Some(SortKey::Int(Int::Big(value.clone()))),
+            Object::Float(value) => return Some(SortKey::Float(*value)),
+            Object::String(value) => return Some(SortKey::Str(value.clone())),
+            _ => return None,
+        }
+    }
+}
+
+pub enum SortKey {
+    Int(Int),
+    Float(f64),
+    Str(String),
+}
+
+pub enum HigherOrderError<E> {
+    /// The builtin itself was misused.
+    Builtin(BuiltinError),
+    /// A callback failed; the backend's own error, passed through untouched.
+    Callback(E),
+}
+
+impl<E> From<BuiltinError> for HigherOrderError<E> {
+    fn from(error: BuiltinError) -> Self {
+        return HigherOrderError::Builtin(error);
+    }
+}
+
+type Outcome<H, T> = Result<T, HigherOrderError<<H as Host>::Error>>;
+
+pub fn is_higher_order(id: BuiltinId) -> bool {
+    return name(id).is_some();
+}
+
+fn name(id: BuiltinId) -> Option<&'static str> {
+    let name = match id {
+        BuiltinId::Map => "map",
+        BuiltinId::Filter => "filter",
+        BuiltinId::Reduce => "reduce",
+        BuiltinId::Sort => "sort",
+        BuiltinId::Find => "find",
+        BuiltinId::Any => "any",
+        BuiltinId::All => "all",
+        _ => return None,
+    };
+    return Some(name);
+}
+
+/// Runs the higher-order builtin `id`, returning an owned value. Panics if
+/// `id` is not one (see [`is_higher_order`]).
+pub fn call<H: Host>(host: &mut H, id: BuiltinId, args: &[H::Value]) -> Outcome<H, H::Value> {
+    let name = name(id).expect("not a higher-order builtin");
+    let (min, max) = match id {
+        BuiltinId::Reduce => (3, 3),
+        BuiltinId::Sort => (1, 2),
+        _ => (2, 2),
+    };
+    check_arity(name, min, max, args.len())?;
+    let Some(elements) = host.elements(&args[0]) else {
+        let shown = host.describe(&args[0]);
+        let message = format!("builtin {} not supported for type {}", name, shown);
+        return Err(BuiltinError::Type(message).into());
+    };
+
+    match id {
+        BuiltinId::Map => {
+            let mut mapped = Vec::with_capacity(elements.len());
+            for element in &elements {
+                match callback(host, &args[1], std::slice::from_ref(element)) {
+                    Ok(result) => mapped.push(result),
+                    Err(error) => {
+                        release_all(host, mapped);
+                        return Err(error);
+                    }
+                }
+            }
+            let array = host.array(&mapped);
+            release_all(host, mapped);
+            return Ok(array);
+        }
+        BuiltinId::Filter => {
+            let mut kept = Vec::new();
+            for element in elements {
+                if test(host, &args[1], &element)? {
+                    kept.push(element);
+                }
+            }
+            return Ok(host.array(&kept));
+        }
+        BuiltinId::Find => {
+            for element in elements {
+                if test(host, &args[1], &element)? {
+                    return Ok(host.retain(&element));
+                }
+            }
+            return Ok(host.null());
+        }
+        BuiltinId::Any | BuiltinId::All => {
+            // Both stop at the first element that decides the answer.
+            let wanted = id == BuiltinId::Any;
+            for element in elements {
+                if test(host, &args[1], &element)? == wanted {
+                    return Ok(host.boolean(wanted));
+                }
+            }
+            return Ok(host.boolean(!wanted));
+        }
+        BuiltinId::Reduce => {
+            let mut accumulator = host.retain(&args[2]);
+            for element in elements {
+                let result = callback(host, &args[1], &[accumulator.clone(), element]);
+                host.release(accumulator);
+                accumulator = result?;
+            }
+            return Ok(accumulator);
+        }
+        BuiltinId::Sort => {
+            let comparator = args.get(1);
+            let sorted = merge_sort(host, elements, &mut |host, left, right| match comparator {
+                Some(comparator) => compare_with(host, comparator, left, right),
+                None => compare_keys(host, left, right),
+            })?;
+            return Ok(host.array(&sorted));
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn callback<H: Host>(host: &mut H, function: &H::Value, args: &[H::Value]) -> Outcome<H, H::Value> {
+    return host
+        .call(function, args)
+        .map_err(HigherOrderError::Callback);
+}
+
+fn test<H: Host>(host: &mut H, predicate: &H::Value, element: &H::Value) -> Outcome<H, bool> {
+    let result = callback(host, predicate, std::slice::from_ref(element))?;
+    let truthy = host.truthy(&result);
+    host.release(result);
+    return Ok(truthy);
+}
+
+fn release_all<H: Host>(host: &mut H, values: Vec<H::Value>) {
+    for value in values {
+        host.release(value);
+    }
+}
+
+type Compare<'a, H> = dyn FnMut(&mut H, &<H as ValueHost>::Value, &<H as ValueHost>::Value) -> Outcome<H, Ordering>
+    + 'a;
+
+/// A stable merge sort. `slice::sort_by` would do, except that a comparator
+/// here can fail and can be inconsistent, and the comparisons it makes must
+/// not depend on the Rust version.
+fn merge_sort<H: Host>(
+    host: &mut H,
+    mut items: Vec<H::Value>,
+    compare: &mut Compare<'_, H>,
+) -> Outcome<H, Vec<H::Value>> {
+    if items.len() < 2 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(host, items, compare)?;
+    let right = merge_sort(host, right, compare)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        if compare(host, l, r)? == Ordering::Greater {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    return Ok(merged);
+}
+
+fn compare_with<H: Host>(
+    host: &mut H,
+    comparator: &H::Value,
+    left: &H::Value,
+    right: &H::Value,
+) -> Outcome<H, Ordering> {
+    let result = callback(host, comparator, &[left.clone(), right.clone()])?;
+    let ordering = match host.sort_key(&result) {
+        Some(SortKey::Int(value)) => Some(integer::compare(&value, &Int::Small(0))),
+        Some(SortKey::Float(value)) => value.partial_cmp(&0.0),
+        _ => None,
+    };
+    let shown = host.describe(&result);
+    host.release(result);
+    return ordering.ok_or_else(|| {
+        let message = format!("builtin sort comparator must return a number, got {}", shown);
+        return BuiltinError::Type(message).into();
+    });
+}
+
+/// The default order: numbers by value, strings by code point. Anything else,
+/// including a number next to a string, cannot be sorted without a comparator.
+fn compare_keys<H: Host>(host: &mut H, left: &H::Value, right: &H::Value) -> Outcome<H, Ordering> {
+    let ordering = match (host.sort_key(left), host.sort_key(right)) {
+        (Some(SortKey::Int(left)), Some(SortKey::Int(right))) => {
+            Some(integer::compare(&left, &right))
+        }
+        (Some(SortKey::Str(left)), Some(SortKey::Str(right))) => Some(left.cmp(&right)),
+        (Some(SortKey::Int(left)), Some(SortKey::Float(right))) => {
+            left.to_f64().partial_cmp(&right)
+        }
+        (Some(SortKey::Float(left)), Some(SortKey::Int(right))) => {
+            left.partial_cmp(&right.to_f64())
+        }
+        (Some(SortKey::Float(left)), Some(SortKey::Float(right))) => left.partial_cmp(&right),
+        _ => None,
+    };
+    return ordering.ok_or_else(|| {
+        let message = format!(
+            "builtin sort cannot compare {} and {}",
+            host.describe(left),
+            host.describe(right)
+        );
+        return BuiltinError::Type(message).into();
+    });
+}
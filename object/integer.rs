@@ -0,0 +1,93 @@
+//! Integer arithmetic that promotes to arbitrary precision instead of
+//! overflowing. Every backend routes `+ - * /` and unary minus through here so
+//! that they agree on results.
+//!
+//! Results are always narrowed: a value that fits in `i64` comes back as
+//! [`Int::Small`], so a [`Int::Big`] is never equal to any `i64`. Backends rely
+//! on that to keep equality and hashing of their own integer forms simple.
+
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Int {
+    Small(i64),
+    Big(BigInt),
+}
+
+impl Int {
+    pub fn narrow(value: BigInt) -> Int {
+        match value.to_i64() {
+            Some(small) => return Int::Small(small),
+            None => return Int::Big(value),
+        }
+    }
+
+    pub fn to_bigint(&self) -> BigInt {
+        match self {
+            Int::Small(value) => return BigInt::from(*value),
+            Int::Big(value) => return value.clone(),
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Int::Small(value) => return *value as f64,
+            Int::Big(value) => return value.to_f64().unwrap_or(f64::NAN),
+        }
+    }
+}
+
+/// `left op right`, exact. Returns `None` only when dividing by zero; division
+/// truncates toward zero like `i64` division does.
+pub fn apply(op: IntegerOp, left: &Int, right: &Int) -> Option<Int> {
+    if let (Int::Small(l), Int::Small(r)) = (left, right) {
+        let small = match op {
+            IntegerOp::Add => l.checked_add(*r),
+            IntegerOp::Sub => l.checked_sub(*r),
+            IntegerOp::Mul => l.checked_mul(*r),
+            IntegerOp::Div if *r == 0 => return None,
+            IntegerOp::Div => l.checked_div(*r),
+        };
+        if let Some(value) = small {
+            return Some(Int::Small(value));
+        }
+    }
+
+    let (l, r) = (left.to_bigint(), right.to_bigint());
+    let wide = match op {
+        IntegerOp::Add => l + r,
+        IntegerOp::Sub => l - r,
+        IntegerOp::Mul => l * r,
+        IntegerOp::Div if r.is_zero() => return None,
+        IntegerOp::Div => l / r,
+    };
+    return Some(Int::narrow(wide));
+}
+
+pub fn negate(value: &Int) -> Int {
+    match value {
+        Int::Small(v) => match v.checked_neg() {
+            Some(negated) => return Int::Small(negated),
+            None => return Int::narrow(-BigInt::from(*v)),
+        },
+        Int::Big(v) => return Int::narrow(-v.clone()),
+    }
+}
+
+pub fn compare(left: &Int, right: &Int) -> Ordering {
+    match (left, right) {
+        (Int::Small(l), Int::Small(r)) => return l.cmp(r),
+        _ => return left.to_bigint().cmp(&right.to_bigint()),
+    }
+}
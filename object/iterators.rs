@@ -0,0 +1,343 @@
+//! Lazy sequences: `range`, `take`, `zip`, `enumerate` and `to_array`, and
+//! `len`, `first` and `rest` on iterators. An iterator value only describes
+//! its elements; nothing is computed until something reads them, and reading
+//! never consumes it, so the same iterator can be read any number of times.
+//! `len` works its answer out from that description without reading, and
+//! `to_array` refuses iterators longer than 2^24 elements.
+//!
+//! Iterators read from arrays and from other iterators. Like the
+//! higher-order builtins this is written once against [`IterHost`], which
+//! every backend implements over its own values.
+
+use std::convert::TryFrom;
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+use crate::higher_order::ValueHost;
+use crate::integer::Int;
+use crate::persistent::Vector;
+
+/// What an iterator value holds. The sources are arrays or iterators.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Iter<V> {
+    /// `start`, `end` (exclusive) and a step that is never zero.
+    Range(i64, i64, i64),
+    /// At most `count` elements of the source.
+    Take(V, usize),
+    /// The source without its first `count` elements, made by `rest`.
+    Skip(V, usize),
+    /// `[a, b]` pairs, as long as both sources last.
+    Zip(V, V),
+    /// `[index, element]` pairs.
+    Enumerate(V),
+}
+
+impl<V> Iter<V> {
+    /// Visits the values this iterator reads from, in order.
+    pub fn for_each_source(&self, mut visit: impl FnMut(&V)) {
+        match self {
+            Iter::Range(..) => {}
+            Iter::Take(source, _) | Iter::Skip(source, _) | Iter::Enumerate(source) => {
+                visit(source)
+            }
+            Iter::Zip(left, right) => {
+                visit(left);
+                visit(right);
+            }
+        }
+    }
+
+    /// The same iterator over sources converted by `convert`.
+    pub fn map_sources<W>(&self, mut convert: impl FnMut(&V) -> W) -> Iter<W> {
+        match self {
+            Iter::Range(start, end, step) => return Iter::Range(*start, *end, *step),
+            Iter::Take(source, count) => return Iter::Take(convert(source), *count),
+            Iter::Skip(source, count) => return Iter::Skip(convert(source), *count),
+            Iter::Zip(left, right) => return Iter::Zip(convert(left), convert(right)),
+            Iter::Enumerate(source) => return Iter::Enumerate(convert(source)),
+        }
+    }
+}
+
+/// Something an iterator can read from.
+pub enum IterSource<V> {
+    /// The elements of an array, sharing its structure.
+    Array(Vector<V>),
+    Iter(Iter<V>),
+}
+
+/// A backend's view of its values for the iterator builtins.
+pub trait IterHost: ValueHost {
+    /// The value as a source; `None` for anything but arrays and iterators.
+    fn source(&self, value: &Self::Value) -> Option<IterSource<Self::Value>>;
+    /// Small integers, for `range` and arguments such as `take`'s count.
+    fn integer(&self, value: &Self::Value) -> Option<Int>;
+    fn int(&mut self, value: i64) -> Self::Value;
+    fn iterator(&mut self, iter: Iter<Self::Value>) -> Self::Value;
+}
+
+pub fn is_iterator_builtin(id: BuiltinId) -> bool {
+    return matches!(
+        id,
+        BuiltinId::Range
+            | BuiltinId::Take
+            | BuiltinId::Zip
+            | BuiltinId::Enumerate
+            | BuiltinId::ToArray
+    );
+}
+
+fn name(id: BuiltinId) -> &'static str {
+    match id {
+        BuiltinId::Range => return "range",
+        BuiltinId::Take => return "take",
+        BuiltinId::Zip => return "zip",
+        BuiltinId::Enumerate => return "enumerate",
+        BuiltinId::ToArray => return "to_array",
+        BuiltinId::Len => return "len",
+        BuiltinId::First => return "first",
+        BuiltinId::Rest => return "rest",
+        _ => panic!("not an iterator builtin"),
+    }
+}
+
+/// Runs the iterator builtin `id`, or `len`, `first` or `rest` with an
+/// iterator as the argument, returning an owned value.
+pub fn call<H: IterHost>(
+    host: &mut H,
+    id: BuiltinId,
+    args: &[H::Value],
+) -> Result<H::Value, BuiltinError> {
+    let name = name(id);
+    let unsupported = |host: &H, value: &H::Value| {
+        let message = format!("builtin {} not supported for type {}", name, host.describe(value));
+        return BuiltinError::Type(message);
+    };
+
+    if id == BuiltinId::Range {
+        check_arity(name, 1, 3, args.len())?;
+        let mut bounds = Vec::with_capacity(3);
+        for arg in args {
+            match host.integer(arg) {
+                Some(Int::Small(value)) => bounds.push(value),
+                _ => return Err(unsupported(host, arg)),
+            }
+        }
+        let (start, end, step) = match bounds[..] {
+            [end] => (0, end, 1),
+            [start, end] => (start, end, 1),
+            [start, end, step] => (start, end, step),
+            _ => unreachable!(),
+        };
+        if step == 0 {
+            return Err(BuiltinError::Type("builtin range step must not be zero".to_string()));
+        }
+        return Ok(host.iterator(Iter::Range(start, end, step)));
+    }
+
+    let arity = match id {
+        BuiltinId::Take | BuiltinId::Zip => 2,
+        _ => 1,
+    };
+    check_arity(name, arity, arity, args.len())?;
+    let Some(mut cursor) = open(host, &args[0]) else {
+        return Err(unsupported(host, &args[0]));
+    };
+
+    match id {
+        BuiltinId::Take => {
+            let count = match host.integer(&args[1]) {
+                Some(Int::Small(count)) if count >= 0 => count as usize,
+                _ => return Err(unsupported(host, &args[1])),
+            };
+            return Ok(host.iterator(Iter::Take(args[0].clone(), count)));
+        }
+        BuiltinId::Zip => {
+            if open(host, &args[1]).is_none() {
+                return Err(unsupported(host, &args[1]));
+            }
+            return Ok(host.iterator(Iter::Zip(args[0].clone(), args[1].clone())));
+        }
+        BuiltinId::Enumerate => return Ok(host.iterator(Iter::Enumerate(args[0].clone()))),
+        BuiltinId::ToArray => {
+            if length(host, &args[0]).is_some_and(|length| length > MAX_ARRAY_ELEMENTS) {
+                return Err(BuiltinError::Type(format!(
+                    "builtin to_array iterator has more than {} elements",
+                    MAX_ARRAY_ELEMENTS
+                )));
+            }
+            let mut elements = Vec::new();
+            while let Some(element) = next(host, &mut cursor) {
+                elements.push(element);
+            }
+            let array = host.array(&elements);
+            for element in elements {
+                host.release(element);
+            }
+            return Ok(array);
+        }
+        BuiltinId::Len => match length(host, &args[0]).map(i64::try_from) {
+            Some(Ok(length)) => return Ok(host.int(length)),
+            _ => {
+                return Err(BuiltinError::Type(
+                    "builtin len iterator has too many elements to count".to_string(),
+                ));
+            }
+        },
+        BuiltinId::First => match next(host, &mut cursor) {
+            Some(element) => return Ok(element),
+            None => return Ok(host.null()),
+        },
+        BuiltinId::Rest => {
+            // Like `rest` on an array: nothing left of an empty one.
+            let Some(element) = next(host, &mut cursor) else {
+                return Ok(host.null());
+            };
+            host.release(element);
+            // Keep `rest(rest(...))` one level deep, so reading a sequence
+            // with `first` and `rest` stays linear.
+            let rest = match host.source(&args[0]) {
+                Some(IterSource::Iter(Iter::Range(start, end, step))) => {
+                    Iter::Range(start.checked_add(step).unwrap_or(end), end, step)
+                }
+                Some(IterSource::Iter(Iter::Skip(source, count))) => {
+                    Iter::Skip(source, count.saturating_add(1))
+                }
+                _ => Iter::Skip(args[0].clone(), 1),
+            };
+            return Ok(host.iterator(rest));
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// The most elements `to_array` builds an array of.
+const MAX_ARRAY_ELEMENTS: u128 = 1 << 24;
+
+/// How many elements `value` reads, worked out from its description rather
+/// than by reading them. `None` for anything but arrays and iterators.
+fn length<H: IterHost>(host: &H, value: &H::Value) -> Option<u128> {
+    let length = match host.source(value)? {
+        IterSource::Array(elements) => elements.len() as u128,
+        IterSource::Iter(Iter::Range(start, end, step)) => {
+            let (start, end, step) = (start as i128, end as i128, step as i128);
+            if (step > 0 && start < end) || (step < 0 && start > end) {
+                ((end - start - step.signum()) / step + 1) as u128
+            } else {
+                0
+            }
+        }
+        IterSource::Iter(Iter::Take(source, count)) => length(host, &source)?.min(count as u128),
+        IterSource::Iter(Iter::Skip(source, count)) => {
+            length(host, &source)?.saturating_sub(count as u128)
+        }
+        IterSource::Iter(Iter::Zip(left, right)) => length(host, &left)?.min(length(host, &right)?),
+        IterSource::Iter(Iter::Enumerate(source)) => length(host, &source)?,
+    };
+    return Some(length);
+}
+
+/// Reading position in a source.
+enum Cursor<V> {
+    /// The next element, or `None` once stepping past it would overflow.
+    Range(Option<i64>, i64, i64),
+    /// The elements and the index of the next one.
+    Array(Vector<V>, usize),
+    Take(Box<Cursor<V>>, usize),
+    Zip(Box<Cursor<V>>, Box<Cursor<V>>),
+    Enumerate(Box<Cursor<V>>, i64),
+}
+
+fn open<H: IterHost>(host: &H, value: &H::Value) -> Option<Cursor<H::Value>> {
+    let cursor = match host.source(value)? {
+        IterSource::Array(elements) => Cursor::Array(elements, 0),
+        IterSource::Iter(Iter::Range(start, end, step)) => Cursor::Range(Some(start), end, step),
+        IterSource::Iter(Iter::Take(source, count)) => {
+            Cursor::Take(Box::new(open(host, &source)?), count)
+        }
+        IterSource::Iter(Iter::Skip(source, count)) => {
+            let mut cursor = open(host, &source)?;
+            skip(&mut cursor, count);
+            cursor
+        }
+        IterSource::Iter(Iter::Zip(left, right)) => {
+            Cursor::Zip(Box::new(open(host, &left)?), Box::new(open(host, &right)?))
+        }
+        IterSource::Iter(Iter::Enumerate(source)) => {
+            Cursor::Enumerate(Box::new(open(host, &source)?), 0)
+        }
+    };
+    return Some(cursor);
+}
+
+/// Moves past `count` elements without reading them.
+fn skip<V>(cursor: &mut Cursor<V>, count: usize) {
+    match cursor {
+        Cursor::Range(current, _, step) => {
+            *current = current.and_then(|value| {
+                let target = value as i128 + count as i128 * *step as i128;
+                return i64::try_from(target).ok();
+            });
+        }
+        Cursor::Array(_, index) => *index = index.saturating_add(count),
+        Cursor::Take(source, remaining) => {
+            let count = count.min(*remaining);
+            *remaining -= count;
+            skip(source, count);
+        }
+        Cursor::Zip(left, right) => {
+            skip(left, count);
+            skip(right, count);
+        }
+        Cursor::Enumerate(source, index) => {
+            skip(source, count);
+            *index = index.saturating_add(count as i64);
+        }
+    }
+}
+
+/// The next element, owned.
+fn next<H: IterHost>(host: &mut H, cursor: &mut Cursor<H::Value>) -> Option<H::Value> {
+    match cursor {
+        Cursor::Range(current, end, step) => {
+            let value = (*current)?;
+            if (*step > 0 && value >= *end) || (*step < 0 && value <= *end) {
+                return None;
+            }
+            *current = value.checked_add(*step);
+            return Some(host.int(value));
+        }
+        Cursor::Array(elements, index) => {
+            let element = elements.get(*index)?;
+            *index += 1;
+            return Some(host.retain(element));
+        }
+        Cursor::Take(source, remaining) => {
+            if *remaining == 0 {
+                return None;
+            }
+            *remaining -= 1;
+            return next(host, source);
+        }
+        Cursor::Zip(left, right) => {
+            let left = next(host, left)?;
+            let Some(right) = next(host, right) else {
+                host.release(left);
+                return None;
+            };
+            return Some(pair(host, left, right));
+        }
+        Cursor::Enumerate(source, index) => {
+            let element = next(host, source)?;
+            let position = host.int(*index);
+            *index += 1;
+            return Some(pair(host, position, element));
+        }
+    }
+}
+
+fn pair<H: IterHost>(host: &mut H, first: H::Value, second: H::Value) -> H::Value {
+    let pair = host.array(&[first.clone(), second.clone()]);
+    host.release(first);
+    host.release(second);
+    return pair;
+}
@@ -0,0 +1,270 @@
+//! `json_encode` and `json_decode`. Like the other shared libraries, every
+//! backend describes its values to [`encode`] through a [`JsonView`] and
+//! builds its own values back from the [`Json`] tree [`decode`] returns.
+//!
+//! Integers, floats, strings, booleans, `null`, arrays and hashes map to
+//! their JSON counterparts; functions, classes and instances have none and
+//! are refused. JSON object keys are strings, so integer and boolean hash
+//! keys are written the way `puts` shows them, and decoding always produces
+//! string keys. Objects keep their key order in both directions.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+
+use num_bigint::BigInt;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+use crate::format_float;
+use crate::integer::Int;
+
+/// One value as [`encode`] sees it.
+pub enum JsonView<N> {
+    Null,
+    Bool(bool),
+    Int(Int),
+    Float(f64),
+    Str(String),
+    /// An array and an id that stays unique to it while it is being encoded,
+    /// used to refuse values that contain themselves.
+    Array(usize, Vec<N>),
+    /// A hash with its keys already rendered as strings, and an id as for
+    /// `Array`.
+    Hash(usize, Vec<(String, N)>),
+    /// A value JSON has no form for, rendered the way `puts` shows it.
+    Other(String),
+}
+
+/// A decoded document.
+#[derive(Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(Int),
+    Float(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Hash(Vec<(String, Json)>),
+}
+
+pub enum JsonArg<'a> {
+    Str(&'a str),
+    /// Any other value, rendered the way `puts` shows it.
+    Other(String),
+}
+
+pub fn is_json_builtin(id: BuiltinId) -> bool {
+    return id == BuiltinId::JsonEncode || id == BuiltinId::JsonDecode;
+}
+
+/// `json_encode`: the compact JSON text for `args[0]`.
+pub fn encode<N>(args: &[N], view: impl Fn(&N) -> JsonView<N>) -> Result<String, BuiltinError> {
+    check_arity("json_encode", 1, 1, args.len())?;
+    let mut out = String::new();
+    write_value(&args[0], &view, &mut Vec::new(), &mut out)?;
+    return Ok(out);
+}
+
+fn write_value<N>(
+    node: &N,
+    view: &impl Fn(&N) -> JsonView<N>,
+    open: &mut Vec<usize>,
+    out: &mut String,
+) -> Result<(), BuiltinError> {
+    match view(node) {
+        JsonView::Null => out.push_str("null"),
+        JsonView::Bool(value) => out.push_str(if value { "true" } else { "false" }),
+        JsonView::Int(Int::Small(value)) => out.push_str(&value.to_string()),
+        JsonView::Int(Int::Big(value)) => out.push_str(&value.to_string()),
+        JsonView::Float(value) => {
+            if !value.is_finite() {
+                return Err(cannot_encode(&format_float(value)));
+            }
+            out.push_str(&serde_json::to_string(&value).expect("finite floats encode"));
+        }
+        JsonView::Str(text) => write_string(&text, out),
+        JsonView::Array(id, items) => {
+            enter(id, open)?;
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_value(item, view, open, out)?;
+            }
+            out.push(']');
+            open.pop();
+        }
+        JsonView::Hash(id, entries) => {
+            enter(id, open)?;
+            out.push('{');
+            for (index, (key, value)) in entries.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_string(key, out);
+                out.push(':');
+                write_value(value, view, open, out)?;
+            }
+            out.push('}');
+            open.pop();
+        }
+        JsonView::Other(shown) => return Err(cannot_encode(&shown)),
+    }
+    return Ok(());
+}
+
+fn enter(id: usize, open: &mut Vec<usize>) -> Result<(), BuiltinError> {
+    if open.contains(&id) {
+        return Err(BuiltinError::Type(
+            "builtin json_encode cannot encode a value that contains itself".to_string(),
+        ));
+    }
+    open.push(id);
+    return Ok(());
+}
+
+fn write_string(text: &str, out: &mut String) {
+    out.push_str(&serde_json::to_string(text).expect("strings encode"));
+}
+
+fn cannot_encode(shown: &str) -> BuiltinError {
+    return BuiltinError::Type(format!("builtin json_encode cannot encode {}", shown));
+}
+
+/// `json_decode`: the document in `args[0]`. Numbers without a fraction or
+/// exponent decode as integers however many digits they have, anything else
+/// as a float.
+pub fn decode(args: &[JsonArg<'_>]) -> Result<Json, BuiltinError> {
+    check_arity("json_decode", 1, 1, args.len())?;
+    let text = match &args[0] {
+        JsonArg::Str(text) => *text,
+        JsonArg::Other(shown) => {
+            return Err(BuiltinError::Type(format!(
+                "builtin json_decode not supported for type {}",
+                shown
+            )));
+        }
+    };
+    let floats = RefCell::new(float_literals(text));
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let decoded = JsonSeed {
+        floats: &floats,
+    }
+    .deserialize(&mut deserializer)
+    .and_then(|json| deserializer.end().map(|()| json));
+    return decoded.map_err(|error| {
+        BuiltinError::Type(format!("builtin json_decode got invalid JSON: {}", error))
+    });
+}
+
+/// The number literals in `text` that serde_json reads through `visit_f64`,
+/// in document order: `Some` holds the exact value of an integer too long
+/// for 64 bits, `None` stands for a real float (serde_json reads `-0` as
+/// one too). The scan assumes valid JSON; anything else serde_json rejects
+/// before the list matters.
+fn float_literals(text: &str) -> VecDeque<Option<BigInt>> {
+    let mut literals = VecDeque::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '"' {
+            while let Some((_, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' => break,
+                    _ => {}
+                }
+            }
+        } else if c == '-' || c.is_ascii_digit() {
+            let mut end = start + c.len_utf8();
+            while let Some(&(at, c)) = chars.peek() {
+                if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                    break;
+                }
+                end = at + c.len_utf8();
+                chars.next();
+            }
+            let literal = &text[start..end];
+            if literal.contains(['.', 'e', 'E']) || literal == "-0" {
+                literals.push_back(None);
+            } else if literal.parse::<i64>().is_err() && literal.parse::<u64>().is_err() {
+                literals.push_back(literal.parse::<BigInt>().ok());
+            }
+        }
+    }
+    return literals;
+}
+
+/// Builds a [`Json`] tree, swapping each float serde_json reports for an
+/// overlong integer literal back to its exact value from `floats`.
+#[derive(Clone, Copy)]
+struct JsonSeed<'a> {
+    floats: &'a RefCell<VecDeque<Option<BigInt>>>,
+}
+
+impl<'de> DeserializeSeed<'de> for JsonSeed<'_> {
+    type Value = Json;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Json, D::Error> {
+        return deserializer.deserialize_any(self);
+    }
+}
+
+impl<'de> Visitor<'de> for JsonSeed<'_> {
+    type Value = Json;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Json, E> {
+        return Ok(Json::Null);
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Json, E> {
+        return Ok(Json::Bool(value));
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Json, E> {
+        return Ok(Json::Int(Int::Small(value)));
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Json, E> {
+        return Ok(Json::Int(Int::narrow(BigInt::from(value))));
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Json, E> {
+        return match self.floats.borrow_mut().pop_front() {
+            Some(Some(exact)) => Ok(Json::Int(Int::narrow(exact))),
+            _ => Ok(Json::Float(value)),
+        };
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Json, E> {
+        return Ok(Json::Str(value.to_string()));
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Json, E> {
+        return Ok(Json::Str(value));
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(self)? {
+            items.push(item);
+        }
+        return Ok(Json::Array(items));
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(self)?;
+            entries.push((key, value));
+        }
+        return Ok(Json::Hash(entries));
+    }
+}
@@ -0,0 +1,171 @@
+//! The math builtins (`abs`, `min`, `max`, `pow`, `sqrt`, `floor`, `ceil`,
+//! `round`). As with the string library, every backend decodes its values
+//! into [`NumArg`], calls [`call`] and encodes the [`NumResult`] back.
+//!
+//! Integers stay exact: `abs`, `min`, `max` and `pow` with a non-negative
+//! integer exponent return integers, promoting to big integers like the
+//! arithmetic operators do. `floor`, `ceil` and `round` turn floats into
+//! integers (`null` for NaN and infinities, like `int`), and `sqrt` always
+//! returns a float.
+
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, Signed};
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+use crate::integer::{self, Int};
+
+/// `pow` refuses integer results wider than this many bits rather than
+/// trying to allocate them.
+const MAX_POW_BITS: u64 = 1 << 20;
+
+pub enum NumArg {
+    Int(Int),
+    Float(f64),
+    /// Any other value, rendered the way `puts` shows it.
+    Other(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NumResult {
+    Int(Int),
+    Float(f64),
+    Null,
+}
+
+pub fn is_math_builtin(id: BuiltinId) -> bool {
+    return name(id).is_some();
+}
+
+fn name(id: BuiltinId) -> Option<&'static str> {
+    let name = match id {
+        BuiltinId::Abs => "abs",
+        BuiltinId::Min => "min",
+        BuiltinId::Max => "max",
+        BuiltinId::Pow => "pow",
+        BuiltinId::Sqrt => "sqrt",
+        BuiltinId::Floor => "floor",
+        BuiltinId::Ceil => "ceil",
+        BuiltinId::Round => "round",
+        _ => return None,
+    };
+    return Some(name);
+}
+
+/// Runs the math builtin `id`. Panics if `id` is not one
+/// (see [`is_math_builtin`]).
+pub fn call(id: BuiltinId, args: Vec<NumArg>) -> Result<NumResult, BuiltinError> {
+    let name = name(id).expect("not a math builtin");
+    let (min, max) = match id {
+        BuiltinId::Min | BuiltinId::Max => (1, usize::MAX),
+        BuiltinId::Pow => (2, 2),
+        _ => (1, 1),
+    };
+    check_arity(name, min, max, args.len())?;
+    let mut numbers = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            NumArg::Int(value) => numbers.push(NumResult::Int(value)),
+            NumArg::Float(value) => numbers.push(NumResult::Float(value)),
+            NumArg::Other(shown) => {
+                let message = format!("builtin {} not supported for type {}", name, shown);
+                return Err(BuiltinError::Type(message));
+            }
+        }
+    }
+
+    let mut numbers = numbers.into_iter();
+    let first = numbers.next().expect("arity checked");
+    let result = match id {
+        BuiltinId::Abs => match first {
+            NumResult::Int(value) if is_negative(&value) => NumResult::Int(integer::negate(&value)),
+            NumResult::Float(value) => NumResult::Float(value.abs()),
+            other => other,
+        },
+        BuiltinId::Min | BuiltinId::Max => {
+            let wanted = if id == BuiltinId::Min { Ordering::Less } else { Ordering::Greater };
+            let mut best = first;
+            for number in numbers {
+                match compare(&number, &best) {
+                    Some(ordering) if ordering == wanted => best = number,
+                    Some(_) => {}
+                    None => return Ok(NumResult::Float(f64::NAN)),
+                }
+            }
+            best
+        }
+        BuiltinId::Pow => {
+            let exponent = numbers.next().expect("arity checked");
+            match (&first, &exponent) {
+                (NumResult::Int(base), NumResult::Int(exponent)) if !is_negative(exponent) => {
+                    NumResult::Int(int_pow(name, base, exponent)?)
+                }
+                _ => NumResult::Float(to_f64(&first).powf(to_f64(&exponent))),
+            }
+        }
+        BuiltinId::Sqrt => NumResult::Float(to_f64(&first).sqrt()),
+        _ => match first {
+            NumResult::Float(value) if !value.is_finite() => NumResult::Null,
+            NumResult::Float(value) => {
+                let rounded = match id {
+                    BuiltinId::Floor => value.floor(),
+                    BuiltinId::Ceil => value.ceil(),
+                    // halfway cases round away from zero
+                    _ => value.round(),
+                };
+                let exact = BigInt::from_f64(rounded).expect("finite float is an integer");
+                NumResult::Int(Int::narrow(exact))
+            }
+            other => other,
+        },
+    };
+    return Ok(result);
+}
+
+fn is_negative(value: &Int) -> bool {
+    match value {
+        Int::Small(value) => return *value < 0,
+        Int::Big(value) => return value.is_negative(),
+    }
+}
+
+fn to_f64(number: &NumResult) -> f64 {
+    match number {
+        NumResult::Int(value) => return value.to_f64(),
+        NumResult::Float(value) => return *value,
+        NumResult::Null => return f64::NAN,
+    }
+}
+
+/// Exact for two integers, widened to floats otherwise; `None` if either side
+/// is NaN.
+fn compare(left: &NumResult, right: &NumResult) -> Option<Ordering> {
+    match (left, right) {
+        (NumResult::Int(left), NumResult::Int(right)) => {
+            return Some(integer::compare(left, right))
+        }
+        _ => return to_f64(left).partial_cmp(&to_f64(right)),
+    }
+}
+
+fn int_pow(name: &str, base: &Int, exponent: &Int) -> Result<Int, BuiltinError> {
+    if *exponent == Int::Small(0) {
+        return Ok(Int::Small(1));
+    }
+    let base = base.to_bigint();
+    // 0, 1 and -1 stay small for any exponent; only the parity matters.
+    if base.magnitude().bits() <= 1 {
+        let odd = exponent.to_bigint() % 2 != BigInt::from(0);
+        return Ok(Int::narrow(base.pow(if odd { 1u32 } else { 2u32 })));
+    }
+    let exponent = match exponent {
+        Int::Small(value)
+            if base.magnitude().bits().saturating_mul(*value as u64) <= MAX_POW_BITS =>
+        {
+            *value as u32
+        }
+        _ => return Err(BuiltinError::Type(format!("builtin {} result is too large", name))),
+    };
+    return Ok(Int::narrow(base.pow(exponent)));
+}
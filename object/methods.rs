@@ -0,0 +1,81 @@
+//! Methods on strings, arrays and hashes. Reading `name` off one of these
+//! values gives the builtin of that name with the value bound as its first
+//! argument, so `arr.push(4)` is `push(arr, 4)` and `s.split(",")` is
+//! `split(s, ",")`. Every backend looks names up here, so the same methods
+//! exist everywhere.
+
+use crate::builtins::{BuiltIns, BuiltinId};
+
+/// The kinds of value that have methods.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Receiver {
+    String,
+    Array,
+    Hash,
+}
+
+impl Receiver {
+    /// The name `type_of` reports for the receiver, used when showing a
+    /// bound method.
+    pub fn name(self) -> &'static str {
+        match self {
+            Receiver::String => return "string",
+            Receiver::Array => return "array",
+            Receiver::Hash => return "hash",
+        }
+    }
+}
+
+const STRING_METHODS: &[BuiltinId] = &[
+    BuiltinId::Len,
+    BuiltinId::Split,
+    BuiltinId::Trim,
+    BuiltinId::Upper,
+    BuiltinId::Lower,
+    BuiltinId::Contains,
+    BuiltinId::StartsWith,
+    BuiltinId::EndsWith,
+    BuiltinId::Replace,
+    BuiltinId::Substr,
+    BuiltinId::IndexOf,
+    BuiltinId::Chars,
+    BuiltinId::Int,
+];
+
+const ARRAY_METHODS: &[BuiltinId] = &[
+    BuiltinId::Len,
+    BuiltinId::First,
+    BuiltinId::Last,
+    BuiltinId::Rest,
+    BuiltinId::Push,
+    BuiltinId::Join,
+    BuiltinId::Map,
+    BuiltinId::Filter,
+    BuiltinId::Reduce,
+    BuiltinId::Sort,
+    BuiltinId::Find,
+    BuiltinId::Any,
+    BuiltinId::All,
+];
+
+const HASH_METHODS: &[BuiltinId] = &[
+    BuiltinId::Keys,
+    BuiltinId::Values,
+    BuiltinId::Entries,
+    BuiltinId::Has,
+    BuiltinId::Delete,
+    BuiltinId::Merge,
+];
+
+/// The builtin `receiver.name` binds to, if there is one.
+pub fn lookup(receiver: Receiver, name: &str) -> Option<BuiltinId> {
+    let methods = match receiver {
+        Receiver::String => STRING_METHODS,
+        Receiver::Array => ARRAY_METHODS,
+        Receiver::Hash => HASH_METHODS,
+    };
+    return BuiltIns
+        .iter()
+        .find(|definition| definition.name == name && methods.contains(&definition.id))
+        .map(|definition| definition.id);
+}
@@ -11,11 +11,32 @@ use parser::ast::{BlockStatement, IDENTIFIER};
 extern crate lazy_static;
 
 use crate::environment::Env;
+use crate::generators::GeneratorRef;
+use crate::integer::Int;
+use crate::iterators::Iter;
+use crate::methods::Receiver;
+use crate::registry::NativeBuiltin;
 
 pub mod builtins;
 pub mod environment;
+pub mod generators;
+pub mod hashes;
+pub mod higher_order;
+pub mod integer;
+pub mod iterators;
+pub mod json;
+pub mod math;
+pub mod methods;
+pub mod output;
+pub mod persistent;
+pub mod random;
+pub mod reflect;
+pub mod registry;
+pub mod strings;
+
+pub use num_bigint::BigInt;
+pub use persistent::{OrderedMap, Vector};
 
-pub type EvalError = String;
 pub type BuiltinFunc = fn(Vec<Rc<Object>>) -> Rc<Object>;
 
 pub type ClassRef = Rc<RefCell<ClassObject>>;
@@ -24,20 +45,34 @@ pub type InstanceRef = Rc<RefCell<InstanceObject>>;
 #[derive(Clone)]
 pub enum Object {
     Integer(i64),
+    // only ever holds values outside the `i64` range, see `integer`
+    BigInt(BigInt),
+    Float(f64),
     Boolean(bool),
     String(String),
-    Array(Vec<Rc<Object>>),
-    Hash(HashMap<Rc<Object>, Rc<Object>>),
+    Array(Vector<Rc<Object>>),
+    /// Entries in insertion order; equality ignores the order.
+    Hash(OrderedMap<Rc<Object>, Rc<Object>>),
     Null,
     ReturnValue(Rc<Object>),
     Function(Vec<IDENTIFIER>, BlockStatement, Env),
+    /// A `fn*` function in the interpreter; calling it makes a `Generator`.
+    GeneratorFunction(Vec<IDENTIFIER>, BlockStatement, Env),
     Builtin(BuiltinFunc),
+    /// A builtin the host registered, see `registry`.
+    Native(Rc<NativeBuiltin>),
     Error(String),
     CompiledFunction(Rc<CompiledFunction>),
     ClosureObj(Closure),
     Class(ClassRef),
     Instance(InstanceRef),
     BoundMethod(Rc<BoundMethodObject>),
+    /// A builtin read off a string, array or hash, see `methods`.
+    BoundBuiltin(Rc<BoundBuiltinObject>),
+    /// A lazy sequence from `range` and friends, see `iterators`.
+    Iterator(Rc<Iter<Rc<Object>>>),
+    /// A suspended `fn*` body, see `generators`.
+    Generator(GeneratorRef),
 }
 
 #[derive(Clone)]
@@ -60,10 +95,20 @@ pub struct BoundMethodObject {
     pub name: String,
 }
 
+#[derive(Clone)]
+pub struct BoundBuiltinObject {
+    pub receiver: Rc<Object>,
+    pub kind: Receiver,
+    pub name: &'static str,
+    pub function: BuiltinFunc,
+}
+
 impl fmt::Display for Object {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Object::Integer(i) => write!(f, "{}", i),
+            Object::BigInt(i) => write!(f, "{}", i),
+            Object::Float(x) => write!(f, "{}", format_float(*x)),
             Object::Boolean(b) => write!(f, "{}", b),
             Object::String(s) => write!(f, "{}", s),
             Object::Null => write!(f, "null"),
@@ -76,7 +121,15 @@ impl fmt::Display for Object {
                     .join(", ");
                 write!(f, "fn({}) {{ {} }}", func_params, body)
             }
-            Object::Builtin(_) => write!(f, "[builtin function]"),
+            Object::GeneratorFunction(params, body, _env) => {
+                let func_params = params
+                    .iter()
+                    .map(|stmt| stmt.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "fn*({}) {{ {} }}", func_params, body)
+            }
+            Object::Builtin(_) | Object::Native(_) => write!(f, "[builtin function]"),
             Object::Error(e) => write!(f, "{}", e),
             Object::Array(e) => write!(
                 f,
@@ -108,6 +161,11 @@ impl fmt::Display for Object {
                 let class_name = method.receiver.borrow().class.borrow().name.clone();
                 write!(f, "[bound method {}.{}]", class_name, method.name)
             }
+            Object::BoundBuiltin(method) => {
+                write!(f, "[bound method {}.{}]", method.kind.name(), method.name)
+            }
+            Object::Iterator(_) => write!(f, "[iterator]"),
+            Object::Generator(_) => write!(f, "[generator]"),
         }
     }
 }
@@ -116,6 +174,8 @@ impl fmt::Debug for Object {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Object::Integer(value) => f.debug_tuple("Integer").field(value).finish(),
+            Object::BigInt(value) => f.debug_tuple("BigInt").field(value).finish(),
+            Object::Float(value) => f.debug_tuple("Float").field(value).finish(),
             Object::Boolean(value) => f.debug_tuple("Boolean").field(value).finish(),
             Object::String(value) => f.debug_tuple("String").field(value).finish(),
             Object::Array(value) => f.debug_tuple("Array").field(value).finish(),
@@ -127,15 +187,24 @@ impl fmt::Debug for Object {
                 .field("params", params)
                 .field("body", body)
                 .finish_non_exhaustive(),
+            Object::GeneratorFunction(params, body, _) => f
+                .debug_struct("GeneratorFunction")
+                .field("params", params)
+                .field("body", body)
+                .finish_non_exhaustive(),
             Object::Builtin(_) => write!(f, "Builtin([function])"),
+            Object::Native(native) => f.debug_tuple("Native").field(&native.name).finish(),
             Object::Error(value) => f.debug_tuple("Error").field(value).finish(),
             Object::CompiledFunction(value) => {
                 f.debug_tuple("CompiledFunction").field(value).finish()
             }
             Object::ClosureObj(value) => f.debug_tuple("ClosureObj").field(value).finish(),
-            Object::Class(_) | Object::Instance(_) | Object::BoundMethod(_) => {
-                write!(f, "{}", self)
-            }
+            Object::Iterator(value) => f.debug_tuple("Iterator").field(value).finish(),
+            Object::Class(_)
+            | Object::Instance(_)
+            | Object::BoundMethod(_)
+            | Object::BoundBuiltin(_)
+            | Object::Generator(_) => write!(f, "{}", self),
         }
     }
 }
@@ -144,6 +213,11 @@ impl PartialEq for Object {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Object::Integer(left), Object::Integer(right)) => left == right,
+            (Object::BigInt(left), Object::BigInt(right)) => left == right,
+            (Object::Float(_), _) | (_, Object::Float(_)) => match float_operands(self, other) {
+                Some((left, right)) => left == right,
+                None => false,
+            },
             (Object::Boolean(left), Object::Boolean(right)) => left == right,
             (Object::String(left), Object::String(right)) => left == right,
             (Object::Array(left), Object::Array(right)) => left == right,
@@ -158,13 +232,25 @@ impl PartialEq for Object {
                     && left_body == right_body
                     && Rc::ptr_eq(left_env, right_env)
             }
+            (
+                Object::GeneratorFunction(left_params, left_body, left_env),
+                Object::GeneratorFunction(right_params, right_body, right_env),
+            ) => {
+                left_params == right_params
+                    && left_body == right_body
+                    && Rc::ptr_eq(left_env, right_env)
+            }
             (Object::Builtin(left), Object::Builtin(right)) => std::ptr::fn_addr_eq(*left, *right),
+            (Object::Native(left), Object::Native(right)) => Rc::ptr_eq(left, right),
             (Object::Error(left), Object::Error(right)) => left == right,
             (Object::CompiledFunction(left), Object::CompiledFunction(right)) => left == right,
             (Object::ClosureObj(left), Object::ClosureObj(right)) => left == right,
             (Object::Class(left), Object::Class(right)) => Rc::ptr_eq(left, right),
             (Object::Instance(left), Object::Instance(right)) => Rc::ptr_eq(left, right),
             (Object::BoundMethod(left), Object::BoundMethod(right)) => Rc::ptr_eq(left, right),
+            (Object::BoundBuiltin(left), Object::BoundBuiltin(right)) => Rc::ptr_eq(left, right),
+            (Object::Iterator(left), Object::Iterator(right)) => Rc::ptr_eq(left, right),
+            (Object::Generator(left), Object::Generator(right)) => Rc::ptr_eq(left, right),
             _ => false,
         }
     }
@@ -175,16 +261,56 @@ impl Eq for Object {}
 impl Object {
     pub fn is_hashable(&self) -> bool {
         match self {
-            Object::Integer(_) | Object::Boolean(_) | Object::String(_) => return true,
+            Object::Integer(_) | Object::BigInt(_) | Object::Boolean(_) | Object::String(_) => {
+                return true
+            }
             _ => return false,
         }
     }
+
+    /// The value of an `Integer` or `BigInt`, for the shared arithmetic in
+    /// `integer`.
+    pub fn as_int(&self) -> Option<Int> {
+        match self {
+            Object::Integer(value) => return Some(Int::Small(*value)),
+            Object::BigInt(value) => return Some(Int::Big(value.clone())),
+            _ => return None,
+        }
+    }
+}
+
+impl From<Int> for Object {
+    fn from(value: Int) -> Self {
+        match value {
+            Int::Small(value) => return Object::Integer(value),
+            Int::Big(value) => return Object::BigInt(value),
+        }
+    }
+}
+
+/// Render a float so it never reads as an integer: `1.0`, `0.5`, `1e20`,
+/// `NaN`, `inf`. Every backend prints floats through this.
+pub fn format_float(value: f64) -> String {
+    return format!("{:?}", value);
+}
+
+/// Both operands widened to `f64` when at least one is a float and the other
+/// is a number. Integer-only operations return `None` and keep their exact
+/// integer semantics.
+pub fn float_operands(left: &Object, right: &Object) -> Option<(f64, f64)> {
+    match (left, right) {
+        (Object::Float(l), Object::Float(r)) => Some((*l, *r)),
+        (Object::Float(l), r) => Some((*l, r.as_int()?.to_f64())),
+        (l, Object::Float(r)) => Some((l.as_int()?.to_f64(), *r)),
+        _ => None,
+    }
 }
 
 impl Hash for Object {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
             Object::Integer(i) => i.hash(state),
+            Object::BigInt(i) => i.hash(state),
             Object::Boolean(b) => b.hash(state),
             Object::String(s) => s.hash(state),
             t => panic!("can't hashable for {}", t),
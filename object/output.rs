@@ -0,0 +1,79 @@
+//! Where `puts` and `print` write. Each argument becomes one line. The
+//! interpreter keeps its sink in the environment, the VMs in the VM and the
+//! asm runtime passes one to its builtins. All of them write to stdout
+//! unless the host hands them another sink, so a host embedding a runtime
+//! can keep Monkey's output apart from its own.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+pub trait OutputSink {
+    fn write_line(&mut self, line: &str);
+}
+
+/// The process's stdout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write_line(&mut self, line: &str) {
+        println!("{}", line);
+    }
+}
+
+/// Collects the lines, each ending in `\n`.
+impl OutputSink for String {
+    fn write_line(&mut self, line: &str) {
+        self.push_str(line);
+        self.push('\n');
+    }
+}
+
+/// A sink shared between a runtime and its host, so it outlives one run and
+/// the host can read what a buffer collected.
+#[derive(Clone)]
+pub struct Output(Rc<RefCell<dyn OutputSink>>);
+
+impl Output {
+    pub fn new(sink: impl OutputSink + 'static) -> Output {
+        return Output(Rc::new(RefCell::new(sink)));
+    }
+
+    /// Writes to the sink `sink` holds, which the caller can still read.
+    pub fn shared<S: OutputSink + 'static>(sink: &Rc<RefCell<S>>) -> Output {
+        let sink: Rc<RefCell<dyn OutputSink>> = Rc::clone(sink) as _;
+        return Output(sink);
+    }
+
+    pub fn stdout() -> Output {
+        return Output::new(StdoutSink);
+    }
+}
+
+impl Default for Output {
+    fn default() -> Output {
+        return Output::stdout();
+    }
+}
+
+impl OutputSink for Output {
+    fn write_line(&mut self, line: &str) {
+        self.0.borrow_mut().write_line(line);
+    }
+}
+
+impl fmt::Debug for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str("Output");
+    }
+}
+
+/// Two handles are equal when they write to the same sink.
+impl PartialEq for Output {
+    fn eq(&self, other: &Output) -> bool {
+        return Rc::ptr_eq(&self.0, &other.0);
+    }
+}
+
+impl Eq for Output {}
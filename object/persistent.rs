@@ -0,0 +1,146 @@
+//! The persistent collections behind arrays and hashes. Monkey arrays and
+//! hashes are values: `push`, `rest`, `delete` and `merge` return new ones
+//! and leave their arguments alone. Copying the whole collection for that
+//! makes building one up element by element quadratic, so both are trees
+//! whose nodes are shared between versions and an update only copies the
+//! path it changes, O(log n).
+//!
+//! Arrays are [`Vector`]s. Hashes need their insertion order, which no
+//! persistent hash map keeps, so [`OrderedMap`] pairs one with an ordered
+//! map from insertion position to entry.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+use im::{HashMap, OrdMap};
+
+pub use im::Vector;
+
+/// A persistent map that iterates in insertion order.
+/// Overwriting a key keeps its position; removing one keeps the order of
+/// the rest. Equality ignores the order.
+#[derive(Clone)]
+pub struct OrderedMap<K, V> {
+    /// Entries by the position they were inserted at.
+    entries: OrdMap<u64, (K, V)>,
+    /// The position of every key in `entries`.
+    positions: HashMap<K, u64>,
+    /// The position the next new key gets. Positions are never reused.
+    next: u64,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> OrderedMap<K, V> {
+    pub fn new() -> OrderedMap<K, V> {
+        return OrderedMap {
+            entries: OrdMap::new(),
+            positions: HashMap::new(),
+            next: 0,
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let position = self.positions.get(key)?;
+        return self.entries.get(position).map(|(_, value)| value);
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.positions.contains_key(key);
+    }
+
+    /// Sets `key` to `value`, returning the value it replaced. A new key
+    /// goes last.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(position) = self.positions.get(&key) {
+            let (_, old) = self.entries.insert(*position, (key, value))?;
+            return Some(old);
+        }
+        let position = self.next;
+        self.next += 1;
+        self.positions.insert(key.clone(), position);
+        self.entries.insert(position, (key, value));
+        return None;
+    }
+
+    /// Removes `key`, returning its value.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let position = self.positions.remove(key)?;
+        return self.entries.remove(&position).map(|(_, value)| value);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        return self.entries.values().map(|(key, value)| (key, value));
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        return self.iter().map(|(key, _)| key);
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        return self.iter().map(|(_, value)| value);
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for OrderedMap<K, V> {
+    fn default() -> OrderedMap<K, V> {
+        return OrderedMap::new();
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> FromIterator<(K, V)> for OrderedMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> OrderedMap<K, V> {
+        let mut map = OrderedMap::new();
+        for (key, value) in entries {
+            map.insert(key, value);
+        }
+        return map;
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone, const N: usize> From<[(K, V); N]> for OrderedMap<K, V> {
+    fn from(entries: [(K, V); N]) -> OrderedMap<K, V> {
+        return IntoIterator::into_iter(entries).collect();
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone + PartialEq> PartialEq for OrderedMap<K, V> {
+    fn eq(&self, other: &OrderedMap<K, V>) -> bool {
+        return self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value));
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone + Eq> Eq for OrderedMap<K, V> {}
+
+impl<K, V> fmt::Debug for OrderedMap<K, V>
+where
+    K: Clone + Eq + Hash + fmt::Debug,
+    V: Clone + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_map().entries(self.iter()).finish();
+    }
+}
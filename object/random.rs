@@ -0,0 +1,89 @@
+//! `random(n)` and `seed(s)`. The generator is SplitMix64 and its state is
+//! part of each run: the interpreter keeps it in the environment, the VMs in
+//! the VM and the asm runtime in its value store. A fresh run always starts
+//! from the same state, so programs are reproducible until they call `seed`,
+//! and a given seed produces the same numbers in every backend.
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+use crate::integer::Int;
+use crate::math::{NumArg, NumResult};
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        return Random {
+            state: seed,
+        };
+    }
+
+    /// Where the sequence is; `Random::new(state)` carries on from here.
+    pub fn state(&self) -> u64 {
+        return self.state;
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return z ^ (z >> 31);
+    }
+
+    /// Uniform in `0..bound`; rejection sampling keeps small bounds unbiased.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+pub fn is_random_builtin(id: BuiltinId) -> bool {
+    return matches!(id, BuiltinId::Random | BuiltinId::Seed);
+}
+
+/// Runs `random` or `seed` against `random`. Panics for any other `id`.
+pub fn call(
+    random: &mut Random,
+    id: BuiltinId,
+    args: Vec<NumArg>,
+) -> Result<NumResult, BuiltinError> {
+    let name = match id {
+        BuiltinId::Random => "random",
+        BuiltinId::Seed => "seed",
+        _ => panic!("not a random builtin"),
+    };
+    check_arity(name, 1, 1, args.len())?;
+    let value = match &args[0] {
+        NumArg::Int(Int::Small(value)) => *value,
+        NumArg::Int(Int::Big(value)) => {
+            let message = format!("builtin {} expected a 64-bit integer, got {}", name, value);
+            return Err(BuiltinError::Type(message));
+        }
+        NumArg::Float(value) => {
+            let shown = crate::format_float(*value);
+            let message = format!("builtin {} not supported for type {}", name, shown);
+            return Err(BuiltinError::Type(message));
+        }
+        NumArg::Other(shown) => {
+            let message = format!("builtin {} not supported for type {}", name, shown);
+            return Err(BuiltinError::Type(message));
+        }
+    };
+    if id == BuiltinId::Seed {
+        *random = Random::new(value as u64);
+        return Ok(NumResult::Null);
+    }
+    if value <= 0 {
+        let message = format!("builtin random expected a positive bound, got {}", value);
+        return Err(BuiltinError::Type(message));
+    }
+    return Ok(NumResult::Int(Int::Small(random.below(value as u64) as i64)));
+}
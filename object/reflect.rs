@@ -0,0 +1,132 @@
+//! The reflection builtins (`type_of`, `instance_of`, `fields`, `methods`).
+//! As with the hash library, every backend decodes its values into
+//! [`ReflectArg`], calls [`call`] and encodes the [`ReflectResult`] back.
+//!
+//! `fields` and `methods` list names in sorted order, since neither
+//! instances nor classes remember the order their members were added in.
+//! `methods` leaves out the constructor.
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+
+/// What `type_of` reports. The names are the ones the gc heap reports use
+/// for the matching `ValueKind`, so a value is called the same thing in
+/// both places.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TypeOf {
+    Integer,
+    Float,
+    Boolean,
+    String,
+    Null,
+    Array,
+    Hash,
+    Closure,
+    Builtin,
+    Class,
+    Instance,
+    BoundMethod,
+    Iterator,
+    Generator,
+    Error,
+    CompiledFunction,
+}
+
+impl TypeOf {
+    pub fn name(self) -> &'static str {
+        match self {
+            TypeOf::Integer => return "integer",
+            TypeOf::Float => return "float",
+            TypeOf::Boolean => return "boolean",
+            TypeOf::String => return "string",
+            TypeOf::Null => return "null",
+            TypeOf::Array => return "array",
+            TypeOf::Hash => return "hash",
+            TypeOf::Closure => return "closure",
+            TypeOf::Builtin => return "builtin",
+            TypeOf::Class => return "class",
+            TypeOf::Instance => return "instance",
+            TypeOf::BoundMethod => return "boundMethod",
+            TypeOf::Iterator => return "iterator",
+            TypeOf::Generator => return "generator",
+            TypeOf::Error => return "error",
+            TypeOf::CompiledFunction => return "compiledFunction",
+        }
+    }
+}
+
+pub enum ReflectArg {
+    /// A class: an id that is the same for every reference to it, and its
+    /// method names.
+    Class(usize, Vec<String>),
+    /// An instance: the id of its class, and its field names.
+    Instance(usize, Vec<String>),
+    Other(TypeOf),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReflectResult {
+    Str(&'static str),
+    Bool(bool),
+    Names(Vec<String>),
+}
+
+pub fn is_reflect_builtin(id: BuiltinId) -> bool {
+    return name(id).is_some();
+}
+
+fn name(id: BuiltinId) -> Option<&'static str> {
+    let name = match id {
+        BuiltinId::TypeOf => "type_of",
+        BuiltinId::InstanceOf => "instance_of",
+        BuiltinId::Fields => "fields",
+        BuiltinId::Methods => "methods",
+        _ => return None,
+    };
+    return Some(name);
+}
+
+/// Runs the reflection builtin `id`. `describe(i)` renders argument `i` the
+/// way `puts` shows it, for error messages. Panics if `id` is not one (see
+/// [`is_reflect_builtin`]).
+pub fn call(
+    id: BuiltinId,
+    args: Vec<ReflectArg>,
+    describe: impl Fn(usize) -> String,
+) -> Result<ReflectResult, BuiltinError> {
+    let name = name(id).expect("not a reflection builtin");
+    let arity = if id == BuiltinId::InstanceOf { 2 } else { 1 };
+    check_arity(name, arity, arity, args.len())?;
+    let unsupported = |index: usize| {
+        BuiltinError::Type(format!("builtin {} not supported for type {}", name, describe(index)))
+    };
+
+    let mut args = args.into_iter();
+    let value = args.next().expect("arity checked");
+    let result = match (id, value) {
+        (BuiltinId::TypeOf, ReflectArg::Class(..)) => ReflectResult::Str(TypeOf::Class.name()),
+        (BuiltinId::TypeOf, ReflectArg::Instance(..)) => {
+            ReflectResult::Str(TypeOf::Instance.name())
+        }
+        (BuiltinId::TypeOf, ReflectArg::Other(kind)) => ReflectResult::Str(kind.name()),
+        (BuiltinId::InstanceOf, value) => {
+            let ReflectArg::Class(class, _) = args.next().expect("arity checked") else {
+                return Err(unsupported(1));
+            };
+            let is_instance = match value {
+                ReflectArg::Instance(own, _) => own == class,
+                _ => false,
+            };
+            ReflectResult::Bool(is_instance)
+        }
+        (BuiltinId::Fields, ReflectArg::Instance(_, mut fields)) => {
+            fields.sort();
+            ReflectResult::Names(fields)
+        }
+        (BuiltinId::Methods, ReflectArg::Class(_, mut methods)) => {
+            methods.sort();
+            ReflectResult::Names(methods)
+        }
+        _ => return Err(unsupported(0)),
+    };
+    return Ok(result);
+}
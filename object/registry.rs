@@ -0,0 +1,217 @@
+//! The set of builtins a program can see. [`Builtins::standard`] is the
+//! library every backend ships (the [`BuiltIns`] table); embedders add their
+//! own functions with [`Builtins::register`], which may capture whatever
+//! state they need, and hand the registry to the interpreter (through the
+//! root environment), the compiler and the VMs.
+//!
+//! Compiled code names a builtin by its position in the registry the compiler
+//! saw. `Bytecode` carries the names behind those positions, so a VM, or a
+//! snapshot loaded by another host, resolves them by name against its own
+//! registry.
+
+use std::fmt;
+use std::rc::Rc;
+
+use crate::builtins::{check_arity, BuiltIns, BuiltinDefinition, BuiltinError};
+use crate::random::Random;
+use crate::Object;
+
+/// How many arguments a builtin takes; `max` is `usize::MAX` when it is
+/// variadic.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Arity {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Arity {
+    pub const fn exactly(count: usize) -> Arity {
+        return Arity {
+            min: count,
+            max: count,
+        };
+    }
+
+    pub const fn between(min: usize, max: usize) -> Arity {
+        return Arity {
+            min,
+            max,
+        };
+    }
+
+    pub const fn at_least(min: usize) -> Arity {
+        return Arity {
+            min,
+            max: usize::MAX,
+        };
+    }
+}
+
+/// The parts of the running program a native function may use.
+pub struct Ctx<'a> {
+    random: &'a mut Random,
+}
+
+impl<'a> Ctx<'a> {
+    pub fn new(random: &'a mut Random) -> Ctx<'a> {
+        return Ctx {
+            random,
+        };
+    }
+
+    /// The generator `random` and `seed` use, so natives draw from the same
+    /// reproducible sequence.
+    pub fn random(&mut self) -> &mut Random {
+        return self.random;
+    }
+}
+
+pub type NativeFn = dyn Fn(&mut Ctx, &[Rc<Object>]) -> Result<Rc<Object>, BuiltinError>;
+
+/// A builtin registered by the host.
+pub struct NativeBuiltin {
+    pub name: String,
+    pub arity: Arity,
+    pub doc: String,
+    function: Box<NativeFn>,
+}
+
+impl NativeBuiltin {
+    /// Checks the argument count against `arity` and runs the function. A
+    /// failure becomes an `Error` value, the way the standard builtins report
+    /// bad arguments.
+    pub fn call(&self, ctx: &mut Ctx, args: &[Rc<Object>]) -> Rc<Object> {
+        let result = check_arity(&self.name, self.arity.min, self.arity.max, args.len())
+            .and_then(|()| (self.function)(ctx, args));
+        match result {
+            Ok(value) => return value,
+            Err(error) => return Rc::new(Object::Error(error.message().to_string())),
+        }
+    }
+}
+
+impl fmt::Debug for NativeBuiltin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeBuiltin")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Natives have no structure to compare, so two are equal only if they are
+/// the same registration.
+impl PartialEq for NativeBuiltin {
+    fn eq(&self, other: &Self) -> bool {
+        return std::ptr::eq(self, other);
+    }
+}
+
+impl Eq for NativeBuiltin {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuiltinEntry {
+    Standard(BuiltinDefinition),
+    Native(Rc<NativeBuiltin>),
+}
+
+impl BuiltinEntry {
+    pub fn name(&self) -> &str {
+        match self {
+            BuiltinEntry::Standard(definition) => return definition.name,
+            BuiltinEntry::Native(native) => return &native.name,
+        }
+    }
+
+    pub fn arity(&self) -> Arity {
+        match self {
+            BuiltinEntry::Standard(definition) => return definition.arity,
+            BuiltinEntry::Native(native) => return native.arity,
+        }
+    }
+
+    pub fn doc(&self) -> &str {
+        match self {
+            BuiltinEntry::Standard(definition) => return definition.doc,
+            BuiltinEntry::Native(native) => return &native.doc,
+        }
+    }
+
+    /// The value a program gets when it names this builtin.
+    pub fn to_object(&self) -> Object {
+        match self {
+            BuiltinEntry::Standard(definition) => return Object::Builtin(definition.function),
+            BuiltinEntry::Native(native) => return Object::Native(Rc::clone(native)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Builtins {
+    entries: Vec<BuiltinEntry>,
+}
+
+impl Default for Builtins {
+    fn default() -> Self {
+        return Builtins::standard();
+    }
+}
+
+impl Builtins {
+    pub fn standard() -> Builtins {
+        return Builtins {
+            entries: BuiltIns
+                .iter()
+                .copied()
+                .map(BuiltinEntry::Standard)
+                .collect(),
+        };
+    }
+
+    /// Adds a native under `name`. Registering a name that already exists
+    /// replaces that builtin in place, so it keeps its position.
+    pub fn register<F>(&mut self, name: &str, arity: Arity, doc: &str, function: F) -> &mut Builtins
+    where
+        F: Fn(&mut Ctx, &[Rc<Object>]) -> Result<Rc<Object>, BuiltinError> + 'static,
+    {
+        let entry = BuiltinEntry::Native(Rc::new(NativeBuiltin {
+            name: name.to_string(),
+            arity,
+            doc: doc.to_string(),
+            function: Box::new(function),
+        }));
+        match self.position(name) {
+            Some(index) => self.entries[index] = entry,
+            None => self.entries.push(entry),
+        }
+        return self;
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BuiltinEntry> {
+        return self.entries.iter().find(|entry| entry.name() == name);
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        return self.entries.iter().position(|entry| entry.name() == name);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BuiltinEntry> {
+        return self.entries.iter();
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        return self
+            .entries
+            .iter()
+            .map(|entry| entry.name().to_string())
+            .collect();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+}
@@ -0,0 +1,186 @@
+//! The string builtins (`split`, `join`, `trim`, ..., `str`, `int`). Every
+//! backend decodes its own values into [`TextArg`], calls [`call`] and encodes
+//! the [`TextResult`] back, so the library behaves the same in the
+//! interpreter, both VMs and the asm runtime.
+//!
+//! Positions (`substr`, `index_of`) count characters, not bytes.
+
+use num_bigint::BigInt;
+
+use crate::builtins::{check_arity, BuiltinError, BuiltinId};
+use crate::integer::Int;
+
+pub enum TextArg {
+    Str(String),
+    Int(Int),
+    Float(f64),
+    /// An array, its elements already rendered the way `puts` shows them.
+    Array(Vec<String>),
+    /// Any other value, rendered the way `puts` shows it.
+    Other(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TextResult {
+    Str(String),
+    Int(Int),
+    Bool(bool),
+    Array(Vec<String>),
+    Null,
+}
+
+impl TextArg {
+    fn shown(&self) -> String {
+        match self {
+            TextArg::Str(text) | TextArg::Other(text) => return text.clone(),
+            TextArg::Int(Int::Small(value)) => return value.to_string(),
+            TextArg::Int(Int::Big(value)) => return value.to_string(),
+            TextArg::Float(value) => return crate::format_float(*value),
+            TextArg::Array(items) => return format!("[{}]", items.join(", ")),
+        }
+    }
+}
+
+pub fn is_string_builtin(id: BuiltinId) -> bool {
+    return name(id).is_some();
+}
+
+fn name(id: BuiltinId) -> Option<&'static str> {
+    let name = match id {
+        BuiltinId::Split => "split",
+        BuiltinId::Join => "join",
+        BuiltinId::Trim => "trim",
+        BuiltinId::Upper => "upper",
+        BuiltinId::Lower => "lower",
+        BuiltinId::Contains => "contains",
+        BuiltinId::StartsWith => "starts_with",
+        BuiltinId::EndsWith => "ends_with",
+        BuiltinId::Replace => "replace",
+        BuiltinId::Substr => "substr",
+        BuiltinId::IndexOf => "index_of",
+        BuiltinId::Chars => "chars",
+        BuiltinId::Str => "str",
+        BuiltinId::Int => "int",
+        _ => return None,
+    };
+    return Some(name);
+}
+
+/// Runs the string builtin `id`. Panics if `id` is not one
+/// (see [`is_string_builtin`]).
+pub fn call(id: BuiltinId, args: Vec<TextArg>) -> Result<TextResult, BuiltinError> {
+    let name = name(id).expect("not a string builtin");
+    let (min, max) = match id {
+        BuiltinId::Trim
+        | BuiltinId::Upper
+        | BuiltinId::Lower
+        | BuiltinId::Chars
+        | BuiltinId::Str
+        | BuiltinId::Int => (1, 1),
+        BuiltinId::Replace => (3, 3),
+        BuiltinId::Substr => (2, 3),
+        _ => (2, 2),
+    };
+    check_arity(name, min, max, args.len())?;
+    let unsupported = |arg: &TextArg| {
+        BuiltinError::Type(format!("builtin {} not supported for type {}", name, arg.shown()))
+    };
+
+    match id {
+        BuiltinId::Str => return Ok(TextResult::Str(args[0].shown())),
+        BuiltinId::Int => return to_int(&args[0]).ok_or_else(|| unsupported(&args[0])),
+        BuiltinId::Join => {
+            let items = match &args[0] {
+                TextArg::Array(items) => items,
+                other => return Err(unsupported(other)),
+            };
+            let separator = string(&args[1]).ok_or_else(|| unsupported(&args[1]))?;
+            return Ok(TextResult::Str(items.join(separator)));
+        }
+        _ => {}
+    }
+
+    let text = string(&args[0]).ok_or_else(|| unsupported(&args[0]))?;
+    let result = match id {
+        BuiltinId::Trim => TextResult::Str(text.trim().to_string()),
+        BuiltinId::Upper => TextResult::Str(text.to_uppercase()),
+        BuiltinId::Lower => TextResult::Str(text.to_lowercase()),
+        BuiltinId::Chars => TextResult::Array(text.chars().map(String::from).collect()),
+        BuiltinId::Substr => {
+            let start = position(&args[1]).ok_or_else(|| unsupported(&args[1]))?;
+            let length = match args.get(2) {
+                Some(arg) => position(arg).ok_or_else(|| unsupported(arg))?,
+                None => usize::MAX,
+            };
+            TextResult::Str(text.chars().skip(start).take(length).collect())
+        }
+        BuiltinId::Replace => {
+            let from = string(&args[1]).ok_or_else(|| unsupported(&args[1]))?;
+            let to = string(&args[2]).ok_or_else(|| unsupported(&args[2]))?;
+            TextResult::Str(text.replace(from, to))
+        }
+        _ => {
+            let needle = string(&args[1]).ok_or_else(|| unsupported(&args[1]))?;
+            match id {
+                BuiltinId::Split if needle.is_empty() => {
+                    TextResult::Array(text.chars().map(String::from).collect())
+                }
+                BuiltinId::Split => {
+                    TextResult::Array(text.split(needle).map(String::from).collect())
+                }
+                BuiltinId::Contains => TextResult::Bool(text.contains(needle)),
+                BuiltinId::StartsWith => TextResult::Bool(text.starts_with(needle)),
+                BuiltinId::EndsWith => TextResult::Bool(text.ends_with(needle)),
+                BuiltinId::IndexOf => {
+                    let index = match text.find(needle) {
+                        Some(byte) => text[..byte].chars().count() as i64,
+                        None => -1,
+                    };
+                    TextResult::Int(Int::Small(index))
+                }
+                _ => unreachable!(),
+            }
+        }
+    };
+    return Ok(result);
+}
+
+fn string(arg: &TextArg) -> Option<&str> {
+    match arg {
+        TextArg::Str(text) => return Some(text),
+        _ => return None,
+    }
+}
+
+/// A non-negative character position; negative positions count as 0.
+fn position(arg: &TextArg) -> Option<usize> {
+    match arg {
+        TextArg::Int(Int::Small(value)) => return Some((*value).max(0) as usize),
+        TextArg::Int(Int::Big(value)) if value.sign() == num_bigint::Sign::Minus => return Some(0),
+        TextArg::Int(Int::Big(_)) => return Some(usize::MAX),
+        _ => return None,
+    }
+}
+
+/// `int(x)`: integers pass through, floats truncate toward zero, strings are
+/// parsed as (optionally signed) decimal. Text that is not a number, and
+/// floats that are NaN or infinite, give `null` rather than an error so
+/// programs can validate input.
+fn to_int(arg: &TextArg) -> Option<TextResult> {
+    match arg {
+        TextArg::Int(value) => return Some(TextResult::Int(value.clone())),
+        TextArg::Float(value) if !value.is_finite() => return Some(TextResult::Null),
+        TextArg::Float(value) => {
+            let truncated = format!("{:.0}", value.trunc());
+            let parsed = truncated
+                .parse::<BigInt>()
+                .expect("finite float renders as an integer");
+            return Some(TextResult::Int(Int::narrow(parsed)));
+        }
+        TextArg::Str(text) => match text.trim().parse::<BigInt>() {
+            Ok(parsed) => return Some(TextResult::Int(Int::narrow(parsed))),
+            Err(_) => return Some(TextResult::Null),
+        },
+        _ => return None,
+    }
+}
@@ -4,20 +4,74 @@ import { walk } from '../walk'
 
 /**
  * Builtins whose arity *both* backends reject identically, regardless of the
- * argument types. Only `len` qualifies for v0:
+ * argument types:
  *
  *   - `len` errors cleanly on any count other than 1 in the interpreter
  *     (`args.len() != 1`) and in the GC VM (`call_builtin_with_output`).
+ *   - The string builtins check arity once, in `object/strings.rs`, which
+ *     every backend calls. `substr` takes 2 or 3 arguments and is left out of
+ *     this single-count table.
  *   - `first` / `last` / `rest` / `push` diverge: the interpreter indexes
  *     `args[0]` (and `args.last()`) directly, so too *many* arguments are
  *     silently ignored and too *few* panic rather than returning an error,
  *     while the VM returns a clean arity error. Flagging them would be unsound
  *     against the interpreter, so they are intentionally excluded until the
  *     backends converge.
+ *   - The higher-order builtins check arity once, in `object/higher_order.rs`;
+ *     `sort` takes an optional comparator and is left out. The hash builtins
+ *     do the same in `object/hashes.rs`, and the math builtins, `random` and
+ *     `seed` in `object/math.rs` / `object/random.rs`, the JSON builtins in
+ *     `object/json.rs`, the reflection builtins in `object/reflect.rs` and
+ *     the iterator builtins in `object/iterators.rs`; `min` / `max` are
+ *     variadic, `range` takes 1 to 3 bounds and `next` an optional sent
+ *     value, so they are left out.
  *   - `puts` / `print` are variadic.
  */
 const FIXED_ARITY: Record<string, number> = {
   len: 1,
+  trim: 1,
+  upper: 1,
+  lower: 1,
+  chars: 1,
+  str: 1,
+  int: 1,
+  split: 2,
+  join: 2,
+  contains: 2,
+  starts_with: 2,
+  ends_with: 2,
+  index_of: 2,
+  replace: 3,
+  map: 2,
+  filter: 2,
+  find: 2,
+  any: 2,
+  all: 2,
+  reduce: 3,
+  keys: 1,
+  values: 1,
+  entries: 1,
+  has: 2,
+  delete: 2,
+  merge: 2,
+  abs: 1,
+  pow: 2,
+  sqrt: 1,
+  floor: 1,
+  ceil: 1,
+  round: 1,
+  random: 1,
+  seed: 1,
+  json_encode: 1,
+  json_decode: 1,
+  type_of: 1,
+  instance_of: 2,
+  fields: 1,
+  methods: 1,
+  take: 2,
+  zip: 2,
+  enumerate: 1,
+  to_array: 1,
 }
 
 export const builtinArity: Rule = {
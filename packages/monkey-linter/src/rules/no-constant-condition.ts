@@ -4,10 +4,10 @@ import { walk } from '../walk'
 
 /**
  * An `if` whose condition is a literal, so the branch is decided at parse time.
- * Both backends treat only `false` and `null` as falsy — every integer (even
- * `0`) and every string (even `""`) is truthy — so the message states the fixed
- * outcome. `null` is not a literal in the grammar, so the only falsy constant is
- * `false`.
+ * Both backends treat only `false` and `null` as falsy — every number (even
+ * `0` or `0.0`) and every string (even `""`) is truthy — so the message states
+ * the fixed outcome. `null` is not a literal in the grammar, so the only falsy
+ * constant is `false`.
  */
 export const noConstantCondition: Rule = {
   name: 'no-constant-condition',
@@ -21,6 +21,8 @@ export const noConstantCondition: Rule = {
       if (
         condition.type !== 'Boolean' &&
         condition.type !== 'Integer' &&
+        condition.type !== 'BigInt' &&
+        condition.type !== 'Float' &&
         condition.type !== 'String'
       ) {
         return
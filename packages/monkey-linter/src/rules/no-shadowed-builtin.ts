@@ -5,7 +5,7 @@ const BUILTINS: ReadonlySet<string> = new Set(BUILTIN_NAMES)
 
 /**
  * A `let`, parameter, or class binding whose name collides with a predefined
- * builtin (`len`, `puts`, `first`, `last`, `rest`, `push`, `print`). Shadowing
+ * builtin (`len`, `puts`, `split`, `str`, ... — see `BUILTIN_NAMES`). Shadowing
  * is legal — the compiler resolves the local binding — but it makes the builtin
  * unreachable for the rest of that scope, which is almost always a mistake.
  */
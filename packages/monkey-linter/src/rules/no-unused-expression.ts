@@ -111,8 +111,18 @@ export const noUnusedExpression: Rule = {
         case 'PropertyExpression':
           descend(expression.object, true)
           return
+        case 'TemplateLiteral':
+          for (const substitution of expression.expressions) {
+            descend(substitution, true)
+          }
+          return
+        case 'YieldExpression':
+          if (expression.argument) {
+            descend(expression.argument, true)
+          }
+          return
         default:
-          // IDENTIFIER, Integer, Boolean, String, ThisExpression: no nested
+          // IDENTIFIER, Integer, BigInt, Float, Boolean, String, ThisExpression: no nested
           // statement lists.
           return
       }
@@ -127,21 +137,26 @@ export const noUnusedExpression: Rule = {
  * error. Calls, `new`, conditionals, function literals, index/property reads,
  * operators, and hashes are conservatively excluded: even apparently pure
  * forms can fail because of arity, types, division by zero, or unhashable keys.
- * Array literals are safe only when every element is safe.
+ * Array and template literals are safe only when every element or
+ * substitution is safe; rendering a value for a template cannot fail.
  */
 function isPure(expression: Expression): boolean {
   switch (expression.type) {
     case 'IDENTIFIER':
     case 'Integer':
+    case 'BigInt':
+    case 'Float':
     case 'Boolean':
     case 'String':
     case 'ThisExpression':
       return true
     case 'Array':
       return expression.elements.every(isPure)
+    case 'TemplateLiteral':
+      return expression.expressions.every(isPure)
     default:
       // UnaryExpression, BinaryExpression, Hash, FunctionCall, NewExpression,
-      // IF, FunctionDeclaration, Index, PropertyExpression.
+      // IF, FunctionDeclaration, Index, PropertyExpression, YieldExpression.
       return false
   }
 }
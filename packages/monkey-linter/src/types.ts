@@ -111,6 +111,8 @@ export interface FunctionDeclaration extends ASTNode {
   params: Identifier[]
   body: BlockStatement
   name: string
+  /** `fn*`; omitted for plain functions. */
+  generator?: boolean
 }
 
 export interface FunctionCall extends ASTNode {
@@ -141,11 +143,36 @@ export interface NewExpression extends ASTNode {
   arguments: Expression[]
 }
 
+/** `` `a${x}b` ``: `quasis` has one more entry than `expressions`. */
+export interface TemplateLiteral extends ASTNode {
+  type: 'TemplateLiteral'
+  quasis: string[]
+  expressions: Expression[]
+}
+
+/** `yield value` or `yield* generator`; a bare `yield` has no argument. */
+export interface YieldExpression extends ASTNode {
+  type: 'YieldExpression'
+  argument?: Expression
+  delegate?: boolean
+}
+
 export interface IntegerLiteral extends ASTNode {
   type: 'Integer'
   raw: string
 }
 
+/** An integer literal outside the i64 range; `raw` holds its decimal digits. */
+export interface BigIntLiteral extends ASTNode {
+  type: 'BigInt'
+  raw: string
+}
+
+export interface FloatLiteral extends ASTNode {
+  type: 'Float'
+  raw: number
+}
+
 export interface BooleanLiteral extends ASTNode {
   type: 'Boolean'
   raw: boolean
@@ -168,6 +195,8 @@ export interface HashLiteral extends ASTNode {
 
 export type Literal =
   | IntegerLiteral
+  | BigIntLiteral
+  | FloatLiteral
   | BooleanLiteral
   | StringLiteral
   | ArrayLiteral
@@ -185,6 +214,8 @@ export type Expression =
   | ThisExpression
   | PropertyExpression
   | NewExpression
+  | TemplateLiteral
+  | YieldExpression
 
 export function identifierName(statement: LetStatement): string {
   return statement.identifier.kind.value.name
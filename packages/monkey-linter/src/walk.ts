@@ -16,7 +16,9 @@ import type {
   PropertyExpression,
   ReturnStatement,
   SetPropertyStatement,
+  TemplateLiteral,
   UnaryExpression,
+  YieldExpression,
 } from './types'
 
 /**
@@ -101,8 +103,14 @@ export function childrenOf(node: ASTNode): ASTNode[] {
       const expression = node as NewExpression
       return [expression.callee, ...expression.arguments]
     }
+    case 'TemplateLiteral':
+      return (node as TemplateLiteral).expressions
+    case 'YieldExpression': {
+      const argument = (node as YieldExpression).argument
+      return argument ? [argument] : []
+    }
     default:
-      // Leaves: IDENTIFIER, Integer, Boolean, String, ThisExpression.
+      // Leaves: IDENTIFIER, Integer, BigInt, Float, Boolean, String, ThisExpression.
       return []
   }
 }
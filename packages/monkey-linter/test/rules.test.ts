@@ -84,6 +84,7 @@ describe('no-unused-expression', () => {
     ['let f = fn() { 1; 2; }; f();', 1],
     // Non-tail statement inside an if branch (branch tail is the return value).
     ['let f = fn(flag) { if (flag) { 1; flag; } else { 2; } }; f(true);', 1],
+    ['let n = 1; `n = ${n}`; puts(n);', 1],
   ])('flags a discarded pure expression: %s', (source, count) => {
     expect(rulesOf(source)).toEqual(
       Array.from({ length: count }, () => 'no-unused-expression')
@@ -101,6 +102,8 @@ describe('no-unused-expression', () => {
     // missing property), so v0 leaves them alone.
     'let xs = [1, 2]; xs[5]; puts(xs);',
     'class P { constructor() { this.v = 1; } } let p = new P(); p.v; puts(p);',
+    // A yield suspends the generator even when nothing reads the sent value.
+    'let g = fn*() { yield 1; yield 2; }; next(g());',
   ])('stays quiet otherwise: %s', (source) => {
     expect(rulesOf(source)).not.toContain('no-unused-expression')
   })
@@ -113,6 +116,7 @@ describe('no-unused-expression', () => {
     'let c = true; !(if (c) { true; } else { false; }); puts(c);',
     'let c = true; 1 + if (c) { 2; } else { 3; }; puts(c);',
     'let c = true; [if (c) { 1; } else { 2; }]; puts(c);',
+    'let c = true; `${if (c) { 1; } else { 2; }}`; puts(c);',
     'let c = true; {"k": if (c) { 1; } else { 2; }}; puts(c);',
     // Calls/new and index/property access consume all nested values.
     'let c = true; let id = fn(x) { x; }; id(if (c) { 1; } else { 2; });',
@@ -129,7 +133,12 @@ describe('no-unused-expression', () => {
     }
   )
 
-  it.each(['1 / 0; puts(1);', '-"x"; puts(1);', '{[]: 1}; puts(1);'])(
+  it.each([
+    '1 / 0; puts(1);',
+    '-"x"; puts(1);',
+    '{[]: 1}; puts(1);',
+    '`${1 / 0}`; puts(1);',
+  ])(
     'does not suggest removing an expression that can fail: %s',
     (source) => {
       expect(rulesOf(source)).not.toContain('no-unused-expression')
@@ -182,6 +191,38 @@ describe('builtin-arity', () => {
     }
   )
 
+  it.each(['split("a");', 'replace("a", "b");', 'str();', 'trim(" a ", " ");'])(
+    'flags a wrong-arity string builtin call: %s',
+    (source) => {
+      expect(rulesOf(source)).toEqual(['builtin-arity'])
+    }
+  )
+
+  it.each(['keys();', 'has({});', 'merge({}, {}, {});'])(
+    'flags a wrong-arity hash builtin call: %s',
+    (source) => {
+      expect(rulesOf(source)).toEqual(['builtin-arity'])
+    }
+  )
+
+  it.each([
+    'abs();',
+    'pow(2);',
+    'random(1, 2);',
+    'json_decode();',
+    'instance_of(1);',
+    'zip(range(3));',
+  ])('flags a wrong-arity library builtin call: %s', (source) => {
+    expect(rulesOf(source)).toEqual(['builtin-arity'])
+  })
+
+  it.each(['map([1]);', 'reduce([1], len);', 'any([1], len, len);'])(
+    'flags a wrong-arity higher-order builtin call: %s',
+    (source) => {
+      expect(rulesOf(source)).toEqual(['builtin-arity'])
+    }
+  )
+
   it.each([
     'len("hi");',
     'len([1, 2]);',
@@ -189,6 +230,12 @@ describe('builtin-arity', () => {
     // handling diverges from the VM's.
     'first([1], [2]);',
     'push([1]);',
+    // substr takes an optional length.
+    'substr("abc", 1);',
+    'substr("abc", 1, 1);',
+    // sort takes an optional comparator.
+    'sort([2, 1]);',
+    'sort([2, 1], len);',
   ])('stays quiet otherwise: %s', (source) => {
     expect(rulesOf(source)).not.toContain('builtin-arity')
   })
@@ -11,8 +11,7 @@ import { tokenType } from './types'
 import { analyzeScopes, type ScopeAnalysis } from './scope'
 
 const ZERO = BigInt(0)
-const NEGATIVE_ONE = BigInt(-1)
-const I64_MIN = -(BigInt(1) << BigInt(63))
+const I64_MAX = (BigInt(1) << BigInt(63)) - BigInt(1)
 
 type ConstantValue =
   | { kind: 'integer'; value: bigint }
@@ -95,6 +94,8 @@ function foldExpression(
   switch (expression.type) {
     case 'IDENTIFIER':
     case 'Integer':
+    case 'BigInt':
+    case 'Float':
     case 'Boolean':
     case 'String':
     case 'ThisExpression':
@@ -165,12 +166,23 @@ function foldExpression(
         foldExpression(argument, analysis)
       )
       return expression
+    case 'TemplateLiteral':
+      expression.expressions = expression.expressions.map((part) =>
+        foldExpression(part, analysis)
+      )
+      return expression
+    case 'YieldExpression':
+      if (expression.argument) {
+        expression.argument = foldExpression(expression.argument, analysis)
+      }
+      return expression
   }
 }
 
 function evaluateConstant(expression: Expression): ConstantValue | null {
   switch (expression.type) {
     case 'Integer':
+    case 'BigInt':
       return { kind: 'integer', value: BigInt(expression.raw) }
     case 'Boolean':
       return { kind: 'boolean', value: expression.raw }
@@ -184,7 +196,7 @@ function evaluateConstant(expression: Expression): ConstantValue | null {
       switch (tokenType(expression.op)) {
         case 'MINUS':
           return operand.kind === 'integer'
-            ? { kind: 'integer', value: BigInt.asIntN(64, -operand.value) }
+            ? { kind: 'integer', value: -operand.value }
             : null
         case 'BANG':
           return {
@@ -215,16 +227,13 @@ function evaluateBinary(
   if (left.kind === 'integer' && right.kind === 'integer') {
     switch (operator) {
       case 'PLUS':
-        return integer(BigInt.asIntN(64, left.value + right.value))
+        return integer(left.value + right.value)
       case 'MINUS':
-        return integer(BigInt.asIntN(64, left.value - right.value))
+        return integer(left.value - right.value)
       case 'ASTERISK':
-        return integer(BigInt.asIntN(64, left.value * right.value))
+        return integer(left.value * right.value)
       case 'SLASH':
-        if (
-          right.value === ZERO ||
-          (left.value === I64_MIN && right.value === NEGATIVE_ONE)
-        ) {
+        if (right.value === ZERO) {
           return null
         }
         return integer(left.value / right.value)
@@ -283,20 +292,25 @@ function constantToExpression(value: ConstantValue | null): Expression | null {
     case 'string':
       return { type: 'String', raw: value.value }
     case 'integer':
-      if (value.value === I64_MIN) {
-        return null
-      }
       if (value.value < ZERO) {
         return {
           type: 'UnaryExpression',
           op: { kind: { type: 'MINUS' } },
-          operand: { type: 'Integer', raw: (-value.value).toString() },
+          operand: integerLiteral(-value.value),
         }
       }
-      return { type: 'Integer', raw: value.value.toString() }
+      return integerLiteral(value.value)
   }
 }
 
+// The lexer reads digits past i64::MAX as a BigInt literal, so the node type
+// has to match what parsing the printed digits back would produce.
+function integerLiteral(value: bigint): Expression {
+  return value > I64_MAX
+    ? { type: 'BigInt', raw: value.toString() }
+    : { type: 'Integer', raw: value.toString() }
+}
+
 function isTruthy(value: ConstantValue): boolean {
   return value.kind === 'boolean' ? value.value : true
 }
@@ -368,6 +382,13 @@ function expressionChangesScope(expression: Expression): boolean {
       return expressionChangesScope(expression.object)
     case 'NewExpression':
       return expression.arguments.some(expressionChangesScope)
+    case 'TemplateLiteral':
+      return expression.expressions.some(expressionChangesScope)
+    case 'YieldExpression':
+      return (
+        expression.argument !== undefined &&
+        expressionChangesScope(expression.argument)
+      )
     // Function bodies have their own compiler symbol scope.
     case 'FunctionDeclaration':
     default:
@@ -520,6 +541,16 @@ function removeNestedExpression(
           removeNestedExpression(item, analysis, removeLets) || removed,
         false
       )
+    case 'TemplateLiteral':
+      return expression.expressions.reduce(
+        (removed, item) =>
+          removeNestedExpression(item, analysis, removeLets) || removed,
+        false
+      )
+    case 'YieldExpression':
+      return expression.argument
+        ? removeNestedExpression(expression.argument, analysis, removeLets)
+        : false
     default:
       return false
   }
@@ -597,6 +628,13 @@ function expressionContainsIncompleteIf(expression: Expression): boolean {
       return expressionContainsIncompleteIf(expression.object)
     case 'NewExpression':
       return expression.arguments.some(expressionContainsIncompleteIf)
+    case 'TemplateLiteral':
+      return expression.expressions.some(expressionContainsIncompleteIf)
+    case 'YieldExpression':
+      return (
+        expression.argument !== undefined &&
+        expressionContainsIncompleteIf(expression.argument)
+      )
     case 'FunctionDeclaration':
     default:
       return false
@@ -637,6 +675,8 @@ function blockCanFallThroughWithoutValue(block: BlockStatement): boolean {
 function isPureTotal(expression: Expression, analysis: ScopeAnalysis): boolean {
   switch (expression.type) {
     case 'Integer':
+    case 'BigInt':
+    case 'Float':
     case 'Boolean':
     case 'String':
       return true
@@ -648,6 +688,11 @@ function isPureTotal(expression: Expression, analysis: ScopeAnalysis): boolean {
       )
     case 'Hash':
       return hashIsPureTotal(expression, analysis)
+    // Rendering a substitution cannot fail, so only the parts matter.
+    case 'TemplateLiteral':
+      return expression.expressions.every((part) =>
+        isPureTotal(part, analysis)
+      )
     case 'UnaryExpression':
       return (
         isPureTotal(expression.operand, analysis) &&
@@ -664,8 +709,8 @@ function isPureTotal(expression: Expression, analysis: ScopeAnalysis): boolean {
     case 'FunctionDeclaration':
       return !containsDiagnostic(expression, analysis)
     default:
-      // Calls/new can have effects. Property/index operations may throw. If
-      // and unknown future nodes are deliberately retained.
+      // Calls/new and yields can have effects. Property/index operations may
+      // throw. If and unknown future nodes are deliberately retained.
       return false
   }
 }
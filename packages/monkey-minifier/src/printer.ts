@@ -10,6 +10,7 @@ import type {
   Program,
   SetPropertyStatement,
   Statement,
+  TemplateLiteral,
 } from './types'
 import { identifierName, tokenType } from './types'
 
@@ -119,7 +120,10 @@ function renderExpression(expression: Expression): PrintedExpression {
     case 'IDENTIFIER':
       return primary(expression.name)
     case 'Integer':
+    case 'BigInt':
       return primary(expression.raw)
+    case 'Float':
+      return primary(printFloat(expression.raw))
     case 'Boolean':
       return primary(String(expression.raw))
     case 'String':
@@ -187,9 +191,27 @@ function renderExpression(expression: Expression): PrintedExpression {
           .join(',')})`,
         precedence: Precedence.Postfix,
       }
+    case 'TemplateLiteral':
+      return primary(printTemplate(expression))
+    case 'YieldExpression':
+      return {
+        code: `${expression.delegate ? 'yield*' : 'yield'}${
+          expression.argument ? ` ${printExpression(expression.argument)}` : ''
+        }`,
+        precedence: Precedence.Lowest,
+      }
   }
 }
 
+function printTemplate(template: TemplateLiteral): string {
+  let code = '`' + template.quasis[0]
+  template.expressions.forEach((substitution, index) => {
+    code += '${' + printExpression(substitution) + '}'
+    code += template.quasis[index + 1]
+  })
+  return code + '`'
+}
+
 function renderBinary(expression: BinaryExpression): PrintedExpression {
   const kind = tokenType(expression.op)
   const precedence = infixPrecedence[kind]
@@ -206,13 +228,33 @@ function renderBinary(expression: BinaryExpression): PrintedExpression {
 
 function renderFunction(expression: FunctionDeclaration): PrintedExpression {
   return {
-    code: `fn(${expression.params
+    code: `${expression.generator ? 'fn*' : 'fn'}(${expression.params
       .map((param) => param.name)
       .join(',')})${printBlock(expression.body)}`,
     precedence: Precedence.Lowest,
   }
 }
 
+// Monkey only lexes `digits.digits`: keep the decimal point on integral
+// values and spell out what JavaScript would print in exponent notation.
+function printFloat(raw: number): string {
+  const text = String(raw)
+  if (!text.includes('e')) {
+    return text.includes('.') ? text : `${text}.0`
+  }
+  const [mantissa, exponent] = text.split('e')
+  const [whole, fraction = ''] = mantissa.split('.')
+  const digits = whole + fraction
+  const point = whole.length + Number(exponent)
+  if (point <= 0) {
+    return `0.${'0'.repeat(-point)}${digits}`
+  }
+  if (point >= digits.length) {
+    return `${digits}${'0'.repeat(point - digits.length)}.0`
+  }
+  return `${digits.slice(0, point)}.${digits.slice(point)}`
+}
+
 function primary(code: string): PrintedExpression {
   return { code, precedence: Precedence.Primary }
 }
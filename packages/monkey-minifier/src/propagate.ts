@@ -142,6 +142,8 @@ function substituteExpression(
       return cloneLiteral(template)
     }
     case 'Integer':
+    case 'BigInt':
+    case 'Float':
     case 'Boolean':
     case 'String':
     case 'ThisExpression':
@@ -200,5 +202,18 @@ function substituteExpression(
         substituteExpression(argument, substitution)
       )
       return expression
+    case 'TemplateLiteral':
+      expression.expressions = expression.expressions.map((part) =>
+        substituteExpression(part, substitution)
+      )
+      return expression
+    case 'YieldExpression':
+      if (expression.argument) {
+        expression.argument = substituteExpression(
+          expression.argument,
+          substitution
+        )
+      }
+      return expression
   }
 }
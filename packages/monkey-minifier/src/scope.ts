@@ -20,6 +20,55 @@ export const BUILTIN_NAMES = [
   'rest',
   'push',
   'print',
+  'split',
+  'join',
+  'trim',
+  'upper',
+  'lower',
+  'contains',
+  'starts_with',
+  'ends_with',
+  'replace',
+  'substr',
+  'index_of',
+  'chars',
+  'str',
+  'int',
+  'map',
+  'filter',
+  'reduce',
+  'sort',
+  'find',
+  'any',
+  'all',
+  'keys',
+  'values',
+  'entries',
+  'has',
+  'delete',
+  'merge',
+  'abs',
+  'min',
+  'max',
+  'pow',
+  'sqrt',
+  'floor',
+  'ceil',
+  'round',
+  'random',
+  'seed',
+  'json_encode',
+  'json_decode',
+  'type_of',
+  'instance_of',
+  'fields',
+  'methods',
+  'range',
+  'take',
+  'zip',
+  'enumerate',
+  'to_array',
+  'next',
 ] as const
 
 export type BindingKind = 'builtin' | 'class' | 'let' | 'parameter' | 'this'
@@ -267,6 +316,8 @@ function analyzeExpression(
       analyzeIdentifier(expression, scope, analysis)
       return
     case 'Integer':
+    case 'BigInt':
+    case 'Float':
     case 'Boolean':
     case 'String':
       return
@@ -326,6 +377,16 @@ function analyzeExpression(
         analyzeExpression(argument, scope, analysis, context)
       }
       return
+    case 'TemplateLiteral':
+      for (const substitution of expression.expressions) {
+        analyzeExpression(substitution, scope, analysis, context)
+      }
+      return
+    case 'YieldExpression':
+      if (expression.argument) {
+        analyzeExpression(expression.argument, scope, analysis, context)
+      }
+      return
     default:
       analysis.safe = false
   }
@@ -8,20 +8,22 @@ describe('constant folding and conservative DCE', () => {
 
   it.each([
     ['40 + 2', '42;'],
-    ['9223372036854775807 + 2', '-9223372036854775807;'],
+    ['9223372036854775807 + 2', '9223372036854775809;'],
+    ['-9223372036854775807 - 2', '-9223372036854775809;'],
+    ['100000000000000000000 / 10', '10000000000000000000;'],
     ['"mon" + "key"', '"monkey";'],
     ['1 < 2 == true', 'true;'],
     ['!!1', 'true;'],
     ['if (true) { 1 } else { 2 }', '1;'],
+    ['`${1 + 2}!`', '`${3}!`;'],
   ])('folds %s', (source, expected) => {
     expect(optimize(source)).toBe(expected)
   })
 
-  it('retains arithmetic errors and unprintable i64::MIN results', () => {
+  it('retains division by zero for the VM to report', () => {
     expect(optimize('1 / 0')).toBe('1/0;')
-    expect(optimize('9223372036854775807 + 1')).toBe('9223372036854775807+1;')
     expect(optimize('(-9223372036854775807 - 1) / -1')).toBe(
-      '(-9223372036854775807-1)/-1;'
+      '9223372036854775808;'
     )
   })
 
@@ -47,6 +49,7 @@ describe('constant folding and conservative DCE', () => {
 
   it('deletes pure unused lets to a fixed point', () => {
     expect(optimize('let first = 1; let second = first; 42;')).toBe('42;')
+    expect(optimize('let n = 1; let label = `n = ${n}`; 42;')).toBe('42;')
     expect(optimize('let helper = fn(x) { puts(x) }; 0;')).toBe('0;')
   })
 
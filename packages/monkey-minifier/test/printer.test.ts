@@ -19,6 +19,14 @@ describe('compact printer', () => {
     ['(fn(x) { x })(1)', '(fn(x){x;})(1);'],
     ['{"a": [1, 2], true: {}}', '{"a":[1,2],true:{}};'],
     ['new Thing(1).value[0]', 'new Thing(1).value[0];'],
+    ['1.0 + 0.25', '1.0+0.25;'],
+    ['100000000000000000000000.0', '100000000000000000000000.0;'],
+    ['0.0000001', '0.0000001;'],
+    ['`a ${ x + 1 } b ${[1, 2]}`', '`a ${x+1} b ${[1,2]}`;'],
+    [
+      'fn*(g) { let x = yield; yield x + 1; return yield* g }',
+      'fn*(g){let x=yield;yield x+1;return yield* g;};',
+    ],
   ])('prints %s', (source, expected) => {
     expect(print(source)).toBe(expected)
   })
@@ -176,8 +176,11 @@ const valueKindLabels: Record<ValueKind, string> = {
   boundMethod: 'Bound method',
   closure: 'Closure',
   array: 'Array',
+  iterator: 'Iterator',
+  generator: 'Generator',
   hash: 'Hash',
   integer: 'Integer',
+  float: 'Float',
   boolean: 'Boolean',
   string: 'String',
   null: 'Null',
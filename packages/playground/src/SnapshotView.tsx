@@ -52,6 +52,7 @@ const summaryListClass = `m-0 mb-3 grid grid-cols-4 gap-2 max-[900px]:grid-cols-
 
 const sectionDotTones: Record<SnapshotSection, string> = {
   header: 'bg-(--blue-9)',
+  builtins: 'bg-(--cyan-9)',
   main: 'bg-(--green-9)',
   constants: 'bg-(--amber-9)',
   debug: 'bg-(--violet-9)',
@@ -59,6 +60,7 @@ const sectionDotTones: Record<SnapshotSection, string> = {
 
 const sectionHelp: Record<SnapshotSection, string> = {
   header: 'Fixed 10 bytes: magic, version, ABI fingerprint, flags.',
+  builtins: 'Names behind OpGetBuiltin indices, resolved by the loading VM.',
   main: 'Length-prefixed top-level instruction stream.',
   constants: 'Tagged constant pool: integers, strings, functions.',
   debug: 'Optional pc→source-span tables; gone with --strip.',
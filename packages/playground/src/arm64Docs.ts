@@ -281,6 +281,16 @@ const RUNTIME_DOCS: Record<string, Arm64TokenDoc> = {
     detail:
       'Boxes an integer too large for the inline tagged form (beyond ±2⁶²) into a heap object.',
   },
+  rt_box_float: {
+    title: 'rt_box_float(bits) → value',
+    detail:
+      'Boxes a float into a heap object; the f64 bit pattern arrives in x0 (loaded just above), so no FP registers are involved.',
+  },
+  rt_bigint_from_decimal: {
+    title: 'rt_bigint_from_decimal(ptr, len) → value',
+    detail:
+      'Parses an integer literal beyond the i64 range from its decimal digits in .rodata (see the .Lstr labels) into a heap BigInt.',
+  },
   rt_array: {
     title: 'rt_array(argv, len) → value',
     detail:
@@ -290,6 +300,11 @@ const RUNTIME_DOCS: Record<string, Arm64TokenDoc> = {
     title: 'rt_hash(argv, pairs) → value',
     detail: 'Builds a hash from key/value pairs packed on the stack at argv.',
   },
+  rt_concat: {
+    title: 'rt_concat(argv, len) → value',
+    detail:
+      'Joins the len template-literal parts packed on the stack at argv into one string, rendering each part the way puts prints it.',
+  },
   rt_closure: {
     title: 'rt_closure(code, num_params, free, num_free) → value',
     detail:
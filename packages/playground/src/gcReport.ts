@@ -4,8 +4,11 @@ export const valueKinds = [
   'boundMethod',
   'closure',
   'array',
+  'iterator',
+  'generator',
   'hash',
   'integer',
+  'float',
   'boolean',
   'string',
   'null',
@@ -35,6 +38,10 @@ export const edgeRelationKinds = [
   'instanceField',
   'boundMethodReceiver',
   'boundMethodFunction',
+  'iteratorSource',
+  'generatorClosure',
+  'generatorStack',
+  'generatorInner',
   'unknown',
 ] as const
 
@@ -54,6 +61,10 @@ export type EdgeRelation =
   | { kind: 'instanceField'; name: string }
   | { kind: 'boundMethodReceiver' }
   | { kind: 'boundMethodFunction' }
+  | { kind: 'iteratorSource'; index: number }
+  | { kind: 'generatorClosure' }
+  | { kind: 'generatorStack'; index: number }
+  | { kind: 'generatorInner' }
   | { kind: 'unknown' }
 
 export interface HeapSnapshot {
@@ -354,6 +365,14 @@ export function formatEdgeRelation(relation: EdgeRelation): string {
       return 'receiver'
     case 'boundMethodFunction':
       return 'method'
+    case 'iteratorSource':
+      return `sources[${relation.index}]`
+    case 'generatorClosure':
+      return 'closure'
+    case 'generatorStack':
+      return `stack[${relation.index}]`
+    case 'generatorInner':
+      return 'delegate'
     case 'unknown':
       return 'unknown'
   }
@@ -494,6 +513,24 @@ function readEdgeRelation(value: unknown, path: string): EdgeRelation {
       return { kind: 'boundMethodReceiver' }
     case 'boundMethodFunction':
       return { kind: 'boundMethodFunction' }
+    case 'iteratorSource': {
+      const index = readNumber(value, 'index', path)
+      if (!Number.isSafeInteger(index)) {
+        throw new Error(`${path}.index must be a non-negative safe integer`)
+      }
+      return { kind: 'iteratorSource', index }
+    }
+    case 'generatorClosure':
+      return { kind: 'generatorClosure' }
+    case 'generatorStack': {
+      const index = readNumber(value, 'index', path)
+      if (!Number.isSafeInteger(index)) {
+        throw new Error(`${path}.index must be a non-negative safe integer`)
+      }
+      return { kind: 'generatorStack', index }
+    }
+    case 'generatorInner':
+      return { kind: 'generatorInner' }
     case 'unknown':
       return { kind: 'unknown' }
   }
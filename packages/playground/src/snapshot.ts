@@ -2,6 +2,7 @@ import type { SourceSpan } from './gcReport'
 
 export const snapshotSections = [
   'header',
+  'builtins',
   'main',
   'constants',
   'debug',
@@ -11,6 +12,7 @@ export type SnapshotSection = (typeof snapshotSections)[number]
 
 export const snapshotSectionTitles: Record<SnapshotSection, string> = {
   header: 'Header',
+  builtins: 'Builtin names',
   main: 'Main program',
   constants: 'Constant pool',
   debug: 'Debug info',
@@ -289,7 +291,7 @@ export interface SnapshotSectionGroup {
 
 /**
  * Group consecutive same-section regions for display. Sections appear in
- * file order (header, main, constants, debug), so this yields one group per
+ * file order (header, builtins, main, constants, debug), so this yields one group per
  * section that is present.
  */
 export function groupRegionsBySection(
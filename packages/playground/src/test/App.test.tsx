@@ -155,8 +155,11 @@ const counts = (overrides: Partial<ValueKindCounts> = {}): ValueKindCounts => ({
   boundMethod: 0,
   closure: 0,
   array: 0,
+  iterator: 0,
+  generator: 0,
   hash: 0,
   integer: 0,
+  float: 0,
   boolean: 0,
   string: 0,
   null: 0,
@@ -8,8 +8,11 @@ const EMITTED_RUNTIME_CALLS = [
   'rt_globals_init',
   'rt_string_from_bytes',
   'rt_box_int',
+  'rt_box_float',
+  'rt_bigint_from_decimal',
   'rt_array',
   'rt_hash',
+  'rt_concat',
   'rt_closure',
   'rt_get_free',
   'rt_class',
@@ -16,8 +16,11 @@ const counts = (): ValueKindCounts => ({
   boundMethod: 0,
   closure: 0,
   array: 0,
+  iterator: 0,
+  generator: 0,
   hash: 0,
   integer: 0,
+  float: 0,
   boolean: 0,
   string: 0,
   null: 0,
@@ -13,6 +13,8 @@ import type {
   IndexExpression,
   Identifier,
   IntegerLiteral,
+  BigIntLiteral,
+  FloatLiteral,
   BooleanLiteral,
   StringLiteral,
   ArrayLiteral,
@@ -25,6 +27,8 @@ import type {
   ThisExpression,
   PropertyExpression,
   NewExpression,
+  TemplateLiteral,
+  YieldExpression,
 } from './types'
 
 const { group, indent, line, softline, hardline, join, ifBreak } = doc.builders
@@ -89,7 +93,13 @@ export function print(
       return printPropertyExpression(node as PropertyExpression, path, print)
     case 'NewExpression':
       return printNewExpression(node as NewExpression, path, print)
+    case 'TemplateLiteral':
+      return printTemplateLiteral(node as TemplateLiteral, path, print)
+    case 'YieldExpression':
+      return printYieldExpression(node as YieldExpression, path, print)
     case 'Integer':
+    case 'BigInt':
+    case 'Float':
     case 'Boolean':
     case 'String':
     case 'Array':
@@ -334,7 +344,7 @@ function printFunctionDeclaration(
   options: Options
 ): Doc {
   return group([
-    'fn',
+    node.generator ? 'fn*' : 'fn',
     printDelimitedList(path, print, 'params'),
     ' ',
     path.call(print, 'body'),
@@ -395,6 +405,34 @@ function printNewExpression(
   ])
 }
 
+// Template text is printed verbatim; only the code inside `${}` is formatted,
+// so the string a template evaluates to never changes.
+function printTemplateLiteral(
+  node: TemplateLiteral,
+  path: AstPath,
+  print: (path: AstPath) => Doc
+): Doc {
+  const expressions = path.map(print, 'expressions')
+  const parts: Doc[] = ['`', node.quasis[0]]
+  expressions.forEach((expression, index) => {
+    parts.push('${', expression, '}', node.quasis[index + 1])
+  })
+  parts.push('`')
+  return parts
+}
+
+function printYieldExpression(
+  node: YieldExpression,
+  path: AstPath,
+  print: (path: AstPath) => Doc
+): Doc {
+  const keyword = node.delegate ? 'yield*' : 'yield'
+  if (!node.argument) {
+    return keyword
+  }
+  return group([keyword, ' ', path.call(print, 'argument')])
+}
+
 function printDelimitedList(
   path: AstPath,
   print: (path: AstPath) => Doc,
@@ -448,6 +486,28 @@ function printInnerComments(node: ASTNode): Doc[] {
   return comments
 }
 
+/**
+ * Float literals must stay `digits.digits`: `1.0` keeps its point, and values
+ * JavaScript would render as `1e+21` or `1e-7` are expanded to plain digits.
+ */
+function printFloat(raw: number): string {
+  const text = String(raw)
+  if (!text.includes('e')) {
+    return text.includes('.') ? text : `${text}.0`
+  }
+  const [mantissa, exponent] = text.split('e')
+  const [whole, fraction = ''] = mantissa.split('.')
+  const digits = whole + fraction
+  const point = whole.length + Number(exponent)
+  if (point <= 0) {
+    return `0.${'0'.repeat(-point)}${digits}`
+  }
+  if (point >= digits.length) {
+    return `${digits}${'0'.repeat(point - digits.length)}.0`
+  }
+  return `${digits.slice(0, point)}.${digits.slice(point)}`
+}
+
 function printLiteral(
   node: Literal,
   path: AstPath,
@@ -457,6 +517,10 @@ function printLiteral(
   switch (node.type) {
     case 'Integer':
       return String((node as IntegerLiteral).raw)
+    case 'BigInt':
+      return (node as BigIntLiteral).raw
+    case 'Float':
+      return printFloat((node as FloatLiteral).raw)
     case 'Boolean':
       return String((node as BooleanLiteral).raw)
     case 'String': {
@@ -118,6 +118,8 @@ export interface FunctionDeclaration extends ASTNode {
   params: Identifier[]
   body: BlockStatement
   name?: string
+  /** `fn*`; omitted for plain functions. */
+  generator?: boolean
 }
 
 export interface FunctionCall extends ASTNode {
@@ -148,12 +150,37 @@ export interface NewExpression extends ASTNode {
   arguments: ASTNode[]
 }
 
+/** `` `a${x}b` ``: `quasis` has one more entry than `expressions`. */
+export interface TemplateLiteral extends ASTNode {
+  type: 'TemplateLiteral'
+  quasis: string[]
+  expressions: ASTNode[]
+}
+
+/** `yield value` or `yield* generator`; a bare `yield` has no argument. */
+export interface YieldExpression extends ASTNode {
+  type: 'YieldExpression'
+  argument?: ASTNode
+  delegate?: boolean
+}
+
 // Literals
 export interface IntegerLiteral extends ASTNode {
   type: 'Integer'
   raw: number
 }
 
+/** An integer literal outside the i64 range; `raw` holds its decimal digits. */
+export interface BigIntLiteral extends ASTNode {
+  type: 'BigInt'
+  raw: string
+}
+
+export interface FloatLiteral extends ASTNode {
+  type: 'Float'
+  raw: number
+}
+
 export interface BooleanLiteral extends ASTNode {
   type: 'Boolean'
   raw: boolean
@@ -176,6 +203,8 @@ export interface HashLiteral extends ASTNode {
 
 export type Literal =
   | IntegerLiteral
+  | BigIntLiteral
+  | FloatLiteral
   | BooleanLiteral
   | StringLiteral
   | ArrayLiteral
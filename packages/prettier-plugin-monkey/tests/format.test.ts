@@ -50,6 +50,22 @@ describe('Prettier Plugin Monkey', () => {
     expect(await format(input)).toBe(expected)
   })
 
+  it('formats template literals', async () => {
+    const input = 'let s=`n = ${ n+1 }, ${[1,2]}!`;'
+    const expected = 'let s = `n = ${n + 1}, ${[1, 2]}!`;\n'
+    expect(await format(input)).toBe(expected)
+  })
+
+  it('formats generator functions', async () => {
+    const input = 'let gen=fn*(g){let x=yield   1;return yield*g;};'
+    const expected = `let gen = fn*(g) {
+  let x = yield 1;
+  return yield* g;
+};
+`
+    expect(await format(input)).toBe(expected)
+  })
+
   it('formats arrays', async () => {
     const input = 'let arr=[1,2,3];'
     const expected = 'let arr = [1, 2, 3];\n'
@@ -1,8 +1,11 @@
 use core::fmt;
 use core::fmt::Result;
-use lexer::token::{Span, Token, TokenKind};
+use lexer::token::{Float64, Span, Token, TokenKind};
+use serde::de::{self, DeserializeOwned, Deserializer};
 use serde::{Deserialize, Serialize};
+use std::cell::{Ref, RefCell};
 use std::fmt::Formatter;
+use std::hash::Hasher;
 
 // still wait for https://github.com/serde-rs/serde/issues/1402
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
@@ -53,7 +56,7 @@ impl fmt::Display for Program {
     }
 }
 
-#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Serialize, Hash, PartialEq)]
 #[serde(untagged)]
 pub enum Statement {
     Let(Let),
@@ -67,16 +70,30 @@ pub enum Statement {
 #[serde(tag = "type")]
 pub struct ClassDeclaration {
     pub name: IDENTIFIER,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldDeclaration>,
     pub methods: Vec<MethodDefinition>,
     pub span: Span,
 }
 
+/// `name: Type;` inside a class body. Only the type checker reads these;
+/// instances still get their fields from property assignments at runtime.
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+#[serde(tag = "type")]
+pub struct FieldDeclaration {
+    pub name: IDENTIFIER,
+    pub type_annotation: TypeAnnotation,
+    pub span: Span,
+}
+
 #[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
 #[serde(tag = "type")]
 pub struct MethodDefinition {
     pub kind: MethodKind,
     pub name: IDENTIFIER,
     pub params: Vec<IDENTIFIER>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<TypeAnnotation>,
     pub body: BlockStatement,
     pub span: Span,
 }
@@ -100,8 +117,13 @@ pub struct SetPropertyStatement {
 #[serde(tag = "type")]
 pub struct Let {
     pub identifier: Token, // rust can't do precise type with enum
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_annotation: Option<TypeAnnotation>,
     pub expr: Expression,
     pub span: Span,
+    /// The binding the statement assigns.
+    #[serde(skip)]
+    pub resolution: Resolution,
 }
 
 #[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
@@ -116,6 +138,7 @@ impl fmt::Display for Statement {
         match self {
             Statement::Let(Let {
                 identifier: id,
+                type_annotation,
                 expr,
                 ..
             }) => {
@@ -123,7 +146,10 @@ impl fmt::Display for Statement {
                     name,
                 } = &id.kind
                 {
-                    return write!(f, "let {} = {};", name, expr);
+                    return match type_annotation {
+                        Some(annotation) => write!(f, "let {}: {} = {};", name, annotation, expr),
+                        None => write!(f, "let {} = {};", name, expr),
+                    };
                 }
                 panic!("unreachable")
             }
@@ -134,13 +160,19 @@ impl fmt::Display for Statement {
                 write!(f, "return {};", argument)
             }
             Statement::Class(class) => {
+                let fields = class
+                    .fields
+                    .iter()
+                    .map(|field| format!("{}: {};", field.name.name, field.type_annotation))
+                    .collect::<Vec<_>>()
+                    .join("");
                 let methods = class
                     .methods
                     .iter()
                     .map(|method| method.to_string())
                     .collect::<Vec<_>>()
                     .join("");
-                write!(f, "class {} {{{}}}", class.name, methods)
+                write!(f, "class {} {{{}{}}}", class.name, fields, methods)
             }
             Statement::SetProperty(set) => {
                 write!(f, "{}.{} = {};", set.object, set.property, set.value)
@@ -158,7 +190,12 @@ impl fmt::Display for MethodDefinition {
             .map(ToString::to_string)
             .collect::<Vec<_>>()
             .join(", ");
-        write!(f, "{}({}) {{{}}}", self.name, params, self.body)
+        match &self.return_type {
+            Some(return_type) => {
+                write!(f, "{}({}): {} {{{}}}", self.name, params, return_type, self.body)
+            }
+            None => write!(f, "{}({}) {{{}}}", self.name, params, self.body),
+        }
     }
 }
 
@@ -175,7 +212,7 @@ impl fmt::Display for BlockStatement {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Serialize, Eq, Hash, PartialEq)]
 #[serde(untagged)]
 pub enum Expression {
     IDENTIFIER(IDENTIFIER),
@@ -189,12 +226,16 @@ pub enum Expression {
     This(ThisExpression),
     Property(PropertyExpression),
     New(NewExpression),
+    Template(TemplateLiteral),
+    Yield(YieldExpression),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
 #[serde(tag = "type")]
 pub struct ThisExpression {
     pub span: Span,
+    #[serde(skip)]
+    pub resolution: Resolution,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
@@ -213,16 +254,94 @@ pub struct NewExpression {
     pub span: Span,
 }
 
+/// `yield value`, a bare `yield`, which yields null, or `yield* generator`.
+/// Validation only allows it in a generator's body, at the level of a
+/// statement.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[serde(tag = "type")]
+pub struct YieldExpression {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument: Option<Box<Expression>>,
+    /// `yield*`, which always has an argument.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub delegate: bool,
+    pub span: Span,
+}
+
+/// `` `a${x}b` ``: `quasis` holds the text around each substitution, so it
+/// always has exactly one more entry than `expressions`.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[serde(tag = "type")]
+pub struct TemplateLiteral {
+    pub quasis: Vec<String>,
+    pub expressions: Vec<Expression>,
+    pub span: Span,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, Hash, PartialEq)]
 #[serde(tag = "type")]
 pub struct IDENTIFIER {
     pub name: String,
+    /// Only set on function and method parameters (`fn(a: int) {}`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_annotation: Option<TypeAnnotation>,
     pub span: Span,
+    /// Where the name is bound, for a reference or a class name. Empty on
+    /// parameters, whose slots follow their order.
+    #[serde(skip)]
+    pub resolution: Resolution,
+}
+
+/// Where a name is bound, filled in by
+/// [`validate_program`](crate::validation::validate_program) so the
+/// interpreter can find bindings without looking names up. It is derived
+/// from the rest of the tree: serialization, equality and hashing ignore it.
+///
+/// The bindings are tried in order. A `let` inside an `if` branch may not
+/// have run, so a slot it declares can be unset; the lookup then goes on to
+/// the binding the name would have without it.
+#[derive(Clone, Debug, Default)]
+pub struct Resolution(RefCell<Vec<Binding>>);
+
+impl Resolution {
+    pub fn bindings(&self) -> Ref<'_, [Binding]> {
+        return Ref::map(self.0.borrow(), Vec::as_slice);
+    }
+
+    pub fn set(&self, bindings: Vec<Binding>) {
+        *self.0.borrow_mut() = bindings;
+    }
+}
+
+impl PartialEq for Resolution {
+    fn eq(&self, _: &Resolution) -> bool {
+        return true;
+    }
+}
+
+impl Eq for Resolution {}
+
+impl std::hash::Hash for Resolution {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    /// Slot `index` of the function or method scope `depth` scopes out from
+    /// the innermost one. A function's parameters come first, then its
+    /// `let`s and classes; a method's `this` follows its parameters.
+    Local { depth: usize, index: usize },
+    /// A top-level name, looked up by name in the scope `depth` scopes out,
+    /// which is where the program was evaluated.
+    Global { depth: usize },
 }
 
 impl fmt::Display for IDENTIFIER {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{}", self.name)
+        match &self.type_annotation {
+            Some(annotation) => write!(f, "{}: {}", self.name, annotation),
+            None => write!(f, "{}", self.name),
+        }
     }
 }
 
@@ -256,9 +375,15 @@ pub struct IF {
 #[serde(tag = "type")]
 pub struct FunctionDeclaration {
     pub params: Vec<IDENTIFIER>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<TypeAnnotation>,
     pub body: BlockStatement,
     pub span: Span,
     pub name: String,
+    /// `fn*`: calling it returns a generator that runs the body up to each
+    /// `yield`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub generator: bool,
 }
 
 // function can be Identifier or FunctionLiteral (think iife)
@@ -316,7 +441,9 @@ impl fmt::Display for Expression {
             Expression::FUNCTION(FunctionDeclaration {
                 name,
                 params,
+                return_type,
                 body,
+                generator,
                 ..
             }) => {
                 let func_params = params
@@ -324,7 +451,15 @@ impl fmt::Display for Expression {
                     .map(|stmt| stmt.to_string())
                     .collect::<Vec<String>>()
                     .join(", ");
-                write!(f, "fn {}({}) {{ {} }}", name, func_params, body)
+                let keyword = if *generator { "fn*" } else { "fn" };
+                match return_type {
+                    Some(return_type) => write!(
+                        f,
+                        "{} {}({}): {} {{ {} }}",
+                        keyword, name, func_params, return_type, body
+                    ),
+                    None => write!(f, "{} {}({}) {{ {} }}", keyword, name, func_params, body),
+                }
             }
             Expression::FunctionCall(FunctionCall {
                 callee,
@@ -351,6 +486,28 @@ impl fmt::Display for Expression {
                 arguments,
                 ..
             }) => write!(f, "new {}({})", callee, format_expressions(arguments)),
+            Expression::Template(TemplateLiteral {
+                quasis,
+                expressions,
+                ..
+            }) => {
+                write!(f, "`{}", quasis[0])?;
+                for (expression, quasi) in expressions.iter().zip(&quasis[1..]) {
+                    write!(f, "${{{}}}{}", expression, quasi)?;
+                }
+                write!(f, "`")
+            }
+            Expression::Yield(YieldExpression {
+                argument,
+                delegate,
+                ..
+            }) => {
+                let keyword = if *delegate { "yield*" } else { "yield" };
+                match argument {
+                    Some(argument) => write!(f, "{} {}", keyword, argument),
+                    None => write!(f, "{}", keyword),
+                }
+            }
         }
     }
 }
@@ -381,6 +538,8 @@ impl Expression {
             Expression::This(expression) => &expression.span,
             Expression::Property(expression) => &expression.span,
             Expression::New(expression) => &expression.span,
+            Expression::Template(expression) => &expression.span,
+            Expression::Yield(expression) => &expression.span,
         }
     }
 }
@@ -389,6 +548,8 @@ impl Literal {
     pub fn span(&self) -> &Span {
         match self {
             Literal::Integer(literal) => &literal.span,
+            Literal::BigInt(literal) => &literal.span,
+            Literal::Float(literal) => &literal.span,
             Literal::Boolean(literal) => &literal.span,
             Literal::String(literal) => &literal.span,
             Literal::Array(literal) => &literal.span,
@@ -401,6 +562,8 @@ impl Literal {
 #[serde(tag = "type")]
 pub enum Literal {
     Integer(Integer),
+    BigInt(BigInteger),
+    Float(Float),
     Boolean(Boolean),
     String(StringType),
     Array(Array),
@@ -413,6 +576,20 @@ pub struct Integer {
     pub span: Span,
 }
 
+/// An integer literal that does not fit in `i64`; `raw` holds its decimal
+/// digits.
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+pub struct BigInteger {
+    pub raw: String,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+pub struct Float {
+    pub raw: Float64,
+    pub span: Span,
+}
+
 #[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
 pub struct Boolean {
     pub raw: bool,
@@ -444,6 +621,14 @@ impl fmt::Display for Literal {
                 raw: i,
                 ..
             }) => write!(f, "{}", i),
+            Literal::BigInt(BigInteger {
+                raw: digits,
+                ..
+            }) => write!(f, "{}", digits),
+            Literal::Float(Float {
+                raw: x,
+                ..
+            }) => write!(f, "{}", x),
             Literal::Boolean(Boolean {
                 raw: b,
                 ..
@@ -472,6 +657,96 @@ impl fmt::Display for Literal {
     }
 }
 
+/// Type syntax accepted after `:` in let bindings, parameters, return
+/// positions and class fields. Every backend ignores it; `monkey-typeck`
+/// is the only consumer.
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+#[serde(tag = "type")]
+pub enum TypeAnnotation {
+    TypeReference(TypeReference),
+    ArrayType(ArrayType),
+    HashType(HashType),
+    FunctionType(FunctionType),
+    UnionType(UnionType),
+}
+
+/// `int`, `bool`, `string`, `null`, `any` or a class name.
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+pub struct TypeReference {
+    pub name: String,
+    pub span: Span,
+}
+
+/// `[T]`
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+pub struct ArrayType {
+    pub element: Box<TypeAnnotation>,
+    pub span: Span,
+}
+
+/// `{K: V}`
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+pub struct HashType {
+    pub key: Box<TypeAnnotation>,
+    pub value: Box<TypeAnnotation>,
+    pub span: Span,
+}
+
+/// `fn(A, B): R`
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+pub struct FunctionType {
+    pub params: Vec<TypeAnnotation>,
+    pub return_type: Box<TypeAnnotation>,
+    pub span: Span,
+}
+
+/// `A | B`
+#[derive(Clone, Debug, Eq, Serialize, Deserialize, Hash, PartialEq)]
+pub struct UnionType {
+    pub types: Vec<TypeAnnotation>,
+    pub span: Span,
+}
+
+impl TypeAnnotation {
+    pub fn span(&self) -> &Span {
+        match self {
+            TypeAnnotation::TypeReference(annotation) => &annotation.span,
+            TypeAnnotation::ArrayType(annotation) => &annotation.span,
+            TypeAnnotation::HashType(annotation) => &annotation.span,
+            TypeAnnotation::FunctionType(annotation) => &annotation.span,
+            TypeAnnotation::UnionType(annotation) => &annotation.span,
+        }
+    }
+}
+
+impl fmt::Display for TypeAnnotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeAnnotation::TypeReference(reference) => write!(f, "{}", reference.name),
+            TypeAnnotation::ArrayType(array) => write!(f, "[{}]", array.element),
+            TypeAnnotation::HashType(hash) => write!(f, "{{{}: {}}}", hash.key, hash.value),
+            TypeAnnotation::FunctionType(function) => {
+                let params = function
+                    .params
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "fn({}): {}", params, function.return_type)
+            }
+            TypeAnnotation::UnionType(union) => {
+                let types = union
+                    .types
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                write!(f, "{}", types)
+            }
+        }
+    }
+}
+
 fn format_statements(statements: &[Statement]) -> String {
     return statements
         .iter()
@@ -487,3 +762,70 @@ fn format_expressions(exprs: &[Expression]) -> String {
         .collect::<Vec<String>>()
         .join(", ");
 }
+
+// `Statement` and `Expression` serialize as the node they hold, whose `type`
+// says which variant it is. A derived untagged `Deserialize` would not check
+// that tag on structs (serde-rs/serde#1402) and would take the first variant
+// whose fields fit, reading a function as an identifier, so these dispatch on
+// the tag instead.
+
+impl<'de> Deserialize<'de> for Statement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let (tag, node) = tagged_node::<D>(deserializer)?;
+        match tag.as_str() {
+            "Let" => from_node(node).map(Statement::Let),
+            "ReturnStatement" => from_node(node).map(Statement::Return),
+            "ClassDeclaration" => from_node(node).map(Statement::Class),
+            "SetPropertyStatement" => from_node(node).map(Statement::SetProperty),
+            _ => expression_from_node(&tag, node).map(Statement::Expr),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let (tag, node) = tagged_node::<D>(deserializer)?;
+        expression_from_node(&tag, node)
+    }
+}
+
+fn tagged_node<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<(String, serde_json::Value), D::Error> {
+    let node = serde_json::Value::deserialize(deserializer)?;
+    let tag = match node.get("type").and_then(serde_json::Value::as_str) {
+        Some(tag) => tag.to_string(),
+        None => return Err(de::Error::custom("AST node without a type")),
+    };
+    Ok((tag, node))
+}
+
+fn from_node<T: DeserializeOwned, E: de::Error>(
+    node: serde_json::Value,
+) -> std::result::Result<T, E> {
+    serde_json::from_value(node).map_err(E::custom)
+}
+
+fn expression_from_node<E: de::Error>(
+    tag: &str,
+    node: serde_json::Value,
+) -> std::result::Result<Expression, E> {
+    match tag {
+        "IDENTIFIER" => from_node(node).map(Expression::IDENTIFIER),
+        "Integer" | "BigInt" | "Float" | "Boolean" | "String" | "Array" | "Hash" => {
+            from_node(node).map(Expression::LITERAL)
+        }
+        "UnaryExpression" => from_node(node).map(Expression::PREFIX),
+        "BinaryExpression" => from_node(node).map(Expression::INFIX),
+        "IF" => from_node(node).map(Expression::IF),
+        "FunctionDeclaration" => from_node(node).map(Expression::FUNCTION),
+        "FunctionCall" => from_node(node).map(Expression::FunctionCall),
+        "Index" => from_node(node).map(Expression::Index),
+        "ThisExpression" => from_node(node).map(Expression::This),
+        "PropertyExpression" => from_node(node).map(Expression::Property),
+        "NewExpression" => from_node(node).map(Expression::New),
+        "TemplateLiteral" => from_node(node).map(Expression::Template),
+        "YieldExpression" => from_node(node).map(Expression::Yield),
+        _ => Err(E::custom(format!("unknown AST node type {}", tag))),
+    }
+}
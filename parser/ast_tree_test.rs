@@ -46,12 +46,24 @@ mod tests {
     }
 
     // https://astexplorer.net/#/gist/0911a07ddb31d261074d1d59f6291a7c/ebbaf31807ccdcec6605aaa2d3a222258cac7f28
+    #[test]
+    fn test_template() {
+        let input = "`n = ${n}!`";
+        test_ast_tree("test_template", input)
+    }
+
     #[test]
     fn test_return() {
         let input = "return 3";
         test_ast_tree("test_return", input)
     }
 
+    #[test]
+    fn test_float() {
+        let input = "let ratio = 0.75";
+        test_ast_tree("test_float", input)
+    }
+
     // https://astexplorer.net/#/gist/0911a07ddb31d261074d1d59f6291a7c/5bf612e5f406bc59076f54371671af05022a74d0
     #[test]
     fn test_unary() {
@@ -109,6 +109,7 @@ impl<'a> Parser<'a> {
             } => name.to_string(),
             _ => return Err(format!("{} not an identifier", self.current_token)),
         };
+        let type_annotation = self.parse_optional_type_annotation()?;
 
         self.expect_peek(&TokenKind::ASSIGN)?;
         self.next_token();
@@ -129,11 +130,13 @@ impl<'a> Parser<'a> {
 
         return Ok(Statement::Let(Let {
             identifier: name,
+            type_annotation,
             expr: value,
             span: Span {
                 start,
                 end,
             },
+            resolution: Resolution::default(),
         }));
     }
 
@@ -232,7 +235,9 @@ impl<'a> Parser<'a> {
                 return Ok((
                     Expression::IDENTIFIER(IDENTIFIER {
                         name: name.clone(),
+                        type_annotation: None,
                         span: span.clone(),
+                        resolution: Resolution::default(),
                     }),
                     span,
                 ));
@@ -247,6 +252,26 @@ impl<'a> Parser<'a> {
                     span,
                 ));
             }
+            TokenKind::BIGINT(digits) => {
+                let span = self.current_token.span.clone();
+                return Ok((
+                    Expression::LITERAL(Literal::BigInt(BigInteger {
+                        raw: digits.clone(),
+                        span: span.clone(),
+                    })),
+                    span,
+                ));
+            }
+            TokenKind::FLOAT(x) => {
+                let span = self.current_token.span.clone();
+                return Ok((
+                    Expression::LITERAL(Literal::Float(Float {
+                        raw: *x,
+                        span: span.clone(),
+                    })),
+                    span,
+                ));
+            }
             TokenKind::STRING(s) => {
                 let span = self.current_token.span.clone();
                 return Ok((
@@ -257,6 +282,22 @@ impl<'a> Parser<'a> {
                     span,
                 ));
             }
+            TokenKind::TemplateString(text) => {
+                let span = self.current_token.span.clone();
+                return Ok((
+                    Expression::Template(TemplateLiteral {
+                        quasis: vec![text.clone()],
+                        expressions: vec![],
+                        span: span.clone(),
+                    }),
+                    span,
+                ));
+            }
+            TokenKind::TemplateHead(_) => {
+                let expression = self.parse_template_literal()?;
+                let span = expression.span().clone();
+                Ok((expression, span))
+            }
             b @ TokenKind::TRUE | b @ TokenKind::FALSE => {
                 let span = self.current_token.span.clone();
                 return Ok((
@@ -326,6 +367,7 @@ impl<'a> Parser<'a> {
                 Ok((
                     Expression::This(ThisExpression {
                         span: span.clone(),
+                        resolution: Resolution::default(),
                     }),
                     span,
                 ))
@@ -335,6 +377,11 @@ impl<'a> Parser<'a> {
                 let span = expression.span().clone();
                 Ok((expression, span))
             }
+            TokenKind::YIELD => {
+                let expression = self.parse_yield_expression()?;
+                let span = expression.span().clone();
+                Ok((expression, span))
+            }
             _ => Err(format!("no prefix function for token: {}", self.current_token)),
         }
     }
@@ -462,9 +509,14 @@ impl<'a> Parser<'a> {
 
     fn parse_fn_expression(&mut self) -> Result<Expression, ParseError> {
         let start = self.current_token.span.start;
+        let generator = self.peek_token_is(&TokenKind::ASTERISK);
+        if generator {
+            self.next_token();
+        }
         self.expect_peek(&TokenKind::LPAREN)?;
 
         let params = self.parse_fn_parameters()?;
+        let return_type = self.parse_optional_type_annotation()?;
 
         self.expect_peek(&TokenKind::LBRACE)?;
 
@@ -474,15 +526,51 @@ impl<'a> Parser<'a> {
 
         Ok(Expression::FUNCTION(FunctionDeclaration {
             params,
+            return_type,
             body: function_body,
             span: Span {
                 start,
                 end,
             },
             name: "".to_string(),
+            generator,
         }))
     }
 
+    fn parse_yield_expression(&mut self) -> Result<Expression, ParseError> {
+        let mut span = self.current_token.span.clone();
+        let delegate = self.peek_token_is(&TokenKind::ASTERISK);
+        if delegate {
+            self.next_token();
+            span.end = self.current_token.span.end;
+        }
+        let ends_argument = [
+            TokenKind::SEMICOLON,
+            TokenKind::RPAREN,
+            TokenKind::RBRACE,
+            TokenKind::RBRACKET,
+            TokenKind::COMMA,
+            TokenKind::EOF,
+        ];
+        let argument = if ends_argument.contains(&self.peek_token.kind) {
+            if delegate {
+                return Err("yield* needs a generator to delegate to".to_string());
+            }
+            None
+        } else {
+            self.next_token();
+            let (argument, argument_span) = self.parse_expression(Precedence::Lowest)?;
+            span.end = argument_span.end;
+            Some(Box::new(argument))
+        };
+
+        return Ok(Expression::Yield(YieldExpression {
+            argument,
+            delegate,
+            span,
+        }));
+    }
+
     fn parse_fn_parameters(&mut self) -> Result<Vec<IDENTIFIER>, ParseError> {
         let mut params = Vec::new();
         if self.peek_token_is(&TokenKind::RPAREN) {
@@ -491,41 +579,140 @@ impl<'a> Parser<'a> {
         }
 
         self.next_token();
+        params.push(self.parse_fn_parameter()?);
 
-        match &self.current_token.kind {
+        while self.peek_token_is(&TokenKind::COMMA) {
+            self.next_token();
+            self.next_token();
+            params.push(self.parse_fn_parameter()?);
+        }
+
+        self.expect_peek(&TokenKind::RPAREN)?;
+
+        return Ok(params);
+    }
+
+    fn parse_fn_parameter(&mut self) -> Result<IDENTIFIER, ParseError> {
+        let name = match &self.current_token.kind {
             TokenKind::IDENTIFIER {
                 name,
-            } => params.push(IDENTIFIER {
-                name: name.clone(),
-                span: self.current_token.span.clone(),
-            }),
+            } => name.clone(),
             token => {
                 return Err(format!("expected function params  to be an identifier, got {}", token))
             }
+        };
+        let span = self.current_token.span.clone();
+        let type_annotation = self.parse_optional_type_annotation()?;
+
+        Ok(IDENTIFIER {
+            name,
+            type_annotation,
+            span,
+            resolution: Resolution::default(),
+        })
+    }
+
+    /// Parses `: Type` when the next token is a colon; leaves the parser on
+    /// the last token of the annotation.
+    fn parse_optional_type_annotation(&mut self) -> Result<Option<TypeAnnotation>, ParseError> {
+        if !self.peek_token_is(&TokenKind::COLON) {
+            return Ok(None);
         }
+        self.next_token();
+        self.next_token();
+        self.parse_type_annotation().map(Some)
+    }
 
-        while self.peek_token_is(&TokenKind::COMMA) {
+    fn parse_type_annotation(&mut self) -> Result<TypeAnnotation, ParseError> {
+        let first = self.parse_primary_type_annotation()?;
+        if !self.peek_token_is(&TokenKind::PIPE) {
+            return Ok(first);
+        }
+
+        let start = first.span().start;
+        let mut types = vec![first];
+        while self.peek_token_is(&TokenKind::PIPE) {
             self.next_token();
             self.next_token();
-            match &self.current_token.kind {
-                TokenKind::IDENTIFIER {
-                    name,
-                } => params.push(IDENTIFIER {
-                    name: name.clone(),
-                    span: self.current_token.span.clone(),
-                }),
-                token => {
-                    return Err(format!(
-                        "expected function params  to be an identifier, got {}",
-                        token
-                    ))
-                }
-            }
+            types.push(self.parse_primary_type_annotation()?);
         }
+        let end = self.current_token.span.end;
 
-        self.expect_peek(&TokenKind::RPAREN)?;
+        Ok(TypeAnnotation::UnionType(UnionType {
+            types,
+            span: Span {
+                start,
+                end,
+            },
+        }))
+    }
 
-        return Ok(params);
+    fn parse_primary_type_annotation(&mut self) -> Result<TypeAnnotation, ParseError> {
+        let start = self.current_token.span.start;
+        match &self.current_token.kind {
+            TokenKind::IDENTIFIER {
+                name,
+            } => Ok(TypeAnnotation::TypeReference(TypeReference {
+                name: name.clone(),
+                span: self.current_token.span.clone(),
+            })),
+            TokenKind::LBRACKET => {
+                self.next_token();
+                let element = self.parse_type_annotation()?;
+                self.expect_peek(&TokenKind::RBRACKET)?;
+                Ok(TypeAnnotation::ArrayType(ArrayType {
+                    element: Box::new(element),
+                    span: Span {
+                        start,
+                        end: self.current_token.span.end,
+                    },
+                }))
+            }
+            TokenKind::LBRACE => {
+                self.next_token();
+                let key = self.parse_type_annotation()?;
+                self.expect_peek(&TokenKind::COLON)?;
+                self.next_token();
+                let value = self.parse_type_annotation()?;
+                self.expect_peek(&TokenKind::RBRACE)?;
+                Ok(TypeAnnotation::HashType(HashType {
+                    key: Box::new(key),
+                    value: Box::new(value),
+                    span: Span {
+                        start,
+                        end: self.current_token.span.end,
+                    },
+                }))
+            }
+            TokenKind::FUNCTION => {
+                self.expect_peek(&TokenKind::LPAREN)?;
+                let mut params = Vec::new();
+                if self.peek_token_is(&TokenKind::RPAREN) {
+                    self.next_token();
+                } else {
+                    self.next_token();
+                    params.push(self.parse_type_annotation()?);
+                    while self.peek_token_is(&TokenKind::COMMA) {
+                        self.next_token();
+                        self.next_token();
+                        params.push(self.parse_type_annotation()?);
+                    }
+                    self.expect_peek(&TokenKind::RPAREN)?;
+                }
+                self.expect_peek(&TokenKind::COLON)?;
+                self.next_token();
+                let return_type = self.parse_type_annotation()?;
+                Ok(TypeAnnotation::FunctionType(FunctionType {
+                    params,
+                    return_type: Box::new(return_type),
+                    span: Span {
+                        start,
+                        end: self.current_token.span.end,
+                    },
+                }))
+            }
+            token => Err(format!("expected a type, got {}", token)),
+        }
     }
 
     fn parse_fn_call_expression(
@@ -628,7 +815,9 @@ impl<'a> Parser<'a> {
                 name,
             } => IDENTIFIER {
                 name: name.clone(),
+                type_annotation: None,
                 span: self.current_token.span.clone(),
+                resolution: Resolution::default(),
             },
             _ => return Err("expected property name after '.'".to_string()),
         };
@@ -654,7 +843,9 @@ impl<'a> Parser<'a> {
                 name,
             } => IDENTIFIER {
                 name: name.clone(),
+                type_annotation: None,
                 span: self.current_token.span.clone(),
+                resolution: Resolution::default(),
             },
             _ => return Err("expected class name after 'new'".to_string()),
         };
@@ -682,12 +873,15 @@ impl<'a> Parser<'a> {
                 name,
             } => IDENTIFIER {
                 name: name.clone(),
+                type_annotation: None,
                 span: self.current_token.span.clone(),
+                resolution: Resolution::default(),
             },
             _ => return Err("expected class name after 'class'".to_string()),
         };
 
         self.expect_peek(&TokenKind::LBRACE)?;
+        let mut fields = Vec::new();
         let mut methods = Vec::new();
         let mut method_names = std::collections::HashSet::new();
         let mut has_constructor = false;
@@ -703,10 +897,23 @@ impl<'a> Parser<'a> {
                     name,
                 } => IDENTIFIER {
                     name: name.clone(),
+                    type_annotation: None,
                     span: self.current_token.span.clone(),
+                    resolution: Resolution::default(),
                 },
                 _ => return Err("expected method definition in class body".to_string()),
             };
+            if self.peek_token_is(&TokenKind::COLON) {
+                let field = self.parse_field_declaration(&class_name, method_name)?;
+                if fields
+                    .iter()
+                    .any(|existing: &FieldDeclaration| existing.name.name == field.name.name)
+                {
+                    return Err(format!("duplicate field {}.{}", class_name.name, field.name.name));
+                }
+                fields.push(field);
+                continue;
+            }
             let method_start = method_name.span.start;
             let kind = if method_name.name == "constructor" {
                 if has_constructor {
@@ -726,6 +933,7 @@ impl<'a> Parser<'a> {
 
             self.expect_peek(&TokenKind::LPAREN)?;
             let params = self.parse_fn_parameters()?;
+            let return_type = self.parse_optional_type_annotation()?;
             self.expect_peek(&TokenKind::LBRACE)?;
             let body = self.parse_block_statement()?;
             let method_end = body.span.end;
@@ -733,6 +941,7 @@ impl<'a> Parser<'a> {
                 kind,
                 name: method_name,
                 params,
+                return_type,
                 body,
                 span: Span {
                     start: method_start,
@@ -744,6 +953,7 @@ impl<'a> Parser<'a> {
         self.next_token();
         Ok(Statement::Class(ClassDeclaration {
             name: class_name,
+            fields,
             methods,
             span: Span {
                 start,
@@ -752,6 +962,70 @@ impl<'a> Parser<'a> {
         }))
     }
 
+    fn parse_field_declaration(
+        &mut self,
+        class_name: &IDENTIFIER,
+        name: IDENTIFIER,
+    ) -> Result<FieldDeclaration, ParseError> {
+        let start = name.span.start;
+        let type_annotation = self
+            .parse_optional_type_annotation()?
+            .expect("caller checked for ':'");
+        if self.peek_token_is(&TokenKind::SEMICOLON) {
+            self.next_token();
+        }
+        if name.name == "constructor" {
+            return Err(format!(
+                "class {} cannot declare a field named constructor",
+                class_name.name
+            ));
+        }
+        Ok(FieldDeclaration {
+            name,
+            type_annotation,
+            span: Span {
+                start,
+                end: self.current_token.span.end,
+            },
+        })
+    }
+
+    fn parse_template_literal(&mut self) -> Result<Expression, ParseError> {
+        let start = self.current_token.span.start;
+        let mut quasis = Vec::new();
+        let mut expressions = Vec::new();
+        if let TokenKind::TemplateHead(text) = &self.current_token.kind {
+            quasis.push(text.clone());
+        }
+        loop {
+            self.next_token();
+            expressions.push(self.parse_expression(Precedence::Lowest)?.0);
+            self.next_token();
+            match &self.current_token.kind {
+                TokenKind::TemplateMiddle(text) => quasis.push(text.clone()),
+                TokenKind::TemplateTail(text) => {
+                    quasis.push(text.clone());
+                    break;
+                }
+                _ => {
+                    return Err(format!(
+                        "expected `}}` to close template substitution, got: {}",
+                        self.current_token
+                    ))
+                }
+            }
+        }
+
+        Ok(Expression::Template(TemplateLiteral {
+            quasis,
+            expressions,
+            span: Span {
+                start,
+                end: self.current_token.span.end,
+            },
+        }))
+    }
+
     fn parse_hash_expression(&mut self) -> Result<Expression, ParseError> {
         let mut map = Vec::new();
         let start = self.current_token.span.start;
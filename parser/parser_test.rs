@@ -146,6 +146,37 @@ mod tests {
         verify_program(&test_case);
     }
 
+    #[test]
+    fn test_float_literal_expression() {
+        let test_case = [
+            ("1.5", "1.5"),
+            ("2.0 * -0.25", "(2.0 * (-0.25))"),
+            ("1.len", "1.len"),
+        ];
+        verify_program(&test_case);
+    }
+
+    #[test]
+    fn test_bigint_literal_expression() {
+        let test_case = [
+            ("9223372036854775807", "9223372036854775807"),
+            ("-9223372036854775808", "(-9223372036854775808)"),
+            ("100000000000000000000 * 2", "(100000000000000000000 * 2)"),
+        ];
+        verify_program(&test_case);
+    }
+
+    #[test]
+    fn test_template_literal_expression() {
+        let test_case = [
+            ("`plain`", "`plain`"),
+            ("`a${1 + 2}b`", "`a${(1 + 2)}b`"),
+            ("`${x}${y}`", "`${x}${y}`"),
+            (r#"`${ {"k": `${v}`}["k"] }!`"#, r#"`${({"k": `${v}`}["k"])}!`"#),
+        ];
+        verify_program(&test_case);
+    }
+
     #[test]
     fn test_array_literal_expression() {
         let test_case = [("[]", "[]"), ("[1, 2 * 2, 3 + 3]", "[1, (2 * 2), (3 + 3)]")];
@@ -201,6 +232,77 @@ connect();"#;
         assert!(matches!(*call.callee, Expression::Property(_)));
     }
 
+    #[test]
+    fn parses_type_annotations() {
+        let test_case = [
+            ("let x: int = 5;", "let x: int = 5;"),
+            ("let x: int | null = f();", "let x: int | null = f();"),
+            ("let xs: [string] = [];", "let xs: [string] = [];"),
+            ("let h: {string: [int]} = {};", "let h: {string: [int]} = {};"),
+            ("fn(a: string, b): int { len(a) }", "fn (a: string, b): int { len(a) }"),
+            ("let f: fn(int, bool): int | null = g;", "let f: fn(int, bool): int | null = g;"),
+            (
+                "class P { x: int; y: int | null; get(): int { this.x } }",
+                "class P {x: int;y: int | null;get(): int {this.x}}",
+            ),
+        ];
+        verify_program(&test_case);
+    }
+
+    #[test]
+    fn annotations_stay_out_of_unannotated_json() {
+        let json =
+            parse_ast_lossless_json_string("let f = fn(a) { a }; class A { m() {} }").unwrap();
+        assert!(!json.contains("type_annotation"));
+        assert!(!json.contains("return_type"));
+        assert!(!json.contains("fields"));
+
+        let json = parse_ast_lossless_json_string("let f = fn(a: int): [int] { [a] };").unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let function = &value["Program"]["body"][0]["expr"];
+        assert_eq!(function["params"][0]["type_annotation"]["type"], "TypeReference");
+        assert_eq!(function["return_type"]["type"], "ArrayType");
+        assert_eq!(function["return_type"]["element"]["name"], "int");
+    }
+
+    #[test]
+    fn parses_generators_and_yield() {
+        let test_case = [
+            ("fn*(n) { yield n; }", "fn* (n) { yield n }"),
+            (
+                "let g = fn*() { let x = yield; return yield x + 1; };",
+                "let g = fn* g() { let x = yield;return yield (x + 1); };",
+            ),
+            (
+                "fn*(g) { let x = yield* g; return yield* x; }",
+                "fn* (g) { let x = yield* g;return yield* x; }",
+            ),
+        ];
+        verify_program(&test_case);
+
+        let json = parse_ast_lossless_json_string("fn*() { yield; }; fn() {}").unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let body = &value["Program"]["body"];
+        assert_eq!(body[0]["generator"], true);
+        assert_eq!(body[0]["body"]["body"][0]["type"], "YieldExpression");
+        assert!(body[1].get("generator").is_none());
+        assert!(parse("fn*() { yield*; }").is_err());
+    }
+
+    #[test]
+    fn ast_json_reads_back_as_the_same_tree() {
+        let input = "let f = fn*(a, b) { let x = yield a + 1; if (x) { return yield* b; } \
+                     else { [1, 2.5, \"s\", {true: -x}] } }; \
+                     class A { constructor(v) { this.v = v; } m() { return new A(this.v).v; } } \
+                     let s = `a${f}b`; 99999999999999999999999; f(1)[0]; yield;";
+        let node = parse(input).unwrap();
+        let json = serde_json::to_string(&node).unwrap();
+        assert_eq!(serde_json::from_str::<Node>(&json).unwrap(), node);
+
+        let error = serde_json::from_str::<Expression>(r#"{"type": "Loop"}"#).unwrap_err();
+        assert!(error.to_string().contains("unknown AST node type Loop"));
+    }
+
     #[test]
     fn parses_property_set_as_statement() {
         let input = "node.next.value = new Node(1);";
@@ -235,11 +337,15 @@ connect();"#;
             ("class A { constructor() {} constructor() {} }", "more than one constructor"),
             ("class A { method() {} method() {} }", "duplicate method"),
             ("class A { let value = 1; }", "expected method definition"),
+            ("class A { x: int; x: bool; }", "duplicate field"),
+            ("let x: = 1", "expected a type"),
+            ("fn(a: [int) {}", "expected token"),
             ("fn() { class A {} }", "only allowed at top level"),
             ("new A", "requires an argument list"),
             ("value = 1", "only instance property assignment"),
             ("let value = object.field = 1", "only allowed as a statement"),
             ("1 + ;", "no prefix function"),
+            ("`a${1 2}`", "to close template substitution"),
             ("fn() { 1 + ; }", "no prefix function"),
         ] {
             let errors = parse(input).unwrap_err();
@@ -1,4 +1,18 @@
-use std::collections::HashSet;
+//! Checks that a program only uses names that are in scope, and records in
+//! each [`Resolution`] where the name is bound, the same way the compiler's
+//! symbol table would.
+//!
+//! Top-level names are bound by name, so a REPL can add more in later
+//! programs. Function and method scopes are numbered slots: parameters
+//! first, then `this` for methods, then `let`s and classes in source order.
+//! `if` branches do not open a scope.
+//!
+//! A `yield` must be the whole of an expression statement, a `let`
+//! initializer or a `return` value, in a generator's body or in `if`s used as
+//! statements there. The interpreter resumes a generator by statement, so it
+//! can not stop in the middle of an expression.
+
+use std::collections::HashMap;
 use std::fmt;
 
 use lexer::token::{Span, TokenKind};
@@ -20,28 +34,78 @@ impl fmt::Display for ValidationError {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum CallableKind {
     Function,
+    Generator,
     Method,
     Constructor,
 }
 
+/// A name visible in a scope.
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    /// The scope holding the binding, counted from the top level. It is the
+    /// surrounding scope for a function's own name.
+    level: usize,
+    /// `None` at the top level, where names are bound by name.
+    slot: Option<usize>,
+    /// Only declared inside `if` branches so far, so it may be unset.
+    conditional: bool,
+}
+
+impl Entry {
+    /// The binding as seen from a scope at `level`.
+    fn binding(self, level: usize) -> Binding {
+        let depth = level - self.level;
+        match self.slot {
+            Some(index) => Binding::Local {
+                depth,
+                index,
+            },
+            None => Binding::Global {
+                depth,
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+struct Scope {
+    names: HashMap<String, Entry>,
+    slots: usize,
+}
+
 struct Validator {
-    scopes: Vec<HashSet<String>>,
+    scopes: Vec<Scope>,
     callable_kinds: Vec<CallableKind>,
     receiver_available: bool,
     context: Vec<String>,
+    /// How many `if` branches of the current function surround the
+    /// statement being validated.
+    branch_depth: usize,
+    /// How many `if` branches of the current function surround the statement
+    /// while the `if` is used as a value rather than as a statement.
+    value_branch_depth: usize,
 }
 
 pub fn validate_program(
     program: &Program,
     predefined_globals: &[&str],
 ) -> Result<(), ValidationError> {
-    let mut globals = HashSet::new();
-    globals.extend(predefined_globals.iter().map(|name| (*name).to_string()));
+    let mut globals = Scope::default();
+    for name in predefined_globals {
+        let entry = Entry {
+            level: 0,
+            slot: None,
+            conditional: false,
+        };
+        globals.names.insert((*name).to_string(), entry);
+    }
     let mut validator = Validator {
         scopes: vec![globals],
         callable_kinds: Vec::new(),
         receiver_available: false,
         context: Vec::new(),
+        branch_depth: 0,
+        value_branch_depth: 0,
     };
     validator.validate_statements(&program.body)
 }
@@ -63,8 +127,16 @@ impl Validator {
                     } => name.clone(),
                     _ => unreachable!("parser only creates let statements with identifiers"),
                 };
-                self.validate_expression(&statement.expr)?;
-                self.scopes.last_mut().unwrap().insert(name);
+                let entry = self.declare(&name);
+                match &statement.expr {
+                    Expression::FUNCTION(function) if function.name == name => {
+                        self.validate_function(function, Some(entry))?
+                    }
+                    Expression::Yield(expression) => self.validate_yield(expression)?,
+                    expression => self.validate_expression(expression)?,
+                }
+                self.define(name, entry);
+                statement.resolution.set(vec![entry.binding(self.level())]);
                 Ok(())
             }
             Statement::Return(statement) => {
@@ -74,22 +146,64 @@ impl Validator {
                         span: statement.span.clone(),
                     });
                 }
-                self.validate_expression(&statement.argument)
+                match &statement.argument {
+                    Expression::Yield(expression) => self.validate_yield(expression),
+                    expression => self.validate_expression(expression),
+                }
             }
             Statement::Class(class) => self.validate_class(class),
             Statement::SetProperty(statement) => {
                 self.validate_expression(&statement.object)?;
                 self.validate_expression(&statement.value)
             }
+            Statement::Expr(Expression::Yield(expression)) => self.validate_yield(expression),
+            Statement::Expr(Expression::IF(expression)) => self.validate_if(expression),
             Statement::Expr(expression) => self.validate_expression(expression),
         }
     }
 
+    fn validate_if(&mut self, expression: &IF) -> Result<(), ValidationError> {
+        self.validate_expression(&expression.condition)?;
+        self.branch_depth += 1;
+        let result = self
+            .validate_statements(&expression.consequent.body)
+            .and_then(|()| match &expression.alternate {
+                Some(alternate) => self.validate_statements(&alternate.body),
+                None => Ok(()),
+            });
+        self.branch_depth -= 1;
+        result
+    }
+
+    /// A `yield` where a statement may stop: see the module docs.
+    fn validate_yield(&mut self, expression: &YieldExpression) -> Result<(), ValidationError> {
+        if self.callable_kinds.last() != Some(&CallableKind::Generator)
+            || self.value_branch_depth > 0
+        {
+            return Err(self.misplaced_yield(expression));
+        }
+        match &expression.argument {
+            Some(argument) => self.validate_expression(argument),
+            None => Ok(()),
+        }
+    }
+
+    fn misplaced_yield(&self, expression: &YieldExpression) -> ValidationError {
+        let message = if self.callable_kinds.last() == Some(&CallableKind::Generator) {
+            "yield must be a statement, a let initializer or a return value"
+        } else {
+            "yield outside a generator function"
+        };
+        ValidationError {
+            message: message.to_string(),
+            span: expression.span.clone(),
+        }
+    }
+
     fn validate_class(&mut self, class: &ClassDeclaration) -> Result<(), ValidationError> {
-        self.scopes
-            .last_mut()
-            .unwrap()
-            .insert(class.name.name.clone());
+        let entry = self.declare(&class.name.name);
+        self.define(class.name.name.clone(), entry);
+        class.name.resolution.set(vec![entry.binding(self.level())]);
         self.context.push(format!("class {}", class.name.name));
         for method in &class.methods {
             self.validate_method(method)?;
@@ -107,16 +221,16 @@ impl Validator {
         self.callable_kinds.push(callable_kind);
         let old_receiver_available = self.receiver_available;
         self.receiver_available = true;
-        self.scopes.push(
-            method
-                .params
-                .iter()
-                .map(|parameter| parameter.name.clone())
-                .collect(),
-        );
+        self.push_scope(&method.params);
+        let this = self.declare("this");
+        self.define("this".to_string(), this);
+        let old_branch_depth = std::mem::take(&mut self.branch_depth);
+        let old_value_branch_depth = std::mem::take(&mut self.value_branch_depth);
 
         let result = self.validate_statements(&method.body.body);
 
+        self.branch_depth = old_branch_depth;
+        self.value_branch_depth = old_value_branch_depth;
         self.scopes.pop();
         self.receiver_available = old_receiver_available;
         self.callable_kinds.pop();
@@ -124,27 +238,112 @@ impl Validator {
         result
     }
 
-    fn validate_function(&mut self, function: &FunctionDeclaration) -> Result<(), ValidationError> {
-        self.callable_kinds.push(CallableKind::Function);
-        let mut scope = function
-            .params
-            .iter()
-            .map(|parameter| parameter.name.clone())
-            .collect::<HashSet<_>>();
-        if !function.name.is_empty() {
+    /// `own_binding` is the `let` a function is the initializer of.
+    fn validate_function(
+        &mut self,
+        function: &FunctionDeclaration,
+        own_binding: Option<Entry>,
+    ) -> Result<(), ValidationError> {
+        self.callable_kinds.push(if function.generator {
+            CallableKind::Generator
+        } else {
+            CallableKind::Function
+        });
+        self.push_scope(&function.params);
+        if let Some(entry) = own_binding {
             // A directly let-bound function gets its binding name from the
             // parser. The compiler exposes that name only inside the function
             // body, which permits recursion without exposing an uninitialized
-            // let binding to the initializer as a whole.
-            scope.insert(function.name.clone());
+            // let binding to the initializer as a whole. A parameter of the
+            // same name hides it.
+            let scope = self.scopes.last_mut().unwrap();
+            scope.names.entry(function.name.clone()).or_insert(entry);
         }
-        self.scopes.push(scope);
+        let old_branch_depth = std::mem::take(&mut self.branch_depth);
+        let old_value_branch_depth = std::mem::take(&mut self.value_branch_depth);
         let result = self.validate_statements(&function.body.body);
+        self.branch_depth = old_branch_depth;
+        self.value_branch_depth = old_value_branch_depth;
         self.scopes.pop();
         self.callable_kinds.pop();
         result
     }
 
+    fn level(&self) -> usize {
+        self.scopes.len() - 1
+    }
+
+    fn slot_entry(&self, index: usize) -> Entry {
+        Entry {
+            level: self.level(),
+            slot: Some(index),
+            conditional: false,
+        }
+    }
+
+    /// Opens a function or method scope holding `params`.
+    fn push_scope(&mut self, params: &[IDENTIFIER]) {
+        self.scopes.push(Scope::default());
+        for (index, parameter) in params.iter().enumerate() {
+            self.define(parameter.name.clone(), self.slot_entry(index));
+        }
+        self.scopes.last_mut().unwrap().slots = params.len();
+    }
+
+    /// Where a declaration of `name` in the current scope binds it: the slot
+    /// of an earlier declaration in the same scope, or a new one.
+    fn declare(&mut self, name: &str) -> Entry {
+        let level = self.level();
+        let conditional = self.branch_depth > 0;
+        if level == 0 {
+            return Entry {
+                level,
+                slot: None,
+                conditional: false,
+            };
+        }
+        let scope = self.scopes.last_mut().unwrap();
+        if let Some(existing) = scope.names.get(name) {
+            if existing.level == level {
+                return Entry {
+                    conditional: existing.conditional && conditional,
+                    ..*existing
+                };
+            }
+        }
+        scope.slots += 1;
+        Entry {
+            level,
+            slot: Some(scope.slots - 1),
+            conditional,
+        }
+    }
+
+    /// Makes a declaration visible to what follows it.
+    fn define(&mut self, name: String, entry: Entry) {
+        self.scopes.last_mut().unwrap().names.insert(name, entry);
+    }
+
+    /// The bindings `name` may refer to, innermost first, down to the first
+    /// one that is certainly set.
+    fn resolve(&self, name: &str) -> Option<Vec<Binding>> {
+        let level = self.level();
+        let mut bindings = Vec::new();
+        for scope in self.scopes.iter().rev() {
+            if let Some(entry) = scope.names.get(name) {
+                bindings.push(entry.binding(level));
+                if !entry.conditional {
+                    break;
+                }
+            }
+        }
+        if bindings.is_empty() {
+            None
+        } else {
+            Some(bindings)
+        }
+    }
+
     fn validate_expression(&mut self, expression: &Expression) -> Result<(), ValidationError> {
         match expression {
             Expression::IDENTIFIER(identifier) => self.validate_identifier(identifier),
@@ -155,14 +354,12 @@ impl Validator {
                 self.validate_expression(&expression.right)
             }
             Expression::IF(expression) => {
-                self.validate_expression(&expression.condition)?;
-                self.validate_statements(&expression.consequent.body)?;
-                if let Some(alternate) = &expression.alternate {
-                    self.validate_statements(&alternate.body)?;
-                }
-                Ok(())
+                self.value_branch_depth += 1;
+                let result = self.validate_if(expression);
+                self.value_branch_depth -= 1;
+                result
             }
-            Expression::FUNCTION(function) => self.validate_function(function),
+            Expression::FUNCTION(function) => self.validate_function(function, None),
             Expression::FunctionCall(call) => {
                 self.validate_expression(&call.callee)?;
                 self.validate_expressions(&call.arguments)
@@ -173,6 +370,8 @@ impl Validator {
             }
             Expression::This(this) => {
                 if self.receiver_available {
+                    this.resolution
+                        .set(self.resolve("this").unwrap_or_default());
                     Ok(())
                 } else {
                     Err(ValidationError {
@@ -186,6 +385,8 @@ impl Validator {
                 self.validate_identifier(&new_expression.callee)?;
                 self.validate_expressions(&new_expression.arguments)
             }
+            Expression::Template(template) => self.validate_expressions(&template.expressions),
+            Expression::Yield(expression) => Err(self.misplaced_yield(expression)),
         }
     }
 
@@ -211,12 +412,8 @@ impl Validator {
     }
 
     fn validate_identifier(&self, identifier: &IDENTIFIER) -> Result<(), ValidationError> {
-        if self
-            .scopes
-            .iter()
-            .rev()
-            .any(|scope| scope.contains(&identifier.name))
-        {
+        if let Some(bindings) = self.resolve(&identifier.name) {
+            identifier.resolution.set(bindings);
             return Ok(());
         }
 
@@ -288,4 +485,37 @@ let box = new Box(1);"#,
             .message
             .contains("undefined variable 'f'"));
     }
+
+    #[test]
+    fn yield_only_stops_whole_statements_of_a_generator() {
+        validate(
+            "let g = fn*(n) { yield n; let sent = yield; if (sent) { yield 1; } else { return yield 2; } };",
+        )
+        .unwrap();
+        validate("let g = fn*(n) { let total = yield* g(n); return yield* g(total); };").unwrap();
+        validate("let g = fn*() { let inner = fn*() { yield 1 }; yield inner; };").unwrap();
+
+        for input in [
+            "yield 1;",
+            "let f = fn() { yield 1; };",
+            "class A { m() { yield 1; } }",
+        ] {
+            assert!(validate(input)
+                .unwrap_err()
+                .message
+                .contains("outside a generator"));
+        }
+        for input in [
+            "let g = fn*() { 1 + yield 2; };",
+            "let g = fn*() { len([yield 1]); };",
+            "let g = fn*() { yield yield 1; };",
+            "let g = fn*() { if (yield 1) { 2 } };",
+            "let g = fn*() { let x = if (true) { yield 1 } else { 2 }; };",
+        ] {
+            assert!(validate(input)
+                .unwrap_err()
+                .message
+                .contains("must be a statement"));
+        }
+    }
 }
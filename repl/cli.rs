@@ -0,0 +1,93 @@
+//! The command line the monkey binaries share: how a command reports its
+//! result or failure, and the `run` and `check` arguments every binary
+//! accepts. Bad usage exits with code 2 and the binary's usage text; a
+//! script that cannot be read, parsed, compiled or run exits with code 1.
+
+use std::fmt;
+use std::io::{stdin, Read};
+
+/// Why a command failed, and the exit code that says so.
+#[derive(Debug)]
+pub struct CliError {
+    pub exit_code: i32,
+    pub message: String,
+}
+
+impl CliError {
+    /// Bad invocation: exit code 2, reported together with the usage text.
+    pub fn usage(message: impl fmt::Display) -> CliError {
+        return CliError {
+            exit_code: 2,
+            message: format!("error: {}", message),
+        };
+    }
+
+    /// The script could not be read, parsed, compiled or run: exit code 1.
+    pub fn failure(message: impl fmt::Display) -> CliError {
+        return CliError {
+            exit_code: 1,
+            message: format!("error: {}", message),
+        };
+    }
+}
+
+/// Prints what a command produced, if anything, or reports its error and
+/// exits with the error's code, adding `usage` after a usage error.
+pub fn finish(outcome: Result<Option<String>, CliError>, usage: &str) {
+    match outcome {
+        Ok(Some(result)) => println!("{}", result),
+        Ok(None) => {}
+        Err(error) => {
+            eprintln!("{}", error.message);
+            if error.exit_code == 2 {
+                eprintln!("{}", usage);
+            }
+            std::process::exit(error.exit_code);
+        }
+    }
+}
+
+/// The script `run` reads, `-` for stdin, and the arguments after `--`
+/// meant for it.
+pub fn parse_run_args(args: &[String]) -> Result<(String, Vec<String>), CliError> {
+    let mut input: Option<String> = None;
+    let mut iter = args.iter();
+    for arg in iter.by_ref() {
+        match arg.as_str() {
+            "--" => break,
+            _ if input.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                input = Some(arg.clone())
+            }
+            _ => return Err(CliError::usage(format!("unexpected argument `{}`", arg))),
+        }
+    }
+    let input = input.ok_or_else(|| CliError::usage("run needs an input file"))?;
+    return Ok((input, iter.cloned().collect()));
+}
+
+/// The source of the script at `input`, or of stdin for `-`.
+pub fn read_script(input: &str) -> Result<String, CliError> {
+    if input == "-" {
+        let mut source = String::new();
+        stdin()
+            .read_to_string(&mut source)
+            .map_err(|error| CliError::failure(format!("cannot read stdin: {}", error)))?;
+        return Ok(source);
+    }
+    return std::fs::read_to_string(input)
+        .map_err(|error| CliError::failure(format!("cannot read {}: {}", input, error)));
+}
+
+/// `check <file.monkey>`: type checks the annotations in the file.
+pub fn check_command(args: &[String]) -> Result<(), CliError> {
+    let input = match args {
+        [path] if !path.starts_with('-') => path,
+        [] => return Err(CliError::usage("check needs an input file")),
+        _ => {
+            return Err(CliError::usage(format!("unexpected argument `{}`", args[args.len() - 1])))
+        }
+    };
+    let source = std::fs::read_to_string(input)
+        .map_err(|error| CliError::failure(format!("cannot read {}: {}", input, error)))?;
+    return typeck::check_source(&source).map_err(CliError::failure);
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::cli::{check_command, parse_run_args, read_script};
+
+    /// Scratch file under a shared temp directory. Tests run in parallel, so
+    /// every test uses file names unique to itself.
+    fn write_source(name: &str, source: &str) -> String {
+        let dir = std::env::temp_dir().join("monkey-repl-cli-tests");
+        fs::create_dir_all(&dir).unwrap();
+        let path: PathBuf = dir.join(name);
+        fs::write(&path, source).unwrap();
+        return path.display().to_string();
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        return args.iter().map(|arg| arg.to_string()).collect();
+    }
+
+    #[test]
+    fn run_args_split_at_the_separator() {
+        let (input, script_args) =
+            parse_run_args(&strings(&["foo.monkey", "--", "-v", "two"])).unwrap();
+        assert_eq!(input, "foo.monkey");
+        assert_eq!(script_args, strings(&["-v", "two"]));
+
+        let (input, script_args) = parse_run_args(&strings(&["-"])).unwrap();
+        assert_eq!(input, "-");
+        assert!(script_args.is_empty());
+    }
+
+    #[test]
+    fn bad_run_args_are_usage_errors() {
+        assert_eq!(parse_run_args(&[]).unwrap_err().exit_code, 2);
+        assert_eq!(
+            parse_run_args(&strings(&["--", "a"]))
+                .unwrap_err()
+                .exit_code,
+            2
+        );
+        let unknown_flag = parse_run_args(&strings(&["foo.monkey", "--frobnicate"])).unwrap_err();
+        assert_eq!(unknown_flag.exit_code, 2);
+        assert_eq!(unknown_flag.message, "error: unexpected argument `--frobnicate`");
+        assert_eq!(
+            parse_run_args(&strings(&["a.monkey", "b.monkey"]))
+                .unwrap_err()
+                .exit_code,
+            2
+        );
+    }
+
+    #[test]
+    fn missing_scripts_fail_with_code_one() {
+        let source = write_source("read.monkey", "1 + 1");
+        assert_eq!(read_script(&source).unwrap(), "1 + 1");
+
+        let missing = read_script("no-such-script.monkey").unwrap_err();
+        assert_eq!(missing.exit_code, 1);
+        assert!(missing
+            .message
+            .starts_with("error: cannot read no-such-script.monkey:"));
+    }
+
+    #[test]
+    fn check_reports_type_errors_with_positions() {
+        let good = write_source(
+            "typed-ok.monkey",
+            "let add = fn(a: int, b: int): int { a + b }; add(1, 2)",
+        );
+        check_command(&[good]).unwrap();
+
+        let bad = write_source("typed-bad.monkey", "let x = 1;\nlet s: string = x;\ns");
+        let error = check_command(&[bad]).unwrap_err();
+        assert_eq!(error.exit_code, 1);
+        assert_eq!(
+            error.message,
+            "error: 2:17: type error: type mismatch in let s: expected string, found int"
+        );
+
+        assert_eq!(check_command(&[]).unwrap_err().exit_code, 2);
+        assert_eq!(
+            check_command(&strings(&["a.monkey", "b.monkey"]))
+                .unwrap_err()
+                .exit_code,
+            2
+        );
+        assert_eq!(
+            check_command(&strings(&["no-such-script.monkey"]))
+                .unwrap_err()
+                .exit_code,
+            1
+        );
+    }
+}
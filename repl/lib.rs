@@ -0,0 +1,262 @@
+//! The read-eval-print loop behind `monkey-interpreter`, `monkey-compiler`
+//! and `monkey-gc`. Each binary supplies a [`Backend`] that evaluates input;
+//! reading lines, history, multi-line input and the `:` commands live here.
+//! [`cli`] has the command-line handling the three binaries share.
+
+use std::path::PathBuf;
+
+use parser::lexer::token::TokenKind;
+use parser::lexer::Lexer;
+use parser::{parse, parse_ast_json_string};
+#[cfg(not(target_arch = "wasm32"))]
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+pub mod cli;
+mod cli_test;
+mod repl_test;
+
+const HELP: &str = "\
+:tokens <source>     the tokens the lexer reads from source
+:ast <source>        the syntax tree of source, as JSON
+:bytecode <source>   the bytecode source compiles to
+:env                 every name defined so far
+:heap                what the collector's heap holds
+:load <file>         evaluate a file
+:reset               forget everything defined so far
+:help                this list
+:quit                leave, as does Ctrl-D";
+
+/// What a REPL evaluates with.
+pub trait Backend {
+    /// Evaluates a complete input, rendering its value or its error.
+    fn eval(&mut self, source: &str) -> Result<String, String>;
+
+    /// Forgets everything evaluated so far.
+    fn reset(&mut self);
+
+    /// Each name defined so far with its value rendered, sorted by name.
+    fn env(&self) -> Vec<(String, String)>;
+
+    /// The bytecode `source` would compile to, if this backend compiles.
+    fn bytecode(&self, _source: &str) -> Option<Result<String, String>> {
+        None
+    }
+
+    /// A summary of the heap, if this backend manages one.
+    fn heap(&self) -> Option<String> {
+        None
+    }
+}
+
+/// What a line of input led to.
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    /// Something to print; empty when there is nothing to say.
+    Output(String),
+    Error(String),
+    /// The input so far is unfinished; read another line.
+    Incomplete,
+    Quit,
+}
+
+/// Collects lines into complete inputs and hands them to a backend.
+pub struct Repl<B> {
+    backend: B,
+    pending: String,
+}
+
+impl<B: Backend> Repl<B> {
+    pub fn new(backend: B) -> Self {
+        return Repl {
+            backend,
+            pending: String::new(),
+        };
+    }
+
+    pub fn backend(&self) -> &B {
+        return &self.backend;
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        if self.pending.is_empty() {
+            return "> ";
+        }
+        return ".. ";
+    }
+
+    /// Drops an unfinished input.
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Takes one line. A blank line ends an unfinished input early, so a
+    /// stray `{` can always be got out of.
+    pub fn feed(&mut self, line: &str) -> Reply {
+        if self.pending.is_empty() {
+            let line = line.trim();
+            if line.is_empty() {
+                return Reply::Output(String::new());
+            }
+            if line.starts_with(':') {
+                return self.command(line);
+            }
+        }
+        let blank = line.trim().is_empty();
+        self.pending.push_str(line);
+        self.pending.push('\n');
+        if !blank && is_incomplete(&self.pending) {
+            return Reply::Incomplete;
+        }
+        let source = std::mem::take(&mut self.pending);
+        return reply(self.backend.eval(&source));
+    }
+
+    fn command(&mut self, line: &str) -> Reply {
+        let (name, argument) = match line.find(char::is_whitespace) {
+            Some(end) => (&line[..end], line[end..].trim()),
+            None => (line, ""),
+        };
+        let needs_argument = matches!(name, ":tokens" | ":ast" | ":bytecode" | ":load");
+        if needs_argument && argument.is_empty() {
+            let what = if name == ":load" { "<file>" } else { "<source>" };
+            return Reply::Error(format!("usage: {} {}", name, what));
+        }
+        match name {
+            ":tokens" => return Reply::Output(tokens(argument)),
+            ":ast" => {
+                return reply(
+                    parse_ast_json_string(argument)
+                        .map_err(|errors| format!("parse error: {}", errors[0])),
+                )
+            }
+            ":bytecode" => match self.backend.bytecode(argument) {
+                Some(bytecode) => return reply(bytecode),
+                None => return unavailable(name),
+            },
+            ":env" => {
+                let lines: Vec<String> = self
+                    .backend
+                    .env()
+                    .into_iter()
+                    .map(|(name, value)| format!("{} = {}", name, value))
+                    .collect();
+                return Reply::Output(lines.join("\n"));
+            }
+            ":heap" => match self.backend.heap() {
+                Some(heap) => return Reply::Output(heap),
+                None => return unavailable(name),
+            },
+            ":load" => match std::fs::read_to_string(argument) {
+                Ok(source) => return reply(self.backend.eval(&source)),
+                Err(error) => return Reply::Error(format!("cannot read {}: {}", argument, error)),
+            },
+            ":reset" => {
+                self.backend.reset();
+                return Reply::Output(String::new());
+            }
+            ":help" => return Reply::Output(HELP.to_string()),
+            ":quit" | ":q" => return Reply::Quit,
+            _ => return Reply::Error(format!("unknown command {}, try :help", name)),
+        }
+    }
+}
+
+fn reply(result: Result<String, String>) -> Reply {
+    match result {
+        Ok(output) => return Reply::Output(output),
+        Err(error) => return Reply::Error(error),
+    }
+}
+
+fn unavailable(command: &str) -> Reply {
+    return Reply::Error(format!("{} is not available in this REPL", command));
+}
+
+fn tokens(source: &str) -> String {
+    let mut lexer = Lexer::new(source);
+    let mut lines = Vec::new();
+    loop {
+        let token = lexer.next_token();
+        if token.kind == TokenKind::EOF {
+            return lines.join("\n");
+        }
+        lines.push(token.to_string());
+    }
+}
+
+/// Whether `source` fails to parse only because it stops inside a bracket,
+/// brace, parenthesis or `${`, so that more lines could still finish it.
+pub fn is_incomplete(source: &str) -> bool {
+    if parse(source).is_ok() {
+        return false;
+    }
+    let mut lexer = Lexer::new(source);
+    let mut depth: i64 = 0;
+    loop {
+        match lexer.next_token().kind {
+            TokenKind::LPAREN | TokenKind::LBRACE | TokenKind::LBRACKET => depth += 1,
+            TokenKind::TemplateHead(_) => depth += 1,
+            TokenKind::RPAREN | TokenKind::RBRACE | TokenKind::RBRACKET => depth -= 1,
+            TokenKind::TemplateTail(_) => depth -= 1,
+            TokenKind::EOF => return depth > 0,
+            _ => {}
+        }
+        // A closer with nothing open is an error no further line can fix.
+        if depth < 0 {
+            return false;
+        }
+    }
+}
+
+/// `~/.<binary>_history`, where a REPL keeps the lines it has read.
+pub fn history_file(binary: &str) -> Option<PathBuf> {
+    return std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join(format!(".{}_history", binary)));
+}
+
+/// Prints `banner`, then reads and evaluates lines until `:quit` or the end
+/// of input, remembering them in `history` across runs.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run<B: Backend>(banner: &str, history: Option<PathBuf>, backend: B) {
+    println!("{}", banner);
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(error) => {
+            eprintln!("error: cannot start the line editor: {}", error);
+            return;
+        }
+    };
+    if let Some(path) = &history {
+        // There is no history yet on a first run.
+        let _ = editor.load_history(path);
+    }
+    let mut repl = Repl::new(backend);
+    loop {
+        match editor.readline(repl.prompt()) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = editor.add_history_entry(line.as_str());
+                }
+                match repl.feed(&line) {
+                    Reply::Output(output) if output.is_empty() => {}
+                    Reply::Output(output) => println!("{}", output),
+                    Reply::Error(error) => eprintln!("{}", error),
+                    Reply::Incomplete => {}
+                    Reply::Quit => break,
+                }
+            }
+            Err(ReadlineError::Interrupted) => repl.cancel(),
+            Err(ReadlineError::Eof) => break,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                break;
+            }
+        }
+    }
+    if let Some(path) = &history {
+        if let Err(error) = editor.save_history(path) {
+            eprintln!("warning: history not saved: {}", error);
+        }
+    }
+    println!("bye");
+}
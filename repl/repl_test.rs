@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use crate::{is_incomplete, Backend, Repl, Reply};
+
+    /// Echoes what it is asked to evaluate and remembers it as a binding.
+    #[derive(Default)]
+    struct Echo {
+        evaluated: Vec<String>,
+    }
+
+    impl Backend for Echo {
+        fn eval(&mut self, source: &str) -> Result<String, String> {
+            self.evaluated.push(source.to_string());
+            return Ok(source.trim_end().to_string());
+        }
+
+        fn reset(&mut self) {
+            self.evaluated.clear();
+        }
+
+        fn env(&self) -> Vec<(String, String)> {
+            return self
+                .evaluated
+                .iter()
+                .enumerate()
+                .map(|(index, source)| (format!("input{}", index), source.trim_end().to_string()))
+                .collect();
+        }
+    }
+
+    fn output(text: &str) -> Reply {
+        return Reply::Output(text.to_string());
+    }
+
+    #[test]
+    fn unfinished_input_waits_for_more_lines() {
+        let mut repl = Repl::new(Echo::default());
+        assert_eq!(repl.feed("let add = fn(a, b) {"), Reply::Incomplete);
+        assert_eq!(repl.prompt(), ".. ");
+        assert_eq!(repl.feed("  a + b"), Reply::Incomplete);
+        assert_eq!(repl.feed("};"), output("let add = fn(a, b) {\n  a + b\n};"));
+        assert_eq!(repl.prompt(), "> ");
+
+        assert_eq!(repl.feed("[1,"), Reply::Incomplete);
+        assert_eq!(repl.feed(""), output("[1,"));
+        assert_eq!(repl.backend().evaluated.len(), 2);
+    }
+
+    #[test]
+    fn incomplete_means_an_opener_is_still_waiting() {
+        assert!(is_incomplete("if (x) {"));
+        assert!(is_incomplete("puts(`a ${b"));
+        assert!(is_incomplete("{\"a\": [1, 2"));
+        assert!(!is_incomplete("1 + 2"));
+        assert!(!is_incomplete("let x = ;"));
+        assert!(!is_incomplete("let x = 1; } {"));
+    }
+
+    #[test]
+    fn blank_lines_do_not_end_the_session() {
+        let mut repl = Repl::new(Echo::default());
+        assert_eq!(repl.feed(""), output(""));
+        assert_eq!(repl.feed("   "), output(""));
+        assert!(repl.backend().evaluated.is_empty());
+        assert_eq!(repl.feed(":quit"), Reply::Quit);
+    }
+
+    #[test]
+    fn meta_commands() {
+        let mut repl = Repl::new(Echo::default());
+        assert_eq!(
+            repl.feed(":tokens let x"),
+            output("start: 0, end: 3, kind: let\nstart: 4, end: 5, kind: x")
+        );
+        match repl.feed(":ast 1") {
+            Reply::Output(ast) => assert!(ast.contains("\"type\": \"Program\""), "got: {}", ast),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            repl.feed(":ast let = 1"),
+            Reply::Error("parse error: start: 4, end: 5, kind: = not an identifier".to_string())
+        );
+        assert_eq!(
+            repl.feed(":bytecode 1"),
+            Reply::Error(":bytecode is not available in this REPL".to_string())
+        );
+        assert_eq!(
+            repl.feed(":heap"),
+            Reply::Error(":heap is not available in this REPL".to_string())
+        );
+        assert_eq!(repl.feed(":tokens"), Reply::Error("usage: :tokens <source>".to_string()));
+        assert_eq!(
+            repl.feed(":frobnicate"),
+            Reply::Error("unknown command :frobnicate, try :help".to_string())
+        );
+
+        repl.feed("let a = 1;");
+        repl.feed("let b = 2;");
+        assert_eq!(repl.feed(":env"), output("input0 = let a = 1;\ninput1 = let b = 2;"));
+        assert_eq!(repl.feed(":reset"), output(""));
+        assert_eq!(repl.feed(":env"), output(""));
+    }
+
+    #[test]
+    fn load_evaluates_a_whole_file() {
+        let path = std::env::temp_dir().join("monkey-repl-load-test.monkey");
+        std::fs::write(&path, "let f = fn() {\n  1\n};\nf()\n").unwrap();
+        let mut repl = Repl::new(Echo::default());
+        assert_eq!(
+            repl.feed(&format!(":load {}", path.display())),
+            output("let f = fn() {\n  1\n};\nf()")
+        );
+        match repl.feed(":load no-such-file.monkey") {
+            Reply::Error(error) => {
+                assert!(error.starts_with("cannot read no-such-file.monkey"), "got: {}", error)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
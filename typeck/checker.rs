@@ -0,0 +1,1088 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use object::builtins::BuiltIns;
+use object::methods::{self, Receiver};
+use parser::ast::*;
+use parser::lexer::token::{Span, TokenKind};
+
+use crate::types::Type;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Default)]
+struct ClassInfo {
+    fields: HashMap<String, Type>,
+    methods: HashMap<String, Type>,
+    constructor: Option<Vec<Type>>,
+}
+
+impl ClassInfo {
+    /// Classes that declare fields are closed: every property must be a
+    /// declared field or a method. Undeclared classes stay fully dynamic.
+    fn is_closed(&self) -> bool {
+        !self.fields.is_empty()
+    }
+}
+
+/// A name in scope. `annotated` bindings promise their type; inferred ones
+/// only describe what the value might be.
+#[derive(Clone)]
+struct Binding {
+    ty: Type,
+    annotated: bool,
+}
+
+/// Return statements seen while checking one function body.
+struct ReturnContext {
+    declared: Option<Type>,
+    returns: Vec<Type>,
+}
+
+struct Checker {
+    scopes: Vec<HashMap<String, Binding>>,
+    classes: HashMap<String, ClassInfo>,
+    returns: Vec<ReturnContext>,
+    receivers: Vec<String>,
+    errors: Vec<TypeError>,
+}
+
+/// Checks annotations against locally inferred types. Unannotated code is
+/// inferred where it can be and falls back to `any` where it cannot, so a
+/// program without annotations only fails on operations that are wrong for
+/// every input (e.g. `1 + "a"`). All errors are collected, in source order.
+pub fn check_program(program: &Program) -> Result<(), Vec<TypeError>> {
+    let mut checker = Checker {
+        scopes: vec![HashMap::new()],
+        classes: HashMap::new(),
+        returns: Vec::new(),
+        receivers: Vec::new(),
+        errors: Vec::new(),
+    };
+    checker.declare_classes(&program.body);
+    checker.check_statements(&program.body);
+    if checker.errors.is_empty() {
+        return Ok(());
+    }
+    // Class signatures are checked before the program body.
+    checker
+        .errors
+        .sort_by_key(|error| (error.span.start, error.span.end));
+    Err(checker.errors)
+}
+
+fn mismatch(expected: &Type, found: &Type) -> String {
+    format!("expected {}, found {}", expected, found)
+}
+
+impl Checker {
+    fn error(&mut self, message: impl Into<String>, span: &Span) {
+        self.errors.push(TypeError {
+            message: message.into(),
+            span: span.clone(),
+        });
+    }
+
+    fn expect_assignable(&mut self, found: &Type, expected: &Type, context: &str, span: &Span) {
+        if !found.is_assignable_to(expected) {
+            self.error(format!("{}: {}", context, mismatch(expected, found)), span);
+        }
+    }
+
+    fn bind(&mut self, name: &str, ty: Type, annotated: bool) {
+        let binding = Binding {
+            ty,
+            annotated,
+        };
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(name.to_string(), binding);
+    }
+
+    fn binding(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn lookup(&self, name: &str) -> Type {
+        if let Some(binding) = self.binding(name) {
+            return binding.ty.clone();
+        }
+        if BuiltIns.iter().any(|builtin| builtin.name == name) {
+            return Type::Builtin(name.to_string());
+        }
+        // Undefined names are the validator's job, not ours.
+        Type::Any
+    }
+
+    /// Classes only exist at top level, so their signatures are collected up
+    /// front; annotations may then name any class regardless of order.
+    fn declare_classes(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            if let Statement::Class(class) = statement {
+                self.classes
+                    .insert(class.name.name.clone(), ClassInfo::default());
+            }
+        }
+        for statement in statements {
+            if let Statement::Class(class) = statement {
+                let mut info = ClassInfo::default();
+                for field in &class.fields {
+                    let ty = self.resolve(&field.type_annotation);
+                    info.fields.insert(field.name.name.clone(), ty);
+                }
+                for method in &class.methods {
+                    let params = self.param_types(&method.params);
+                    match method.kind {
+                        MethodKind::Constructor => info.constructor = Some(params),
+                        MethodKind::Method => {
+                            let ret = self.optional_type(&method.return_type);
+                            info.methods.insert(
+                                method.name.name.clone(),
+                                Type::Function(params, Box::new(ret)),
+                            );
+                        }
+                    }
+                }
+                self.classes.insert(class.name.name.clone(), info);
+            }
+        }
+    }
+
+    fn resolve(&mut self, annotation: &TypeAnnotation) -> Type {
+        match annotation {
+            TypeAnnotation::TypeReference(reference) => match reference.name.as_str() {
+                "int" => Type::Int,
+                "float" => Type::Float,
+                "bool" => Type::Bool,
+                "string" => Type::String,
+                "null" => Type::Null,
+                "any" => Type::Any,
+                name if self.classes.contains_key(name) => Type::Instance(name.to_string()),
+                name => {
+                    self.error(format!("unknown type '{}'", name), &reference.span);
+                    Type::Any
+                }
+            },
+            TypeAnnotation::ArrayType(array) => Type::Array(Box::new(self.resolve(&array.element))),
+            TypeAnnotation::HashType(hash) => {
+                let key = self.resolve(&hash.key);
+                if !key.is_hashable() {
+                    self.error(format!("{} cannot be used as a hash key", key), hash.key.span());
+                }
+                Type::Hash(Box::new(key), Box::new(self.resolve(&hash.value)))
+            }
+            TypeAnnotation::FunctionType(function) => {
+                let params = function
+                    .params
+                    .iter()
+                    .map(|param| self.resolve(param))
+                    .collect();
+                Type::Function(params, Box::new(self.resolve(&function.return_type)))
+            }
+            TypeAnnotation::UnionType(union) => {
+                let members = union
+                    .types
+                    .iter()
+                    .map(|member| self.resolve(member))
+                    .collect::<Vec<_>>();
+                Type::join(members).unwrap_or(Type::Any)
+            }
+        }
+    }
+
+    fn optional_type(&mut self, annotation: &Option<TypeAnnotation>) -> Type {
+        match annotation {
+            Some(annotation) => self.resolve(annotation),
+            None => Type::Any,
+        }
+    }
+
+    fn param_types(&mut self, params: &[IDENTIFIER]) -> Vec<Type> {
+        params
+            .iter()
+            .map(|param| self.optional_type(&param.type_annotation))
+            .collect()
+    }
+
+    /// Checks statements and returns the block's completion type: the value
+    /// of a trailing expression or return statement, otherwise `null`.
+    fn check_statements(&mut self, statements: &[Statement]) -> Type {
+        let mut completion = Type::Null;
+        for statement in statements {
+            completion = self.check_statement(statement);
+        }
+        completion
+    }
+
+    fn check_statement(&mut self, statement: &Statement) -> Type {
+        match statement {
+            Statement::Let(statement) => {
+                let name = match &statement.identifier.kind {
+                    TokenKind::IDENTIFIER {
+                        name,
+                    } => name.clone(),
+                    _ => unreachable!("parser only creates let statements with identifiers"),
+                };
+                let value = self.infer(&statement.expr);
+                let ty = match &statement.type_annotation {
+                    Some(annotation) => {
+                        let declared = self.resolve(annotation);
+                        let context = format!("type mismatch in let {}", name);
+                        self.expect_assignable(&value, &declared, &context, statement.expr.span());
+                        declared
+                    }
+                    None => value,
+                };
+                self.bind(&name, ty, statement.type_annotation.is_some());
+                Type::Null
+            }
+            Statement::Return(statement) => {
+                let value = self.infer(&statement.argument);
+                if let Some(context) = self.returns.last_mut() {
+                    context.returns.push(value.clone());
+                    if let Some(declared) = context.declared.clone() {
+                        self.expect_assignable(
+                            &value,
+                            &declared,
+                            "return type mismatch",
+                            statement.argument.span(),
+                        );
+                    }
+                }
+                value
+            }
+            Statement::Class(class) => {
+                self.bind(&class.name.name, Type::Class(class.name.name.clone()), true);
+                self.receivers.push(class.name.name.clone());
+                for method in &class.methods {
+                    self.check_method(&class.name.name, method);
+                }
+                self.receivers.pop();
+                Type::Null
+            }
+            Statement::SetProperty(statement) => {
+                let object = self.infer(&statement.object);
+                let value = self.infer(&statement.value);
+                if let Type::Instance(class_name) = &object {
+                    let info = &self.classes[class_name];
+                    match info.fields.get(&statement.property.name) {
+                        Some(declared) => {
+                            let declared = declared.clone();
+                            let context = format!(
+                                "type mismatch for field {}.{}",
+                                class_name, statement.property.name
+                            );
+                            self.expect_assignable(
+                                &value,
+                                &declared,
+                                &context,
+                                statement.value.span(),
+                            );
+                        }
+                        None if info.is_closed() => {
+                            let message = format!(
+                                "{} has no field '{}'",
+                                class_name, statement.property.name
+                            );
+                            self.error(message, &statement.property.span);
+                        }
+                        None => {}
+                    }
+                }
+                Type::Null
+            }
+            Statement::Expr(expression) => self.infer(expression),
+        }
+    }
+
+    fn check_method(&mut self, class_name: &str, method: &MethodDefinition) {
+        // Signatures were resolved by declare_classes; resolving them again
+        // would report unknown types twice.
+        let info = &self.classes[class_name];
+        let (params, declared) = match method.kind {
+            MethodKind::Constructor => (info.constructor.clone().unwrap_or_default(), None),
+            MethodKind::Method => match &info.methods[&method.name.name] {
+                Type::Function(params, ret) => {
+                    (params.clone(), method.return_type.as_ref().map(|_| (**ret).clone()))
+                }
+                _ => unreachable!("methods are declared with function types"),
+            },
+        };
+        self.check_callable(&method.params, params, declared, &method.body);
+    }
+
+    fn check_function(&mut self, function: &FunctionDeclaration) -> Type {
+        let params = self.param_types(&function.params);
+        let declared = function
+            .return_type
+            .as_ref()
+            .map(|annotation| self.resolve(annotation));
+        let mut scope = HashMap::new();
+        if !function.name.is_empty() {
+            // Named recursion sees the declared signature; an unannotated
+            // return type is `any` until the body has been inferred.
+            let ret = declared.clone().unwrap_or(Type::Any);
+            let binding = Binding {
+                ty: Type::Function(params.clone(), Box::new(ret)),
+                annotated: declared.is_some(),
+            };
+            scope.insert(function.name.clone(), binding);
+        }
+        self.scopes.push(scope);
+        let ret = self.check_callable(&function.params, params.clone(), declared, &function.body);
+        self.scopes.pop();
+        if function.generator {
+            // Calling a generator function returns a generator, which has
+            // no type of its own; a declared type is what its body returns.
+            return Type::Function(params, Box::new(Type::Any));
+        }
+        Type::Function(params, Box::new(ret))
+    }
+
+    /// Checks a function or method body and returns its result type.
+    fn check_callable(
+        &mut self,
+        params: &[IDENTIFIER],
+        param_types: Vec<Type>,
+        declared: Option<Type>,
+        body: &BlockStatement,
+    ) -> Type {
+        self.scopes.push(
+            params
+                .iter()
+                .zip(param_types)
+                .map(|(param, ty)| {
+                    let binding = Binding {
+                        ty,
+                        annotated: param.type_annotation.is_some(),
+                    };
+                    (param.name.clone(), binding)
+                })
+                .collect(),
+        );
+        self.returns.push(ReturnContext {
+            declared: declared.clone(),
+            returns: Vec::new(),
+        });
+        let completion = self.check_statements(&body.body);
+        let context = self.returns.pop().unwrap();
+        self.scopes.pop();
+
+        let ends_in_return = matches!(body.body.last(), Some(Statement::Return(_)));
+        if let Some(declared) = declared {
+            if !ends_in_return {
+                let span = body.body.last().map_or(&body.span, Statement::span);
+                self.expect_assignable(&completion, &declared, "return type mismatch", span);
+            }
+            return declared;
+        }
+        Type::join(
+            context
+                .returns
+                .into_iter()
+                .chain(std::iter::once(completion)),
+        )
+        .unwrap_or(Type::Null)
+    }
+
+    fn infer(&mut self, expression: &Expression) -> Type {
+        match expression {
+            Expression::IDENTIFIER(identifier) => self.lookup(&identifier.name),
+            Expression::LITERAL(literal) => self.infer_literal(literal),
+            Expression::PREFIX(prefix) => {
+                let operand = self.infer(&prefix.operand);
+                match prefix.op.kind {
+                    TokenKind::MINUS => match numeric_result(&operand, &operand) {
+                        Some(ty) => ty,
+                        None if self
+                            .possible_types(&prefix.operand, &operand)
+                            .iter()
+                            .any(|ty| numeric_result(ty, ty).is_some()) =>
+                        {
+                            Type::Any
+                        }
+                        None => {
+                            self.error(
+                                format!("operator - cannot be applied to {}", operand),
+                                &prefix.span,
+                            );
+                            Type::Any
+                        }
+                    },
+                    _ => Type::Bool,
+                }
+            }
+            Expression::INFIX(infix) => self.infer_infix(infix),
+            Expression::IF(if_expression) => {
+                self.infer(&if_expression.condition);
+                let consequent = self.check_statements(&if_expression.consequent.body);
+                let alternate = match &if_expression.alternate {
+                    Some(alternate) => self.check_statements(&alternate.body),
+                    None => Type::Null,
+                };
+                Type::union(consequent, alternate)
+            }
+            Expression::FUNCTION(function) => self.check_function(function),
+            Expression::FunctionCall(call) => self.infer_call(call),
+            Expression::Index(index) => {
+                let object = self.infer(&index.object);
+                let key = self.infer(&index.index);
+                if let Some(ty) =
+                    self.infer_special_method(&object, "get", &key, &index.index, &index.span)
+                {
+                    return ty;
+                }
+                match object {
+                    Type::Any => Type::Any,
+                    // Out-of-range reads produce null at runtime; tracking that
+                    // would make every index expression nullable.
+                    Type::Array(element) => {
+                        self.expect_assignable(&key, &Type::Int, "array index", index.index.span());
+                        *element
+                    }
+                    Type::Hash(key_type, value) => {
+                        self.expect_assignable(&key, &key_type, "hash key", index.index.span());
+                        *value
+                    }
+                    other => {
+                        self.error(format!("cannot index a value of type {}", other), &index.span);
+                        Type::Any
+                    }
+                }
+            }
+            Expression::This(_) => match self.receivers.last() {
+                Some(class_name) => Type::Instance(class_name.clone()),
+                None => Type::Any,
+            },
+            Expression::Property(property) => {
+                let object = self.infer(&property.object);
+                self.infer_property(property, object)
+            }
+            Expression::New(new_expression) => {
+                let arguments = self.infer_all(&new_expression.arguments);
+                let class_name = &new_expression.callee.name;
+                match self.lookup(class_name) {
+                    Type::Class(class_name) => {
+                        let params = self.classes[&class_name]
+                            .constructor
+                            .clone()
+                            .unwrap_or_default();
+                        let callee = format!("{} constructor", class_name);
+                        self.check_arguments(
+                            &callee,
+                            &params,
+                            &arguments,
+                            &new_expression.arguments,
+                            &new_expression.span,
+                        );
+                        Type::Instance(class_name)
+                    }
+                    Type::Any => Type::Any,
+                    other => {
+                        self.error(
+                            format!("cannot construct a value of type {}", other),
+                            &new_expression.span,
+                        );
+                        Type::Any
+                    }
+                }
+            }
+            Expression::Template(template) => {
+                // Every value has a display form, so substitutions accept any type.
+                self.infer_all(&template.expressions);
+                Type::String
+            }
+            Expression::Yield(yield_expression) => {
+                // The value sent back in by `next` can be anything.
+                if let Some(argument) = &yield_expression.argument {
+                    self.infer(argument);
+                }
+                Type::Any
+            }
+        }
+    }
+
+    fn infer_all(&mut self, expressions: &[Expression]) -> Vec<Type> {
+        expressions
+            .iter()
+            .map(|expression| self.infer(expression))
+            .collect()
+    }
+
+    fn infer_literal(&mut self, literal: &Literal) -> Type {
+        match literal {
+            Literal::Integer(_) | Literal::BigInt(_) => Type::Int,
+            Literal::Float(_) => Type::Float,
+            Literal::Boolean(_) => Type::Bool,
+            Literal::String(_) => Type::String,
+            Literal::Array(array) => {
+                let elements = self.infer_all(&array.elements);
+                Type::Array(Box::new(Type::join(elements).unwrap_or(Type::Any)))
+            }
+            Literal::Hash(hash) => {
+                let mut keys = Vec::new();
+                let mut values = Vec::new();
+                for (key, value) in &hash.elements {
+                    let key_type = self.infer(key);
+                    if !key_type.is_hashable() {
+                        self.error(
+                            format!("{} cannot be used as a hash key", key_type),
+                            key.span(),
+                        );
+                    }
+                    keys.push(key_type);
+                    values.push(self.infer(value));
+                }
+                Type::Hash(
+                    Box::new(Type::join(keys).unwrap_or(Type::Any)),
+                    Box::new(Type::join(values).unwrap_or(Type::Any)),
+                )
+            }
+        }
+    }
+
+    fn infer_infix(&mut self, infix: &BinaryExpression) -> Type {
+        let left = self.infer(&infix.left);
+        let right = self.infer(&infix.right);
+        let method = match infix.op.kind {
+            TokenKind::PLUS => Some("add"),
+            TokenKind::MINUS => Some("sub"),
+            TokenKind::ASTERISK => Some("mul"),
+            TokenKind::SLASH => Some("div"),
+            TokenKind::EQ | TokenKind::NotEq => Some("eq"),
+            _ => None,
+        };
+        if let Some(method) = method {
+            if let Some(ty) =
+                self.infer_special_method(&left, method, &right, &infix.right, &infix.span)
+            {
+                return if method == "eq" { Type::Bool } else { ty };
+            }
+        }
+        if matches!(infix.op.kind, TokenKind::EQ | TokenKind::NotEq) {
+            return Type::Bool;
+        }
+        let op = &infix.op.kind;
+        if let Some(ty) = operator_result(op, &left, &right) {
+            return ty;
+        }
+        // When some combination of members works, only running the program
+        // can tell whether this one does.
+        let rights = self.possible_types(&infix.right, &right);
+        let supported = self.possible_types(&infix.left, &left).iter().any(|left| {
+            rights
+                .iter()
+                .any(|right| operator_result(op, left, right).is_some())
+        });
+        if !supported {
+            let message =
+                format!("operator {} cannot be applied to {} and {}", infix.op.kind, left, right);
+            self.error(message, &infix.span);
+        }
+        Type::Any
+    }
+
+    /// The types `expression` may turn out to have at runtime: each member of
+    /// an inferred union, but an annotated union as a whole, since the
+    /// annotation promises every member is handled.
+    fn possible_types(&self, expression: &Expression, ty: &Type) -> Vec<Type> {
+        match ty {
+            Type::Union(members) if !self.is_annotated(expression) => members.clone(),
+            _ => vec![ty.clone()],
+        }
+    }
+
+    /// Whether the type of `expression` comes straight from an annotation:
+    /// an annotated binding, a class field, or a call to a class method,
+    /// whose result is `any` unless annotated.
+    fn is_annotated(&self, expression: &Expression) -> bool {
+        match expression {
+            Expression::IDENTIFIER(identifier) => self
+                .binding(&identifier.name)
+                .is_some_and(|binding| binding.annotated),
+            Expression::Property(_) => true,
+            Expression::FunctionCall(call) => matches!(&*call.callee, Expression::Property(_)),
+            _ => false,
+        }
+    }
+
+    /// Operators on an instance whose class defines the matching special
+    /// method (`add`, `eq`, `get`, ...) type like a call to that method.
+    fn infer_special_method(
+        &mut self,
+        receiver: &Type,
+        name: &str,
+        argument: &Type,
+        argument_node: &Expression,
+        span: &Span,
+    ) -> Option<Type> {
+        let Type::Instance(class_name) = receiver else {
+            return None;
+        };
+        let Some(Type::Function(params, ret)) = self.classes[class_name].methods.get(name).cloned()
+        else {
+            return None;
+        };
+        let callee = format!("{}.{}", class_name, name);
+        self.check_arguments(
+            &callee,
+            &params,
+            std::slice::from_ref(argument),
+            std::slice::from_ref(argument_node),
+            span,
+        );
+        Some(*ret)
+    }
+
+    /// `object.name` once `object` has been inferred. Strings, arrays and
+    /// hashes have the methods `object::methods` lists.
+    fn infer_property(&mut self, property: &PropertyExpression, object: Type) -> Type {
+        if builtin_method(&object, &property.property.name).is_some() {
+            return Type::Any;
+        }
+        match &object {
+            Type::Instance(class_name) => {
+                let info = &self.classes[class_name];
+                let name = &property.property.name;
+                if let Some(ty) = info.fields.get(name).or_else(|| info.methods.get(name)) {
+                    return ty.clone();
+                }
+                if info.is_closed() {
+                    let message = format!("{} has no property '{}'", class_name, name);
+                    self.error(message, &property.property.span);
+                }
+                Type::Any
+            }
+            Type::Any | Type::Class(_) => Type::Any,
+            other => {
+                let message =
+                    format!("cannot read property '{}' of {}", property.property.name, other);
+                self.error(message, &property.span);
+                Type::Any
+            }
+        }
+    }
+
+    fn infer_call(&mut self, call: &FunctionCall) -> Type {
+        let callee = match &*call.callee {
+            Expression::Property(property) => {
+                let object = self.infer(&property.object);
+                if let Some(name) = builtin_method(&object, &property.property.name) {
+                    return self.infer_builtin_method_call(name, property, object, call);
+                }
+                self.infer_property(property, object)
+            }
+            callee => self.infer(callee),
+        };
+        let arguments = self.infer_all(&call.arguments);
+        match callee {
+            Type::Any => Type::Any,
+            Type::Function(params, ret) => {
+                let name = match &*call.callee {
+                    Expression::IDENTIFIER(identifier) => identifier.name.clone(),
+                    Expression::Property(property) => property.property.name.clone(),
+                    _ => "function".to_string(),
+                };
+                self.check_arguments(&name, &params, &arguments, &call.arguments, &call.span);
+                *ret
+            }
+            Type::Builtin(name) => self.infer_builtin_call(&name, &arguments, call),
+            Type::Class(name) => {
+                self.error(format!("class {} must be constructed with new", name), &call.span);
+                Type::Any
+            }
+            other => {
+                self.error(format!("a value of type {} is not callable", other), &call.span);
+                Type::Any
+            }
+        }
+    }
+
+    /// `s.split(",")` types exactly like `split(s, ",")`.
+    fn infer_builtin_method_call(
+        &mut self,
+        name: &str,
+        property: &PropertyExpression,
+        receiver: Type,
+        call: &FunctionCall,
+    ) -> Type {
+        let mut arguments = vec![receiver];
+        arguments.extend(self.infer_all(&call.arguments));
+        let mut argument_nodes = vec![(*property.object).clone()];
+        argument_nodes.extend(call.arguments.iter().cloned());
+        let desugared = FunctionCall {
+            callee: call.callee.clone(),
+            arguments: argument_nodes,
+            span: call.span.clone(),
+        };
+        self.infer_builtin_call(name, &arguments, &desugared)
+    }
+
+    fn check_arguments(
+        &mut self,
+        callee: &str,
+        params: &[Type],
+        arguments: &[Type],
+        argument_nodes: &[Expression],
+        span: &Span,
+    ) {
+        if params.len() != arguments.len() {
+            let message = format!(
+                "{} expects {} argument(s), found {}",
+                callee,
+                params.len(),
+                arguments.len()
+            );
+            self.error(message, span);
+            return;
+        }
+        for (index, (param, argument)) in params.iter().zip(arguments).enumerate() {
+            let context = format!("argument {} of {}", index + 1, callee);
+            self.expect_assignable(argument, param, &context, argument_nodes[index].span());
+        }
+    }
+
+    /// Builtins are generic over their element types, so each gets its own
+    /// rule instead of a fixed signature. Unknown builtins return `any`.
+    fn infer_builtin_call(&mut self, name: &str, arguments: &[Type], call: &FunctionCall) -> Type {
+        if let Some(result) = self.infer_string_builtin_call(name, arguments, call) {
+            return result;
+        }
+        if let Some(result) = self.infer_higher_order_call(name, arguments, call) {
+            return result;
+        }
+        if let Some(result) = self.infer_hash_builtin_call(name, arguments, call) {
+            return result;
+        }
+        if let Some(result) = self.infer_math_builtin_call(name, arguments, call) {
+            return result;
+        }
+        let arity = match name {
+            "puts" | "print" => None,
+            "push" => Some(2),
+            "len" | "first" | "last" | "rest" => Some(1),
+            _ => return Type::Any,
+        };
+        if let Some(arity) = arity {
+            if arguments.len() != arity {
+                let message =
+                    format!("{} expects {} argument(s), found {}", name, arity, arguments.len());
+                self.error(message, &call.span);
+                return Type::Any;
+            }
+        }
+
+        let element = match arguments.first() {
+            Some(Type::Array(element)) => Some((**element).clone()),
+            Some(Type::Any) => Some(Type::Any),
+            _ => None,
+        };
+        match name {
+            "puts" | "print" => Type::Null,
+            "len" => {
+                let argument = &arguments[0];
+                let accepted = Type::union(Type::String, Type::Array(Box::new(Type::Any)));
+                if !argument.is_assignable_to(&accepted) {
+                    let message = format!("len expects a string or an array, found {}", argument);
+                    self.error(message, call.arguments[0].span());
+                }
+                Type::Int
+            }
+            _ => {
+                let element = match element {
+                    Some(element) => element,
+                    None => {
+                        let message = format!("{} expects an array, found {}", name, arguments[0]);
+                        self.error(message, call.arguments[0].span());
+                        return Type::Any;
+                    }
+                };
+                match name {
+                    "first" | "last" => Type::union(element, Type::Null),
+                    "rest" => Type::union(Type::Array(Box::new(element)), Type::Null),
+                    _ => Type::Array(Box::new(Type::union(element, arguments[1].clone()))),
+                }
+            }
+        }
+    }
+
+    /// The string library, JSON, reflection and iterator builtins have fixed
+    /// parameter types; `None` when `name` is not one of them. What
+    /// `json_decode` returns depends on the text, so it is `any`, and the
+    /// reflection builtins take `any` because a parameter cannot name "some
+    /// class". Iterators and generators have no type of their own and are `any`
+    /// as well.
+    fn infer_string_builtin_call(
+        &mut self,
+        name: &str,
+        arguments: &[Type],
+        call: &FunctionCall,
+    ) -> Option<Type> {
+        let strings = Type::Array(Box::new(Type::String));
+        let (parameters, optional, result) = match name {
+            "trim" | "upper" | "lower" => (vec![Type::String], 0, Type::String),
+            "chars" => (vec![Type::String], 0, strings),
+            "split" => (vec![Type::String, Type::String], 0, strings),
+            "join" => (vec![Type::Array(Box::new(Type::Any)), Type::String], 0, Type::String),
+            "contains" | "starts_with" | "ends_with" => {
+                (vec![Type::String, Type::String], 0, Type::Bool)
+            }
+            "index_of" => (vec![Type::String, Type::String], 0, Type::Int),
+            "replace" => (vec![Type::String, Type::String, Type::String], 0, Type::String),
+            "substr" => (vec![Type::String, Type::Int, Type::Int], 1, Type::String),
+            "str" => (vec![Type::Any], 0, Type::String),
+            "int" => {
+                let accepted = Type::union(Type::union(Type::Int, Type::Float), Type::String);
+                (vec![accepted], 0, Type::union(Type::Int, Type::Null))
+            }
+            "json_encode" => (vec![Type::Any], 0, Type::String),
+            "json_decode" => (vec![Type::String], 0, Type::Any),
+            "type_of" => (vec![Type::Any], 0, Type::String),
+            "instance_of" => (vec![Type::Any, Type::Any], 0, Type::Bool),
+            "fields" | "methods" => (vec![Type::Any], 0, strings),
+            "range" => (vec![Type::Int, Type::Int, Type::Int], 2, Type::Any),
+            "take" => (vec![Type::Any, Type::Int], 0, Type::Any),
+            "zip" => (vec![Type::Any, Type::Any], 0, Type::Any),
+            "enumerate" => (vec![Type::Any], 0, Type::Any),
+            "to_array" => (vec![Type::Any], 0, Type::Array(Box::new(Type::Any))),
+            "next" => {
+                let step = Type::Hash(Box::new(Type::String), Box::new(Type::Any));
+                (vec![Type::Any, Type::Any], 1, step)
+            }
+            _ => return None,
+        };
+        let required = parameters.len() - optional;
+        if arguments.len() < required || arguments.len() > parameters.len() {
+            let expected = if optional == 0 {
+                required.to_string()
+            } else {
+                format!("{} to {}", required, parameters.len())
+            };
+            let message =
+                format!("{} expects {} argument(s), found {}", name, expected, arguments.len());
+            self.error(message, &call.span);
+            return Some(Type::Any);
+        }
+        for (index, (argument, parameter)) in arguments.iter().zip(&parameters).enumerate() {
+            let context = format!("argument {} of {}", index + 1, name);
+            self.expect_assignable(argument, parameter, &context, call.arguments[index].span());
+        }
+        Some(result)
+    }
+
+    /// `map`, `filter`, `reduce`, `sort`, `find`, `any` and `all`: the result
+    /// follows the array's element type and, where it is known, the callback's
+    /// return type. `None` when `name` is not one of them.
+    fn infer_higher_order_call(
+        &mut self,
+        name: &str,
+        arguments: &[Type],
+        call: &FunctionCall,
+    ) -> Option<Type> {
+        let (required, allowed) = match name {
+            "map" | "filter" | "find" | "any" | "all" => (2, 2),
+            "reduce" => (3, 3),
+            "sort" => (1, 2),
+            _ => return None,
+        };
+        if arguments.len() < required || arguments.len() > allowed {
+            let expected = if required == allowed {
+                required.to_string()
+            } else {
+                format!("{} to {}", required, allowed)
+            };
+            let message =
+                format!("{} expects {} argument(s), found {}", name, expected, arguments.len());
+            self.error(message, &call.span);
+            return Some(Type::Any);
+        }
+        let element = match &arguments[0] {
+            Type::Array(element) => (**element).clone(),
+            Type::Any => Type::Any,
+            other => {
+                let message = format!("{} expects an array, found {}", name, other);
+                self.error(message, call.arguments[0].span());
+                return Some(Type::Any);
+            }
+        };
+        let returned = match arguments.get(1) {
+            Some(Type::Function(_, ret)) => (**ret).clone(),
+            _ => Type::Any,
+        };
+        let result = match name {
+            "map" => Type::Array(Box::new(returned)),
+            "filter" | "sort" => Type::Array(Box::new(element)),
+            "find" => Type::union(element, Type::Null),
+            "reduce" => Type::union(arguments[2].clone(), returned),
+            _ => Type::Bool,
+        };
+        Some(result)
+    }
+
+    /// `keys`, `values`, `entries`, `has`, `delete` and `merge` keep the key
+    /// and value types of the hashes they are given. `None` when `name` is not
+    /// one of them.
+    fn infer_hash_builtin_call(
+        &mut self,
+        name: &str,
+        arguments: &[Type],
+        call: &FunctionCall,
+    ) -> Option<Type> {
+        let arity = match name {
+            "keys" | "values" | "entries" => 1,
+            "has" | "delete" | "merge" => 2,
+            _ => return None,
+        };
+        if arguments.len() != arity {
+            let message =
+                format!("{} expects {} argument(s), found {}", name, arity, arguments.len());
+            self.error(message, &call.span);
+            return Some(Type::Any);
+        }
+        let hashes = if name == "merge" { 2 } else { 1 };
+        let mut entries = Vec::new();
+        for (index, argument) in arguments.iter().enumerate().take(hashes) {
+            match argument {
+                Type::Hash(key, value) => entries.push(((**key).clone(), (**value).clone())),
+                Type::Any => entries.push((Type::Any, Type::Any)),
+                other => {
+                    let message = format!("{} expects a hash, found {}", name, other);
+                    self.error(message, call.arguments[index].span());
+                    return Some(Type::Any);
+                }
+            }
+        }
+        let (key, value) = entries[0].clone();
+        let result = match name {
+            "keys" => Type::Array(Box::new(key)),
+            "values" => Type::Array(Box::new(value)),
+            "entries" => Type::Array(Box::new(Type::Array(Box::new(Type::union(key, value))))),
+            "has" => Type::Bool,
+            "delete" => arguments[0].clone(),
+            _ => {
+                let (other_key, other_value) = entries[1].clone();
+                Type::Hash(
+                    Box::new(Type::union(key, other_key)),
+                    Box::new(Type::union(value, other_value)),
+                )
+            }
+        };
+        Some(result)
+    }
+
+    /// The math builtins plus `random` and `seed`. Integers stay integers
+    /// where the runtime keeps them exact; `pow` of two ints may still be a
+    /// float because of negative exponents. `None` when `name` is not one of
+    /// them.
+    fn infer_math_builtin_call(
+        &mut self,
+        name: &str,
+        arguments: &[Type],
+        call: &FunctionCall,
+    ) -> Option<Type> {
+        let number = Type::union(Type::Int, Type::Float);
+        let (parameter, required, allowed) = match name {
+            "abs" | "sqrt" | "floor" | "ceil" | "round" => (&number, 1, 1),
+            "min" | "max" => (&number, 1, usize::MAX),
+            "pow" => (&number, 2, 2),
+            "random" | "seed" => (&Type::Int, 1, 1),
+            _ => return None,
+        };
+        if arguments.len() < required || arguments.len() > allowed {
+            let expected = if allowed == usize::MAX {
+                format!("at least {}", required)
+            } else {
+                required.to_string()
+            };
+            let message =
+                format!("{} expects {} argument(s), found {}", name, expected, arguments.len());
+            self.error(message, &call.span);
+            return Some(Type::Any);
+        }
+        for (index, argument) in arguments.iter().enumerate() {
+            let context = format!("argument {} of {}", index + 1, name);
+            self.expect_assignable(argument, parameter, &context, call.arguments[index].span());
+        }
+        let first = arguments[0].clone();
+        let result = match name {
+            "abs" => first,
+            "min" | "max" => arguments
+                .iter()
+                .cloned()
+                .reduce(Type::union)
+                .expect("arity checked"),
+            "pow" => match numeric_result(&first, &arguments[1]) {
+                Some(Type::Int) => number,
+                _ => Type::Float,
+            },
+            "sqrt" => Type::Float,
+            "random" => Type::Int,
+            "seed" => Type::Null,
+            _ => match first {
+                Type::Int | Type::Any => first,
+                _ => Type::union(Type::Int, Type::Null),
+            },
+        };
+        Some(result)
+    }
+}
+
+/// The builtin `receiver.name` binds to when `receiver` is a string, array
+/// or hash that has such a method.
+fn builtin_method(receiver: &Type, name: &str) -> Option<&'static str> {
+    let kind = match receiver {
+        Type::String => Receiver::String,
+        Type::Array(_) => Receiver::Array,
+        Type::Hash(..) => Receiver::Hash,
+        _ => return None,
+    };
+    let id = methods::lookup(kind, name)?;
+    BuiltIns
+        .iter()
+        .find(|definition| definition.id == id)
+        .map(|definition| definition.name)
+}
+
+/// Result of `left op right` for the arithmetic and comparison operators;
+/// `None` when the operator does not apply to those types.
+fn operator_result(op: &TokenKind, left: &Type, right: &Type) -> Option<Type> {
+    match op {
+        TokenKind::PLUS => match (left, right) {
+            (Type::String, Type::String) => Some(Type::String),
+            (Type::Any, Type::String) | (Type::String, Type::Any) => Some(Type::String),
+            _ => numeric_result(left, right),
+        },
+        TokenKind::LT | TokenKind::GT => numeric_result(left, right).map(|_| Type::Bool),
+        _ => numeric_result(left, right),
+    }
+}
+
+/// Result of arithmetic on two numbers: int only when both sides are ints,
+/// float as soon as one side is, and `None` when either side is not numeric.
+fn numeric_result(left: &Type, right: &Type) -> Option<Type> {
+    let number = Type::union(Type::Int, Type::Float);
+    if !left.is_assignable_to(&number) || !right.is_assignable_to(&number) {
+        return None;
+    }
+    if *left == Type::Float || *right == Type::Float {
+        return Some(Type::Float);
+    }
+    if *left == Type::Any || *right == Type::Any {
+        return Some(Type::Any);
+    }
+    if left.is_assignable_to(&Type::Int) && right.is_assignable_to(&Type::Int) {
+        return Some(Type::Int);
+    }
+    Some(number)
+}
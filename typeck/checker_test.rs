@@ -0,0 +1,368 @@
+use parser::ast::Node;
+use parser::parse;
+
+use crate::checker::{check_program, TypeError};
+use crate::line_column;
+
+fn check(input: &str) -> Result<(), Vec<TypeError>> {
+    let Node::Program(program) = parse(input).unwrap() else { panic!("expected program") };
+    check_program(&program)
+}
+
+fn assert_ok(inputs: &[&str]) {
+    for input in inputs {
+        if let Err(errors) = check(input) {
+            panic!("{:?}: unexpected type errors {:?}", input, errors);
+        }
+    }
+}
+
+/// Each case expects exactly one error containing `message`, spanning
+/// `snippet`.
+fn assert_errors(cases: &[(&str, &str, &str)]) {
+    for (input, message, snippet) in cases {
+        let errors = check(input).unwrap_err();
+        assert_eq!(errors.len(), 1, "{:?}: {:?}", input, errors);
+        let error = &errors[0];
+        assert!(error.message.contains(message), "{:?}: got {:?}", input, error.message);
+        assert_eq!(&input[error.span.start..error.span.end], *snippet, "{:?}", input);
+    }
+}
+
+#[test]
+fn unannotated_programs_check() {
+    assert_ok(&[
+        "let add = fn(a, b) { a + b }; add(1, 2); add(\"a\", \"b\")",
+        "let fib = fn(n) { if (n < 2) { return n; } fib(n - 1) + fib(n - 2) }; fib(10)",
+        "let xs = [1, 2, 3]; len(xs) + xs[0]; push(xs, 4)",
+        "let h = {\"one\": 1}; h[\"one\"] * 2",
+        "if (true) { let y = 1; } y",
+        r#"class Counter {
+  constructor(start) { this.count = start; }
+  next() { this.count = this.count + 1; this.count }
+}
+let counter = new Counter(1);
+counter.next() + counter.missing"#,
+    ]);
+}
+
+#[test]
+fn annotations_accept_matching_values() {
+    assert_ok(&[
+        "let x: int = 5; let s: string = \"a\"; let b: bool = !x;",
+        "let xs: [int] = []; let h: {string: [int]} = {\"a\": [1]};",
+        "let n: int = 3; let label: string = `n = ${n}, ok = ${n > 1}`;",
+        "let maybe: int | null = first([1]); let none: int | null = if (false) { 1 };",
+        "let add = fn(a: int, b: int): int { a + b }; let total: int = add(1, 2);",
+        "let apply = fn(f: fn(int): int, x: int): int { f(x) }; apply(fn(n) { n * 2 }, 3)",
+        "let early = fn(n: int): int { if (n > 0) { return n; } 0 }; early(1)",
+        "let fact = fn(n: int): int { if (n < 2) { 1 } else { n * fact(n - 1) } }; fact(5)",
+        r#"class Point {
+  x: int;
+  y: int;
+  constructor(x: int, y: int) { this.x = x; this.y = y; }
+  sum(): int { this.x + this.y }
+}
+let p: Point = new Point(1, 2);
+let total: int = p.sum() + p.x;"#,
+    ]);
+}
+
+#[test]
+fn reports_mismatches_at_the_offending_expression() {
+    assert_errors(&[
+        (
+            "let x: int = \"five\";",
+            "type mismatch in let x: expected int, found string",
+            "\"five\"",
+        ),
+        ("let x: int = first([1]);", "expected int, found int | null", "first([1])"),
+        ("let x: int = `${1}`;", "expected int, found string", "`${1}`"),
+        ("let xs: [int] = [1, true];", "expected [int], found [int | bool]", "[1, true]"),
+        (
+            "let f = fn(a: int) { a }; f(\"a\")",
+            "argument 1 of f: expected int, found string",
+            "\"a\"",
+        ),
+        ("let f = fn(a: int) { a }; f(1, 2)", "f expects 1 argument(s), found 2", "f(1, 2)"),
+        (
+            "let f = fn(): int { \"a\" }",
+            "return type mismatch: expected int, found string",
+            "\"a\"",
+        ),
+        ("let f = fn(): int { return true; }", "return type mismatch", "true"),
+        (
+            "let f = fn(n: int): int { if (n > 0) { n } }",
+            "expected int, found int | null",
+            "if (n > 0) { n }",
+        ),
+        ("1 + \"a\"", "operator + cannot be applied to int and string", "1 + \"a\""),
+        ("let s: string = \"a\"; -s", "operator - cannot be applied to string", "-s"),
+        (
+            "let x: int | null = 1; x * 2",
+            "operator * cannot be applied to int | null and int",
+            "x * 2",
+        ),
+        ("let n = 5; n()", "a value of type int is not callable", "n()"),
+        ("let n = 5; n[0]", "cannot index a value of type int", "n[0]"),
+        ("[1][\"a\"]", "array index: expected int, found string", "\"a\""),
+        ("{[1]: 2}", "[int] cannot be used as a hash key", "[1]"),
+        ("len(1)", "len expects a string or an array, found int", "1"),
+        ("push([1])", "push expects 2 argument(s), found 1", "push([1])"),
+        ("let x: Missing = 1;", "unknown type 'Missing'", "Missing"),
+    ]);
+}
+
+#[test]
+fn inferred_unions_only_fail_when_no_member_fits() {
+    assert_ok(&[
+        "let xs = [1, \"a\"]; xs[0] + 1",
+        "let x = if (true) { 1 }; x + 1; -x",
+        "let pick = fn(n) { if (n > 0) { 1 } else { \"a\" } }; pick(1) * 2",
+        "let xs = [1, \"a\"]; let ys = [2, true]; xs[0] < ys[0]",
+    ]);
+    assert_errors(&[
+        (
+            "let xs = [true, \"a\"]; xs[0] * 2",
+            "operator * cannot be applied to bool | string and int",
+            "xs[0] * 2",
+        ),
+        (
+            "let xs = [1, \"a\"]; xs[0] + true",
+            "operator + cannot be applied to int | string and bool",
+            "xs[0] + true",
+        ),
+        (
+            "let x: int | string = 1; x + 1",
+            "operator + cannot be applied to int | string and int",
+            "x + 1",
+        ),
+        ("let f = fn(x: int | null) { -x }", "operator - cannot be applied to int | null", "-x"),
+    ]);
+}
+
+#[test]
+fn floats_widen_integer_arithmetic() {
+    assert_ok(&[
+        "let ratio: float = 3 / 4.0; let pct: float = ratio * 100;",
+        "let half = fn(n: int): float { n / 2.0 }; let lt: bool = half(3) < 2;",
+        "let neg: float = -1.5; let avg = fn(a, b): float { (a + b) / 2.0 };",
+    ]);
+    assert_errors(&[
+        ("let n: int = 1 + 0.5;", "expected int, found float", "1 + 0.5"),
+        ("1.5 + \"a\"", "operator + cannot be applied to float and string", "1.5 + \"a\""),
+        ("{1.5: true}", "float cannot be used as a hash key", "1.5"),
+    ]);
+}
+
+#[test]
+fn string_builtins_have_fixed_signatures() {
+    assert_ok(&[
+        "let words: [string] = split(trim(\" a b \"), \" \"); let s: string = join(words, \",\");",
+        "let found: bool = contains(\"abc\", \"b\"); let at: int = index_of(\"abc\", \"c\");",
+        "let head: string = substr(\"abc\", 1); let mid: string = substr(upper(\"abc\"), 1, 1);",
+        "let n: int | null = int(\"42\"); let shown: string = str([1, 2]);",
+    ]);
+    assert_errors(&[
+        ("upper(1)", "argument 1 of upper: expected string, found int", "1"),
+        ("substr(\"a\", \"0\")", "argument 2 of substr: expected int, found string", "\"0\""),
+        ("let n: int = int(\"1\");", "expected int, found int | null", "int(\"1\")"),
+        ("int(true)", "argument 1 of int: expected int | float | string, found bool", "true"),
+        ("substr(\"a\")", "substr expects 2 to 3 argument(s), found 1", "substr(\"a\")"),
+    ]);
+}
+
+#[test]
+fn higher_order_builtins_follow_the_element_and_callback_types() {
+    assert_ok(&[
+        "let names: [string] = map([1, 2], fn(n: int): string { str(n) });",
+        "let small: [int] = filter([3, 1], fn(n) { n < 2 }); let ordered: [int] = sort(small);",
+        "let hit: int | null = find([1, 2], fn(n) { n > 1 }); let ok: bool = any([1], fn(n) { true });",
+        "let total: int = reduce([1, 2], fn(acc: int, n: int): int { acc + n }, 0);",
+    ]);
+    assert_errors(&[
+        ("map(1, fn(n) { n })", "map expects an array, found int", "1"),
+        (
+            "reduce([1], fn(a, b) { a })",
+            "reduce expects 3 argument(s), found 2",
+            "reduce([1], fn(a, b) { a })",
+        ),
+        (
+            "let n: int = find([1], fn(n) { true });",
+            "expected int, found int | null",
+            "find([1], fn(n) { true })",
+        ),
+    ]);
+}
+
+#[test]
+fn hash_builtins_keep_key_and_value_types() {
+    assert_ok(&[
+        "let h = {\"a\": 1}; let ks: [string] = keys(h); let vs: [int] = values(h);",
+        "let h = {\"a\": 1}; let found: bool = has(h, \"a\"); let rest: {string: int} = delete(h, \"a\");",
+        "let both: {string | int: int | bool} = merge({\"a\": 1}, {2: true});",
+        "let pairs: [[string | int]] = entries({\"a\": 1});",
+    ]);
+    assert_errors(&[
+        ("keys([1])", "keys expects a hash, found [int]", "[1]"),
+        ("merge({}, 1)", "merge expects a hash, found int", "1"),
+        ("has({})", "has expects 2 argument(s), found 1", "has({})"),
+    ]);
+}
+
+#[test]
+fn math_builtins_keep_integers_exact() {
+    assert_ok(&[
+        "let a: int = abs(-1); let f: float = abs(-1.5); let m: int = max(1, 2, 3);",
+        "let p: int | float = pow(2, 10); let q: float = pow(2.0, 0.5); let r: float = sqrt(4);",
+        "let down: int | null = floor(2.5); let up: int = ceil(3);",
+        "seed(7); let dice: int = random(6) + 1;",
+    ]);
+    assert_errors(&[
+        ("abs(\"a\")", "argument 1 of abs: expected int | float, found string", "\"a\""),
+        ("random(1.5)", "argument 1 of random: expected int, found float", "1.5"),
+        ("let n: int = min(1, 2.5);", "expected int, found int | float", "min(1, 2.5)"),
+        ("max()", "max expects at least 1 argument(s), found 0", "max()"),
+    ]);
+}
+
+#[test]
+fn json_builtins_take_any_value_and_text() {
+    assert_ok(&[
+        "let text: string = json_encode([1, {\"a\": true}]); let n: int = json_decode(text);",
+    ]);
+    assert_errors(&[(
+        "json_decode(1)",
+        "argument 1 of json_decode: expected string, found int",
+        "1",
+    )]);
+}
+
+#[test]
+fn reflection_builtins_return_names_and_flags() {
+    assert_ok(&[
+        "class P { constructor() { this.x = 1; } } let p = new P(); let t: string = type_of(p);",
+        "class P {} let yes: bool = instance_of(new P(), P); let names: [string] = methods(P);",
+    ]);
+    assert_errors(&[("let n: int = type_of(1);", "expected int, found string", "type_of(1)")]);
+}
+
+#[test]
+fn iterator_builtins_take_ints_and_return_any() {
+    assert_ok(&[
+        "let xs: [any] = to_array(take(zip(range(1, 9, 2), [\"a\"]), 1)); let n: int = len(range(3));",
+        "let pairs = enumerate(range(3)); let first_pair = first(pairs);",
+    ]);
+    assert_errors(&[("range(\"a\")", "argument 1 of range: expected int, found string", "\"a\"")]);
+}
+
+#[test]
+fn generator_functions_return_any_and_next_returns_a_hash() {
+    assert_ok(&[
+        "let count = fn*(n: int): int { let sent: string = yield n; n }; let step: {string: any} = next(count(1), 2); let done: int = step[\"value\"];",
+    ]);
+    assert_errors(&[
+        ("next()", "next expects 1 to 2 argument(s), found 0", "next()"),
+        (
+            "fn*(): int { yield 1; \"no\" }",
+            "return type mismatch: expected int, found string",
+            "\"no\"",
+        ),
+    ]);
+}
+
+#[test]
+fn builtin_methods_type_like_their_builtins() {
+    assert_ok(&[
+        "let parts: [string] = \"a,b\".split(\",\"); let n: int = parts.len();",
+        "let xs: [int] = [1, 2].push(3).map(fn(x: int): int { x * 2 });",
+        "let ks: [string] = {\"a\": 1}.keys(); let f = [1].push;",
+    ]);
+    assert_errors(&[
+        ("let n: int = \"abc\".upper();", "expected int, found string", "\"abc\".upper()"),
+        ("\"abc\".split(1)", "argument 2 of split: expected string, found int", "1"),
+        ("\"abc\".keys()", "cannot read property 'keys' of string", "\"abc\".keys"),
+    ]);
+}
+
+#[test]
+fn checks_classes_with_declared_fields() {
+    let class = r#"class Point {
+  x: int;
+  constructor(x: int) { this.x = x; }
+  get(): int { this.x }
+}
+"#;
+    let cases = [
+        (format!("{}new Point(\"a\")", class), "argument 1 of Point constructor", "\"a\""),
+        (format!("{}new Point()", class), "Point constructor expects 1 argument(s)", "new Point()"),
+        (format!("{}let p = new Point(1); p.y", class), "Point has no property 'y'", "y"),
+        (format!("{}let p = new Point(1); p.x = true;", class), "field Point.x", "true"),
+        (format!("{}let p = new Point(1); p.z = 1;", class), "Point has no field 'z'", "z"),
+        (
+            format!("{}let s: string = new Point(1).get();", class),
+            "expected string, found int",
+            "new Point(1).get()",
+        ),
+        (format!("{}Point(1)", class), "class Point must be constructed with new", "Point(1)"),
+    ];
+    for (input, message, snippet) in &cases {
+        assert_errors(&[(input.as_str(), message, snippet)]);
+    }
+}
+
+#[test]
+fn operators_on_instances_use_special_methods() {
+    let class = r#"class Money {
+  cents: int;
+  constructor(cents: int) { this.cents = cents; }
+  add(other: Money): Money { new Money(this.cents + other.cents) }
+  eq(other: Money): int { this.cents - other.cents }
+  get(unit: string): int { this.cents }
+}
+"#;
+    assert_ok(&[
+        &format!("{}let total: Money = new Money(1) + new Money(2);", class),
+        &format!("{}let same: bool = new Money(1) != new Money(2);", class),
+        &format!("{}let cents: int = new Money(5)[\"usd\"];", class),
+    ]);
+    let cases = [
+        (format!("{}new Money(1) + 2", class), "argument 1 of Money.add", "2"),
+        (format!("{}new Money(1)[0]", class), "argument 1 of Money.get", "0"),
+        (
+            format!("{}new Money(1) - new Money(2)", class),
+            "operator - cannot be applied to Money and Money",
+            "new Money(1) - new Money(2)",
+        ),
+    ];
+    for (input, message, snippet) in &cases {
+        assert_errors(&[(input.as_str(), message, snippet)]);
+    }
+}
+
+#[test]
+fn collects_every_error_in_source_order() {
+    let input = r#"let a: int = "a";
+class Box { value: Missing; }
+let b: bool = 1;"#;
+    let errors = check(input).unwrap_err();
+    let messages = errors
+        .iter()
+        .map(|error| error.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 3, "{:?}", messages);
+    assert!(messages[0].contains("let a"));
+    assert!(messages[1].contains("unknown type 'Missing'"));
+    assert!(messages[2].contains("let b"));
+}
+
+#[test]
+fn check_source_renders_line_and_column() {
+    let report = crate::check_source("let x = 1;\nlet y: string = x;").unwrap_err();
+    assert_eq!(report, "2:17: type error: type mismatch in let y: expected string, found int");
+    assert!(crate::check_source("let x = y;")
+        .unwrap_err()
+        .contains("undefined variable 'y'"));
+    assert_eq!(line_column("let é = 1;\nx", 12), (2, 1));
+    assert_eq!(line_column("\"é\" + 1", 5), (1, 5));
+}
@@ -0,0 +1,50 @@
+//! Optional static type checking for Monkey.
+//!
+//! The parser accepts annotations on let bindings, parameters, return types
+//! and class fields (`let x: int = 1`, `fn(a: string): int {}`,
+//! `class P { x: int; }`). The interpreter and both compilers ignore them;
+//! this crate infers types locally and reports mismatches with source spans.
+
+pub mod checker;
+pub mod types;
+
+#[cfg(test)]
+mod checker_test;
+
+pub use checker::{check_program, TypeError};
+
+use object::builtins::BuiltIns;
+use parser::ast::Node;
+use parser::validation::validate_program;
+
+/// Parses, validates and type checks `source`. Failures are rendered one per
+/// line as `line:column: message`, ready for a CLI to print.
+pub fn check_source(source: &str) -> Result<(), String> {
+    let node = parser::parse(source).map_err(|errors| format!("parse error: {}", errors[0]))?;
+    let Node::Program(program) = &node else {
+        unreachable!("parse always returns a Program node");
+    };
+    let builtin_names: Vec<&str> = BuiltIns.iter().map(|builtin| builtin.name).collect();
+    validate_program(program, &builtin_names).map_err(|error| {
+        let (line, column) = line_column(source, error.span.start);
+        format!("{}:{}: {}", line, column, error.message)
+    })?;
+    check_program(program).map_err(|errors| {
+        errors
+            .iter()
+            .map(|error| {
+                let (line, column) = line_column(source, error.span.start);
+                format!("{}:{}: type error: {}", line, column, error.message)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// 1-based line and column (in characters) of a UTF-8 byte offset.
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let prefix = source.get(..offset).unwrap_or(source);
+    let line = prefix.matches('\n').count() + 1;
+    let line_start = prefix.rfind('\n').map_or(0, |index| index + 1);
+    (line, prefix[line_start..].chars().count() + 1)
+}
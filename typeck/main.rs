@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: monkey-typeck <file.monkey>";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let input = match args.as_slice() {
+        [path] if !path.starts_with('-') => PathBuf::from(path),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    let source = match std::fs::read_to_string(&input) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", input.display(), error);
+            std::process::exit(1);
+        }
+    };
+    if let Err(report) = typeck::check_source(&source) {
+        for line in report.lines() {
+            eprintln!("{}:{}", input.display(), line);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+
+/// Static types inferred by the checker. `Any` is the type of everything the
+/// checker cannot see through (unannotated parameters, unknown properties)
+/// and is compatible with every other type in both directions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Type {
+    Any,
+    Int,
+    Float,
+    Bool,
+    String,
+    Null,
+    Array(Box<Type>),
+    Hash(Box<Type>, Box<Type>),
+    Function(Vec<Type>, Box<Type>),
+    /// A builtin function, by the name it was referenced with.
+    Builtin(String),
+    /// The class value itself, as bound by a class declaration.
+    Class(String),
+    /// An instance of the named class.
+    Instance(String),
+    /// Flattened and deduplicated; never nested, never holds `Any`.
+    Union(Vec<Type>),
+}
+
+impl Type {
+    /// `a | b`, normalized: `Any` absorbs everything and members are unique.
+    pub fn union(a: Type, b: Type) -> Type {
+        if a == Type::Any || b == Type::Any {
+            return Type::Any;
+        }
+        let mut members = Vec::new();
+        for member in a.into_members().into_iter().chain(b.into_members()) {
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+        if members.len() == 1 {
+            return members.pop().unwrap();
+        }
+        Type::Union(members)
+    }
+
+    /// Union of all `types`; `None` when there are none.
+    pub fn join(types: impl IntoIterator<Item = Type>) -> Option<Type> {
+        types.into_iter().reduce(Type::union)
+    }
+
+    fn into_members(self) -> Vec<Type> {
+        match self {
+            Type::Union(members) => members,
+            other => vec![other],
+        }
+    }
+
+    /// Whether a value of type `self` may flow into a slot declared `target`.
+    /// Arrays and hashes are checked covariantly, function parameters
+    /// contravariantly.
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        match (self, target) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::Union(members), _) => {
+                members.iter().all(|member| member.is_assignable_to(target))
+            }
+            (_, Type::Union(members)) => members.iter().any(|member| self.is_assignable_to(member)),
+            (Type::Array(element), Type::Array(target_element)) => {
+                element.is_assignable_to(target_element)
+            }
+            (Type::Hash(key, value), Type::Hash(target_key, target_value)) => {
+                key.is_assignable_to(target_key) && value.is_assignable_to(target_value)
+            }
+            (Type::Function(params, ret), Type::Function(target_params, target_ret)) => {
+                params.len() == target_params.len()
+                    && target_params
+                        .iter()
+                        .zip(params)
+                        .all(|(target_param, param)| target_param.is_assignable_to(param))
+                    && ret.is_assignable_to(target_ret)
+            }
+            (Type::Builtin(_), Type::Function(..)) => true,
+            _ => self == target,
+        }
+    }
+
+    /// Types the runtimes accept as hash keys.
+    pub fn is_hashable(&self) -> bool {
+        match self {
+            Type::Any | Type::Int | Type::Bool | Type::String => true,
+            Type::Union(members) => members.iter().all(Type::is_hashable),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "any"),
+            Type::Int => write!(f, "int"),
+            Type::Float => write!(f, "float"),
+            Type::Bool => write!(f, "bool"),
+            Type::String => write!(f, "string"),
+            Type::Null => write!(f, "null"),
+            Type::Array(element) => write!(f, "[{}]", element),
+            Type::Hash(key, value) => write!(f, "{{{}: {}}}", key, value),
+            Type::Function(params, ret) => {
+                let params = params
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "fn({}): {}", params, ret)
+            }
+            Type::Builtin(name) => write!(f, "builtin {}", name),
+            Type::Class(name) => write!(f, "class {}", name),
+            Type::Instance(name) => write!(f, "{}", name),
+            Type::Union(members) => {
+                let members = members
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                write!(f, "{}", members)
+            }
+        }
+    }
+}
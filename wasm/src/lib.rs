@@ -1,20 +1,28 @@
 mod utils;
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use crate::utils::set_panic_hook;
 use compiler::compiler::Compiler;
 use compiler::snapshot::{read_bytecode, write_bytecode};
 use compiler::snapshot_layout::describe_bytecode;
+use interpreter::{EvalErrorKind, EvalOptions};
 use monkey_asm::emitter::AsmDialect;
 use monkey_asm::lower::lower_node;
 use object::builtins::BuiltIns;
+use object::environment::Env;
+use object::output::Output;
 use parser::ast::Node;
 use parser::parse as parser_pase;
 use parser::validation::validate_program;
 use parser::{parse_ast_json_string, parse_ast_lossless_json_string, stringify_integer_literals};
+use typeck::check_program;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::throw_str;
 
 const PLAYGROUND_GC_INSTRUCTION_BUDGET: usize = 10_000;
+const PLAYGROUND_INTERPRETER_STEP_BUDGET: usize = 100_000;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -57,7 +65,11 @@ pub fn parse_lossless(input: &str) -> String {
 ///
 /// Standalone source is validated against the same predefined globals a fresh
 /// interpreter/compiler sees — the full builtin table (`len`, `puts`, `first`,
-/// `last`, `rest`, `push`, `print`).
+/// `last`, `rest`, `push`, `print`, the string builtins `split` ... `int`, the
+/// higher-order builtins `map` ... `all`, the hash builtins `keys` ... `merge`,
+/// the math builtins `abs` ... `seed`, `json_encode` / `json_decode`, the
+/// reflection builtins `type_of` ... `methods`, the iterator builtins
+/// `range` ... `to_array` and `next`).
 #[wasm_bindgen]
 pub fn analyze_lossless(input: &str) -> String {
     set_panic_hook();
@@ -103,6 +115,65 @@ fn analyze_envelope(input: &str) -> Result<serde_json::Value, AnalyzeFailure> {
     Ok(ast)
 }
 
+/// Parse, validate and type check Monkey source against its optional type
+/// annotations. Returns `{ status: "ok" }` or
+/// `{ status: "error", stage, errors: [{ message, span? }] }` where `stage` is
+/// `parse`, `validation` or `typecheck`; only type checking reports more than
+/// one error.
+#[wasm_bindgen]
+pub fn typecheck(input: &str) -> String {
+    set_panic_hook();
+
+    let envelope = match typecheck_envelope(input) {
+        Ok(()) => serde_json::json!({ "status": "ok" }),
+        Err((stage, errors)) => serde_json::json!({
+            "status": "error",
+            "stage": stage,
+            "errors": errors
+                .into_iter()
+                .map(|(message, span)| serde_json::json!({
+                    "message": message,
+                    "span": span.map(|(start, end)| serde_json::json!({ "start": start, "end": end })),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+    };
+    serde_json::to_string(&envelope).expect("typecheck envelope serialization should not fail")
+}
+
+type TypecheckFailure = (&'static str, Vec<(String, Option<(usize, usize)>)>);
+
+fn typecheck_envelope(input: &str) -> Result<(), TypecheckFailure> {
+    let node = parser_pase(input).map_err(|errors| {
+        (
+            "parse",
+            errors
+                .into_iter()
+                .take(1)
+                .map(|message| (message, None))
+                .collect(),
+        )
+    })?;
+    let Node::Program(program) = &node else {
+        unreachable!("parse always returns a Program node");
+    };
+
+    let predefined = BuiltIns
+        .iter()
+        .map(|builtin| builtin.name)
+        .collect::<Vec<_>>();
+    validate_program(program, &predefined).map_err(|error| {
+        ("validation", vec![(error.message, Some((error.span.start, error.span.end)))])
+    })?;
+    check_program(program).map_err(|errors| {
+        let errors = errors
+            .into_iter()
+            .map(|error| (error.message, Some((error.span.start, error.span.end))))
+            .collect();
+        ("typecheck", errors)
+    })
+}
+
 #[wasm_bindgen]
 pub fn compile(input: &str) -> String {
     set_panic_hook();
@@ -178,6 +249,58 @@ pub fn run_gc_with_report(input: &str) -> String {
     serde_json::to_string(&envelope).expect("GC run envelope serialization should not fail")
 }
 
+/// Evaluate Monkey source with the tree-walking interpreter and return a tagged
+/// JSON envelope: `{ status: "ok", result, stdout }` or
+/// `{ status: "error", stage, kind, message, span, callStack, stdout }`.
+///
+/// `stdout` is what `puts` printed before the run ended, even if it failed.
+/// `stage` is `parse`, `compile` (validation and unknown names) or `runtime`;
+/// `callStack` lists the Monkey calls the error passed through, innermost
+/// first, as `{ function, span }`. Like [`run_gc_with_report`], failures are
+/// data in the envelope, not JavaScript exceptions, and runs are cut off by a
+/// step budget.
+#[wasm_bindgen]
+pub fn interpret(input: &str) -> String {
+    set_panic_hook();
+
+    let options = EvalOptions {
+        step_budget: PLAYGROUND_INTERPRETER_STEP_BUDGET,
+        ..EvalOptions::default()
+    };
+    let stdout = Rc::new(RefCell::new(String::new()));
+    let env = Env::default();
+    env.borrow_mut().set_output(Output::shared(&stdout));
+    let envelope = match parser_pase(input) {
+        Err(errors) => serde_json::json!({
+            "status": "error",
+            "stage": "parse",
+            "kind": "syntax",
+            "message": errors.first().cloned().unwrap_or_else(|| "unknown parse error".to_string()),
+            "span": null,
+            "callStack": [],
+            "stdout": "",
+        }),
+        Ok(node) => match interpreter::eval_with_options(node, &env, &options) {
+            Ok(result) => serde_json::json!({
+                "status": "ok",
+                "result": result.to_string(),
+                "stdout": stdout.take(),
+            }),
+            Err(error) => serde_json::json!({
+                "status": "error",
+                "stage": if error.kind == EvalErrorKind::Compile { "compile" } else { "runtime" },
+                "kind": error.kind,
+                "message": error.message,
+                "span": error.span,
+                "callStack": error.call_stack,
+                "stdout": stdout.take(),
+            }),
+        },
+    };
+
+    serde_json::to_string(&envelope).expect("interpreter envelope serialization should not fail")
+}
+
 /// Compile Monkey source to AArch64 assembly and return a tagged JSON envelope
 /// of per-line `text`/`kind`/`span` records for the playground's godbolt-style
 /// ARM64 view (arm64 backend design §12 V1).
@@ -4,8 +4,8 @@
 
 extern crate wasm_bindgen_test;
 use monkey_wasm::{
-    analyze_lossless, compile_to_arm64, compile_to_snapshot, parse, parse_lossless,
-    run_gc_with_report, run_snapshot, run_snapshot_with_output,
+    analyze_lossless, compile_to_arm64, compile_to_snapshot, interpret, parse, parse_lossless,
+    run_gc_with_report, run_snapshot, run_snapshot_with_output, typecheck,
 };
 use serde_json::Value;
 use wasm_bindgen_test::*;
@@ -189,6 +189,49 @@ fn gc_error_envelope_distinguishes_all_stages_and_instruction_limit() {
         .contains("instruction limit exceeded"));
 }
 
+fn run_interpreter(source: &str) -> Value {
+    serde_json::from_str(&interpret(source)).expect("valid interpreter envelope JSON")
+}
+
+#[wasm_bindgen_test]
+fn interpreter_errors_carry_kind_span_and_call_stack() {
+    assert_eq!(run_interpreter("1 + 2")["result"], "3");
+
+    let parse_error = run_interpreter("let =");
+    assert_eq!(parse_error["stage"], "parse");
+    assert_eq!(parse_error["kind"], "syntax");
+
+    let compile_error = run_interpreter("this;");
+    assert_eq!(compile_error["stage"], "compile");
+    assert_eq!(compile_error["kind"], "compile");
+
+    let runtime_error = run_interpreter("let f = fn() { 1 / 0 }; f()");
+    assert_eq!(runtime_error["status"], "error");
+    assert_eq!(runtime_error["stage"], "runtime");
+    assert_eq!(runtime_error["kind"], "arithmetic");
+    assert_eq!(runtime_error["span"]["start"], 15);
+    assert_eq!(runtime_error["span"]["end"], 20);
+    assert_eq!(runtime_error["callStack"][0]["function"], "f");
+    assert_eq!(runtime_error["callStack"][0]["span"]["start"], 24);
+
+    let step_error = run_interpreter("let f = fn(n) { f(n) + f(n) }; f(0)");
+    assert_eq!(step_error["kind"], "stack");
+    let step_error =
+        run_interpreter("let f = fn(n) { if (n == 0) { 0 } else { f(n - 1) + f(n - 1) } }; f(30)");
+    assert_eq!(step_error["kind"], "executionLimit");
+}
+
+#[wasm_bindgen_test]
+fn interpreter_output_is_captured_in_the_envelope() {
+    let ok = run_interpreter(r#"puts("one", 2); 3"#);
+    assert_eq!(ok["result"], "3");
+    assert_eq!(ok["stdout"], "one\n2\n");
+
+    let failed = run_interpreter(r#"puts("before"); 1 / 0"#);
+    assert_eq!(failed["stage"], "runtime");
+    assert_eq!(failed["stdout"], "before\n");
+}
+
 fn build_snapshot(source: &str, strip_debug: bool) -> Value {
     serde_json::from_str(&compile_to_snapshot(source, strip_debug))
         .expect("valid snapshot envelope JSON")
@@ -390,3 +433,21 @@ fn arm64_parse_and_lowering_failures_are_envelope_data() {
     assert_eq!(compile_error["span"]["start"], 0);
     assert_eq!(compile_error["span"]["end"], 7);
 }
+
+#[wasm_bindgen_test]
+fn typecheck_reports_every_type_error_with_spans() {
+    let ok: Value =
+        serde_json::from_str(&typecheck("let add = fn(a: int, b: int): int { a + b };")).unwrap();
+    assert_eq!(ok["status"], "ok");
+
+    let failed: Value =
+        serde_json::from_str(&typecheck("let a: int = \"a\"; let b: bool = 1;")).unwrap();
+    assert_eq!(failed["status"], "error");
+    assert_eq!(failed["stage"], "typecheck");
+    assert_eq!(failed["errors"].as_array().unwrap().len(), 2);
+    assert_eq!(failed["errors"][0]["span"]["start"], 13);
+    assert_eq!(failed["errors"][0]["span"]["end"], 16);
+
+    let invalid: Value = serde_json::from_str(&typecheck("let x: int = y;")).unwrap();
+    assert_eq!(invalid["stage"], "validation");
+}